@@ -0,0 +1,681 @@
+//! This is an implementation of a simple form of the Alberti Cipher.
+//!
+//! The key is a [`CipherDisk`] with the Latin Alphabet on its outer ring and a
+//! mixed Latin Alphabet on its inner ring, together with a _period_. Each
+//! plaintext letter on the outer ring is replaced by the letter under it on
+//! the inner ring, and the inner ring is rotated by one step after every
+//! `period` letters. Alberti himself signalled each rotation inside the
+//! ciphertext; rotating on a fixed schedule instead keeps the cipher
+//! deterministic.
+//!
+//! Since the disk returns to its starting position after every `26 * period`
+//! letters, the cipher is periodic, like the Vigenère Cipher.
+use crate::{
+    alphabet::{CustomAlphabet, Latin},
+    disk::CipherDisk,
+    errors::StreamError,
+    keyfile::KeyFile,
+    policy::CipherSettings,
+    secret::Secret,
+    session::CipherSession,
+    stream,
+    trace::{Step, Trace, TracedCipher},
+    verify::SampleMessage,
+    CipherInfo, CipherTrait, Ciphertext as Ciphtxt, EncodingError, KeyTrait, Message as Msg, Ring,
+    RingElement, WeakKey,
+};
+use rand::{seq::SliceRandom, CryptoRng, Rng, RngCore};
+use std::{convert::Infallible, io::Read, str::FromStr};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The ciphertext space for the Alberti Cipher.
+// Notes: This is a wrapper type around the library's private representation of a ciphertext,
+// following the Latin Shift Cipher.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Ciphertext(Ciphtxt);
+
+crate::text_api!(ciphertext Ciphertext in "alberti");
+
+/// The message space of the Alberti Cipher.
+// Notes: This is a wrapper type around the library's private representation of a message,
+// following the Latin Shift Cipher.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Message(Msg);
+
+crate::text_api!(message Message in "alberti");
+
+impl Message {
+    /// Create a new message from a string.
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::alberti::Message;
+    /// let msg = Message::new("de cifris").expect("This example is hardcoded; it should work!");
+    ///
+    /// // Spaces are dropped.
+    /// assert_eq!(msg.to_string(), "decifris");
+    /// ```
+    pub fn new(str: &str) -> Result<Message, EncodingError> {
+        Ok(Message(Msg::new(str)?))
+    }
+}
+
+/// A cryptographic key for the Alberti Cipher: a cipher disk at its starting
+/// rotation, and the number of letters encrypted between rotations.
+#[derive(Debug)]
+pub struct Key(Secret<(CipherDisk, usize)>);
+
+// Keys are compared in constant time, see the `secret` module. The outer ring of every key is the
+// Latin Alphabet in order, so it is left out.
+impl ConstantTimeEq for Key {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let inner = |key: &Key| -> Vec<u32> {
+            key.disk()
+                .inner()
+                .chars()
+                .iter()
+                .map(|&c| u32::from(c))
+                .collect()
+        };
+
+        inner(self)[..].ct_eq(&inner(other)[..])
+            & self.disk().offset().ct_eq(&other.disk().offset())
+            & self.period().ct_eq(&other.period())
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Key {}
+
+impl Key {
+    /// Create a key from a cipher disk at its starting rotation and a period.
+    ///
+    /// # Errors
+    /// This function returns an error if the outer ring of the disk is not the
+    /// Latin Alphabet in order, if the inner ring is not a mixed Latin
+    /// Alphabet, or if the period is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{alphabet::{CustomAlphabet, Latin}, alberti::Key, disk::CipherDisk};
+    /// let inner = CustomAlphabet::from_alphabet::<Latin, 26>().keyword_mixed("alberti").unwrap();
+    /// let mut disk = CipherDisk::latin(inner).unwrap();
+    /// disk.align('a', 'k').unwrap();
+    ///
+    /// let key = Key::from_disk(disk, 4).unwrap();
+    /// ```
+    pub fn from_disk(disk: CipherDisk, period: usize) -> Result<Key, EncodingError> {
+        let latin = CustomAlphabet::from_alphabet::<Latin, 26>();
+        let mut inner = disk.inner().chars().to_vec();
+        inner.sort_unstable();
+
+        if disk.outer() != &latin || inner != latin.chars() {
+            return Err(EncodingError::InvalidKey(disk.inner().to_string()));
+        }
+        if period == 0 {
+            return Err(EncodingError::InvalidKey(period.to_string()));
+        }
+
+        Ok(Key(Secret::new((disk, period))))
+    }
+
+    // Get the disk at its starting rotation.
+    fn disk(&self) -> &CipherDisk {
+        &self.0.expose_secret().0
+    }
+
+    // Get the number of letters encrypted between rotations.
+    fn period(&self) -> usize {
+        self.0.expose_secret().1
+    }
+}
+
+impl KeyTrait for Key {
+    /// Generate a cryptographic key: the inner ring is a uniformly random
+    /// permutation of the Latin Alphabet at a uniformly random rotation, and
+    /// the period is chosen uniformly at random between 1 and 26.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{KeyTrait, alberti::Key};
+    /// use rand::thread_rng;
+    ///
+    /// let key = Key::new(&mut thread_rng());
+    /// ```
+    fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut letters = CustomAlphabet::from_alphabet::<Latin, 26>()
+            .chars()
+            .to_vec();
+        letters.shuffle(rng);
+
+        let inner = CustomAlphabet::from_chars(&letters).expect("The letters are distinct");
+        let mut disk = CipherDisk::latin(inner).expect("There are exactly 26 letters");
+        disk.set_offset(rng.gen_range(0..26));
+
+        Key(Secret::new((disk, rng.gen_range(1..=26))))
+    }
+
+    /// Export the cryptographic key, insecurely, as the letters of the inner
+    /// ring, the starting rotation and the period, separated by spaces.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{alphabet::{CustomAlphabet, Latin}, alberti::Key, disk::CipherDisk, KeyTrait};
+    /// let inner = CustomAlphabet::from_alphabet::<Latin, 26>().keyword_mixed("alberti").unwrap();
+    /// let key = Key::from_disk(CipherDisk::latin(inner).unwrap(), 4).unwrap();
+    ///
+    /// assert_eq!(key.export(), "alberticdfghjkmnopqsuvwxyz 0 4");
+    /// assert_eq!(Key::try_import(&key.export()).unwrap(), key);
+    /// ```
+    fn export(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.disk().inner(),
+            self.disk().offset(),
+            self.period()
+        )
+    }
+
+    fn try_import(s: &str) -> Result<Self, EncodingError> {
+        let invalid = || EncodingError::InvalidKey(s.to_string());
+
+        let [inner, offset, period] = s.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(invalid());
+        };
+        let inner = CustomAlphabet::from_chars(&inner.chars().collect::<Vec<_>>())
+            .map_err(|_| invalid())?;
+        let mut disk = CipherDisk::latin(inner).map_err(|_| invalid())?;
+        match (usize::from_str(offset), usize::from_str(period)) {
+            (Ok(offset), Ok(period)) if offset < disk.size() => {
+                disk.set_offset(offset);
+                Key::from_disk(disk, period).map_err(|_| invalid())
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// A key whose inner ring is the Latin Alphabet in order is weak, since
+    /// each rotation of the disk is then a shift.
+    fn is_weak(&self) -> Option<WeakKey> {
+        (self.disk().inner() == self.disk().outer()).then_some(WeakKey::UnmixedAlphabet)
+    }
+}
+
+/// An implementation of the Alberti Cipher.
+///
+/// An instance of the cipher carries [`CipherSettings`], which
+/// [`encrypt_text`](Self::encrypt_text) and
+/// [`decrypt_text`](Self::decrypt_text) use to work on strings.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AlbertiCipher {
+    settings: CipherSettings,
+}
+
+impl AlbertiCipher {
+    /// Create the cipher with the default settings.
+    pub fn new() -> Self {
+        AlbertiCipher::default()
+    }
+
+    /// Set the settings of the cipher.
+    pub fn with_settings(self, settings: CipherSettings) -> Self {
+        AlbertiCipher { settings }
+    }
+
+    /// Get the settings of the cipher.
+    pub fn settings(&self) -> CipherSettings {
+        self.settings
+    }
+
+    /// Encrypt a message given as a string, after preparing it according to
+    /// the settings of the cipher.
+    ///
+    /// # Errors
+    /// This method returns an error if the policy of the settings rejects the
+    /// message, or if the prepared message is not a message.
+    pub fn encrypt_text(&self, msg: &str, key: &Key) -> Result<String, EncodingError> {
+        let msg = Message(Msg::with_policy(msg, &self.settings.policy())?);
+        let ciphertxt = self.encrypt(&msg, key).unwrap_or_else(|e| match e {});
+
+        Ok(self
+            .settings
+            .group(ciphertxt.format_with_case(self.settings.case())))
+    }
+
+    /// Decrypt a ciphertext given as a string, parsed according to the
+    /// [`ParsePolicy`](crate::policy::ParsePolicy) of the settings.
+    ///
+    /// # Errors
+    /// This method returns an error if the string is not a ciphertext.
+    pub fn decrypt_text(&self, ciphertxt: &str, key: &Key) -> Result<String, EncodingError> {
+        let ciphertxt = Ciphertext::with_parsing(ciphertxt, self.settings.parsing())?;
+        let msg = self.decrypt(&ciphertxt, key).unwrap_or_else(|e| match e {});
+
+        Ok(msg.format_with_case(self.settings.case()))
+    }
+
+    /// Encrypt a message given as a stream of characters, yielding each
+    /// letter of the ciphertext as soon as the corresponding letter of the
+    /// message is read. Whitespace is skipped.
+    ///
+    /// # Errors
+    /// The stream yields an error for each character that is not a lowercase
+    /// letter or whitespace, and then carries on. Invalid characters do not
+    /// rotate the disk.
+    pub fn encrypt_stream<I: IntoIterator<Item = char>>(
+        chars: I,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> {
+        Self::encrypt_chars(chars.into_iter().map(Ok), key)
+    }
+
+    /// Encrypt a message read from `reader`, see
+    /// [`encrypt_stream`](Self::encrypt_stream).
+    ///
+    /// # Errors
+    /// The stream also yields an error if the reader fails or does not contain
+    /// valid UTF-8.
+    pub fn encrypt_reader<R: Read>(
+        reader: R,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> {
+        Self::encrypt_chars(stream::read_chars(reader), key)
+    }
+
+    /// Decrypt a ciphertext given as a stream of characters, yielding each
+    /// letter of the message as soon as the corresponding letter of the
+    /// ciphertext is read. Whitespace is skipped and case is ignored.
+    ///
+    /// # Errors
+    /// The stream yields an error for each character that is not a letter or
+    /// whitespace, and then carries on.
+    pub fn decrypt_stream<I: IntoIterator<Item = char>>(
+        chars: I,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> {
+        Self::decrypt_chars(chars.into_iter().map(Ok), key)
+    }
+
+    /// Decrypt a ciphertext read from `reader`, see
+    /// [`decrypt_stream`](Self::decrypt_stream).
+    ///
+    /// # Errors
+    /// The stream also yields an error if the reader fails or does not contain
+    /// valid UTF-8.
+    pub fn decrypt_reader<R: Read>(
+        reader: R,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> {
+        Self::decrypt_chars(stream::read_chars(reader), key)
+    }
+
+    fn encrypt_chars<I: Iterator<Item = Result<char, StreamError>>>(
+        chars: I,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> {
+        // Following Stinson's convention, ciphertexts are ALL CAPS
+        Self::apply(<Msg>::parse_stream(chars), key, CipherDisk::encode)
+            .map(|x| Ok(x?.to_char_lossy::<Latin>().to_ascii_uppercase()))
+    }
+
+    fn decrypt_chars<I: Iterator<Item = Result<char, StreamError>>>(
+        chars: I,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> {
+        Self::apply(<Ciphtxt>::parse_stream(chars), key, CipherDisk::decode)
+            .map(|y| Ok(y?.to_char_lossy::<Latin>()))
+    }
+
+    // Apply the disk to each letter in turn, rotating it after every `period` letters. Errors are
+    // passed through and do not rotate the disk.
+    fn apply<E, I: Iterator<Item = Result<RingElement, E>>>(
+        text: I,
+        key: &Key,
+        lookup: fn(&CipherDisk, char) -> Option<char>,
+    ) -> impl Iterator<Item = Result<RingElement, E>> {
+        let mut session = Session::new(key);
+        text.map(move |letter| Ok(session.step(letter?, lookup)))
+    }
+}
+
+/// A running encryption or decryption with the Alberti Cipher, which tracks
+/// the rotation of the disk from one letter to the next.
+///
+/// The state of the session is its [`position`](Session::position).
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{alberti::{AlbertiCipher, Key, Message, Session}, session::CipherSession, CipherTrait, KeyTrait};
+/// # use rand::thread_rng;
+/// let key = Key::new(&mut thread_rng());
+/// let ciphertxt = AlbertiCipher::new().encrypt(&Message::new("de cifris").unwrap(), &key).unwrap();
+///
+/// // Decrypt from the fourth letter on
+/// let mut session = Session::new(&key);
+/// session.restore(3);
+/// let msg: Message = session.decrypt_all(ciphertxt.iter().skip(3).copied());
+/// assert_eq!(msg.to_string(), "ifris");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Session {
+    start: CipherDisk,
+    disk: CipherDisk,
+    period: usize,
+    position: usize,
+}
+
+impl Session {
+    /// Start a session with the disk of `key` at its starting rotation.
+    pub fn new(key: &Key) -> Self {
+        Session {
+            start: key.disk().clone(),
+            disk: key.disk().clone(),
+            period: key.period(),
+            position: 0,
+        }
+    }
+
+    /// Get the number of letters encrypted or decrypted so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    // Look up the letter on the disk, then rotate the disk if a period has passed.
+    fn step(
+        &mut self,
+        letter: RingElement,
+        lookup: fn(&CipherDisk, char) -> Option<char>,
+    ) -> RingElement {
+        // Both rings of the disk hold the Latin Alphabet, so the letter is never left as is
+        let output = lookup(&self.disk, letter.to_char_lossy::<Latin>())
+            .and_then(RingElement::from_char::<Latin>)
+            .unwrap_or(letter);
+
+        self.position += 1;
+        if self.position.is_multiple_of(self.period) {
+            self.disk.rotate(1);
+        }
+        output
+    }
+}
+
+impl CipherSession for Session {
+    type State = usize;
+
+    fn encrypt_symbol(&mut self, x: RingElement) -> RingElement {
+        self.step(x, CipherDisk::encode)
+    }
+
+    fn decrypt_symbol(&mut self, y: RingElement) -> RingElement {
+        self.step(y, CipherDisk::decode)
+    }
+
+    fn reset(&mut self) {
+        self.restore(0);
+    }
+
+    fn snapshot(&self) -> usize {
+        self.position
+    }
+
+    /// Move the session to `position`, i.e., as if `position` letters had
+    /// been encrypted or decrypted.
+    fn restore(&mut self, position: usize) {
+        self.disk = self.start.clone();
+        self.disk.rotate(position / self.period);
+        self.position = position;
+    }
+}
+
+impl CipherTrait for AlbertiCipher {
+    type Message = Message;
+    type Ciphertext = Ciphertext;
+    type Key = Key;
+    type EncryptionError = Infallible;
+    type DecryptionError = Infallible;
+
+    /// Encrypt a message. This never fails.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{CipherTrait, KeyTrait, alberti::{AlbertiCipher, Key, Message}};
+    /// # use rand::thread_rng;
+    /// let key = Key::new(&mut thread_rng());
+    /// let msg = Message::new("de componendis cifris").unwrap();
+    ///
+    /// let ciphertxt = AlbertiCipher::new().encrypt(&msg, &key).unwrap();
+    /// assert_eq!(AlbertiCipher::new().decrypt(&ciphertxt, &key).unwrap(), msg);
+    /// ```
+    fn encrypt(
+        &self,
+        msg: &Self::Message,
+        key: &Self::Key,
+    ) -> Result<Self::Ciphertext, Infallible> {
+        Self::apply(msg.iter().copied().map(Ok), key, CipherDisk::encode).collect()
+    }
+
+    /// Decrypt a ciphertext with a given key. This never fails.
+    fn decrypt(
+        &self,
+        ciphertxt: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Result<Self::Message, Infallible> {
+        Self::apply(ciphertxt.iter().copied().map(Ok), key, CipherDisk::decode).collect()
+    }
+
+    /// Describe the Alberti Cipher. Any period can be chosen, so there is no
+    /// bound on the number of keys.
+    fn info() -> CipherInfo {
+        CipherInfo {
+            name: "alberti",
+            inventor: "Leon Battista Alberti",
+            era: "c. 1467",
+            alphabet: "Latin",
+            alphabet_size: 26,
+            block_size: 1,
+            key_count: None,
+        }
+    }
+}
+
+impl KeyFile for Key {
+    type Cipher = AlbertiCipher;
+}
+
+impl SampleMessage for AlbertiCipher {
+    fn sample_message<R: RngCore + CryptoRng>(rng: &mut R, len: usize) -> Message {
+        (0..len).map(|_| RingElement::random(rng)).collect()
+    }
+}
+
+impl TracedCipher for AlbertiCipher {
+    /// Encrypt a message and trace each letter. The key element of each step
+    /// is the rotation of the disk. This never fails.
+    fn encrypt_traced(&self, msg: &Message, key: &Key) -> Result<(Ciphertext, Trace), Infallible> {
+        let ciphertxt = self.encrypt(msg, key)?;
+        let size = key.disk().size();
+        let trace = msg
+            .iter()
+            .zip(&ciphertxt)
+            .enumerate()
+            .map(|(i, (&x, &y))| {
+                // The rotation is reduced mod 26, so it fits in a `u16`
+                let rotation = (key.disk().offset() + i / key.period()) % size;
+                Step::new(x, RingElement::new(rotation as u16), y)
+            })
+            .collect();
+
+        Ok((ciphertxt, trace))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    fn reversed_key(period: usize) -> Key {
+        let inner = CustomAlphabet::from_chars(&('a'..='z').rev().collect::<Vec<_>>()).unwrap();
+        Key::from_disk(CipherDisk::latin(inner).unwrap(), period).unwrap()
+    }
+
+    #[test]
+    fn weak_keys() {
+        assert_eq!(reversed_key(2).is_weak(), None);
+
+        let mut disk = CipherDisk::latin(CustomAlphabet::from_alphabet::<Latin, 26>()).unwrap();
+        disk.set_offset(5);
+        let key = Key::from_disk(disk, 3).unwrap();
+        assert_eq!(key.is_weak(), Some(WeakKey::UnmixedAlphabet));
+    }
+
+    #[test]
+    fn traced() {
+        let msg = Message::new("aaab").unwrap();
+        let key = reversed_key(2);
+        let (ciphertxt, trace) = AlbertiCipher::new().encrypt_traced(&msg, &key).unwrap();
+        assert_eq!(ciphertxt, AlbertiCipher::new().encrypt(&msg, &key).unwrap());
+
+        // The key element is the rotation of the disk, which turns every two letters
+        let rotations: Vec<u16> = trace.iter().map(|s| s.key_element.into_inner()).collect();
+        assert_eq!(rotations, [0, 0, 1, 1]);
+        let letters: String = trace.iter().map(|s| s.output).collect();
+        assert_eq!(letters, ciphertxt.to_string());
+        assert_eq!(trace.steps()[3].input, 'b');
+    }
+
+    #[test]
+    fn session() {
+        let key = reversed_key(2);
+        let msg = Message::new("aaaaab").unwrap();
+        let ciphertxt = AlbertiCipher::new().encrypt(&msg, &key).unwrap();
+
+        let mut session = Session::new(&key);
+        let encrypted: Ciphertext = session.encrypt_all(msg.iter().copied());
+        assert_eq!(encrypted, ciphertxt);
+        assert_eq!(session.position(), 6);
+
+        // Restoring a snapshot gives the rotation reached by encryption
+        session.reset();
+        let _: Ciphertext = session.encrypt_all(msg.iter().take(3).copied());
+        let state = session.snapshot();
+        let rest: Ciphertext = session.encrypt_all(msg.iter().skip(3).copied());
+        session.restore(state);
+        assert_eq!(
+            session.encrypt_all::<_, Ciphertext>(msg.iter().skip(3).copied()),
+            rest
+        );
+
+        // Decryption can start in the middle of a ciphertext
+        let mut session = Session::new(&key);
+        session.restore(5);
+        assert_eq!(session.decrypt_symbol(ciphertxt[5]), msg[5]);
+    }
+
+    #[test]
+    fn encrypt() {
+        // With period 1, the letter `a` is encrypted under each rotation in turn.
+        let msg = Message::new("aaaa").unwrap();
+        let ciphertxt = AlbertiCipher::new()
+            .encrypt(&msg, &reversed_key(1))
+            .unwrap();
+        assert_eq!(ciphertxt.to_string(), "ZYXW");
+
+        let ciphertxt = AlbertiCipher::new()
+            .encrypt(&msg, &reversed_key(2))
+            .unwrap();
+        assert_eq!(ciphertxt.to_string(), "ZZYY");
+        assert_eq!(
+            AlbertiCipher::new()
+                .decrypt(&ciphertxt, &reversed_key(2))
+                .unwrap(),
+            msg
+        );
+    }
+
+    #[test]
+    fn enc_dec_random_keys() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let msg = Message::new("the cipher disk of alberti turns as we write").unwrap();
+
+        for _ in 0..20 {
+            let key = Key::new(&mut rng);
+            assert!((1..=26).contains(&key.period()));
+            let ciphertxt = AlbertiCipher::new().encrypt(&msg, &key).unwrap();
+            assert_eq!(AlbertiCipher::new().decrypt(&ciphertxt, &key).unwrap(), msg);
+        }
+    }
+
+    #[test]
+    fn stream() {
+        let key = reversed_key(3);
+        let msg = Message::new("wewillmeetatmidnight").unwrap();
+        let ciphertxt = AlbertiCipher::new()
+            .encrypt(&msg, &key)
+            .unwrap()
+            .to_string();
+
+        let streamed: String =
+            AlbertiCipher::encrypt_reader("we will\nmeet at midnight\n".as_bytes(), &key)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(streamed, ciphertxt);
+
+        // Invalid characters are reported without rotating the disk
+        let results: Vec<_> = AlbertiCipher::decrypt_stream(
+            ciphertxt
+                .chars()
+                .take(4)
+                .chain(['!'])
+                .chain(ciphertxt.chars().skip(4)),
+            &key,
+        )
+        .collect();
+        assert!(results[4].is_err());
+        let decrypted: String = results.into_iter().filter_map(Result::ok).collect();
+        assert_eq!(decrypted, msg.to_string());
+    }
+
+    #[test]
+    fn key_import() {
+        let key = reversed_key(3);
+        assert_eq!(key.export(), "zyxwvutsrqponmlkjihgfedcba 0 3");
+        assert_eq!(Key::try_import(&key.export()).unwrap(), key);
+
+        for s in [
+            "zyxwvutsrqponmlkjihgfedcba 0",
+            "zyxwvutsrqponmlkjihgfedcba 26 3",
+            "zyxwvutsrqponmlkjihgfedcba 0 0",
+            "zyxwvutsrqponmlkjihgfedcbb 0 3",
+            "zyxwvutsrqponmlkjihgfedcb 0 3",
+        ] {
+            assert_eq!(
+                Key::try_import(s),
+                Err(EncodingError::InvalidKey(s.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn key_errors() {
+        let inner = CustomAlphabet::from_chars(&('a'..='z').rev().collect::<Vec<_>>()).unwrap();
+        let disk = CipherDisk::latin(inner.clone()).unwrap();
+        assert!(Key::from_disk(disk, 0).is_err());
+
+        // The outer ring must be the Latin Alphabet in order
+        let disk = CipherDisk::new(inner.clone(), inner).unwrap();
+        assert!(Key::from_disk(disk, 1).is_err());
+
+        let inner = CustomAlphabet::from_chars(&('A'..='Z').collect::<Vec<_>>()).unwrap();
+        let disk = CipherDisk::latin(inner).unwrap();
+        assert!(Key::from_disk(disk, 1).is_err());
+    }
+}
@@ -0,0 +1,517 @@
+//! Alphabets, i.e., the sets of characters that messages and ciphertexts are
+//! written in.
+//!
+//! Classical ciphers operate on an encoding of the characters of an alphabet
+//! as integers: the _i_-th character of an alphabet with _m_ characters is
+//! encoded as the element _i_ of the ring of integers modulo _m_, denoted by
+//! &#x2124;/_m_&#x2124;. The number of characters is a parameter of the
+//! [`Alphabet`] trait, so that the modulus of the ring is checked at compile
+//! time.
+use crate::errors::AlphabetError;
+use std::{collections::BTreeMap, fmt, hash::Hash};
+
+/// An alphabet, i.e., an ordered set of `M` characters.
+///
+/// Alphabets are types without values, and are used as type parameters to
+/// select the alphabet that the messages and ciphertexts underlying the
+/// ciphers are written in. By default, this is the [`Latin`] Alphabet. The
+/// characters are encoded as elements of the ring &#x2124;/`M`&#x2124;, whose
+/// arithmetic is shared by all alphabets with the same number of characters.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::alphabet::{Alphabet, Latin};
+/// assert_eq!(Latin::index_of('c'), Some(2));
+/// assert_eq!(Latin::char_at(25), Some('z'));
+///
+/// // The number of characters is part of the type
+/// fn size<A: Alphabet<M>, const M: u16>() -> u16 {
+///     M
+/// }
+/// assert_eq!(size::<Latin, 26>(), 26);
+/// ```
+pub trait Alphabet<const M: u16>:
+    Copy + Clone + fmt::Debug + Default + Eq + Hash + Ord + Send + Sync + 'static
+{
+    /// Whether ciphertexts are written in uppercase, following Stinson's
+    /// convention that plaintexts are written in lowercase and ciphertexts in
+    /// uppercase. If true, ciphertexts are also parsed case-insensitively.
+    ///
+    /// This convention only makes sense for alphabets without uppercase
+    /// letters: in an alphabet such as [`PrintableAscii`], `a` and `A` are
+    /// different characters, so ciphertexts are written as they are.
+    const UPPERCASE_CIPHERTEXT: bool = false;
+
+    /// Get the index of a character, if it is in the alphabet.
+    fn index_of(symbol: char) -> Option<usize>;
+
+    /// Get the character at an index, if the index is less than `M`.
+    fn char_at(index: usize) -> Option<char>;
+}
+
+/// The (lowercase) Latin Alphabet, `a` to `z`.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Latin;
+
+impl Alphabet<26> for Latin {
+    const UPPERCASE_CIPHERTEXT: bool = true;
+
+    fn index_of(symbol: char) -> Option<usize> {
+        symbol
+            .is_ascii_lowercase()
+            .then(|| usize::from(symbol as u8 - b'a'))
+    }
+
+    fn char_at(index: usize) -> Option<char> {
+        (index < 26).then(|| char::from(b'a' + index as u8))
+    }
+}
+
+/// The classical Latin Alphabet of 23 letters, i.e., the modern (lowercase)
+/// Latin Alphabet without `j`, `u` and `w`, as used by the Romans and, e.g.,
+/// in historical demonstrations of the Caesar Cipher.
+///
+/// Modern text can be written in this alphabet by folding `j` into `i`, `u`
+/// into `v` and `w` into `vv`, see
+/// [`Folding::ClassicalLatin`](crate::policy::Folding::ClassicalLatin).
+/// Following the convention of the [`Latin`] Alphabet, ciphertexts are written
+/// in uppercase.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::alphabet::{Alphabet, ClassicalLatin};
+/// assert_eq!(ClassicalLatin::index_of('k'), Some(9));
+/// assert_eq!(ClassicalLatin::index_of('j'), None);
+/// assert_eq!(ClassicalLatin::char_at(22), Some('z'));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ClassicalLatin;
+
+impl ClassicalLatin {
+    // The letters of the alphabet, in order.
+    const LETTERS: &'static str = "abcdefghiklmnopqrstvxyz";
+}
+
+impl Alphabet<23> for ClassicalLatin {
+    const UPPERCASE_CIPHERTEXT: bool = true;
+
+    fn index_of(symbol: char) -> Option<usize> {
+        // All letters are ASCII, so byte and character indices agree
+        Self::LETTERS.find(symbol)
+    }
+
+    fn char_at(index: usize) -> Option<char> {
+        Self::LETTERS.as_bytes().get(index).map(|&b| char::from(b))
+    }
+}
+
+/// The (lowercase) Latin Alphabet followed by the decimal digits, i.e., `a`
+/// to `z` and then `0` to `9`, so that messages containing numbers can be
+/// encrypted without spelling them out, e.g., with the
+/// [`AlphabetShiftCipher`](crate::shift::AlphabetShiftCipher).
+///
+/// Following the convention of the [`Latin`] Alphabet, ciphertexts are written
+/// in uppercase. Digits have no case, so they are written as they are in both
+/// messages and ciphertexts, and e.g. the ciphertext `"X7K2"` may also be
+/// written `"x7k2"`.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::alphabet::{Alphabet, Alphanumeric};
+/// assert_eq!(Alphanumeric::index_of('z'), Some(25));
+/// assert_eq!(Alphanumeric::index_of('0'), Some(26));
+/// assert_eq!(Alphanumeric::char_at(35), Some('9'));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Alphanumeric;
+
+impl Alphabet<36> for Alphanumeric {
+    const UPPERCASE_CIPHERTEXT: bool = true;
+
+    fn index_of(symbol: char) -> Option<usize> {
+        Latin::index_of(symbol).or_else(|| {
+            symbol
+                .is_ascii_digit()
+                .then(|| usize::from(symbol as u8 - b'0') + 26)
+        })
+    }
+
+    fn char_at(index: usize) -> Option<char> {
+        Latin::char_at(index).or_else(|| {
+            (26..36)
+                .contains(&index)
+                .then(|| char::from(b'0' + (index - 26) as u8))
+        })
+    }
+}
+
+/// The 95 printable ASCII characters, i.e., the space `' '` followed by the
+/// characters from `'!'` to `'~'` in ASCII order, so that sentences with
+/// uppercase letters, digits, spaces and punctuation can be encrypted as they
+/// are, e.g., with the [`AlphabetShiftCipher`](crate::shift::AlphabetShiftCipher).
+///
+/// Since uppercase and lowercase letters are different characters of this
+/// alphabet, ciphertexts are _not_ written in uppercase (see
+/// [`Alphabet::UPPERCASE_CIPHERTEXT`]), and spaces are encoded like any other
+/// character rather than being skipped.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::alphabet::{Alphabet, PrintableAscii};
+/// assert_eq!(PrintableAscii::index_of(' '), Some(0));
+/// assert_eq!(PrintableAscii::index_of('A'), Some(33));
+/// assert_eq!(PrintableAscii::char_at(94), Some('~'));
+/// assert_eq!(PrintableAscii::index_of('\n'), None);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PrintableAscii;
+
+impl Alphabet<95> for PrintableAscii {
+    fn index_of(symbol: char) -> Option<usize> {
+        (' '..='~')
+            .contains(&symbol)
+            .then(|| usize::from(symbol as u8 - b' '))
+    }
+
+    fn char_at(index: usize) -> Option<char> {
+        (index < 95).then(|| char::from(b' ' + index as u8))
+    }
+}
+
+/// The 256 byte values, for encrypting binary data, e.g., with a stream
+/// cipher that adds (or XORs) a key stream to the bytes of a file.
+///
+/// The index of a character is its byte value. To write bytes as characters,
+/// byte `b` is mapped to the Unicode code point `U+00b`, i.e., the bytes are
+/// read as Latin-1 (ISO-8859-1) text, so that ASCII text is written as it is.
+/// Ciphertexts are written as they are.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::alphabet::{Alphabet, Byte};
+/// assert_eq!(Byte::index_of('A'), Some(0x41));
+/// assert_eq!(Byte::char_at(0xe9), Some('é'));
+/// assert_eq!(Byte::index_of('€'), None);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Byte;
+
+impl Alphabet<256> for Byte {
+    fn index_of(symbol: char) -> Option<usize> {
+        u8::try_from(symbol).ok().map(usize::from)
+    }
+
+    fn char_at(index: usize) -> Option<char> {
+        u8::try_from(index).ok().map(char::from)
+    }
+}
+
+/// An alphabet defined at runtime from a list of characters, e.g., a set of
+/// emoji or the DNA bases `ACGT`.
+///
+/// The [`Alphabet`] trait describes alphabets that are fixed at compile time,
+/// so that the modulus of the ring is checked by the compiler. A custom
+/// alphabet instead holds its characters as a value, and encodes text as the
+/// indices of its characters, i.e., as integers modulo [`size`](Self::size).
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::alphabet::CustomAlphabet;
+/// let dna = CustomAlphabet::from_chars(&['A', 'C', 'G', 'T']).unwrap();
+///
+/// assert_eq!(dna.size(), 4);
+/// assert_eq!(dna.encode("GATTACA"), Ok(vec![2, 0, 3, 3, 0, 1, 0]));
+/// assert_eq!(dna.decode(&[3, 0, 2]), Some("TAG".to_string()));
+///
+/// // Characters must be unique.
+/// assert!(CustomAlphabet::from_chars(&['A', 'C', 'A']).is_err());
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CustomAlphabet {
+    chars: Vec<char>,
+    indices: BTreeMap<char, usize>,
+}
+
+impl CustomAlphabet {
+    /// The maximum number of characters of an alphabet, which is the largest
+    /// modulus supported by the ring arithmetic.
+    pub const MAX_SIZE: usize = u16::MAX as usize;
+
+    /// Create an alphabet whose _i_-th character is `chars[i]`.
+    ///
+    /// # Errors
+    /// This function returns an error if `chars` is empty, has more than
+    /// [`CustomAlphabet::MAX_SIZE`] characters, or contains a character more
+    /// than once.
+    pub fn from_chars(chars: &[char]) -> Result<Self, AlphabetError> {
+        if chars.is_empty() {
+            return Err(AlphabetError::Empty);
+        }
+        if chars.len() > Self::MAX_SIZE {
+            return Err(AlphabetError::TooLarge {
+                size: chars.len(),
+                max: Self::MAX_SIZE,
+            });
+        }
+
+        let mut indices = BTreeMap::new();
+        for (i, &c) in chars.iter().enumerate() {
+            if indices.insert(c, i).is_some() {
+                return Err(AlphabetError::Repeated(c));
+            }
+        }
+
+        Ok(CustomAlphabet {
+            chars: chars.to_vec(),
+            indices,
+        })
+    }
+
+    /// Create a custom alphabet with the same characters as the alphabet `A`.
+    ///
+    /// # Panics
+    /// This function panics if the definition of `A` is inconsistent, i.e.,
+    /// if it does not have `M` distinct characters.
+    pub fn from_alphabet<A: Alphabet<M>, const M: u16>() -> Self {
+        let chars: Vec<char> = (0..usize::from(M))
+            .map(|i| A::char_at(i).expect("The alphabet must have M characters"))
+            .collect();
+
+        Self::from_chars(&chars).expect("The characters of the alphabet must be unique")
+    }
+
+    /// Create a keyword-mixed version of the alphabet, as used by many
+    /// classical ciphers to derive a permutation of the alphabet from an
+    /// easy to remember keyword.
+    ///
+    /// The mixed alphabet starts with the characters of the keyword, skipping
+    /// repeated characters, followed by the remaining characters of the
+    /// alphabet in order. Spaces in the keyword are ignored unless they are
+    /// in the alphabet.
+    ///
+    /// # Errors
+    /// This method returns an error if the keyword contains a character that
+    /// is not in the alphabet.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::alphabet::{CustomAlphabet, Latin};
+    /// let latin = CustomAlphabet::from_alphabet::<Latin, 26>();
+    /// let mixed = latin.keyword_mixed("zebras").unwrap();
+    ///
+    /// assert_eq!(mixed.to_string(), "zebrascdfghijklmnopqtuvwxy");
+    /// ```
+    pub fn keyword_mixed(&self, keyword: &str) -> Result<CustomAlphabet, AlphabetError> {
+        let keyword = keyword
+            .chars()
+            .filter(|&c| c != ' ' || self.indices.contains_key(&' '))
+            .map(|c| {
+                self.index_of(c)
+                    .map(|_| c)
+                    .ok_or(AlphabetError::NotInAlphabet(c))
+            })
+            .collect::<Result<Vec<char>, _>>()?;
+
+        CustomAlphabet::from_chars(&keyword_mixed(keyword, self.chars.iter().copied()))
+    }
+
+    /// Get the number of characters in the alphabet.
+    pub fn size(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Get the characters of the alphabet, in order.
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Get the index of a character, if it is in the alphabet.
+    pub fn index_of(&self, symbol: char) -> Option<usize> {
+        self.indices.get(&symbol).copied()
+    }
+
+    /// Get the character at an index, if the index is less than
+    /// [`size`](Self::size).
+    pub fn char_at(&self, index: usize) -> Option<char> {
+        self.chars.get(index).copied()
+    }
+
+    /// Encode a string as the indices of its characters.
+    ///
+    /// # Errors
+    /// This method returns an error for the first character that is not in
+    /// the alphabet.
+    pub fn encode(&self, text: &str) -> Result<Vec<usize>, AlphabetError> {
+        text.chars()
+            .map(|c| self.index_of(c).ok_or(AlphabetError::NotInAlphabet(c)))
+            .collect()
+    }
+
+    /// Decode indices as a string, or return `None` if some index is not less
+    /// than [`size`](Self::size).
+    pub fn decode(&self, indices: &[usize]) -> Option<String> {
+        indices.iter().map(|&i| self.char_at(i)).collect()
+    }
+}
+
+/// Display the characters of the alphabet, in order.
+impl fmt::Display for CustomAlphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chars.iter().try_for_each(|c| write!(f, "{c}"))
+    }
+}
+
+/// Mix an alphabet with a keyword: the symbols of the keyword, skipping
+/// repeated symbols, followed by the remaining symbols of the alphabet in order.
+///
+/// This is shared by the ciphers that derive a permutation of the alphabet
+/// from a keyword, such as the Playfair Cipher.
+pub(crate) fn keyword_mixed<T: Copy + PartialEq>(
+    keyword: impl IntoIterator<Item = T>,
+    alphabet: impl IntoIterator<Item = T>,
+) -> Vec<T> {
+    let mut mixed = Vec::new();
+    for symbol in keyword.into_iter().chain(alphabet) {
+        if !mixed.contains(&symbol) {
+            mixed.push(symbol);
+        }
+    }
+    mixed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latin() {
+        for (i, c) in ('a'..='z').enumerate() {
+            assert_eq!(Latin::index_of(c), Some(i));
+            assert_eq!(Latin::char_at(i), Some(c));
+        }
+
+        assert_eq!(Latin::index_of('A'), None);
+        assert_eq!(Latin::index_of(' '), None);
+        assert_eq!(Latin::index_of('é'), None);
+        assert_eq!(Latin::char_at(26), None);
+    }
+
+    #[test]
+    fn alphanumeric() {
+        for (i, c) in ('a'..='z').chain('0'..='9').enumerate() {
+            assert_eq!(Alphanumeric::index_of(c), Some(i));
+            assert_eq!(Alphanumeric::char_at(i), Some(c));
+        }
+
+        assert_eq!(Alphanumeric::index_of('A'), None);
+        assert_eq!(Alphanumeric::index_of('_'), None);
+        assert_eq!(Alphanumeric::char_at(36), None);
+    }
+
+    #[test]
+    fn printable_ascii() {
+        for (i, c) in (' '..='~').enumerate() {
+            assert_eq!(PrintableAscii::index_of(c), Some(i));
+            assert_eq!(PrintableAscii::char_at(i), Some(c));
+        }
+
+        assert_eq!(PrintableAscii::index_of('\t'), None);
+        assert_eq!(PrintableAscii::index_of('\u{7f}'), None);
+        assert_eq!(PrintableAscii::index_of('é'), None);
+        assert_eq!(PrintableAscii::char_at(95), None);
+    }
+
+    #[test]
+    fn custom() {
+        let emoji = CustomAlphabet::from_chars(&['🐱', '🐶', '🐭']).unwrap();
+        assert_eq!(emoji.size(), 3);
+        assert_eq!(emoji.index_of('🐭'), Some(2));
+        assert_eq!(emoji.char_at(3), None);
+        assert_eq!(emoji.encode("🐶🐱"), Ok(vec![1, 0]));
+        assert_eq!(emoji.encode("🐶x"), Err(AlphabetError::NotInAlphabet('x')));
+        assert_eq!(emoji.decode(&[2, 2]), Some("🐭🐭".to_string()));
+        assert_eq!(emoji.decode(&[3]), None);
+        assert_eq!(emoji.to_string(), "🐱🐶🐭");
+
+        let latin = CustomAlphabet::from_alphabet::<Latin, 26>();
+        assert_eq!(latin.to_string(), "abcdefghijklmnopqrstuvwxyz");
+        assert_eq!(
+            CustomAlphabet::from_alphabet::<PrintableAscii, 95>().size(),
+            95
+        );
+    }
+
+    #[test]
+    fn custom_errors() {
+        assert_eq!(CustomAlphabet::from_chars(&[]), Err(AlphabetError::Empty));
+        assert_eq!(
+            CustomAlphabet::from_chars(&['a', 'b', 'a', 'b']),
+            Err(AlphabetError::Repeated('a'))
+        );
+
+        let chars: Vec<char> = (0..=CustomAlphabet::MAX_SIZE as u32)
+            .filter_map(|i| char::from_u32(0x10000 + i))
+            .collect();
+        assert_eq!(
+            CustomAlphabet::from_chars(&chars),
+            Err(AlphabetError::TooLarge {
+                size: 65536,
+                max: 65535
+            })
+        );
+        assert!(CustomAlphabet::from_chars(&chars[1..]).is_ok());
+    }
+
+    #[test]
+    fn byte() {
+        for b in 0..=255u8 {
+            let c = Byte::char_at(usize::from(b)).unwrap();
+            assert_eq!(Byte::index_of(c), Some(usize::from(b)));
+        }
+
+        assert_eq!(Byte::char_at(256), None);
+        assert_eq!(Byte::index_of('\u{100}'), None);
+    }
+
+    #[test]
+    fn keyword_mixing() {
+        assert_eq!(
+            keyword_mixed("bab".chars(), "abcd".chars()),
+            ['b', 'a', 'c', 'd']
+        );
+        assert_eq!(keyword_mixed([], [1, 2, 3]), [1, 2, 3]);
+
+        let latin = CustomAlphabet::from_alphabet::<Latin, 26>();
+        assert_eq!(
+            latin.keyword_mixed("playfair example").unwrap().to_string(),
+            "playfirexmbcdghjknoqstuvwz"
+        );
+        assert_eq!(latin.keyword_mixed("").unwrap(), latin);
+        assert_eq!(
+            latin.keyword_mixed("Zebra"),
+            Err(AlphabetError::NotInAlphabet('Z'))
+        );
+
+        // Spaces are kept if they are in the alphabet
+        let ascii = CustomAlphabet::from_alphabet::<PrintableAscii, 95>();
+        assert_eq!(
+            &ascii.keyword_mixed("a b").unwrap().chars()[..3],
+            ['a', ' ', 'b']
+        );
+    }
+
+    #[test]
+    fn classical_latin() {
+        let letters: Vec<char> = ('a'..='z').filter(|c| !"juw".contains(*c)).collect();
+        assert_eq!(letters.len(), 23);
+        for (i, &c) in letters.iter().enumerate() {
+            assert_eq!(ClassicalLatin::index_of(c), Some(i));
+            assert_eq!(ClassicalLatin::char_at(i), Some(c));
+        }
+
+        for c in ['j', 'u', 'w', 'A', ' '] {
+            assert_eq!(ClassicalLatin::index_of(c), None);
+        }
+        assert_eq!(ClassicalLatin::char_at(23), None);
+    }
+}
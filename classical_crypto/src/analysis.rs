@@ -0,0 +1,41 @@
+//! Tools for the cryptanalysis of classical ciphers.
+//!
+//! Classical ciphers tend to preserve the statistical structure of the
+//! underlying language, which is exactly what lets us break them by hand (and,
+//! much faster, by computer). The tools in this module compute those
+//! statistics and compare them against reference values for a language, such
+//! as those in the [`corpus`](crate::corpus) module.
+mod anneal;
+mod crib;
+mod dictionary;
+mod frequency;
+mod ngrams;
+mod patterns;
+mod period;
+mod randomness;
+mod workbench;
+
+pub use anneal::Annealer;
+pub use crib::{place_crib, CribFamily, CribPlacement};
+pub use dictionary::{respace, Dictionary};
+pub use frequency::FrequencyTable;
+pub use ngrams::NgramTable;
+pub use patterns::{pattern, PatternDictionary};
+pub use period::{estimate_period, KeyLengthReport, PeriodMethod};
+pub use randomness::{test_randomness, RandomnessReport};
+pub use workbench::Workbench;
+
+/// The 30 most common bigrams in English text, from most to least common, as
+/// listed in Section 2.2.2 of Stinson's _Cryptography: Theory and Practice_ (4th
+/// Edition).
+pub const ENGLISH_COMMON_BIGRAMS: [&str; 30] = [
+    "th", "he", "in", "er", "an", "re", "ed", "on", "es", "st", "en", "at", "to", "nt", "ha", "nd",
+    "ou", "ea", "ng", "as", "or", "ti", "is", "et", "it", "ar", "te", "se", "hi", "of",
+];
+
+/// The 12 most common trigrams in English text, from most to least common, as
+/// listed in Section 2.2.2 of Stinson's _Cryptography: Theory and Practice_ (4th
+/// Edition).
+pub const ENGLISH_COMMON_TRIGRAMS: [&str; 12] = [
+    "the", "ing", "and", "her", "ere", "ent", "tha", "nth", "was", "eth", "for", "dth",
+];
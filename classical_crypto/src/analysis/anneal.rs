@@ -0,0 +1,232 @@
+//! Simulated annealing.
+use crate::attack::Monitor;
+use rand::Rng;
+
+// How often a monitored search reports progress and checks for cancellation.
+const REPORT_INTERVAL: usize = 1000;
+
+/// A simulated annealing search.
+///
+/// Simulated annealing is a general method for searching huge spaces, such as
+/// the key space of a Playfair Cipher, for a state with a high score, such as
+/// a key that decrypts a ciphertext to something resembling English. Starting
+/// from some state, we repeatedly make a small random change (a _mutation_)
+/// and keep the result if it scores better. To avoid getting stuck on a state
+/// that merely scores better than all of its neighbors, we sometimes keep a
+/// worse state, too: a state whose score is worse by _d_ is kept with
+/// probability _e_<sup>-_d_/_T_</sup>, where the _temperature_ _T_ starts high
+/// and decreases geometrically over the course of the search.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::analysis::Annealer;
+/// # use rand::{rngs::StdRng, Rng, SeedableRng};
+/// // Find the integer closest to 42 by taking random steps.
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let annealer = Annealer::new(1000, 10.0, 0.1);
+///
+/// let (best, score) = annealer.run(
+///     0,
+///     |x: &i32| -f64::from((x - 42).abs()),
+///     |x, rng| x + rng.gen_range(-3..=3),
+///     &mut rng,
+/// );
+///
+/// assert_eq!(best, 42);
+/// assert_eq!(score, 0.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Annealer {
+    iterations: usize,
+    initial_temperature: f64,
+    final_temperature: f64,
+}
+
+impl Annealer {
+    /// Create a search that runs for the given number of iterations, cooling
+    /// from the initial temperature to the final temperature.
+    ///
+    /// Temperatures are on the same scale as the differences between the scores
+    /// of states.
+    ///
+    /// # Panics
+    /// This function panics if either temperature is not positive.
+    pub fn new(iterations: usize, initial_temperature: f64, final_temperature: f64) -> Self {
+        assert!(
+            initial_temperature > 0.0 && final_temperature > 0.0,
+            "Temperatures must be positive"
+        );
+
+        Annealer {
+            iterations,
+            initial_temperature,
+            final_temperature,
+        }
+    }
+
+    /// Get the number of iterations of the search.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Get the temperature at the given iteration.
+    pub fn temperature(&self, iteration: usize) -> f64 {
+        if self.iterations <= 1 {
+            return self.initial_temperature;
+        }
+
+        let progress = iteration as f64 / (self.iterations - 1) as f64;
+        self.initial_temperature
+            * (self.final_temperature / self.initial_temperature).powf(progress)
+    }
+
+    /// Run the search from an initial state, returning the best state found
+    /// together with its score.
+    ///
+    /// The caller provides the function used to score states, where higher is
+    /// better, as well as the function used to mutate states.
+    pub fn run<S, R, F, M>(&self, initial: S, score: F, mutate: M, rng: &mut R) -> (S, f64)
+    where
+        S: Clone,
+        R: Rng + ?Sized,
+        F: FnMut(&S) -> f64,
+        M: FnMut(&S, &mut R) -> S,
+    {
+        self.run_monitored(initial, score, mutate, rng, &Monitor::default())
+    }
+
+    /// Run the search like [`Annealer::run`], reporting progress to and
+    /// checking for cancellation with the given monitor.
+    ///
+    /// A cancelled search returns the best state found so far.
+    pub fn run_monitored<S, R, F, M>(
+        &self,
+        initial: S,
+        mut score: F,
+        mut mutate: M,
+        rng: &mut R,
+        monitor: &Monitor,
+    ) -> (S, f64)
+    where
+        S: Clone,
+        R: Rng + ?Sized,
+        F: FnMut(&S) -> f64,
+        M: FnMut(&S, &mut R) -> S,
+    {
+        let monitor = monitor.begin(self.iterations);
+        let mut current_score = score(&initial);
+        let mut current = initial;
+        let mut best = (current.clone(), current_score);
+
+        for iteration in 0..self.iterations {
+            if iteration % REPORT_INTERVAL == 0 {
+                if monitor.is_cancelled() {
+                    return best;
+                }
+                monitor.report(iteration, best.1);
+            }
+
+            let candidate = mutate(&current, rng);
+            let candidate_score = score(&candidate);
+
+            let delta = candidate_score - current_score;
+            if delta >= 0.0 || rng.gen::<f64>() < (delta / self.temperature(iteration)).exp() {
+                current = candidate;
+                current_score = candidate_score;
+
+                if current_score > best.1 {
+                    best = (current.clone(), current_score);
+                }
+            }
+        }
+        monitor.report(self.iterations, best.1);
+
+        best
+    }
+}
+
+impl Default for Annealer {
+    /// A search suitable for scores computed by [`NgramModel`] on a few hundred
+    /// letters of text.
+    ///
+    /// [`NgramModel`]: crate::corpus::NgramModel
+    fn default() -> Self {
+        Annealer::new(20_000, 20.0, 0.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn temperature_schedule() {
+        let annealer = Annealer::new(3, 10.0, 0.1);
+
+        assert_eq!(annealer.iterations(), 3);
+        assert!((annealer.temperature(0) - 10.0).abs() < 1e-12);
+        assert!((annealer.temperature(1) - 1.0).abs() < 1e-12);
+        assert!((annealer.temperature(2) - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "Temperatures must be positive")]
+    fn nonpositive_temperature() {
+        let _ = Annealer::new(3, 10.0, 0.0);
+    }
+
+    #[test]
+    fn escapes_local_maximum() {
+        // The score has a local maximum at 0 and a global maximum at 10, and
+        // states can only move one step at a time, so we must accept some
+        // worse states to find the global maximum.
+        let score = |x: &i32| match x {
+            x if *x <= 0 => f64::from(*x),
+            x if *x <= 10 => f64::from(*x - 5),
+            x => f64::from(10 - *x),
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let (best, best_score) = Annealer::new(5000, 5.0, 0.05).run(
+            -5,
+            score,
+            |x, rng| x + if rng.gen() { 1 } else { -1 },
+            &mut rng,
+        );
+        assert_eq!((best, best_score), (10, 5.0));
+    }
+
+    #[test]
+    fn no_iterations() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (best, score) =
+            Annealer::new(0, 1.0, 1.0).run(3, |x: &i32| f64::from(*x), |x, _| x + 1, &mut rng);
+        assert_eq!((best, score), (3, 3.0));
+    }
+
+    #[test]
+    fn monitored() {
+        use crate::attack::{CancellationToken, Progress};
+        use std::sync::{Arc, Mutex};
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let token = CancellationToken::new();
+        let monitor = Monitor::default()
+            .with_progress(move |p: Progress| sink.lock().unwrap().push(p.iterations()))
+            .with_cancellation(token.clone());
+        let mut rng = StdRng::seed_from_u64(0);
+        let annealer = Annealer::new(2500, 1.0, 1.0);
+
+        let _ =
+            annealer.run_monitored(0, |x: &i32| f64::from(*x), |x, _| x + 1, &mut rng, &monitor);
+        assert_eq!(*reports.lock().unwrap(), vec![0, 1000, 2000, 2500]);
+
+        // A cancelled search stops before mutating
+        token.cancel();
+        let (best, _) =
+            annealer.run_monitored(0, |x: &i32| f64::from(*x), |x, _| x + 1, &mut rng, &monitor);
+        assert_eq!(best, 0);
+    }
+}
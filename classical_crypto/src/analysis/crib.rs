@@ -0,0 +1,187 @@
+//! Placing known or guessed plaintext (cribs) along a ciphertext.
+
+/// A family of ciphers for which a crib can be placed, see [`place_crib`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum CribFamily {
+    /// The Shift Cipher: every letter is shifted by the same amount.
+    Shift,
+    /// The Vigen&egrave;re Cipher with the given period (keyword length): the
+    /// letters are shifted by the letters of a repeating keyword.
+    Vigenere {
+        /// The length of the keyword.
+        period: usize,
+    },
+}
+
+impl CribFamily {
+    /// Get the number of letters after which the shifts repeat. This is 1 for
+    /// the Shift Cipher.
+    pub fn period(&self) -> usize {
+        match self {
+            CribFamily::Shift => 1,
+            CribFamily::Vigenere { period } => *period,
+        }
+    }
+}
+
+/// A position at which a crib is consistent with a cipher family.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CribPlacement {
+    position: usize,
+    key: String,
+}
+
+impl CribPlacement {
+    /// Get the position of the crib, counted in letters of the ciphertext.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the (partial) key implied by the placement.
+    ///
+    /// The key has one letter per position of the keyword, where `a` is a
+    /// shift by 0, `b` a shift by 1, and so on. Keyword positions not covered
+    /// by the crib are shown as `?`. For the Shift Cipher, the key is a single
+    /// letter; e.g., `l` is the key 11.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+/// Slide a crib, i.e., a fragment of plaintext that is known or guessed to
+/// occur in the message, along a ciphertext, and return the positions at which
+/// it is consistent with the given cipher family, together with the key
+/// implied by each position.
+///
+/// At each position, the crib and the ciphertext determine the shift of every
+/// letter covered by the crib. The position is consistent if the shifts agree
+/// wherever the family requires them to: everywhere for the Shift Cipher, and
+/// at letters whose distance is a multiple of the period for the
+/// Vigen&egrave;re Cipher. Letters of the ciphertext and the crib are read
+/// case-insensitively, and all other characters are ignored.
+///
+/// # Panics
+/// This function panics if the period of a Vigen&egrave;re family is 0.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::analysis::{place_crib, CribFamily};
+/// // Example 1.1, Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
+/// let placements = place_crib("HPHTWWXPPELEXTOYTRSE", "meet", CribFamily::Shift);
+///
+/// assert_eq!(placements.len(), 1);
+/// assert_eq!(placements[0].position(), 6);
+/// assert_eq!(placements[0].key(), "l");
+/// ```
+pub fn place_crib(ciphertext: &str, crib: &str, family: CribFamily) -> Vec<CribPlacement> {
+    let period = family.period();
+    assert!(period > 0, "The period must be positive");
+
+    let ciphertext = shifts(ciphertext);
+    let crib = shifts(crib);
+    if crib.len() > ciphertext.len() {
+        return Vec::new();
+    }
+
+    (0..=ciphertext.len() - crib.len())
+        .filter_map(|position| {
+            let mut key = vec![None; period];
+            for (i, &m) in crib.iter().enumerate() {
+                let k = (ciphertext[position + i] + 26 - m) % 26;
+                let slot = &mut key[(position + i) % period];
+                match slot {
+                    Some(other) if *other != k => return None,
+                    _ => *slot = Some(k),
+                }
+            }
+
+            let key = key
+                .into_iter()
+                .map(|k| k.map_or('?', |k| char::from(b'a' + k)))
+                .collect();
+            Some(CribPlacement { position, key })
+        })
+        .collect()
+}
+
+// Convert the letters of the Latin Alphabet in `text` to numbers from 0 to 25.
+fn shifts(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_lowercase() - b'a')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shift() {
+        let placements = place_crib("HPHTWWXPPELEXTOYTRSE", "midnight", CribFamily::Shift);
+        assert_eq!(
+            placements,
+            vec![CribPlacement {
+                position: 12,
+                key: "l".to_string()
+            }]
+        );
+
+        // A single letter fits everywhere
+        assert_eq!(place_crib("HPHT", "w", CribFamily::Shift).len(), 4);
+        // Repeated letters must line up with repeated letters
+        assert!(place_crib("ABCD", "ee", CribFamily::Shift).is_empty());
+    }
+
+    #[test]
+    fn vigenere() {
+        // Example 1.4, Stinson 3rd Edition, Example 2.3 Stinson 4th Edition
+        let ciphertext = "VPXZGIAXIVWPUBTTMJPWIZITWZT";
+
+        // A crib no longer than the period fits everywhere
+        let placements = place_crib(ciphertext, "secure", CribFamily::Vigenere { period: 6 });
+        assert_eq!(placements.len(), 22);
+        assert_eq!(placements[21].position(), 21);
+        assert_eq!(placements[21].key(), "cipher");
+
+        let placements = place_crib(ciphertext, "not", CribFamily::Vigenere { period: 6 });
+        assert_eq!(placements[18].key(), "cip???");
+
+        // Longer cribs wrap around the keyword and must be consistent
+        let placements = place_crib(
+            ciphertext,
+            "cryptosystem",
+            CribFamily::Vigenere { period: 6 },
+        );
+        assert_eq!(
+            placements,
+            vec![CribPlacement {
+                position: 4,
+                key: "cipher".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn period_one_is_shift() {
+        assert_eq!(
+            place_crib("HPHTWWXPPELEXTOYTRSE", "meet", CribFamily::Shift),
+            place_crib(
+                "HPHTWWXPPELEXTOYTRSE",
+                "meet",
+                CribFamily::Vigenere { period: 1 }
+            )
+        );
+    }
+
+    #[test]
+    fn crib_too_long() {
+        assert!(place_crib("HPHT", "wewillmeet", CribFamily::Shift).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The period must be positive")]
+    fn zero_period() {
+        let _ = place_crib("HPHT", "we", CribFamily::Vigenere { period: 0 });
+    }
+}
@@ -0,0 +1,241 @@
+//! Word frequencies, and the re-spacing of texts written without spaces.
+use crate::corpus::ENGLISH_SAMPLE;
+use std::{collections::HashMap, fmt};
+
+/// A dictionary of words together with how often each one occurs, used by
+/// [`respace`] to find the most likely words of a text written without
+/// spaces.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::analysis::Dictionary;
+/// let dictionary = Dictionary::from_text("The cat sat on the mat.");
+///
+/// assert_eq!(dictionary.count("the"), 2);
+/// assert_eq!(dictionary.count("dog"), 0);
+/// assert_eq!(dictionary.len(), 5);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Dictionary {
+    counts: HashMap<String, usize>,
+    total: usize,
+    longest: usize,
+}
+
+impl Dictionary {
+    /// Create a dictionary containing the given words.
+    ///
+    /// Words are stored in lowercase, and a repeated word is counted once for
+    /// each time it is given, so that it is more likely.
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut dictionary = Dictionary::default();
+        for word in words {
+            dictionary.add(word.as_ref());
+        }
+        dictionary
+    }
+
+    /// Create a dictionary containing the words of a text, i.e., its runs of
+    /// letters and apostrophes, counted as often as they occur. Apostrophes
+    /// are dropped, since messages cannot contain them, e.g., "don't" is
+    /// stored as "dont".
+    pub fn from_text(text: &str) -> Self {
+        Dictionary::new(
+            text.split(|c: char| !(c.is_alphabetic() || c == '\''))
+                .map(|word| word.replace('\'', ""))
+                .filter(|word| !word.is_empty()),
+        )
+    }
+
+    /// Create a dictionary containing the words of the
+    /// [`ENGLISH_SAMPLE`](crate::corpus::ENGLISH_SAMPLE). The sample is short,
+    /// so the dictionary lacks many common words: build a dictionary from a
+    /// larger corpus for serious use.
+    pub fn english() -> Self {
+        Dictionary::from_text(ENGLISH_SAMPLE)
+    }
+
+    /// Add one occurrence of a word to the dictionary.
+    pub fn add(&mut self, word: &str) {
+        let word = word.to_lowercase();
+        self.longest = self.longest.max(word.chars().count());
+        self.total += 1;
+        *self.counts.entry(word).or_default() += 1;
+    }
+
+    /// Get the number of times a word occurs in the dictionary, ignoring case.
+    pub fn count(&self, word: &str) -> usize {
+        self.counts
+            .get(&word.to_lowercase())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the number of distinct words in the dictionary.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns true if the dictionary contains no words and false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    // Get the log-probability of a word, where a word that is not in the dictionary gets a
+    // probability that shrinks tenfold with each letter, so that unknown words are kept short
+    // but a run of unknown letters is not split into single letters.
+    fn log_probability(&self, word: &str) -> f64 {
+        match self.count(word) {
+            0 => self.unknown_log_probability(word.chars().count()),
+            count => (count as f64 / self.total as f64).ln(),
+        }
+    }
+
+    // Get the log-probability of a word of `length` letters that is not in the dictionary.
+    fn unknown_log_probability(&self, length: usize) -> f64 {
+        (10.0 / self.total as f64).ln() - length as f64 * 10_f64.ln()
+    }
+
+    // Split a word written without spaces into its most likely words, by dynamic programming
+    // over the positions of the word. A piece longer than the longest word of the dictionary is
+    // unknown, so its log-probability only depends on its length, and the best such piece ending
+    // at each position is tracked as we go, so that a long run of unknown letters is kept
+    // together without trying every start.
+    fn split<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        let boundaries: Vec<usize> = word
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([word.len()])
+            .collect();
+
+        // `best[i]` is the score of the best split of the first `i` characters, and the start of
+        // its last word
+        let mut best = vec![(0.0, 0); boundaries.len()];
+        let window = self.longest.max(1);
+        // The best start of a piece longer than the window, scored up to the length of the piece
+        let mut far = (f64::NEG_INFINITY, 0);
+        for end in 1..boundaries.len() {
+            if let Some(start) = end.checked_sub(window + 1) {
+                let score = best[start].0 + start as f64 * 10_f64.ln();
+                if score > far.0 {
+                    far = (score, start);
+                }
+            }
+
+            best[end] = (end.saturating_sub(window)..end)
+                .map(|start| {
+                    let piece = &word[boundaries[start]..boundaries[end]];
+                    (best[start].0 + self.log_probability(piece), start)
+                })
+                .chain([(far.0 + self.unknown_log_probability(end), far.1)])
+                .fold((f64::NEG_INFINITY, 0), |a, b| if b.0 > a.0 { b } else { a });
+        }
+
+        let mut words = Vec::new();
+        let mut end = boundaries.len() - 1;
+        while end > 0 {
+            let start = best[end].1;
+            words.push(&word[boundaries[start]..boundaries[end]]);
+            end = start;
+        }
+        words.reverse();
+        words
+    }
+}
+
+/// Insert spaces between the most likely words of a text written without
+/// spaces, such as a decrypted message, to make it easier to read.
+///
+/// The text is split into the sequence of words that is most likely under
+/// the word frequencies of the dictionary. Letters that do not make up any
+/// word of the dictionary are kept together as unknown words. Spaces already
+/// in the text are kept, and the text is returned unchanged if the dictionary
+/// is empty.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{analysis::{respace, Dictionary}, shift::Message};
+/// let dictionary = Dictionary::from_text("we will meet them at the gate at midnight");
+/// let msg = Message::new("wewillmeetatmidnight").unwrap();
+///
+/// assert_eq!(respace(&msg, &dictionary), "we will meet at midnight");
+/// ```
+pub fn respace<T: fmt::Display + ?Sized>(text: &T, dictionary: &Dictionary) -> String {
+    let text = text.to_string();
+    if dictionary.is_empty() {
+        return text;
+    }
+
+    text.split_whitespace()
+        .flat_map(|word| dictionary.split(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dictionary() {
+        let mut dictionary = Dictionary::new(["That", "that", "this"]);
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.count("THAT"), 2);
+        assert_eq!(dictionary.count("high"), 0);
+
+        dictionary.add("high");
+        assert_eq!(dictionary.count("high"), 1);
+        assert_eq!(Dictionary::from_text("Don't, don't!").count("dont"), 2);
+        assert!(Dictionary::default().is_empty());
+    }
+
+    #[test]
+    fn respacing() {
+        let dictionary = Dictionary::from_text(
+            "this is a choice that we made and an api that is awkward to use",
+        );
+        assert_eq!(
+            respace("thisisanawkwardapichoice", &dictionary),
+            "this is an awkward api choice"
+        );
+
+        // Unknown letters are kept together, and existing spaces are kept
+        assert_eq!(
+            respace("wemadexyzzy thischoice", &dictionary),
+            "we made xyzzy this choice"
+        );
+        assert_eq!(respace("", &dictionary), "");
+
+        // Even a run of unknown letters longer than any word of the dictionary
+        assert_eq!(
+            respace("wemadeqwrtyzxcvbnmplkjhgfd", &dictionary),
+            "we made qwrtyzxcvbnmplkjhgfd"
+        );
+        assert_eq!(
+            respace("xyzzyplughthischoicexyzzyplugh", &dictionary),
+            "xyzzyplugh this choice xyzzyplugh"
+        );
+        assert_eq!(respace("thisis", &Dictionary::default()), "thisis");
+    }
+
+    #[test]
+    fn respacing_prefers_common_words() {
+        // "therein" is a word, but "the rein" is more likely here
+        let dictionary = Dictionary::from_text("the the the the the rein rein rein therein");
+        assert_eq!(respace("therein", &dictionary), "the rein");
+
+        let dictionary = Dictionary::new(["the", "rein", "therein", "therein"]);
+        assert_eq!(respace("therein", &dictionary), "therein");
+    }
+
+    #[test]
+    fn english_dictionary() {
+        let dictionary = Dictionary::english();
+        assert!(dictionary.count("the") > dictionary.count("is"));
+        assert_eq!(respace("thisis", &dictionary), "this is");
+    }
+}
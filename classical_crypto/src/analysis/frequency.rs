@@ -0,0 +1,282 @@
+//! Symbol frequency counts.
+use std::{collections::BTreeMap, fmt};
+
+/// A table of symbol counts for a text.
+///
+/// Counting how often each symbol occurs is the first step in attacking most
+/// classical ciphers: a monoalphabetic cipher such as the Shift Cipher
+/// permutes the symbols of a text but preserves how often each one occurs.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::analysis::FrequencyTable;
+/// let table = FrequencyTable::new("HPHTWWXPPELEXTOYTRSE");
+///
+/// assert_eq!(table.total(), 20);
+/// assert_eq!(table.count('p'), 3);
+/// // The most frequent symbol comes first in the sorted view.
+/// assert_eq!(table.sorted()[0], ('e', 3));
+///
+/// // We can also print the table.
+/// println!("{table}");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FrequencyTable {
+    counts: BTreeMap<char, usize>,
+    total: usize,
+}
+
+impl FrequencyTable {
+    /// Count the letters of a string.
+    ///
+    /// Letters are counted case-insensitively (and are recorded in lowercase),
+    /// while all other characters, such as spaces, digits and punctuation, are
+    /// ignored. Use the [`FromIterator`] implementation to count every
+    /// character exactly as given.
+    pub fn new(text: &str) -> Self {
+        text.chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    /// Get the number of occurrences of a symbol.
+    pub fn count(&self, symbol: char) -> usize {
+        self.counts.get(&symbol).copied().unwrap_or_default()
+    }
+
+    /// Get the total number of symbols counted.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Get the proportion of counted symbols that are equal to `symbol`.
+    ///
+    /// The proportion is 0 for an empty table.
+    pub fn proportion(&self, symbol: char) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.count(symbol) as f64 / self.total as f64
+        }
+    }
+
+    /// Get the proportion of each symbol that occurs in the table, ordered by
+    /// symbol.
+    pub fn proportions(&self) -> Vec<(char, f64)> {
+        self.counts
+            .keys()
+            .map(|&symbol| (symbol, self.proportion(symbol)))
+            .collect()
+    }
+
+    /// Get the symbols that occur in the table together with their counts,
+    /// from most to least frequent.
+    ///
+    /// Symbols that occur equally often are ordered by symbol, so the view is
+    /// deterministic.
+    pub fn sorted(&self) -> Vec<(char, usize)> {
+        let mut sorted: Vec<(char, usize)> = self.counts.iter().map(|(&c, &n)| (c, n)).collect();
+        sorted.sort_by(|(c0, n0), (c1, n1)| n1.cmp(n0).then(c0.cmp(c1)));
+        sorted
+    }
+
+    /// Compute the distance between the observed counts and a reference
+    /// distribution, such as [`ENGLISH_LETTER_PROBABILITIES`].
+    ///
+    /// The distance is Pearson's chi-squared statistic, i.e., the sum over the
+    /// symbols _x_ of the reference of (_o_ - _e_)&sup2; / _e_, where _o_ is the
+    /// observed count of _x_ and _e_ is the count expected from the
+    /// probability of _x_ in the reference. The smaller the distance, the
+    /// better the fit. Symbols that are not in the reference, as well as
+    /// reference symbols with probability 0, do not contribute to the distance.
+    /// An empty table has distance 0 from every reference.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{analysis::FrequencyTable, corpus::ENGLISH_LETTER_PROBABILITIES};
+    /// let english = FrequencyTable::new("wewillmeetatmidnight");
+    /// let gibberish = FrequencyTable::new("qzqzxjxjvkvk");
+    ///
+    /// assert!(
+    ///     english.distance(&ENGLISH_LETTER_PROBABILITIES)
+    ///         < gibberish.distance(&ENGLISH_LETTER_PROBABILITIES)
+    /// );
+    /// ```
+    ///
+    /// [`ENGLISH_LETTER_PROBABILITIES`]: crate::corpus::ENGLISH_LETTER_PROBABILITIES
+    pub fn distance(&self, reference: &[(char, f64)]) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        reference
+            .iter()
+            .filter(|(_, prob)| *prob > 0.0)
+            .map(|&(symbol, prob)| {
+                let expected = prob * self.total as f64;
+                let observed = self.count(symbol) as f64;
+                (observed - expected).powi(2) / expected
+            })
+            .sum()
+    }
+
+    /// Get the _index of coincidence_ of the counted text, i.e., the
+    /// probability that two symbols drawn at random (without replacement) from
+    /// the text are equal.
+    ///
+    /// The index of coincidence does not change when the symbols are permuted,
+    /// so it is the same for a plaintext and its encryption under a
+    /// monoalphabetic cipher. For English text it is about 0.065, while for a
+    /// random string of letters it is about 1/26 &asymp; 0.038 (see the
+    /// cryptanalysis of the Vigen&egrave;re Cipher in Stinson's _Cryptography:
+    /// Theory and Practice_).
+    ///
+    /// The index is 0 if fewer than two symbols were counted.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::analysis::FrequencyTable;
+    /// assert_eq!(FrequencyTable::new("aab").index_of_coincidence(), 1.0 / 3.0);
+    /// ```
+    pub fn index_of_coincidence(&self) -> f64 {
+        if self.total < 2 {
+            return 0.0;
+        }
+
+        let pairs: usize = self.counts.values().map(|&f| f * (f - 1)).sum();
+        pairs as f64 / (self.total * (self.total - 1)) as f64
+    }
+}
+
+impl FromIterator<char> for FrequencyTable {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut table = FrequencyTable::default();
+        for symbol in iter {
+            *table.counts.entry(symbol).or_default() += 1;
+            table.total += 1;
+        }
+        table
+    }
+}
+
+/// Display the table, from most to least frequent symbol.
+impl fmt::Display for FrequencyTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<6} {:>5} {:>10}", "symbol", "count", "proportion")?;
+        for (symbol, count) in self.sorted() {
+            writeln!(
+                f,
+                "{:<6} {:>5} {:>10.3}",
+                symbol,
+                count,
+                self.proportion(symbol)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corpus::ENGLISH_LETTER_PROBABILITIES;
+
+    #[test]
+    fn counts() {
+        let table = FrequencyTable::new("We will meet at midnight!");
+
+        assert_eq!(table.total(), 20);
+        assert_eq!(table.count('w'), 2);
+        assert_eq!(table.count('e'), 3);
+        assert_eq!(table.count('z'), 0);
+        // Non-letters are ignored
+        assert_eq!(table.count(' '), 0);
+        assert_eq!(table.count('!'), 0);
+    }
+
+    #[test]
+    fn counts_from_iter() {
+        let table: FrequencyTable = "a a".chars().collect();
+
+        assert_eq!(table.total(), 3);
+        assert_eq!(table.count('a'), 2);
+        assert_eq!(table.count(' '), 1);
+    }
+
+    #[test]
+    fn proportions() {
+        let table = FrequencyTable::new("aabc");
+
+        assert_eq!(table.proportion('a'), 0.5);
+        assert_eq!(table.proportion('d'), 0.0);
+        assert_eq!(
+            table.proportions(),
+            vec![('a', 0.5), ('b', 0.25), ('c', 0.25)]
+        );
+        assert_eq!(FrequencyTable::default().proportion('a'), 0.0);
+    }
+
+    #[test]
+    fn index_of_coincidence() {
+        assert_eq!(FrequencyTable::new("aaaa").index_of_coincidence(), 1.0);
+        assert_eq!(FrequencyTable::new("abcd").index_of_coincidence(), 0.0);
+        assert_eq!(FrequencyTable::new("a").index_of_coincidence(), 0.0);
+        // Unchanged by encryption with a monoalphabetic cipher
+        assert_eq!(
+            FrequencyTable::new("wewillmeetatmidnight").index_of_coincidence(),
+            FrequencyTable::new("HPHTWWXPPELEXTOYTRSE").index_of_coincidence()
+        );
+    }
+
+    #[test]
+    fn sorted_view() {
+        let table = FrequencyTable::new("cabbcc");
+        assert_eq!(table.sorted(), vec![('c', 3), ('b', 2), ('a', 1)]);
+
+        // Ties are broken by symbol
+        let table = FrequencyTable::new("dcba");
+        assert_eq!(table.sorted(), vec![('a', 1), ('b', 1), ('c', 1), ('d', 1)]);
+    }
+
+    #[test]
+    fn distance() {
+        let table = FrequencyTable::new("aab");
+        let reference = [('a', 0.5), ('b', 0.5)];
+        // Expected count 1.5 for each symbol
+        assert!((table.distance(&reference) - 1.0 / 3.0).abs() < 1e-12);
+
+        // Identical distributions have distance 0
+        let table = FrequencyTable::new("ab");
+        assert_eq!(table.distance(&reference), 0.0);
+
+        // Empty tables have distance 0
+        assert_eq!(
+            FrequencyTable::default().distance(&ENGLISH_LETTER_PROBABILITIES),
+            0.0
+        );
+    }
+
+    #[test]
+    fn distance_prefers_english() {
+        // Example 1.1, Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
+        let plaintext = FrequencyTable::new("wewillmeetatmidnight");
+        let ciphertext = FrequencyTable::new("HPHTWWXPPELEXTOYTRSE");
+
+        assert!(
+            plaintext.distance(&ENGLISH_LETTER_PROBABILITIES)
+                < ciphertext.distance(&ENGLISH_LETTER_PROBABILITIES)
+        );
+    }
+
+    #[test]
+    fn display() {
+        let table = FrequencyTable::new("abb");
+        assert_eq!(
+            table.to_string(),
+            "symbol count proportion\n\
+             b          2      0.667\n\
+             a          1      0.333\n"
+        );
+    }
+}
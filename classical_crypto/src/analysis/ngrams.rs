@@ -0,0 +1,255 @@
+//! Counts of bigrams, trigrams and longer n-grams.
+use std::{collections::BTreeMap, fmt};
+
+/// A table of the n-grams of a text, i.e., its strings of _n_ consecutive
+/// letters.
+///
+/// Single letter counts (see [`FrequencyTable`](super::FrequencyTable)) are
+/// not enough to attack ciphers that encrypt several letters at a time, such
+/// as the Playfair Cipher, and are also not enough to finish off a
+/// Substitution Cipher once the most common letters have been guessed. For
+/// those we look at the most common bigrams (also called digraphs) and
+/// trigrams, and compare them to [`ENGLISH_COMMON_BIGRAMS`] and
+/// [`ENGLISH_COMMON_TRIGRAMS`].
+///
+/// As with [`FrequencyTable::new`](super::FrequencyTable::new), letters are
+/// counted case-insensitively and all other characters are ignored, so n-grams
+/// may span spaces and punctuation.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::analysis::NgramTable;
+/// let bigrams = NgramTable::bigrams("the cat in the hat");
+///
+/// assert_eq!(bigrams.count("th"), 2);
+/// assert_eq!(bigrams.most_common(2), vec![("at".to_string(), 2), ("he".to_string(), 2)]);
+/// ```
+///
+/// [`ENGLISH_COMMON_BIGRAMS`]: super::ENGLISH_COMMON_BIGRAMS
+/// [`ENGLISH_COMMON_TRIGRAMS`]: super::ENGLISH_COMMON_TRIGRAMS
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NgramTable {
+    n: usize,
+    counts: BTreeMap<String, usize>,
+    total: usize,
+}
+
+impl NgramTable {
+    /// Count the (overlapping) n-grams of a string.
+    ///
+    /// # Panics
+    /// This function panics if `n` is 0.
+    pub fn new(text: &str, n: usize) -> Self {
+        assert!(n > 0, "n-grams must have length at least 1");
+
+        let letters = letters(text);
+        NgramTable::from_ngrams(n, letters.windows(n))
+    }
+
+    /// Count the (overlapping) bigrams of a string.
+    pub fn bigrams(text: &str) -> Self {
+        NgramTable::new(text, 2)
+    }
+
+    /// Count the (overlapping) trigrams of a string.
+    pub fn trigrams(text: &str) -> Self {
+        NgramTable::new(text, 3)
+    }
+
+    /// Count the digraphs of a string, i.e., the non-overlapping pairs of
+    /// letters starting from the first letter.
+    ///
+    /// This is how the Playfair Cipher splits a text for encryption, so these
+    /// are the counts to look at when attacking Playfair. A trailing unpaired
+    /// letter is ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::analysis::NgramTable;
+    /// let digraphs = NgramTable::digraphs("abcab");
+    ///
+    /// assert_eq!(digraphs.count("ab"), 1);
+    /// assert_eq!(digraphs.count("ca"), 1);
+    /// // "bc" straddles two digraphs, so is not counted.
+    /// assert_eq!(digraphs.count("bc"), 0);
+    /// ```
+    pub fn digraphs(text: &str) -> Self {
+        let letters = letters(text);
+        NgramTable::from_ngrams(2, letters.chunks_exact(2))
+    }
+
+    fn from_ngrams<'a>(n: usize, ngrams: impl Iterator<Item = &'a [char]>) -> Self {
+        let mut table = NgramTable {
+            n,
+            counts: BTreeMap::new(),
+            total: 0,
+        };
+        for ngram in ngrams {
+            *table.counts.entry(ngram.iter().collect()).or_default() += 1;
+            table.total += 1;
+        }
+        table
+    }
+
+    /// Get the length of the n-grams counted in the table.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Get the number of occurrences of an n-gram.
+    ///
+    /// The n-gram is matched case-insensitively.
+    pub fn count(&self, ngram: &str) -> usize {
+        self.counts
+            .get(&ngram.to_lowercase())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the total number of n-grams counted.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Get the n-grams that occur in the table together with their counts,
+    /// from most to least frequent.
+    ///
+    /// N-grams that occur equally often are ordered alphabetically, so the
+    /// view is deterministic.
+    pub fn sorted(&self) -> Vec<(String, usize)> {
+        let mut sorted: Vec<(String, usize)> = self
+            .counts
+            .iter()
+            .map(|(ngram, &count)| (ngram.clone(), count))
+            .collect();
+        sorted.sort_by(|(g0, n0), (g1, n1)| n1.cmp(n0).then(g0.cmp(g1)));
+        sorted
+    }
+
+    /// Get the `k` most common n-grams together with their counts.
+    pub fn most_common(&self, k: usize) -> Vec<(String, usize)> {
+        let mut sorted = self.sorted();
+        sorted.truncate(k);
+        sorted
+    }
+
+    /// Get the bigrams that occur both as written and reversed, e.g., "ab" and
+    /// "ba", together with the counts of each.
+    ///
+    /// Reversed digraphs are a strong hint when attacking Playfair, since
+    /// Playfair encrypts the digraph "yx" to the reverse of the encryption of
+    /// "xy". Each pair is reported once, with the alphabetically first bigram
+    /// first. For tables that do not count bigrams, the result is empty.
+    pub fn reversals(&self) -> Vec<(String, usize, String, usize)> {
+        if self.n != 2 {
+            return Vec::new();
+        }
+
+        self.counts
+            .iter()
+            .filter_map(|(bigram, &count)| {
+                let reversed: String = bigram.chars().rev().collect();
+                match self.counts.get(&reversed) {
+                    Some(&rev_count) if *bigram < reversed => {
+                        Some((bigram.clone(), count, reversed, rev_count))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Display the table, from most to least frequent n-gram.
+impl fmt::Display for NgramTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.n.max("n-gram".len());
+        writeln!(f, "{:<width$} {:>5}", "n-gram", "count")?;
+        for (ngram, count) in self.sorted() {
+            writeln!(f, "{:<width$} {:>5}", ngram, count)?;
+        }
+        Ok(())
+    }
+}
+
+// Get the letters of a string, in lowercase.
+fn letters(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bigrams() {
+        let table = NgramTable::bigrams("HPHTWWXPPELEXTOYTRSE");
+
+        assert_eq!(table.n(), 2);
+        assert_eq!(table.total(), 19);
+        assert_eq!(table.count("hp"), 1);
+        // Lookups ignore case
+        assert_eq!(table.count("PH"), 1);
+        assert_eq!(table.count("zz"), 0);
+    }
+
+    #[test]
+    fn trigrams() {
+        let table = NgramTable::trigrams("The theme; then THEY left.");
+
+        assert_eq!(table.n(), 3);
+        assert_eq!(table.count("the"), 4);
+        // Trigrams span spaces and punctuation
+        assert_eq!(table.count("eth"), 2);
+        assert_eq!(table.most_common(1), vec![("the".to_string(), 4)]);
+    }
+
+    #[test]
+    fn short_text() {
+        let table = NgramTable::trigrams("ab");
+        assert_eq!(table.total(), 0);
+        assert!(table.sorted().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "n-grams must have length at least 1")]
+    fn zero_length_ngrams() {
+        let _ = NgramTable::new("abc", 0);
+    }
+
+    #[test]
+    fn digraphs() {
+        let table = NgramTable::digraphs("ab cd ab e");
+
+        assert_eq!(table.total(), 3);
+        assert_eq!(table.count("ab"), 2);
+        assert_eq!(table.count("cd"), 1);
+        assert_eq!(table.count("bc"), 0);
+    }
+
+    #[test]
+    fn reversals() {
+        let table = NgramTable::digraphs("abbaabcddc");
+        assert_eq!(
+            table.reversals(),
+            vec![
+                ("ab".to_string(), 2, "ba".to_string(), 1),
+                ("cd".to_string(), 1, "dc".to_string(), 1)
+            ]
+        );
+
+        assert!(NgramTable::trigrams("abccba").reversals().is_empty());
+    }
+
+    #[test]
+    fn display() {
+        let table = NgramTable::bigrams("abab");
+        assert_eq!(
+            table.to_string(),
+            "n-gram count\nab         2\nba         1\n"
+        );
+    }
+}
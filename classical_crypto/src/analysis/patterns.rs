@@ -0,0 +1,166 @@
+//! Letter patterns of words.
+use crate::corpus::ENGLISH_SAMPLE;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Get the letter pattern of a word.
+///
+/// The pattern replaces the first distinct letter of the word by `A`, the
+/// second by `B`, and so on, so two words have the same pattern exactly when
+/// one can be turned into the other by a permutation of the letters. In
+/// particular, a word and its encryption under a monoalphabetic cipher have
+/// the same pattern. Letters are read case-insensitively, and all other
+/// characters, such as apostrophes, are kept as they are.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::analysis::pattern;
+/// assert_eq!(pattern("that"), "ABCA");
+/// assert_eq!(pattern("HPHT"), "ABAC");
+/// assert_eq!(pattern("don't"), "ABC'D");
+/// ```
+pub fn pattern(word: &str) -> String {
+    let mut seen: Vec<char> = Vec::new();
+
+    word.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| {
+            if !c.is_alphabetic() {
+                return c;
+            }
+            let index = seen.iter().position(|&s| s == c).unwrap_or_else(|| {
+                seen.push(c);
+                seen.len() - 1
+            });
+            // Words with more than 26 distinct letters continue past 'Z'
+            char::from_u32(u32::from(b'A') + index as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+        })
+        .collect()
+}
+
+/// A dictionary of words indexed by their letter patterns, used to find the
+/// plaintext words that a ciphertext word might decrypt to.
+///
+/// This is a classic aid for solving cryptograms by hand: a ciphertext word
+/// such as `XQJX` can only decrypt to a word with the same [`pattern`], such
+/// as `that`, and long words or words with repeated letters often have very
+/// few candidates.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::analysis::PatternDictionary;
+/// let dictionary = PatternDictionary::new(["that", "this", "high", "then", "noon"]);
+///
+/// assert_eq!(dictionary.matches("XQJX"), ["high", "that"]);
+/// assert!(dictionary.matches("QQQ").is_empty());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PatternDictionary {
+    words: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl PatternDictionary {
+    /// Create a dictionary containing the given words.
+    ///
+    /// Words are stored in lowercase, and repeated words are stored once.
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut dictionary = PatternDictionary::default();
+        for word in words {
+            dictionary.add(word.as_ref());
+        }
+        dictionary
+    }
+
+    /// Create a dictionary containing the words of a text, i.e., its runs of
+    /// letters and apostrophes.
+    pub fn from_text(text: &str) -> Self {
+        PatternDictionary::new(
+            text.split(|c: char| !(c.is_alphabetic() || c == '\''))
+                .map(|word| word.trim_matches('\''))
+                .filter(|word| !word.is_empty()),
+        )
+    }
+
+    /// Create a dictionary containing the words of the
+    /// [`ENGLISH_SAMPLE`](crate::corpus::ENGLISH_SAMPLE).
+    pub fn english() -> Self {
+        PatternDictionary::from_text(ENGLISH_SAMPLE)
+    }
+
+    /// Add a word to the dictionary.
+    pub fn add(&mut self, word: &str) {
+        let word = word.to_lowercase();
+        let _ = self.words.entry(pattern(&word)).or_default().insert(word);
+    }
+
+    /// Get the number of words in the dictionary.
+    pub fn len(&self) -> usize {
+        self.words.values().map(BTreeSet::len).sum()
+    }
+
+    /// Returns true if the dictionary contains no words and false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Get the words with the same pattern as `word`, in alphabetical order.
+    pub fn matches(&self, word: &str) -> Vec<&str> {
+        self.words
+            .get(&pattern(word))
+            .map(|words| words.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns() {
+        assert_eq!(pattern(""), "");
+        assert_eq!(pattern("a"), "A");
+        assert_eq!(pattern("Letter"), "ABCCBD");
+        assert_eq!(
+            pattern("abcdefghijklmnopqrstuvwxyz"),
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZ"
+        );
+        // Encryption preserves the pattern
+        assert_eq!(
+            pattern("wewillmeetatmidnight"),
+            pattern("HPHTWWXPPELEXTOYTRSE")
+        );
+    }
+
+    #[test]
+    fn dictionary() {
+        let mut dictionary = PatternDictionary::new(["That", "that", "this"]);
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.matches("abca"), ["that"]);
+
+        dictionary.add("high");
+        assert_eq!(dictionary.matches("XQJX"), ["high", "that"]);
+        assert_eq!(dictionary.matches("ABCD"), ["this"]);
+        assert!(dictionary.matches("don't").is_empty());
+
+        assert!(PatternDictionary::default().is_empty());
+    }
+
+    #[test]
+    fn dictionary_from_text() {
+        let dictionary = PatternDictionary::from_text("'Don't,' she said -- that's all.");
+
+        assert_eq!(dictionary.len(), 5);
+        assert_eq!(dictionary.matches("XYZ'Q"), ["don't"]);
+        assert_eq!(dictionary.matches("ABCA'D"), ["that's"]);
+    }
+
+    #[test]
+    fn english_dictionary() {
+        let dictionary = PatternDictionary::english();
+        assert!(dictionary.matches("XQJX").contains(&"that"));
+    }
+}
@@ -0,0 +1,372 @@
+//! Estimating the period (keyword length) of a polyalphabetic cipher.
+use super::FrequencyTable;
+use crate::corpus::ENGLISH_LETTER_PROBABILITIES;
+use std::{collections::BTreeMap, fmt};
+
+// The largest period considered by `estimate_period`.
+const DEFAULT_MAX_PERIOD: usize = 20;
+
+// A period is supported by the Kasiski test if at least this fraction of the distances between
+// repeated trigrams are multiples of it.
+const KASISKI_THRESHOLD: f64 = 0.6;
+
+/// A method for estimating the period of a polyalphabetic cipher.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PeriodMethod {
+    /// The Kasiski test: repeated strings of the ciphertext are often
+    /// encryptions of the same plaintext under the same part of the keyword,
+    /// so the distances between them tend to be multiples of the period.
+    Kasiski,
+    /// The index of coincidence: when the ciphertext is split into columns
+    /// according to the correct period, each column is encrypted with a
+    /// single shift, so its index of coincidence is close to that of the
+    /// language rather than that of random text.
+    IndexOfCoincidence,
+    /// Autocorrelation: comparing the ciphertext with itself shifted by a
+    /// multiple of the period gives more coincidences than other shifts.
+    Autocorrelation,
+}
+
+impl fmt::Display for PeriodMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PeriodMethod::Kasiski => write!(f, "Kasiski test"),
+            PeriodMethod::IndexOfCoincidence => write!(f, "index of coincidence"),
+            PeriodMethod::Autocorrelation => write!(f, "autocorrelation"),
+        }
+    }
+}
+
+/// The results of several methods for estimating the period of a
+/// polyalphabetic cipher, such as the Vigen&egrave;re Cipher, together with a
+/// consensus recommendation. See [`estimate_period`].
+///
+/// The methods are described in the cryptanalysis of the Vigen&egrave;re
+/// Cipher in Stinson's _Cryptography: Theory and Practice_. They all need a
+/// reasonable amount of ciphertext, i.e., many times the period, to give
+/// reliable results.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyLengthReport {
+    max_period: usize,
+    language_coincidence: f64,
+    distances: Vec<usize>,
+    friedman: Option<f64>,
+    coincidence: Vec<(usize, f64)>,
+    autocorrelation: Vec<(usize, f64)>,
+}
+
+/// Estimate the period of a polyalphabetic cipher, such as the
+/// Vigen&egrave;re Cipher, from a ciphertext.
+///
+/// Periods from 1 to 20 are considered, or up to half the length of the
+/// ciphertext if that is shorter. Letters are read case-insensitively and all
+/// other characters are ignored.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{analysis::{estimate_period, PeriodMethod}, corpus::ENGLISH_SAMPLE};
+/// // Encrypt some English with the Vigenere Cipher and the keyword "cipher"
+/// let key = [2, 8, 15, 7, 4, 17];
+/// let ciphertext: String = ENGLISH_SAMPLE
+///     .bytes()
+///     .filter(u8::is_ascii_alphabetic)
+///     .enumerate()
+///     .map(|(i, b)| char::from(b'A' + (b.to_ascii_lowercase() - b'a' + key[i % 6]) % 26))
+///     .collect();
+///
+/// let report = estimate_period(&ciphertext);
+/// assert_eq!(report.estimate(PeriodMethod::Kasiski), Some(6));
+/// assert_eq!(report.recommendation(), Some(6));
+/// ```
+pub fn estimate_period(ciphertext: &str) -> KeyLengthReport {
+    KeyLengthReport::new(ciphertext, DEFAULT_MAX_PERIOD)
+}
+
+impl KeyLengthReport {
+    /// Estimate the period of a ciphertext, considering periods from 1 to
+    /// `max_period`, or up to half the length of the ciphertext if that is
+    /// shorter.
+    pub fn new(ciphertext: &str, max_period: usize) -> Self {
+        let letters: Vec<char> = ciphertext
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_lowercase)
+            .collect();
+        let max_period = max_period.min(letters.len() / 2);
+
+        KeyLengthReport {
+            max_period,
+            language_coincidence: ENGLISH_LETTER_PROBABILITIES
+                .iter()
+                .map(|(_, p)| p * p)
+                .sum(),
+            distances: repeat_distances(&letters),
+            friedman: friedman(&letters.iter().copied().collect()),
+            coincidence: (1..=max_period)
+                .map(|m| (m, column_coincidence(&letters, m)))
+                .collect(),
+            autocorrelation: (1..=max_period)
+                .map(|shift| (shift, autocorrelation(&letters, shift)))
+                .collect(),
+        }
+    }
+
+    /// Get the distances between the starts of repeated trigrams in the
+    /// ciphertext, which the Kasiski test expects to be multiples of the
+    /// period.
+    pub fn kasiski_distances(&self) -> &[usize] {
+        &self.distances
+    }
+
+    /// Get Friedman's estimate of the period, computed from the index of
+    /// coincidence of the whole ciphertext, if the ciphertext is long enough.
+    ///
+    /// This estimate is rough, and is best used to check the other estimates,
+    /// so it does not take part in the [`recommendation`](Self::recommendation).
+    pub fn friedman_estimate(&self) -> Option<f64> {
+        self.friedman
+    }
+
+    /// Get the average index of coincidence of the columns of the ciphertext
+    /// for each period considered.
+    pub fn column_coincidence(&self) -> &[(usize, f64)] {
+        &self.coincidence
+    }
+
+    /// Get the proportion of letters that are equal to the letter the given
+    /// number of positions later, for each shift considered.
+    pub fn autocorrelation(&self) -> &[(usize, f64)] {
+        &self.autocorrelation
+    }
+
+    /// Get the estimate of the period given by a method, if any.
+    pub fn estimate(&self, method: PeriodMethod) -> Option<usize> {
+        match method {
+            PeriodMethod::Kasiski => self.kasiski(),
+            PeriodMethod::IndexOfCoincidence => {
+                // The smallest period whose columns look like the language (rather than random
+                // text) about as much as the best period
+                let spread = self.language_coincidence - RANDOM_COINCIDENCE;
+                let max = self.coincidence.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+                let threshold = (max - spread / 4.0).max(RANDOM_COINCIDENCE + spread / 2.0);
+                self.coincidence
+                    .iter()
+                    .find(|(_, v)| *v >= threshold)
+                    .map(|&(m, _)| m)
+            }
+            PeriodMethod::Autocorrelation => first_peak(&self.autocorrelation),
+        }
+    }
+
+    /// Get the estimates of all methods.
+    pub fn estimates(&self) -> Vec<(PeriodMethod, Option<usize>)> {
+        [
+            PeriodMethod::Kasiski,
+            PeriodMethod::IndexOfCoincidence,
+            PeriodMethod::Autocorrelation,
+        ]
+        .into_iter()
+        .map(|method| (method, self.estimate(method)))
+        .collect()
+    }
+
+    /// Get the recommended period: the estimate shared by the most methods,
+    /// preferring the index of coincidence estimate in case of a tie.
+    pub fn recommendation(&self) -> Option<usize> {
+        let estimates: Vec<usize> = self.estimates().into_iter().flat_map(|(_, m)| m).collect();
+        let votes = |m: usize| estimates.iter().filter(|&&e| e == m).count();
+        let preferred = self.estimate(PeriodMethod::IndexOfCoincidence);
+
+        estimates
+            .iter()
+            .copied()
+            .max_by_key(|&m| (votes(m), Some(m) == preferred, std::cmp::Reverse(m)))
+    }
+
+    fn kasiski(&self) -> Option<usize> {
+        if self.distances.is_empty() {
+            return None;
+        }
+
+        // The largest period that divides most distances, since every divisor of the period
+        // divides at least as many
+        (2..=self.max_period).rev().find(|&m| {
+            let divisible = self.distances.iter().filter(|&&d| d % m == 0).count();
+            divisible as f64 >= KASISKI_THRESHOLD * self.distances.len() as f64
+        })
+    }
+}
+
+/// Display the estimates of each method and the recommendation.
+impl fmt::Display for KeyLengthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |m: Option<usize>| m.map_or("none".to_string(), |m| m.to_string());
+
+        for (method, estimate) in self.estimates() {
+            writeln!(f, "{:<22} {:>5}", method.to_string(), show(estimate))?;
+        }
+        writeln!(
+            f,
+            "{:<22} {:>5}",
+            "recommended",
+            show(self.recommendation())
+        )
+    }
+}
+
+// The smallest period whose value is clearly above average, since multiples of the period also
+// give high values.
+fn first_peak(values: &[(usize, f64)]) -> Option<usize> {
+    let max = values.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let mean = values.iter().map(|(_, v)| v).sum::<f64>() / values.len().max(1) as f64;
+
+    values
+        .iter()
+        .find(|(_, v)| *v >= (max + mean) / 2.0 && *v > 0.0)
+        .map(|&(m, _)| m)
+}
+
+// The index of coincidence of uniformly random letters.
+const RANDOM_COINCIDENCE: f64 = 1.0 / 26.0;
+
+// The distances between the starts of every pair of occurrences of each repeated trigram.
+fn repeat_distances(letters: &[char]) -> Vec<usize> {
+    let mut positions: BTreeMap<&[char], Vec<usize>> = BTreeMap::new();
+    for (i, trigram) in letters.windows(3).enumerate() {
+        positions.entry(trigram).or_default().push(i);
+    }
+
+    let mut distances = Vec::new();
+    for positions in positions.values() {
+        for (i, p) in positions.iter().enumerate() {
+            distances.extend(positions[i + 1..].iter().map(|q| q - p));
+        }
+    }
+    distances.sort_unstable();
+    distances
+}
+
+// Friedman's formula for the period, from the index of coincidence of the whole text.
+fn friedman(table: &FrequencyTable) -> Option<f64> {
+    let language: f64 = ENGLISH_LETTER_PROBABILITIES
+        .iter()
+        .map(|(_, p)| p * p)
+        .sum();
+    let n = table.total() as f64;
+    let ioc = table.index_of_coincidence();
+
+    let denominator = (n - 1.0) * ioc - n * RANDOM_COINCIDENCE + language;
+    (table.total() > 1 && denominator > 0.0)
+        .then(|| (language - RANDOM_COINCIDENCE) * n / denominator)
+}
+
+fn column_coincidence(letters: &[char], period: usize) -> f64 {
+    let total: f64 = (0..period)
+        .map(|start| {
+            letters
+                .iter()
+                .skip(start)
+                .step_by(period)
+                .copied()
+                .collect::<FrequencyTable>()
+                .index_of_coincidence()
+        })
+        .sum();
+    total / period as f64
+}
+
+fn autocorrelation(letters: &[char], shift: usize) -> f64 {
+    let compared = letters.len().saturating_sub(shift);
+    if compared == 0 {
+        return 0.0;
+    }
+    let equal = letters
+        .iter()
+        .zip(&letters[shift..])
+        .filter(|(a, b)| a == b)
+        .count();
+    equal as f64 / compared as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corpus::ENGLISH_SAMPLE;
+
+    fn vigenere(text: &str, key: &str) -> String {
+        let key: Vec<u8> = key.bytes().map(|b| b - b'a').collect();
+        text.bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .enumerate()
+            .map(|(i, b)| {
+                char::from(b'A' + (b.to_ascii_lowercase() - b'a' + key[i % key.len()]) % 26)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn estimates_agree() {
+        for key in ["cipher", "lemon", "cryptography"] {
+            let report = estimate_period(&vigenere(ENGLISH_SAMPLE, key));
+
+            for (method, estimate) in report.estimates() {
+                assert_eq!(estimate, Some(key.len()), "{method} for key {key}");
+            }
+            assert_eq!(report.recommendation(), Some(key.len()));
+        }
+    }
+
+    #[test]
+    fn details() {
+        let report = estimate_period(&vigenere(ENGLISH_SAMPLE, "cipher"));
+
+        assert!(
+            report
+                .kasiski_distances()
+                .iter()
+                .filter(|&&d| d % 6 == 0)
+                .count()
+                > 10
+        );
+        assert_eq!(report.column_coincidence().len(), 20);
+        assert_eq!(report.autocorrelation().len(), 20);
+        // Friedman's estimate is only a rough guide
+        let friedman = report.friedman_estimate().unwrap();
+        assert!((2.0..12.0).contains(&friedman), "{friedman}");
+
+        assert_eq!(
+            report.to_string(),
+            "Kasiski test               6\n\
+             index of coincidence       6\n\
+             autocorrelation            6\n\
+             recommended                6\n"
+        );
+    }
+
+    #[test]
+    fn shift_cipher_has_period_one() {
+        let report = estimate_period(&vigenere(ENGLISH_SAMPLE, "l"));
+        assert_eq!(report.estimate(PeriodMethod::IndexOfCoincidence), Some(1));
+        assert_eq!(report.recommendation(), Some(1));
+    }
+
+    #[test]
+    fn short_ciphertext() {
+        let report = estimate_period("HPHT");
+        assert!(report.kasiski_distances().is_empty());
+        assert_eq!(report.estimate(PeriodMethod::Kasiski), None);
+        assert_eq!(report.column_coincidence().len(), 2);
+
+        let report = estimate_period("");
+        assert_eq!(report.recommendation(), None);
+        assert_eq!(report.friedman_estimate(), None);
+    }
+
+    #[test]
+    fn kasiski_distances() {
+        // "abc" repeats at distances 6 and 12 (and 6 again)
+        let report = KeyLengthReport::new("abcxyzabcuvwabc", 7);
+        assert_eq!(report.kasiski_distances(), [6, 6, 12]);
+        assert_eq!(report.estimate(PeriodMethod::Kasiski), Some(6));
+    }
+}
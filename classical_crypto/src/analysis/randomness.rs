@@ -0,0 +1,308 @@
+//! Statistical tests of whether a ciphertext looks random.
+use super::FrequencyTable;
+use std::fmt;
+
+// The number of letters in the Latin Alphabet.
+const LETTERS: usize = 26;
+
+/// The results of statistical tests of whether the letters of a ciphertext
+/// look like uniformly random letters. See [`test_randomness`].
+///
+/// The output of a strong cipher should be indistinguishable from random, but
+/// classical ciphers leak the statistics of the plaintext: the Shift Cipher,
+/// for instance, keeps the uneven letter frequencies of English, just moved
+/// to different letters. Two tests are run:
+/// - The _chi-squared test_ compares the letter counts to the counts
+///   expected of uniformly random letters.
+/// - The _runs test_ splits the alphabet into two halves, `a` to `m` and `n`
+///   to `z`, and counts the _runs_ of consecutive letters from the same half.
+///   Random letters switch halves about half of the time, while, e.g., a
+///   ciphertext with repeating patterns may switch far too often or rarely.
+///
+/// Each test gives a _p-value_: the probability that uniformly random letters
+/// would give a result at least as extreme. A small p-value, say below 0.01,
+/// is strong evidence that the ciphertext is not random. Note that a large
+/// p-value does not prove that a ciphertext is random, and that neither test
+/// is reliable for very short ciphertexts.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RandomnessReport {
+    letters: usize,
+    chi_squared: f64,
+    chi_squared_p: f64,
+    runs: usize,
+    expected_runs: f64,
+    runs_p: f64,
+}
+
+/// Test whether the letters of a ciphertext look like uniformly random
+/// letters.
+///
+/// Letters are read case-insensitively and all other characters are ignored.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{analysis::test_randomness, corpus::ENGLISH_SAMPLE};
+/// // English text, or an encryption of it under the Shift Cipher, is far from random.
+/// let report = test_randomness(ENGLISH_SAMPLE);
+/// assert!(!report.looks_random(0.01));
+///
+/// println!("{report}");
+/// ```
+pub fn test_randomness(ciphertext: &str) -> RandomnessReport {
+    let letters: Vec<u8> = ciphertext
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_lowercase() - b'a')
+        .collect();
+
+    let uniform: Vec<(char, f64)> = ('a'..='z').map(|c| (c, 1.0 / LETTERS as f64)).collect();
+    let table: FrequencyTable = letters.iter().map(|&i| char::from(b'a' + i)).collect();
+    let chi_squared = table.distance(&uniform);
+    let chi_squared_p = upper_gamma((LETTERS - 1) as f64 / 2.0, chi_squared / 2.0);
+
+    // The runs test (Wald-Wolfowitz) of the halves of the alphabet
+    let halves: Vec<bool> = letters
+        .iter()
+        .map(|&i| usize::from(i) >= LETTERS / 2)
+        .collect();
+    let runs = if halves.is_empty() {
+        0
+    } else {
+        1 + halves.windows(2).filter(|w| w[0] != w[1]).count()
+    };
+    let n = halves.len() as f64;
+    let upper = halves.iter().filter(|&&h| h).count() as f64;
+    let lower = n - upper;
+    let expected_runs = if n > 0.0 {
+        2.0 * upper * lower / n + 1.0
+    } else {
+        0.0
+    };
+    let variance = 2.0 * upper * lower * (2.0 * upper * lower - n) / (n * n * (n - 1.0));
+    let runs_p = if variance > 0.0 {
+        let z = (runs as f64 - expected_runs) / variance.sqrt();
+        erfc(z.abs() / std::f64::consts::SQRT_2)
+    } else {
+        // Too few letters, or all letters from one half, to say anything
+        1.0
+    };
+
+    RandomnessReport {
+        letters: letters.len(),
+        chi_squared,
+        chi_squared_p,
+        runs,
+        expected_runs,
+        runs_p,
+    }
+}
+
+impl RandomnessReport {
+    /// Get the number of letters tested.
+    pub fn letters(&self) -> usize {
+        self.letters
+    }
+
+    /// Get the chi-squared statistic of the letter counts against uniformly
+    /// random letters.
+    pub fn chi_squared(&self) -> f64 {
+        self.chi_squared
+    }
+
+    /// Get the p-value of the chi-squared test.
+    pub fn chi_squared_p(&self) -> f64 {
+        self.chi_squared_p
+    }
+
+    /// Get the number of runs of letters from the same half of the alphabet.
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// Get the number of runs expected of random letters, given the number of
+    /// letters from each half of the alphabet.
+    pub fn expected_runs(&self) -> f64 {
+        self.expected_runs
+    }
+
+    /// Get the p-value of the runs test.
+    pub fn runs_p(&self) -> f64 {
+        self.runs_p
+    }
+
+    /// Returns true if neither test rejects randomness at the given
+    /// significance level (e.g., 0.01), and false otherwise.
+    pub fn looks_random(&self, significance: f64) -> bool {
+        self.chi_squared_p >= significance && self.runs_p >= significance
+    }
+}
+
+/// Display the statistics and p-values of both tests.
+impl fmt::Display for RandomnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<12} {:>10} {:>10}", "test", "statistic", "p-value")?;
+        writeln!(
+            f,
+            "{:<12} {:>10.3} {:>10.4}",
+            "chi-squared", self.chi_squared, self.chi_squared_p
+        )?;
+        writeln!(f, "{:<12} {:>10} {:>10.4}", "runs", self.runs, self.runs_p)
+    }
+}
+
+// The regularized upper incomplete gamma function Q(a, x), i.e., the probability that a
+// chi-squared variable with 2a degrees of freedom exceeds 2x. Computed by its series for small x
+// and its continued fraction otherwise (see Numerical Recipes, Section 6.2).
+fn upper_gamma(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-12;
+    const MAX_ITERATIONS: usize = 500;
+
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        let (mut term, mut sum) = (1.0 / a, 1.0 / a);
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        (1.0 - sum * prefactor).max(0.0)
+    } else {
+        // Modified Lentz's method
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        prefactor * h
+    }
+}
+
+// The logarithm of the gamma function, using the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000_000_000_190_015, |sum, (i, c)| {
+            sum + c / (x + 1.0 + i as f64)
+        });
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+// The complementary error function, with relative error below 1.2e-7 (see Numerical Recipes,
+// Section 6.2).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let polynomial = [
+        -1.265_512_23,
+        1.000_023_68,
+        0.374_091_96,
+        0.096_784_18,
+        -0.186_288_06,
+        0.278_868_07,
+        -1.135_203_98,
+        1.488_515_87,
+        -0.822_152_23,
+        0.170_872_77,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |acc, c| acc * t + c);
+    let result = t * (-z * z + polynomial).exp();
+
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corpus::ENGLISH_SAMPLE;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn special_functions() {
+        // Q(a, x) for a chi-squared variable with 2 degrees of freedom is e^(-x)
+        assert!((upper_gamma(1.0, 2.0) - (-2.0f64).exp()).abs() < 1e-9);
+        assert!((upper_gamma(1.0, 0.5) - (-0.5f64).exp()).abs() < 1e-9);
+        // The 95th percentile of the chi-squared distribution with 25 degrees of freedom
+        assert!((upper_gamma(12.5, 37.652 / 2.0) - 0.05).abs() < 1e-4);
+
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+        assert!((erfc(1.0) - 0.157_299_207).abs() < 1e-7);
+        assert!((erfc(-1.0) - 1.842_700_793).abs() < 1e-7);
+    }
+
+    #[test]
+    fn english_is_not_random() {
+        let report = test_randomness(ENGLISH_SAMPLE);
+
+        assert!(report.chi_squared_p() < 1e-6);
+        assert!(!report.looks_random(0.01));
+    }
+
+    #[test]
+    fn random_letters_look_random() {
+        let mut rng = ChaCha12Rng::from_seed(*b"MY DISTRIBUTION IS NOT UNIFORM!!");
+        let letters: String = (0..2000)
+            .map(|_| char::from(b'a' + rng.gen_range(0..26)))
+            .collect();
+
+        let report = test_randomness(&letters);
+        assert_eq!(report.letters(), 2000);
+        assert!(report.looks_random(0.01), "{report}");
+    }
+
+    #[test]
+    fn runs() {
+        // Alternating halves of the alphabet gives far too many runs
+        let report = test_randomness(&"an".repeat(100));
+        assert_eq!(report.runs(), 200);
+        assert_eq!(report.expected_runs(), 101.0);
+        assert!(report.runs_p() < 1e-6);
+        assert!(!report.looks_random(0.01));
+
+        // Too few letters to say anything
+        assert_eq!(test_randomness("a").runs_p(), 1.0);
+        assert_eq!(test_randomness("").runs(), 0);
+    }
+}
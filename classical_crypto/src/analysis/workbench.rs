@@ -0,0 +1,374 @@
+//! A session for solving a ciphertext by hand.
+use super::{FrequencyTable, NgramTable};
+use crate::errors::WorkbenchError;
+use std::collections::BTreeMap;
+
+/// A session for solving a monoalphabetic ciphertext by hand, such as an
+/// encryption under the Substitution Cipher.
+///
+/// The workbench holds the ciphertext together with the observations made so
+/// far: its letter, bigram and trigram counts, its index of coincidence, any
+/// notes, and the assignments of ciphertext letters to plaintext letters. An
+/// assignment is either a _guess_, which may be overwritten by later guesses,
+/// or _pinned_, in which case it is kept until it is explicitly unpinned.
+/// Since the cipher is a permutation, each plaintext letter is assigned to at
+/// most one ciphertext letter; a new guess replaces any other guess for the
+/// same plaintext letter.
+///
+/// Following Stinson's convention, the provisional plaintext shows the
+/// assigned letters in lowercase and the unassigned ciphertext letters in
+/// uppercase.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::analysis::Workbench;
+/// let mut bench = Workbench::new("HPHTWWXPPELEXTOYTRSE");
+///
+/// // The most common ciphertext letters are e and p.
+/// bench.pin('p', 'e').unwrap();
+/// bench.guess('e', 't').unwrap();
+/// assert_eq!(bench.plaintext(), "HeHTWWXeetLtXTOYTRSt");
+///
+/// // A guess never overwrites a pinned letter.
+/// assert!(bench.guess('p', 'a').is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Workbench {
+    ciphertext: String,
+    frequencies: FrequencyTable,
+    bigrams: NgramTable,
+    trigrams: NgramTable,
+    // Maps each assigned ciphertext letter to its plaintext letter and whether it is pinned.
+    assignments: BTreeMap<char, (char, bool)>,
+    notes: Vec<String>,
+}
+
+impl Workbench {
+    /// Start a session for the given ciphertext.
+    ///
+    /// As for [`FrequencyTable::new`], letters are read case-insensitively and
+    /// all other characters are ignored.
+    pub fn new(ciphertext: &str) -> Self {
+        let ciphertext: String = ciphertext
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        Workbench {
+            frequencies: FrequencyTable::new(&ciphertext),
+            bigrams: NgramTable::bigrams(&ciphertext),
+            trigrams: NgramTable::trigrams(&ciphertext),
+            ciphertext,
+            assignments: BTreeMap::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Get the letters of the ciphertext, in uppercase.
+    pub fn ciphertext(&self) -> String {
+        self.ciphertext.to_uppercase()
+    }
+
+    /// Get the letter counts of the ciphertext.
+    pub fn frequencies(&self) -> &FrequencyTable {
+        &self.frequencies
+    }
+
+    /// Get the bigram counts of the ciphertext.
+    pub fn bigrams(&self) -> &NgramTable {
+        &self.bigrams
+    }
+
+    /// Get the trigram counts of the ciphertext.
+    pub fn trigrams(&self) -> &NgramTable {
+        &self.trigrams
+    }
+
+    /// Get the index of coincidence of the ciphertext.
+    ///
+    /// See [`FrequencyTable::index_of_coincidence`].
+    pub fn index_of_coincidence(&self) -> f64 {
+        self.frequencies.index_of_coincidence()
+    }
+
+    /// Guess that the ciphertext letter `ciphertext` decrypts to the plaintext
+    /// letter `plaintext`, replacing any earlier guess for either letter.
+    ///
+    /// # Errors
+    /// This method returns an error if either character is not a letter, if
+    /// `ciphertext` is pinned, or if `plaintext` is pinned to another
+    /// ciphertext letter.
+    pub fn guess(&mut self, ciphertext: char, plaintext: char) -> Result<(), WorkbenchError> {
+        self.assign(ciphertext, plaintext, false)
+    }
+
+    /// Assign the ciphertext letter `ciphertext` to the plaintext letter
+    /// `plaintext` and pin the assignment, replacing any guess for either
+    /// letter.
+    ///
+    /// # Errors
+    /// This method returns an error if either character is not a letter, if
+    /// `ciphertext` is pinned to a different plaintext letter, or if
+    /// `plaintext` is pinned to another ciphertext letter.
+    pub fn pin(&mut self, ciphertext: char, plaintext: char) -> Result<(), WorkbenchError> {
+        self.assign(ciphertext, plaintext, true)
+    }
+
+    /// Turn the pinned assignment of `ciphertext`, if any, into a guess.
+    ///
+    /// # Errors
+    /// This method returns an error if `ciphertext` is not a letter.
+    pub fn unpin(&mut self, ciphertext: char) -> Result<(), WorkbenchError> {
+        let ciphertext = letter(ciphertext)?;
+        if let Some((_, pinned)) = self.assignments.get_mut(&ciphertext) {
+            *pinned = false;
+        }
+        Ok(())
+    }
+
+    /// Remove the assignment of `ciphertext`, returning the plaintext letter
+    /// it was assigned to, if any.
+    ///
+    /// # Errors
+    /// This method returns an error if `ciphertext` is not a letter or is
+    /// pinned.
+    pub fn unassign(&mut self, ciphertext: char) -> Result<Option<char>, WorkbenchError> {
+        let ciphertext = letter(ciphertext)?;
+        if self.is_pinned(ciphertext) {
+            return Err(WorkbenchError::Pinned(ciphertext));
+        }
+        Ok(self.assignments.remove(&ciphertext).map(|(p, _)| p))
+    }
+
+    /// Apply a partial substitution, given as pairs of ciphertext and
+    /// plaintext letters, as guesses.
+    ///
+    /// Pairs that conflict with pinned assignments are skipped, so a
+    /// substitution proposed by, e.g., matching letter frequencies can be
+    /// applied without disturbing the letters already solved. Returns the
+    /// number of guesses made.
+    ///
+    /// # Errors
+    /// This method returns an error, and makes no guesses, if any of the
+    /// characters is not a letter.
+    pub fn apply<I>(&mut self, substitution: I) -> Result<usize, WorkbenchError>
+    where
+        I: IntoIterator<Item = (char, char)>,
+    {
+        let substitution = substitution
+            .into_iter()
+            .map(|(c, p)| Ok((letter(c)?, letter(p)?)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(substitution
+            .into_iter()
+            .filter(|&(c, p)| self.assign(c, p, false).is_ok())
+            .count())
+    }
+
+    /// Remove every assignment that is not pinned.
+    pub fn clear_guesses(&mut self) {
+        self.assignments.retain(|_, (_, pinned)| *pinned);
+    }
+
+    /// Get the plaintext letter assigned to `ciphertext`, if any.
+    pub fn assignment(&self, ciphertext: char) -> Option<char> {
+        self.assignments
+            .get(&ciphertext.to_lowercase().next()?)
+            .map(|&(p, _)| p)
+    }
+
+    /// Returns true if the assignment of `ciphertext` is pinned and false
+    /// otherwise.
+    pub fn is_pinned(&self, ciphertext: char) -> bool {
+        ciphertext
+            .to_lowercase()
+            .next()
+            .and_then(|c| self.assignments.get(&c))
+            .is_some_and(|&(_, pinned)| pinned)
+    }
+
+    /// Get the assignments as pairs of ciphertext and plaintext letters, in
+    /// alphabetical order of the ciphertext letters.
+    pub fn assignments(&self) -> Vec<(char, char)> {
+        self.assignments
+            .iter()
+            .map(|(&c, &(p, _))| (c, p))
+            .collect()
+    }
+
+    /// Get the provisional plaintext: assigned letters are shown in lowercase,
+    /// and unassigned letters are shown as the uppercase ciphertext letter.
+    pub fn plaintext(&self) -> String {
+        self.ciphertext
+            .chars()
+            .map(|c| match self.assignments.get(&c) {
+                Some(&(p, _)) => p,
+                None => c.to_uppercase().next().unwrap_or(c),
+            })
+            .collect()
+    }
+
+    /// Record a note about the ciphertext.
+    pub fn note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into())
+    }
+
+    /// Get the notes recorded so far.
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    fn assign(
+        &mut self,
+        ciphertext: char,
+        plaintext: char,
+        pin: bool,
+    ) -> Result<(), WorkbenchError> {
+        let (ciphertext, plaintext) = (letter(ciphertext)?, letter(plaintext)?);
+
+        if let Some(&(current, true)) = self.assignments.get(&ciphertext) {
+            if !pin || current != plaintext {
+                return Err(WorkbenchError::Pinned(ciphertext));
+            }
+        }
+        if let Some((&other, _)) = self
+            .assignments
+            .iter()
+            .find(|&(&c, &(p, pinned))| p == plaintext && c != ciphertext && pinned)
+        {
+            return Err(WorkbenchError::PlaintextPinned {
+                plaintext,
+                ciphertext: other,
+            });
+        }
+
+        // Each plaintext letter has at most one ciphertext letter
+        self.assignments.retain(|_, &mut (p, _)| p != plaintext);
+        let _ = self.assignments.insert(ciphertext, (plaintext, pin));
+        Ok(())
+    }
+}
+
+// Check that `c` is a letter, and convert it to lowercase.
+fn letter(c: char) -> Result<char, WorkbenchError> {
+    if c.is_alphabetic() {
+        Ok(c.to_lowercase().next().unwrap_or(c))
+    } else {
+        Err(WorkbenchError::NotALetter(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example 1.1, Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
+    const CIPHERTEXT: &str = "HPHTWWXPPELEXTOYTRSE";
+
+    #[test]
+    fn observations() {
+        let bench = Workbench::new("hpht wwxp, pele!");
+
+        assert_eq!(bench.ciphertext(), "HPHTWWXPPELE");
+        assert_eq!(bench.frequencies(), &FrequencyTable::new("hphtwwxppele"));
+        assert_eq!(bench.bigrams().count("hp"), 1);
+        assert_eq!(bench.trigrams().count("pel"), 1);
+        assert_eq!(
+            bench.index_of_coincidence(),
+            FrequencyTable::new("hphtwwxppele").index_of_coincidence()
+        );
+    }
+
+    #[test]
+    fn solve_shift() {
+        let mut bench = Workbench::new(CIPHERTEXT);
+        assert_eq!(bench.plaintext(), CIPHERTEXT);
+
+        // Decrypting with key 11 is a substitution
+        let guesses = bench
+            .apply(('a'..='z').map(|c| (c, (b'a' + (c as u8 - b'a' + 15) % 26) as char)))
+            .unwrap();
+        assert_eq!(guesses, 26);
+        assert_eq!(bench.plaintext(), "wewillmeetatmidnight");
+
+        bench.clear_guesses();
+        assert!(bench.assignments().is_empty());
+    }
+
+    #[test]
+    fn guesses_replace_guesses() {
+        let mut bench = Workbench::new(CIPHERTEXT);
+
+        bench.guess('P', 'E').unwrap();
+        bench.guess('p', 't').unwrap();
+        assert_eq!(bench.assignment('p'), Some('t'));
+
+        // Only one ciphertext letter per plaintext letter
+        bench.guess('e', 't').unwrap();
+        assert_eq!(bench.assignments(), vec![('e', 't')]);
+
+        assert_eq!(bench.unassign('e'), Ok(Some('t')));
+        assert_eq!(bench.unassign('e'), Ok(None));
+    }
+
+    #[test]
+    fn pins() {
+        let mut bench = Workbench::new(CIPHERTEXT);
+
+        bench.guess('e', 'e').unwrap();
+        bench.pin('p', 'e').unwrap();
+        assert!(bench.is_pinned('P'));
+        // The pin replaced the guess for 'e'
+        assert_eq!(bench.assignments(), vec![('p', 'e')]);
+
+        assert_eq!(bench.guess('p', 't'), Err(WorkbenchError::Pinned('p')));
+        assert_eq!(bench.pin('p', 't'), Err(WorkbenchError::Pinned('p')));
+        assert_eq!(bench.unassign('p'), Err(WorkbenchError::Pinned('p')));
+        assert_eq!(
+            bench.guess('x', 'e'),
+            Err(WorkbenchError::PlaintextPinned {
+                plaintext: 'e',
+                ciphertext: 'p'
+            })
+        );
+        // Pinning again is harmless
+        bench.pin('p', 'e').unwrap();
+
+        // Substitutions skip pinned letters
+        assert_eq!(bench.apply([('p', 'a'), ('x', 'e'), ('h', 'w')]), Ok(1));
+        assert_eq!(bench.assignments(), vec![('h', 'w'), ('p', 'e')]);
+
+        bench.clear_guesses();
+        assert_eq!(bench.assignments(), vec![('p', 'e')]);
+
+        bench.unpin('p').unwrap();
+        bench.guess('p', 't').unwrap();
+        assert_eq!(bench.assignment('p'), Some('t'));
+    }
+
+    #[test]
+    fn invalid_letters() {
+        let mut bench = Workbench::new(CIPHERTEXT);
+
+        assert_eq!(bench.guess('1', 'a'), Err(WorkbenchError::NotALetter('1')));
+        assert_eq!(bench.pin('a', ' '), Err(WorkbenchError::NotALetter(' ')));
+        assert_eq!(
+            bench.apply([('a', 'b'), ('c', '?')]),
+            Err(WorkbenchError::NotALetter('?'))
+        );
+        assert!(bench.assignments().is_empty());
+    }
+
+    #[test]
+    fn notes() {
+        let mut bench = Workbench::new(CIPHERTEXT);
+        bench.note("Index of coincidence suggests a monoalphabetic cipher");
+
+        assert_eq!(
+            bench.notes(),
+            ["Index of coincidence suggests a monoalphabetic cipher"]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! An ASCII-armored text format for keys, so that keys can be pasted into an
+//! email or a chat, e.g., for exercises.
+//!
+//! An armored key consists of, one per line:
+//! - a header naming the cipher, e.g., `-----BEGIN SHIFT KEY-----`,
+//! - the key as written by [`KeyTrait::export`](crate::KeyTrait::export),
+//!   encoded in base32 (RFC 4648, without padding) in lines of at most
+//!   [`LINE_WIDTH`] characters,
+//! - a checksum: `=` followed by the CRC-32 of the key in hexadecimal,
+//! - a footer naming the cipher, e.g., `-----END SHIFT KEY-----`.
+//!
+//! Reading an armored key skips blank lines and the whitespace around each
+//! line, which mail and chat clients tend to add, and checks the cipher and
+//! the checksum, so that a corrupted key is detected. The checksum does not
+//! protect against deliberate changes, and like
+//! [`export`](crate::KeyTrait::export), the format does nothing to protect the
+//! key: anyone who can read the text can read the key.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{armor, shift::Key};
+//! # use std::str::FromStr;
+//! let key = Key::from_str("11").unwrap();
+//!
+//! let armored = armor::encode(&key);
+//! assert_eq!(armored, "-----BEGIN SHIFT KEY-----\nGEYQ\n=D65A1577\n-----END SHIFT KEY-----\n");
+//! assert_eq!(armor::decode::<Key>(&armored).unwrap(), key);
+//! ```
+use crate::{errors::ArmorError, keyfile::KeyFile, CipherTrait};
+
+/// The greatest number of base32 characters on each line of the body.
+pub const LINE_WIDTH: usize = 64;
+
+// The base32 alphabet of RFC 4648.
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Write a key in the armored text format.
+pub fn encode<K: KeyFile>(key: &K) -> String {
+    let name = K::Cipher::info().name.to_uppercase();
+    let material = key.export();
+
+    let mut armored = format!("-----BEGIN {name} KEY-----\n");
+    let body = base32_encode(material.as_bytes());
+    for line in body.as_bytes().chunks(LINE_WIDTH) {
+        armored.push_str(&String::from_utf8_lossy(line));
+        armored.push('\n');
+    }
+    armored.push_str(&format!("={:08X}\n", crc32(material.as_bytes())));
+    armored.push_str(&format!("-----END {name} KEY-----\n"));
+    armored
+}
+
+/// Read a key in the armored text format.
+///
+/// # Errors
+/// This function returns an error if the text has no header or footer, if
+/// it holds a key for a different cipher, if the body is not base32 or the
+/// checksum does not match, or if the key material is not a key.
+pub fn decode<K: KeyFile>(armored: &str) -> Result<K, ArmorError> {
+    let name = K::Cipher::info().name.to_uppercase();
+    let mut lines = armored
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+
+    let found = lines
+        .next()
+        .and_then(|line| line.strip_prefix("-----BEGIN "))
+        .and_then(|line| line.strip_suffix(" KEY-----"))
+        .ok_or(ArmorError::MissingHeader)?;
+    if found != name {
+        return Err(ArmorError::WrongCipher {
+            expected: name.to_lowercase(),
+            found: found.to_lowercase(),
+        });
+    }
+
+    let mut body = String::new();
+    let checksum = loop {
+        match lines.next() {
+            Some(line) if line.starts_with('=') => break &line[1..],
+            Some(line) if !line.starts_with("-----") => body.push_str(line),
+            _ => return Err(ArmorError::MissingChecksum),
+        }
+    };
+    if lines.next() != Some(&format!("-----END {name} KEY-----")) {
+        return Err(ArmorError::MissingFooter);
+    }
+
+    let bytes = base32_decode(&body).ok_or(ArmorError::InvalidBody)?;
+    let expected = u32::from_str_radix(checksum, 16).map_err(|_| ArmorError::MissingChecksum)?;
+    let found = crc32(&bytes);
+    if found != expected {
+        return Err(ArmorError::Checksum { expected, found });
+    }
+
+    let material = String::from_utf8(bytes).map_err(|_| ArmorError::InvalidBody)?;
+    Ok(K::try_import(&material)?)
+}
+
+// Encode bytes in base32, without padding.
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let (mut buffer, mut bits) = (0_u32, 0);
+    for &byte in bytes {
+        buffer = buffer << 8 | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(char::from(BASE32[(buffer >> bits & 31) as usize]));
+        }
+    }
+    if bits > 0 {
+        encoded.push(char::from(BASE32[(buffer << (5 - bits) & 31) as usize]));
+    }
+    encoded
+}
+
+// Decode base32 without padding, or return `None` if it is not base32, e.g., because of a
+// character outside the alphabet or nonzero bits left over at the end.
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let (mut buffer, mut bits) = (0_u32, 0);
+    for c in s.bytes() {
+        let value = BASE32.iter().position(|&b| b == c)? as u32;
+        buffer = buffer << 5 | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    (bits < 5 && buffer & ((1 << bits) - 1) == 0).then_some(decoded)
+}
+
+// Compute the CRC-32 of the bytes, as used by zip and PNG, bit by bit.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0_u32, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            if crc & 1 == 1 {
+                crc >> 1 ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alberti, playfair, shift, EncodingError, KeyTrait};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn base32() {
+        // The test vectors of RFC 4648, without padding
+        for (bytes, encoded) in [
+            ("", ""),
+            ("f", "MY"),
+            ("fo", "MZXQ"),
+            ("foo", "MZXW6"),
+            ("foob", "MZXW6YQ"),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI"),
+        ] {
+            assert_eq!(base32_encode(bytes.as_bytes()), encoded);
+            assert_eq!(base32_decode(encoded).unwrap(), bytes.as_bytes());
+        }
+        assert_eq!(base32_decode("MZ"), None);
+        assert_eq!(base32_decode("my"), None);
+        assert_eq!(base32_decode("M1"), None);
+    }
+
+    #[test]
+    fn checksum() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        for _ in 0..10 {
+            let key = shift::Key::new(&mut rng);
+            assert_eq!(decode::<shift::Key>(&encode(&key)).unwrap(), key);
+
+            let key = playfair::Key::new(&mut rng);
+            assert_eq!(decode::<playfair::Key>(&encode(&key)).unwrap(), key);
+
+            let key = alberti::Key::new(&mut rng);
+            let armored = encode(&key);
+            assert!(armored.lines().all(|line| line.len() <= LINE_WIDTH));
+            assert_eq!(decode::<alberti::Key>(&armored).unwrap(), key);
+
+            // Pasting may indent the lines and add blank ones
+            let pasted: String = armored
+                .lines()
+                .map(|line| format!("  {line}\n\n"))
+                .collect();
+            assert_eq!(decode::<alberti::Key>(&pasted).unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let key = playfair::Key::try_import("playfirexmbcdghknoqstuvwz").unwrap();
+        let armored = encode(&key);
+
+        assert!(matches!(
+            decode::<shift::Key>(&armored),
+            Err(ArmorError::WrongCipher { expected, found }) if expected == "shift" && found == "playfair"
+        ));
+        assert!(matches!(
+            decode::<playfair::Key>(&armored[1..]),
+            Err(ArmorError::MissingHeader)
+        ));
+        assert!(matches!(
+            decode::<playfair::Key>(
+                armored
+                    .trim_end()
+                    .trim_end_matches("-----END PLAYFAIR KEY-----")
+            ),
+            Err(ArmorError::MissingFooter)
+        ));
+        assert!(matches!(
+            decode::<playfair::Key>(
+                "-----BEGIN PLAYFAIR KEY-----\nOBWGC\n-----END PLAYFAIR KEY-----"
+            ),
+            Err(ArmorError::MissingChecksum)
+        ));
+
+        // Changing a single character of the body is detected
+        let corrupted = armored.replacen("OBWGC", "OBWGD", 1);
+        assert_ne!(corrupted, armored);
+        assert!(matches!(
+            decode::<playfair::Key>(&corrupted),
+            Err(ArmorError::Checksum { .. })
+        ));
+        assert!(matches!(
+            decode::<playfair::Key>(&armored.replacen("OBWGC", "OBW!C", 1)),
+            Err(ArmorError::InvalidBody)
+        ));
+
+        // A key that is not valid for the cipher
+        let material = "26";
+        let invalid = format!(
+            "-----BEGIN SHIFT KEY-----\n{}\n={:08X}\n-----END SHIFT KEY-----\n",
+            base32_encode(material.as_bytes()),
+            crc32(material.as_bytes())
+        );
+        assert!(matches!(
+            decode::<shift::Key>(&invalid),
+            Err(ArmorError::Key(EncodingError::KeyFormat { .. }))
+        ));
+    }
+}
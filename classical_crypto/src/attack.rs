@@ -0,0 +1,524 @@
+//! A uniform interface for attacks on ciphers.
+//!
+//! Attacks are classified by the information available to the attacker, as in
+//! Section 2.2 of Stinson's _Cryptography: Theory and Practice_ (4th Edition):
+//! - In a _ciphertext-only attack_, the attacker possesses a ciphertext.
+//! - In a _known plaintext attack_, the attacker possesses a plaintext and the
+//!   corresponding ciphertext.
+//! - In a _chosen plaintext attack_, the attacker may obtain the encryption of
+//!   plaintexts of their choice, i.e., has temporary access to an encryption
+//!   oracle.
+//!
+//! In each case, the goal of the attacker is to determine the key that was
+//! used. Each kind of attack has its own trait, and each attack on a cipher is
+//! a type implementing one of these traits; see, e.g., the attacks in the
+//! [`shift`](crate::shift) module.
+use crate::CipherTrait;
+use std::{
+    fmt, slice,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    vec,
+};
+
+/// The information available to an attacker.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum AttackModel {
+    /// The attacker possesses a ciphertext.
+    CiphertextOnly,
+    /// The attacker possesses a plaintext and the corresponding ciphertext.
+    KnownPlaintext,
+    /// The attacker can obtain the encryption of plaintexts of their choice.
+    ChosenPlaintext,
+}
+
+impl fmt::Display for AttackModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttackModel::CiphertextOnly => write!(f, "ciphertext-only"),
+            AttackModel::KnownPlaintext => write!(f, "known plaintext"),
+            AttackModel::ChosenPlaintext => write!(f, "chosen plaintext"),
+        }
+    }
+}
+
+/// The properties shared by all attacks.
+pub trait Attack {
+    /// A short, human-readable name for the attack.
+    const NAME: &'static str;
+
+    /// The information available to the attacker.
+    const MODEL: AttackModel;
+}
+
+/// A ciphertext-only attack on the cipher `C`.
+pub trait CiphertextOnlyAttack<C: CipherTrait>: Attack {
+    /// Attack a ciphertext, returning candidate keys together with the
+    /// corresponding decryptions.
+    ///
+    /// An empty result means the attack failed.
+    fn attack(&self, ciphertxt: &C::Ciphertext) -> Candidates<C::Key, C::Message>;
+}
+
+/// A known plaintext attack on the cipher `C`.
+pub trait KnownPlaintextAttack<C: CipherTrait>: Attack {
+    /// Attack a ciphertext with knowledge of the corresponding plaintext,
+    /// returning the key if it can be determined.
+    fn attack(&self, msg: &C::Message, ciphertxt: &C::Ciphertext) -> Option<C::Key>;
+}
+
+/// A chosen plaintext attack on the cipher `C`.
+pub trait ChosenPlaintextAttack<C: CipherTrait>: Attack {
+    /// Attack the cipher using an encryption oracle, i.e., a function that
+    /// encrypts messages under an unknown key, returning the key if it can be
+    /// determined.
+    fn attack<O>(&self, oracle: O) -> Option<C::Key>
+    where
+        O: FnMut(&C::Message) -> C::Ciphertext;
+}
+
+/// A candidate solution found by an attack: a key, the plaintext obtained by
+/// decrypting with that key, and a score measuring how plausible the
+/// plaintext is.
+///
+/// Higher scores are more plausible. Scores are only meaningful relative to
+/// other scores computed by the same attack.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate<K, M> {
+    key: K,
+    plaintext: M,
+    score: f64,
+}
+
+impl<K, M> Candidate<K, M> {
+    /// Create a new candidate.
+    pub fn new(key: K, plaintext: M, score: f64) -> Self {
+        Candidate {
+            key,
+            plaintext,
+            score,
+        }
+    }
+
+    /// Get the candidate key.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Get the candidate plaintext.
+    pub fn plaintext(&self) -> &M {
+        &self.plaintext
+    }
+
+    /// Get the score of the candidate.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Split the candidate into its key, plaintext and score.
+    pub fn into_parts(self) -> (K, M, f64) {
+        (self.key, self.plaintext, self.score)
+    }
+}
+
+/// A collection of candidate solutions found by an attack, ordered from
+/// highest to lowest score.
+///
+/// Candidates with equal scores are kept in the order they were added.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::attack::Candidates;
+/// let mut candidates = Candidates::new();
+/// candidates.push(3, "kwxz", -40.0);
+/// candidates.push(11, "meet", -2.5);
+/// candidates.push(7, "aqzh", -31.0);
+///
+/// let best = candidates.best().unwrap();
+/// assert_eq!((*best.key(), *best.plaintext()), (11, "meet"));
+///
+/// let top: Vec<i32> = candidates.top(2).iter().map(|c| *c.key()).collect();
+/// assert_eq!(top, vec![11, 7]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidates<K, M>(Vec<Candidate<K, M>>);
+
+impl<K, M> Candidates<K, M> {
+    /// Create an empty collection of candidates.
+    pub fn new() -> Self {
+        Candidates(Vec::new())
+    }
+
+    /// Add a candidate to the collection.
+    pub fn push(&mut self, key: K, plaintext: M, score: f64) {
+        // Insert after all candidates with a score at least as high, so that
+        // ties keep their insertion order.
+        let index = self
+            .0
+            .partition_point(|candidate| candidate.score.total_cmp(&score).is_ge());
+        self.0.insert(index, Candidate::new(key, plaintext, score));
+    }
+
+    /// Get the candidate with the highest score, if any.
+    pub fn best(&self) -> Option<&Candidate<K, M>> {
+        self.0.first()
+    }
+
+    /// Get (at most) the `n` candidates with the highest scores.
+    pub fn top(&self, n: usize) -> &[Candidate<K, M>] {
+        &self.0[..n.min(self.0.len())]
+    }
+
+    /// Iterate over the candidates, from highest to lowest score.
+    pub fn iter(&self) -> slice::Iter<'_, Candidate<K, M>> {
+        self.0.iter()
+    }
+
+    /// Get the number of candidates.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no candidates and false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<K, M> Default for Candidates<K, M> {
+    fn default() -> Self {
+        Candidates::new()
+    }
+}
+
+impl<K, M> FromIterator<(K, M, f64)> for Candidates<K, M> {
+    fn from_iter<I: IntoIterator<Item = (K, M, f64)>>(iter: I) -> Self {
+        let mut candidates: Vec<Candidate<K, M>> = iter
+            .into_iter()
+            .map(|(key, plaintext, score)| Candidate::new(key, plaintext, score))
+            .collect();
+        // Stable sort, so ties keep their order
+        candidates.sort_by(|c0, c1| c1.score.total_cmp(&c0.score));
+        Candidates(candidates)
+    }
+}
+
+impl<K, M> IntoIterator for Candidates<K, M> {
+    type Item = Candidate<K, M>;
+    type IntoIter = vec::IntoIter<Candidate<K, M>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, K, M> IntoIterator for &'a Candidates<K, M> {
+    type Item = &'a Candidate<K, M>;
+    type IntoIter = slice::Iter<'a, Candidate<K, M>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// A snapshot of the progress of a long-running attack.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Progress {
+    iterations: usize,
+    total: usize,
+    best_score: f64,
+}
+
+impl Progress {
+    /// Get the number of iterations done so far.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Get the total number of iterations the attack will do, unless it is
+    /// cancelled.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Get the best score found so far.
+    pub fn best_score(&self) -> f64 {
+        self.best_score
+    }
+}
+
+/// A receiver of progress reports from long-running attacks, e.g., to display
+/// a progress bar.
+///
+/// This trait is implemented for closures taking a [`Progress`].
+pub trait ProgressSink: Send + Sync {
+    /// Receive a progress report.
+    fn report(&self, progress: Progress);
+}
+
+impl<F: Fn(Progress) + Send + Sync> ProgressSink for F {
+    fn report(&self, progress: Progress) {
+        self(progress)
+    }
+}
+
+/// A token used to stop a long-running attack.
+///
+/// Clones of a token share their state, so one clone can be handed to an
+/// attack while another is used to cancel it, e.g., from another thread.
+/// Cancelled attacks stop as soon as possible and return the candidates found
+/// so far.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new token.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Cancel the attacks using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    /// Returns true if the token has been cancelled and false otherwise.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The progress reporting and cancellation settings of a long-running attack.
+///
+/// Attacks that support monitoring, such as
+/// [`playfair::SimulatedAnnealing`](crate::playfair::SimulatedAnnealing),
+/// hold a monitor and expose `with_progress` and `with_cancellation` methods
+/// to configure it.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{attack::{CancellationToken, CiphertextOnlyAttack, Progress}, shift::{BruteForce, Ciphertext}};
+/// # use std::{str::FromStr, sync::{Arc, Mutex}};
+/// let reports = Arc::new(Mutex::new(Vec::new()));
+/// let sink = Arc::clone(&reports);
+///
+/// let attack = BruteForce::default()
+///     .with_progress(move |progress: Progress| sink.lock().unwrap().push(progress.iterations()));
+/// let _ = attack.attack(&Ciphertext::from_str("HPHTWWXPPELEXTOYTRSE").unwrap());
+///
+/// // The brute force attack reports after trying each key.
+/// assert_eq!(reports.lock().unwrap().len(), 26);
+///
+/// // An attack that is cancelled before it starts finds nothing.
+/// let token = CancellationToken::new();
+/// token.cancel();
+/// let attack = BruteForce::default().with_cancellation(token);
+/// assert!(attack.attack(&Ciphertext::from_str("HPHTWWXPPELEXTOYTRSE").unwrap()).is_empty());
+/// ```
+#[derive(Clone, Default)]
+pub struct Monitor {
+    sink: Option<Arc<dyn ProgressSink>>,
+    token: CancellationToken,
+    // The progress of the whole attack, shared by all of its parts (e.g., restarts), which may
+    // run in parallel.
+    tally: Option<Arc<Tally>>,
+    // The number of iterations of this part of the attack reported so far.
+    done: Arc<AtomicUsize>,
+}
+
+#[derive(Debug)]
+struct Tally {
+    total: usize,
+    done: AtomicUsize,
+    best_score: Mutex<f64>,
+}
+
+impl Monitor {
+    /// Report progress to the given sink.
+    pub fn with_progress(self, sink: impl ProgressSink + 'static) -> Self {
+        Monitor {
+            sink: Some(Arc::new(sink)),
+            ..self
+        }
+    }
+
+    /// Stop when the given token is cancelled.
+    pub fn with_cancellation(self, token: CancellationToken) -> Self {
+        Monitor { token, ..self }
+    }
+
+    /// Returns true if the attack has been cancelled and false otherwise.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Start an attack that will run for `total` iterations, returning the
+    /// monitor to report its progress to.
+    ///
+    /// If this monitor already belongs to a running attack, the new monitor
+    /// belongs to a part of that attack instead, and the iterations it reports
+    /// count towards the total of the whole attack.
+    pub fn begin(&self, total: usize) -> Self {
+        let tally = self.tally.clone().unwrap_or_else(|| {
+            Arc::new(Tally {
+                total,
+                done: AtomicUsize::new(0),
+                best_score: Mutex::new(f64::NEG_INFINITY),
+            })
+        });
+
+        Monitor {
+            tally: Some(tally),
+            done: Arc::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Report that this (part of the) attack has done the given number of
+    /// iterations, and found the given best score, so far.
+    pub fn report(&self, iterations: usize, best_score: f64) {
+        let Some(sink) = &self.sink else {
+            return;
+        };
+
+        match &self.tally {
+            Some(tally) => {
+                // Hold the lock while reporting, so that the sink sees the progress of parts
+                // running in parallel in order.
+                let mut best = tally
+                    .best_score
+                    .lock()
+                    .expect("Lock should not be poisoned");
+                *best = best.max(best_score);
+                let new = iterations.saturating_sub(self.done.swap(iterations, Ordering::Relaxed));
+                sink.report(Progress {
+                    iterations: tally.done.fetch_add(new, Ordering::Relaxed) + new,
+                    total: tally.total,
+                    best_score: *best,
+                })
+            }
+            None => sink.report(Progress {
+                iterations,
+                total: iterations,
+                best_score,
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for Monitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Monitor")
+            .field("sink", &self.sink.as_ref().map(|_| "ProgressSink"))
+            .field("token", &self.token)
+            .field("tally", &self.tally)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Apply `f` to each of the `items`, in parallel if the `rayon` feature is
+/// enabled. Either way, the results are in the same order as the items.
+pub(crate) fn map_items<T, U, F>(items: Vec<T>, f: F) -> Vec<U>
+where
+    T: Send,
+    U: Send,
+    F: Fn(T) -> U + Send + Sync,
+{
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        items.into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        items.into_iter().map(f).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_order() {
+        let mut candidates = Candidates::new();
+        assert!(candidates.is_empty());
+        assert!(candidates.best().is_none());
+
+        candidates.push('a', "first", 1.0);
+        candidates.push('b', "second", 3.0);
+        candidates.push('c', "third", 1.0);
+        candidates.push('d', "fourth", -2.0);
+
+        assert_eq!(candidates.len(), 4);
+        assert_eq!(*candidates.best().unwrap().key(), 'b');
+        // Ties keep insertion order
+        let keys: Vec<char> = candidates.iter().map(|c| *c.key()).collect();
+        assert_eq!(keys, vec!['b', 'a', 'c', 'd']);
+    }
+
+    #[test]
+    fn candidates_top() {
+        let candidates: Candidates<u8, &str> = [(0, "x", 0.5), (1, "y", 2.0), (2, "z", 1.0)]
+            .into_iter()
+            .collect();
+
+        let top: Vec<u8> = candidates.top(2).iter().map(|c| *c.key()).collect();
+        assert_eq!(top, vec![1, 2]);
+        // Asking for more candidates than there are is fine
+        assert_eq!(candidates.top(10).len(), 3);
+        assert_eq!(
+            candidates.into_iter().last().unwrap().into_parts(),
+            (0, "x", 0.5)
+        );
+    }
+
+    #[test]
+    fn cancellation_token() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert!(Monitor::default().with_cancellation(clone).is_cancelled());
+    }
+
+    #[test]
+    fn monitor_reports() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let monitor = Monitor::default().with_progress(move |p: Progress| {
+            sink.lock()
+                .unwrap()
+                .push((p.iterations(), p.total(), p.best_score()))
+        });
+
+        let attack = monitor.begin(30);
+        attack.report(5, -3.0);
+        // Parts of an attack report the overall progress
+        let (part0, part1) = (attack.begin(10), attack.begin(10));
+        part0.report(5, -1.0);
+        part1.report(5, -2.0);
+        part0.report(10, 4.0);
+
+        assert_eq!(
+            *reports.lock().unwrap(),
+            vec![(5, 30, -3.0), (10, 30, -1.0), (15, 30, -1.0), (20, 30, 4.0)]
+        );
+
+        // Without a sink, reports go nowhere
+        Monitor::default().begin(1).report(1, 0.0);
+    }
+
+    #[test]
+    fn map_items_keeps_order() {
+        let items: Vec<usize> = (0..100).collect();
+        assert_eq!(
+            map_items(items.clone(), |i| 2 * i),
+            items.iter().map(|i| 2 * i).collect::<Vec<_>>()
+        );
+    }
+}
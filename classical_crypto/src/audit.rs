@@ -0,0 +1,287 @@
+//! An in-memory audit log of key usage, to show how real systems track which
+//! key was used for what, and when.
+//!
+//! Encrypting and decrypting through an [`AuditLog`] records an
+//! [`AuditEntry`] for each call: the time, the cipher, the [`Operation`],
+//! whether it succeeded, and the [`fingerprint`](KeyTrait::fingerprint) of the
+//! key. The log never holds the key itself, so it can be shown to people who
+//! must not learn the key, e.g., to check that a key that should have been
+//! retired is no longer used, or to count how much ciphertext an attacker may
+//! have seen under one key. The log can be queried by key, by operation and by
+//! time.
+//!
+//! Note that for small key spaces, the fingerprint gives the key away, see
+//! the [`fingerprint`](crate::fingerprint) module.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{audit::{AuditLog, Operation}, shift::{Key, Message, ShiftCipher}};
+//! # use std::str::FromStr;
+//! let key = Key::from_str("11").unwrap();
+//! let other = Key::from_str("3").unwrap();
+//! let mut log = AuditLog::new();
+//!
+//! let ciphertxt = log.encrypt(&ShiftCipher::new(), &Message::new("attack").unwrap(), &key).unwrap();
+//! let _ = log.decrypt(&ShiftCipher::new(), &ciphertxt, &key).unwrap();
+//! let _ = log.encrypt(&ShiftCipher::new(), &Message::new("retreat").unwrap(), &other).unwrap();
+//!
+//! assert_eq!(log.len(), 3);
+//! assert_eq!(log.for_key(&key).count(), 2);
+//! assert_eq!(log.by_operation(Operation::Encrypt).count(), 2);
+//! ```
+use crate::{CipherTrait, KeyTrait};
+use std::{fmt, time::SystemTime};
+
+/// An operation that uses a key.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Operation {
+    /// Encrypting a message.
+    Encrypt,
+    /// Decrypting a ciphertext.
+    Decrypt,
+    /// Any other use of a key, e.g., exporting it, recorded with
+    /// [`AuditLog::record`].
+    Other(&'static str),
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Encrypt => write!(f, "encrypt"),
+            Operation::Decrypt => write!(f, "decrypt"),
+            Operation::Other(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// A record of one use of a key.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AuditEntry {
+    time: SystemTime,
+    cipher: &'static str,
+    operation: Operation,
+    fingerprint: String,
+    succeeded: bool,
+}
+
+impl AuditEntry {
+    /// Get the time at which the key was used.
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Get the name of the cipher, as in
+    /// [`CipherInfo::name`](crate::CipherInfo::name).
+    pub fn cipher(&self) -> &'static str {
+        self.cipher
+    }
+
+    /// Get the operation.
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Get the fingerprint of the key.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Returns true if the operation succeeded and false otherwise.
+    pub fn succeeded(&self) -> bool {
+        self.succeeded
+    }
+}
+
+/// An in-memory log of key usage, in the order of the operations.
+#[derive(Clone, Debug, Default)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        AuditLog::default()
+    }
+
+    /// Record that `key`, a key of the cipher `C`, was used for `operation`.
+    pub fn record<C: CipherTrait>(&mut self, operation: Operation, key: &C::Key, succeeded: bool) {
+        self.entries.push(AuditEntry {
+            time: SystemTime::now(),
+            cipher: C::info().name,
+            operation,
+            fingerprint: key.fingerprint(),
+            succeeded,
+        });
+    }
+
+    /// Encrypt with the [`encrypt`](CipherTrait::encrypt) method of `cipher`,
+    /// and record it.
+    ///
+    /// # Errors
+    /// This method returns the error of the cipher, if any. Failed
+    /// encryptions are recorded too.
+    pub fn encrypt<C: CipherTrait>(
+        &mut self,
+        cipher: &C,
+        msg: &C::Message,
+        key: &C::Key,
+    ) -> Result<C::Ciphertext, C::EncryptionError> {
+        let ciphertxt = cipher.encrypt(msg, key);
+        self.record::<C>(Operation::Encrypt, key, ciphertxt.is_ok());
+        ciphertxt
+    }
+
+    /// Decrypt with the [`decrypt`](CipherTrait::decrypt) method of `cipher`,
+    /// and record it.
+    ///
+    /// # Errors
+    /// This method returns the error of the cipher, if any. Failed
+    /// decryptions are recorded too.
+    pub fn decrypt<C: CipherTrait>(
+        &mut self,
+        cipher: &C,
+        ciphertxt: &C::Ciphertext,
+        key: &C::Key,
+    ) -> Result<C::Message, C::DecryptionError> {
+        let msg = cipher.decrypt(ciphertxt, key);
+        self.record::<C>(Operation::Decrypt, key, msg.is_ok());
+        msg
+    }
+
+    /// Get the entries, oldest first.
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Get the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the log has no entries and false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the entries for keys with the given fingerprint.
+    pub fn for_fingerprint<'a>(
+        &'a self,
+        fingerprint: &'a str,
+    ) -> impl Iterator<Item = &'a AuditEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.fingerprint == fingerprint)
+    }
+
+    /// Iterate over the entries for a key, i.e., for keys with its
+    /// fingerprint.
+    pub fn for_key<K: KeyTrait>(&self, key: &K) -> impl Iterator<Item = &AuditEntry> {
+        let fingerprint = key.fingerprint();
+        self.entries
+            .iter()
+            .filter(move |entry| entry.fingerprint == fingerprint)
+    }
+
+    /// Iterate over the entries for an operation.
+    pub fn by_operation(&self, operation: Operation) -> impl Iterator<Item = &AuditEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.operation == operation)
+    }
+
+    /// Iterate over the entries at or after `time`.
+    pub fn since(&self, time: SystemTime) -> impl Iterator<Item = &AuditEntry> {
+        self.entries.iter().filter(move |entry| entry.time >= time)
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        playfair::{self, PlayfairCipher},
+        shift::{self, ShiftCipher},
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use std::str::FromStr;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn records() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let key = playfair::Key::new(&mut rng);
+        let mut log = AuditLog::new();
+        assert!(log.is_empty());
+
+        let start = SystemTime::now();
+        let msg = playfair::Message::from_str("hidethegold").unwrap();
+        let ciphertxt = log.encrypt(&PlayfairCipher::new(), &msg, &key).unwrap();
+        assert_eq!(
+            log.decrypt(&PlayfairCipher::new(), &ciphertxt, &key)
+                .unwrap()
+                .to_string(),
+            "hidethegoldx"
+        );
+        log.record::<PlayfairCipher>(Operation::Other("export"), &key, true);
+
+        assert_eq!(log.len(), 3);
+        let operations: Vec<Operation> = log.entries().iter().map(AuditEntry::operation).collect();
+        assert_eq!(
+            operations,
+            [
+                Operation::Encrypt,
+                Operation::Decrypt,
+                Operation::Other("export")
+            ]
+        );
+        for entry in log.entries() {
+            assert_eq!(entry.cipher(), "playfair");
+            assert_eq!(entry.fingerprint(), key.fingerprint());
+            assert!(entry.succeeded());
+            assert!(entry.time() >= start);
+        }
+        assert_eq!(log.since(start).count(), 3);
+        assert_eq!(
+            log.since(SystemTime::now() + std::time::Duration::from_secs(60))
+                .count(),
+            0
+        );
+        assert_eq!(Operation::Other("export").to_string(), "export");
+
+        log.clear();
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn queries() {
+        let mut log = AuditLog::new();
+        let keys: Vec<shift::Key> = (0..3)
+            .map(|k| shift::Key::from_str(&k.to_string()).unwrap())
+            .collect();
+        let msg = shift::Message::new("attackatdawn").unwrap();
+        for (i, key) in keys.iter().enumerate() {
+            for _ in 0..=i {
+                let _ = log.encrypt(&ShiftCipher::new(), &msg, key).unwrap();
+            }
+        }
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(log.for_key(key).count(), i + 1);
+            assert_eq!(log.for_fingerprint(&key.fingerprint()).count(), i + 1);
+        }
+        assert_eq!(log.by_operation(Operation::Encrypt).count(), 6);
+        assert_eq!(log.by_operation(Operation::Decrypt).count(), 0);
+
+        // The log does not hold the keys
+        assert!(!format!("{log:?}").contains("Key"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Block ciphers, which encrypt a text a block of letters at a time, and the
+//! padding that fills out the last block.
+//!
+//! A message whose length is not a multiple of the block size must be padded
+//! before it can be encrypted with a block cipher. Every block cipher shares
+//! the same [`Padding`] implementation through
+//! [`BlockCipher::encrypt_padded`].
+use crate::{errors::CipherError, CipherTrait, Ring, RingElement};
+use rand::{CryptoRng, RngCore};
+
+/// How a message is padded to a whole number of blocks.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Padding {
+    /// Pad with the letter x, by tradition. After decryption, the reader must
+    /// recognize and remove the padding.
+    #[default]
+    PadX,
+
+    /// Pad with letters chosen uniformly at random, so that the padding does
+    /// not give away a known plaintext at the end of the message.
+    PadRandom,
+
+    /// Do not pad, and reject messages that are not a whole number of blocks.
+    Reject,
+}
+
+// The letter x, which is traditionally used as padding.
+const X: RingElement = RingElement::new(23);
+
+impl Padding {
+    /// Pad `text` to a multiple of `block` letters. The RNG is only used by
+    /// [`Padding::PadRandom`].
+    ///
+    /// # Errors
+    /// This method returns an error if the padding is [`Padding::Reject`] and
+    /// the length of the text is not a multiple of `block`.
+    ///
+    /// # Panics
+    /// This method panics if `block` is 0.
+    pub fn pad<R: RngCore + CryptoRng>(
+        self,
+        text: &mut Vec<RingElement>,
+        block: usize,
+        rng: &mut R,
+    ) -> Result<(), CipherError> {
+        let missing = (block - text.len() % block) % block;
+        match self {
+            Padding::PadX => text.extend((0..missing).map(|_| X)),
+            Padding::PadRandom => text.extend((0..missing).map(|_| RingElement::random(rng))),
+            Padding::Reject if missing > 0 => {
+                return Err(CipherError::BlockLength {
+                    length: text.len(),
+                    block,
+                })
+            }
+            Padding::Reject => (),
+        }
+        Ok(())
+    }
+}
+
+/// A cipher that encrypts a fixed number of letters at a time.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{block::{BlockCipher, Padding}, playfair::{Key, Message, PlayfairCipher}};
+/// # use std::str::FromStr;
+/// let key = Key::from_str("playfairexample").unwrap();
+/// let msg = Message::new("hidethegold").unwrap();
+/// let mut rng = rand::thread_rng();
+///
+/// let ciphertxt = PlayfairCipher::encrypt_padded(&msg, &key, Padding::PadX, &mut rng).unwrap();
+/// assert_eq!(ciphertxt.to_string(), "BMODZBXDNAGE");
+///
+/// assert!(PlayfairCipher::encrypt_padded(&msg, &key, Padding::Reject, &mut rng).is_err());
+/// ```
+pub trait BlockCipher: CipherTrait {
+    /// The number of letters in each block.
+    const BLOCK_SIZE: usize;
+
+    /// Encrypt a block of exactly [`BLOCK_SIZE`](Self::BLOCK_SIZE) letters.
+    fn encrypt_block(block: &[RingElement], key: &Self::Key) -> Vec<RingElement>;
+
+    /// Decrypt a block of exactly [`BLOCK_SIZE`](Self::BLOCK_SIZE) letters.
+    fn decrypt_block(block: &[RingElement], key: &Self::Key) -> Vec<RingElement>;
+
+    /// Pad a message according to `padding`, and encrypt it block by block.
+    ///
+    /// # Errors
+    /// This function returns an error if the padding rejects the message.
+    fn encrypt_padded<R: RngCore + CryptoRng>(
+        msg: &Self::Message,
+        key: &Self::Key,
+        padding: Padding,
+        rng: &mut R,
+    ) -> Result<Self::Ciphertext, CipherError>
+    where
+        for<'a> &'a Self::Message: IntoIterator<Item = &'a RingElement>,
+        Self::Ciphertext: FromIterator<RingElement>,
+    {
+        let mut letters: Vec<RingElement> = msg.into_iter().copied().collect();
+        padding.pad(&mut letters, Self::BLOCK_SIZE, rng)?;
+
+        Ok(letters
+            .chunks(Self::BLOCK_SIZE)
+            .flat_map(|block| Self::encrypt_block(block, key))
+            .collect())
+    }
+
+    /// Decrypt a ciphertext block by block. Any padding is left in the
+    /// message.
+    ///
+    /// # Errors
+    /// This function returns an error if the ciphertext is not a whole number
+    /// of blocks.
+    fn decrypt_blocks(
+        ciphertxt: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Result<Self::Message, CipherError>
+    where
+        for<'a> &'a Self::Ciphertext: IntoIterator<Item = &'a RingElement>,
+        Self::Message: FromIterator<RingElement>,
+    {
+        let letters: Vec<RingElement> = ciphertxt.into_iter().copied().collect();
+        if letters.len() % Self::BLOCK_SIZE != 0 {
+            return Err(CipherError::BlockLength {
+                length: letters.len(),
+                block: Self::BLOCK_SIZE,
+            });
+        }
+
+        Ok(letters
+            .chunks(Self::BLOCK_SIZE)
+            .flat_map(|block| Self::decrypt_block(block, key))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    fn letters(n: u16) -> Vec<RingElement> {
+        (0..n).map(RingElement::new).collect()
+    }
+
+    #[test]
+    fn padding() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+
+        let mut text = letters(5);
+        Padding::PadX.pad(&mut text, 3, &mut rng).unwrap();
+        assert_eq!(text[3..], [RingElement::new(3), RingElement::new(4), X]);
+
+        let mut text = letters(5);
+        Padding::PadRandom.pad(&mut text, 4, &mut rng).unwrap();
+        assert_eq!(text.len(), 8);
+        assert_eq!(text[..5], letters(5));
+
+        let mut text = letters(6);
+        for padding in [Padding::PadX, Padding::PadRandom, Padding::Reject] {
+            padding.pad(&mut text, 3, &mut rng).unwrap();
+            assert_eq!(text, letters(6));
+        }
+
+        assert_eq!(
+            Padding::Reject.pad(&mut letters(5), 2, &mut rng),
+            Err(CipherError::BlockLength {
+                length: 5,
+                block: 2
+            })
+        );
+    }
+}
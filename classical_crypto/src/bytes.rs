@@ -0,0 +1,101 @@
+//! Messages and ciphertexts of binary data, i.e., of arbitrary bytes.
+//!
+//! The plaintext and ciphertext space are the ring of integers modulo 256,
+//! &#x2124;/256&#x2124;, where each byte is encoded as its value (see the
+//! [`Byte`] Alphabet). This allows ciphers that add a key stream to
+//! the plaintext, such as the One-Time Pad, to operate on files. Bytes can
+//! also be written as strings, by reading them as Latin-1 text.
+use crate::{alphabet::Byte, Ciphertext as Ciphtxt, Message as Msg};
+
+/// A message of arbitrary bytes.
+// Notes: This is a wrapper type around the library's private representation of a message,
+// following the Latin Shift Cipher.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Message(Msg<Byte, 256>);
+
+crate::text_api!(bytes message Message);
+
+impl Message {
+    /// Create a new message from bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::bytes::Message;
+    /// let msg = Message::from_bytes(b"\x00\xffbinary");
+    ///
+    /// assert_eq!(msg.to_bytes(), b"\x00\xffbinary");
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Message {
+        Message(Msg::from_bytes(bytes))
+    }
+
+    /// Get the bytes of the message.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+}
+
+/// A ciphertext of arbitrary bytes.
+// Notes: This is a wrapper type around the library's private representation of a ciphertext,
+// following the Latin Shift Cipher.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Ciphertext(Ciphtxt<Byte, 256>);
+
+crate::text_api!(bytes ciphertext Ciphertext);
+
+impl Ciphertext {
+    /// Create a new ciphertext from bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Ciphertext {
+        Ciphertext(Ciphtxt::from_bytes(bytes))
+    }
+
+    /// Get the bytes of the ciphertext.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn round_trip() {
+        let data: Vec<u8> = (0..=255).collect();
+
+        let msg = Message::from_bytes(&data);
+        assert_eq!(msg.to_bytes(), data);
+        assert_eq!(Message::from_str(&msg.to_string()), Ok(msg));
+
+        let ciphertxt = Ciphertext::from_bytes(&data);
+        assert_eq!(ciphertxt.to_bytes(), data);
+        assert_eq!(Ciphertext::from_str(&ciphertxt.to_string()), Ok(ciphertxt));
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(Message::from_str("café").unwrap().to_bytes(), b"caf\xe9");
+        assert!(Message::from_str("€").is_err());
+        assert_eq!(
+            Message::from_chars("café".chars()).unwrap().to_bytes(),
+            b"caf\xe9"
+        );
+        assert!(Message::from_chars(['c', '€']).is_err());
+
+        // A space is a byte, so it is appended rather than skipped
+        let mut msg = Message::from_bytes(b"a");
+        msg.extend_from_str(" b").unwrap();
+        msg.push_char(' ').unwrap();
+        assert_eq!(msg.to_bytes(), b"a b ");
+        assert!(msg.push_char('€').is_err());
+
+        // Every byte is counted, not only the letters
+        let counts = Message::from_bytes(b"Aa\x00").letter_counts();
+        assert_eq!(
+            (counts.count('a'), counts.count('\0'), counts.total()),
+            (2, 1, 3)
+        );
+        assert!(Ciphertext::from_str("").is_err());
+    }
+}
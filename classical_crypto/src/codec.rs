@@ -0,0 +1,218 @@
+//! Codecs for keys, i.e., formats for writing keys as text and reading them
+//! back, so that each frontend can pick the format that suits its users.
+//!
+//! A [`KeyCodec`] for keys of type `K` encodes and decodes keys. This module
+//! provides:
+//! - [`Exported`], the format of [`KeyTrait::export`] and
+//!   [`KeyTrait::try_import`], for any key,
+//! - [`Numeric`], the number of the shift, e.g., `11`, for the Shift Cipher,
+//! - [`Letter`], the letter that `a` is shifted to, e.g., `l`, for the Shift
+//!   Cipher,
+//! - [`Keyword`], a keyword from which the square is built, for the Playfair
+//!   Cipher,
+//! - [`Armored`], the ASCII-armored format of the [`armor`] module, for any
+//!   key with a [`KeyFile`] format.
+//!
+//! Like [`export`](KeyTrait::export), no codec protects the key: anyone who
+//! can read the text can read the key.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{codec::{Armored, KeyCodec, Letter, Numeric}, shift::Key};
+//! # use std::str::FromStr;
+//! let key = Key::from_str("11").unwrap();
+//!
+//! // A frontend picks its codec once, e.g., from its configuration
+//! let codecs: [&dyn KeyCodec<Key>; 3] = [&Numeric, &Letter, &Armored];
+//! for codec in codecs {
+//!     assert_eq!(codec.decode(&codec.encode(&key)).unwrap(), key);
+//! }
+//! assert_eq!(Letter.encode(&key), "l");
+//!
+//! // Each codec only reads its own format
+//! assert!(Numeric.decode("l").is_err());
+//! ```
+use crate::{
+    armor,
+    errors::{CodecError, EncodingError},
+    keyfile::KeyFile,
+    playfair,
+    shift::{self, KeyDisplayMode},
+    KeyTrait, RingElement,
+};
+use std::str::FromStr;
+
+/// A format for writing keys of type `K` as text and reading them back.
+pub trait KeyCodec<K> {
+    /// Write the key, insecurely, in the format of the codec.
+    fn encode(&self, key: &K) -> String;
+
+    /// Read a key in the format of the codec.
+    ///
+    /// # Errors
+    /// This method returns an error if the text is not a key in the format
+    /// of the codec.
+    fn decode(&self, s: &str) -> Result<K, CodecError>;
+}
+
+/// The format of [`KeyTrait::export`] and [`KeyTrait::try_import`].
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Exported;
+
+impl<K: KeyTrait> KeyCodec<K> for Exported {
+    fn encode(&self, key: &K) -> String {
+        key.export()
+    }
+
+    fn decode(&self, s: &str) -> Result<K, CodecError> {
+        Ok(K::try_import(s)?)
+    }
+}
+
+/// Keys of the Shift Cipher as the number of the shift, between 0 and 25.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Numeric;
+
+impl KeyCodec<shift::Key> for Numeric {
+    fn encode(&self, key: &shift::Key) -> String {
+        key.export_as(KeyDisplayMode::Number)
+    }
+
+    fn decode(&self, s: &str) -> Result<shift::Key, CodecError> {
+        shift_key(s, shift::parse_number(s), "a number between 0 and 25")
+    }
+}
+
+/// Keys of the Shift Cipher as the lowercase letter that `a` is shifted to.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Letter;
+
+impl KeyCodec<shift::Key> for Letter {
+    fn encode(&self, key: &shift::Key) -> String {
+        key.export_as(KeyDisplayMode::Letter)
+    }
+
+    fn decode(&self, s: &str) -> Result<shift::Key, CodecError> {
+        shift_key(s, shift::parse_letter(s), "a lowercase letter")
+    }
+}
+
+// Make a key of the Shift Cipher from a parsed shift, or report the expected format.
+fn shift_key(
+    s: &str,
+    shift: Option<i32>,
+    expected: &'static str,
+) -> Result<shift::Key, CodecError> {
+    let shift = shift.ok_or_else(|| EncodingError::KeyFormat {
+        key: s.to_string(),
+        cipher: "shift",
+        expected,
+    })?;
+    Ok(shift::Key::from(RingElement::from_i32(shift)))
+}
+
+/// Keys of the Playfair Cipher as a keyword, see
+/// [`playfair::Key::from_str`](playfair::Key#impl-FromStr-for-Key).
+///
+/// A square is encoded as its 25 letters, row by row, which is a keyword
+/// that gives back the same square. Decoding accepts any keyword, so many
+/// keywords decode to the same key.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Keyword;
+
+impl KeyCodec<playfair::Key> for Keyword {
+    fn encode(&self, key: &playfair::Key) -> String {
+        key.export()
+    }
+
+    fn decode(&self, s: &str) -> Result<playfair::Key, CodecError> {
+        Ok(playfair::Key::from_str(s)?)
+    }
+}
+
+/// Keys in the ASCII-armored format of the [`armor`] module.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Armored;
+
+impl<K: KeyFile> KeyCodec<K> for Armored {
+    fn encode(&self, key: &K) -> String {
+        armor::encode(key)
+    }
+
+    fn decode(&self, s: &str) -> Result<K, CodecError> {
+        Ok(armor::decode(s)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alberti, errors::ArmorError};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    // Check that a codec decodes what it encodes.
+    fn round_trip<K: KeyTrait + PartialEq + std::fmt::Debug>(codec: &dyn KeyCodec<K>, key: &K) {
+        assert_eq!(&codec.decode(&codec.encode(key)).unwrap(), key);
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        for _ in 0..10 {
+            let key = shift::Key::new(&mut rng);
+            for codec in [&Exported as &dyn KeyCodec<_>, &Numeric, &Letter, &Armored] {
+                round_trip(codec, &key);
+            }
+
+            let key = playfair::Key::new(&mut rng);
+            for codec in [&Exported as &dyn KeyCodec<_>, &Keyword, &Armored] {
+                round_trip(codec, &key);
+            }
+
+            let key = alberti::Key::new(&mut rng);
+            for codec in [&Exported as &dyn KeyCodec<_>, &Armored] {
+                round_trip(codec, &key);
+            }
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let key = shift::Key::from_str("11").unwrap();
+        assert_eq!(Numeric.encode(&key), "11");
+        assert_eq!(Letter.encode(&key), "l");
+        assert_eq!(Letter.decode("l").unwrap(), key);
+
+        let key = playfair::Key::from_str("playfair example").unwrap();
+        assert_eq!(Keyword.encode(&key), "playfirexmbcdghknoqstuvwz");
+        assert_eq!(Keyword.decode("playfair example").unwrap(), key);
+    }
+
+    #[test]
+    fn errors() {
+        for s in ["l", "26", "11 (l)"] {
+            assert!(matches!(
+                KeyCodec::<shift::Key>::decode(&Numeric, s),
+                Err(CodecError::Encoding(EncodingError::KeyFormat { .. }))
+            ));
+        }
+        for s in ["11", "L", "ll"] {
+            assert!(matches!(
+                KeyCodec::<shift::Key>::decode(&Letter, s),
+                Err(CodecError::Encoding(EncodingError::KeyFormat { .. }))
+            ));
+        }
+        assert!(matches!(
+            KeyCodec::<playfair::Key>::decode(&Keyword, "Playfair!"),
+            Err(CodecError::Encoding(EncodingError::KeyFormat { .. }))
+        ));
+        assert!(matches!(
+            KeyCodec::<shift::Key>::decode(&Armored, "11"),
+            Err(CodecError::Armor(ArmorError::MissingHeader))
+        ));
+    }
+}
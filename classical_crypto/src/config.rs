@@ -0,0 +1,454 @@
+//! Loading alphabets and cipher parameters from TOML configuration files, so
+//! that, e.g., an instructor can distribute a setup for a class without
+//! writing Rust.
+//!
+//! A configuration has three optional tables:
+//! - `[alphabet]`: either a `preset` (`"latin"`, `"classical-latin"`,
+//!   `"alphanumeric"` or `"printable-ascii"`) or the `chars` of a custom
+//!   alphabet, optionally mixed with a `keyword`.
+//! - `[cipher]`: the `name` of a cipher (`"shift"` or `"playfair"`) together
+//!   with its key, i.e., the `key` of the Shift Cipher or the `keyword` of the
+//!   Playfair Cipher. The Shift Cipher needs an alphabet of 26 letters, and the
+//!   Playfair Cipher has its own square, so it takes no alphabet.
+//! - `[policy]`: how plaintexts are prepared, i.e., the handling of `digits`
+//!   (`"keep"`, `"reject"`, `"strip"` or `"spell-out"`), the `folding` of
+//!   letters (`"none"` or `"classical-latin"`), the `parsing` of ciphertexts
+//!   (`"strict"`, `"lenient"` or `"permissive"`), and the `group-size` and
+//!   `case` (`"stinson"`, `"preserve-input"` or `"lowercase"`) used to display
+//!   messages and ciphertexts.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{config::Config, policy::DigitPolicy};
+//! let config = Config::from_toml(
+//!     r#"
+//!     [alphabet]
+//!     preset = "latin"
+//!     keyword = "zebras"
+//!
+//!     [cipher]
+//!     name = "shift"
+//!     key = 3
+//!
+//!     [policy]
+//!     digits = "spell-out"
+//!     group-size = 5
+//!     "#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(config.alphabet().unwrap().size(), 26);
+//! assert_eq!(config.policy().digits(), DigitPolicy::SpellOut);
+//! assert_eq!(config.group_size(), Some(5));
+//!
+//! let cipher = config.shift_cipher().unwrap();
+//! let key = config.cipher().unwrap().shift_key().unwrap();
+//! let ciphertxt = cipher.encrypt_text("agent 7", &key).unwrap();
+//! assert_eq!(ciphertxt, "DJAQW FAYAQ");
+//! assert_eq!(cipher.decrypt_text(&ciphertxt, &key).unwrap(), "agentseven");
+//! ```
+use crate::{
+    alphabet::{Alphanumeric, ClassicalLatin, CustomAlphabet, Latin, PrintableAscii},
+    errors::ConfigError,
+    playfair,
+    policy::{CasePolicy, CipherSettings, DigitPolicy, Folding, MessagePolicy, ParsePolicy},
+    shift,
+};
+use serde::Deserialize;
+use std::{fs, path::Path, str::FromStr};
+
+/// A configuration of an alphabet, a cipher, and a message policy, all of
+/// which are optional. See the [module documentation](self) for the format.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Config {
+    alphabet: Option<CustomAlphabet>,
+    cipher: Option<CipherConfig>,
+    policy: MessagePolicy,
+    parsing: ParsePolicy,
+    group_size: Option<usize>,
+    case: CasePolicy,
+}
+
+/// The cipher and key of a [`Config`].
+///
+/// Keys are validated when the configuration is loaded, but are created on
+/// demand, to discourage making copies of secrets.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(tag = "name", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum CipherConfig {
+    /// The Shift Cipher with the given key, from 0 to 25.
+    Shift {
+        /// The key.
+        key: u8,
+    },
+    /// The Playfair Cipher with the square given by a keyword.
+    Playfair {
+        /// The keyword.
+        keyword: String,
+    },
+}
+
+impl CipherConfig {
+    /// Get the key of the Shift Cipher, or `None` for other ciphers.
+    pub fn shift_key(&self) -> Option<shift::Key> {
+        match self {
+            CipherConfig::Shift { key } => shift::Key::from_str(&key.to_string()).ok(),
+            _ => None,
+        }
+    }
+
+    /// Get the key of the Playfair Cipher, or `None` for other ciphers.
+    pub fn playfair_key(&self) -> Option<playfair::Key> {
+        match self {
+            CipherConfig::Playfair { keyword } => playfair::Key::from_str(keyword).ok(),
+            _ => None,
+        }
+    }
+
+    // Check that the key is valid.
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self {
+            CipherConfig::Shift { key } => shift::Key::from_str(&key.to_string()).map(|_| ()),
+            CipherConfig::Playfair { keyword } => playfair::Key::from_str(keyword).map(|_| ()),
+        }
+        .map_err(ConfigError::from)
+    }
+
+    // Check that the cipher can use the alphabet.
+    fn check_alphabet(&self, alphabet: &CustomAlphabet) -> Result<(), ConfigError> {
+        let cipher = match self {
+            CipherConfig::Shift { .. } if alphabet.size() == 26 => return Ok(()),
+            CipherConfig::Shift { .. } => "shift",
+            CipherConfig::Playfair { .. } => "playfair",
+        };
+        Err(ConfigError::IncompatibleAlphabet {
+            cipher,
+            size: alphabet.size(),
+        })
+    }
+}
+
+// The alphabets that can be selected by name.
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Preset {
+    Latin,
+    ClassicalLatin,
+    Alphanumeric,
+    PrintableAscii,
+}
+
+// The configuration as written in the file, before validation.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    alphabet: Option<RawAlphabet>,
+    cipher: Option<CipherConfig>,
+    #[serde(default)]
+    policy: RawPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAlphabet {
+    preset: Option<Preset>,
+    chars: Option<String>,
+    keyword: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RawPolicy {
+    #[serde(default)]
+    digits: DigitPolicy,
+    #[serde(default)]
+    folding: Folding,
+    #[serde(default)]
+    parsing: ParsePolicy,
+    group_size: Option<usize>,
+    #[serde(default)]
+    case: CasePolicy,
+}
+
+impl Config {
+    /// Parse a configuration from TOML.
+    ///
+    /// # Errors
+    /// This function returns an error if the input is not valid TOML, has
+    /// unknown or missing fields, if the alphabet or key is invalid, or if the
+    /// cipher cannot use the alphabet.
+    pub fn from_toml(toml: &str) -> Result<Self, ConfigError> {
+        let raw: RawConfig = toml::from_str(toml)?;
+
+        let alphabet = raw.alphabet.map(RawAlphabet::build).transpose()?;
+        if let Some(cipher) = &raw.cipher {
+            cipher.validate()?;
+            if let Some(alphabet) = &alphabet {
+                cipher.check_alphabet(alphabet)?;
+            }
+        }
+        if raw.policy.group_size == Some(0) {
+            return Err(ConfigError::GroupSize);
+        }
+
+        Ok(Config {
+            alphabet,
+            cipher: raw.cipher,
+            policy: MessagePolicy::new()
+                .with_digits(raw.policy.digits)
+                .with_folding(raw.policy.folding),
+            parsing: raw.policy.parsing,
+            group_size: raw.policy.group_size,
+            case: raw.policy.case,
+        })
+    }
+
+    /// Load a configuration from a TOML file.
+    ///
+    /// # Errors
+    /// This function returns an error if the file cannot be read, or for the
+    /// reasons given in [`Config::from_toml`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Get the alphabet, if one is configured.
+    pub fn alphabet(&self) -> Option<&CustomAlphabet> {
+        self.alphabet.as_ref()
+    }
+
+    /// Get the cipher, if one is configured.
+    pub fn cipher(&self) -> Option<&CipherConfig> {
+        self.cipher.as_ref()
+    }
+
+    /// Get the message policy. This is the default policy if none is
+    /// configured.
+    pub fn policy(&self) -> MessagePolicy {
+        self.policy
+    }
+
+    /// Get how strictly ciphertexts are parsed. This is the default, lenient
+    /// parsing, if none is configured.
+    pub fn parsing(&self) -> ParsePolicy {
+        self.parsing
+    }
+
+    /// Get the size of the groups that ciphertexts are displayed in, if any.
+    pub fn group_size(&self) -> Option<usize> {
+        self.group_size
+    }
+
+    /// Get how the letters of messages and ciphertexts are cased when
+    /// displayed. This is the default, Stinson's convention, if none is
+    /// configured.
+    pub fn case(&self) -> CasePolicy {
+        self.case
+    }
+
+    /// Get the message policy, parse policy, case and group size together, as
+    /// the settings of a cipher instance.
+    pub fn settings(&self) -> CipherSettings {
+        let settings = CipherSettings::new()
+            .with_policy(self.policy)
+            .with_parsing(self.parsing)
+            .with_case(self.case);
+        match self.group_size {
+            Some(size) => settings.with_group_size(size),
+            None => settings,
+        }
+    }
+
+    /// Create the Shift Cipher on the alphabet, or on the Latin Alphabet if
+    /// none is configured, with the [settings](Config::settings) of the
+    /// configuration.
+    ///
+    /// # Errors
+    /// This method returns an error if the alphabet does not have 26 letters.
+    pub fn shift_cipher(&self) -> Result<shift::ShiftCipher, ConfigError> {
+        let cipher = match &self.alphabet {
+            Some(alphabet) => shift::ShiftCipher::with_alphabet(alphabet.clone())?,
+            None => shift::ShiftCipher::new(),
+        };
+        Ok(cipher.with_settings(self.settings()))
+    }
+}
+
+impl RawAlphabet {
+    // Build the alphabet, mixed with the keyword if there is one.
+    fn build(self) -> Result<CustomAlphabet, ConfigError> {
+        let alphabet = match (self.preset, self.chars) {
+            (Some(preset), None) => match preset {
+                Preset::Latin => CustomAlphabet::from_alphabet::<Latin, 26>(),
+                Preset::ClassicalLatin => CustomAlphabet::from_alphabet::<ClassicalLatin, 23>(),
+                Preset::Alphanumeric => CustomAlphabet::from_alphabet::<Alphanumeric, 36>(),
+                Preset::PrintableAscii => CustomAlphabet::from_alphabet::<PrintableAscii, 95>(),
+            },
+            (None, Some(chars)) => {
+                CustomAlphabet::from_chars(&chars.chars().collect::<Vec<char>>())?
+            }
+            _ => return Err(ConfigError::AlphabetSource),
+        };
+
+        match self.keyword {
+            Some(keyword) => Ok(alphabet.keyword_mixed(&keyword)?),
+            None => Ok(alphabet),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{AlphabetError, EncodingError};
+
+    #[test]
+    fn full() {
+        let config = Config::from_toml(
+            r#"
+            [alphabet]
+            preset = "latin"
+            keyword = "zebras"
+
+            [cipher]
+            name = "shift"
+            key = 11
+
+            [policy]
+            digits = "reject"
+            folding = "classical-latin"
+            parsing = "strict"
+            case = "lowercase"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.alphabet().unwrap().to_string(),
+            "zebrascdfghijklmnopqtuvwxy"
+        );
+        let cipher = config.cipher().unwrap();
+        assert!(cipher.playfair_key().is_none());
+        let key = cipher.shift_key().unwrap();
+        assert_eq!(
+            config.policy(),
+            MessagePolicy::new()
+                .with_digits(DigitPolicy::Reject)
+                .with_folding(Folding::ClassicalLatin)
+        );
+        assert_eq!(config.group_size(), None);
+        assert_eq!(config.case(), CasePolicy::Lowercase);
+        assert_eq!(config.parsing(), ParsePolicy::Strict);
+        assert_eq!(
+            config.settings(),
+            CipherSettings::new()
+                .with_policy(config.policy())
+                .with_parsing(ParsePolicy::Strict)
+                .with_case(CasePolicy::Lowercase)
+        );
+
+        // The cipher uses the mixed alphabet and the settings
+        let shift = config.shift_cipher().unwrap();
+        assert_eq!(shift.alphabet(), config.alphabet().unwrap());
+        assert_eq!(shift.settings(), config.settings());
+        let ciphertxt = shift.encrypt_text("julius", &key).unwrap();
+        assert_eq!(ciphertxt, "vdyvdn");
+        // Strict parsing only accepts ciphertexts in ALL CAPS, however they are displayed
+        assert!(shift.decrypt_text(&ciphertxt, &key).is_err());
+        assert_eq!(shift.decrypt_text("VDYVDN", &key).unwrap(), "ivlivs");
+        assert!(shift.encrypt_text("agent 7", &key).is_err());
+    }
+
+    #[test]
+    fn playfair() {
+        let config = Config::from_toml(
+            r#"
+            [cipher]
+            name = "playfair"
+            keyword = "playfair example"
+            "#,
+        )
+        .unwrap();
+
+        let cipher = config.cipher().unwrap();
+        assert!(cipher.playfair_key().is_some());
+        assert!(cipher.shift_key().is_none());
+        assert_eq!(config.alphabet(), None);
+    }
+
+    #[test]
+    fn shift_cipher() {
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(
+            config.shift_cipher().unwrap().alphabet(),
+            shift::ShiftCipher::new().alphabet()
+        );
+
+        // Without a cipher, the alphabet is not checked until the cipher is created
+        let config = Config::from_toml("[alphabet]\nchars = \"ACGT\"").unwrap();
+        assert!(matches!(
+            config.shift_cipher(),
+            Err(ConfigError::Alphabet(AlphabetError::WrongSize {
+                size: 4,
+                expected: 26
+            }))
+        ));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Config::from_toml("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Config::from_toml("[alphabet]\nchars = \"ABA\""),
+            Err(ConfigError::Alphabet(AlphabetError::Repeated('A')))
+        ));
+        assert!(matches!(
+            Config::from_toml("[alphabet]\npreset = \"latin\"\nchars = \"AB\""),
+            Err(ConfigError::AlphabetSource)
+        ));
+        assert!(matches!(
+            Config::from_toml("[alphabet]\npreset = \"latin\"\nkeyword = \"Zebra\""),
+            Err(ConfigError::Alphabet(AlphabetError::NotInAlphabet('Z')))
+        ));
+        assert!(matches!(
+            Config::from_toml("[cipher]\nname = \"shift\"\nkey = 26"),
+            Err(ConfigError::Cipher(EncodingError::KeyFormat { .. }))
+        ));
+        assert!(matches!(
+            Config::from_toml("[alphabet]\nchars = \"ACGT\"\n[cipher]\nname = \"shift\"\nkey = 3"),
+            Err(ConfigError::IncompatibleAlphabet {
+                cipher: "shift",
+                size: 4
+            })
+        ));
+        assert!(matches!(
+            Config::from_toml(
+                "[alphabet]\npreset = \"latin\"\n[cipher]\nname = \"playfair\"\nkeyword = \"zebras\""
+            ),
+            Err(ConfigError::IncompatibleAlphabet {
+                cipher: "playfair",
+                size: 26
+            })
+        ));
+        assert!(matches!(
+            Config::from_toml("[cipher]\nname = \"enigma\""),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            Config::from_toml("[policy]\ngroup-size = 0"),
+            Err(ConfigError::GroupSize)
+        ));
+        assert!(matches!(
+            Config::from_toml("[policy]\ncolour = \"red\""),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            Config::load("does/not/exist.toml"),
+            Err(ConfigError::Io(_))
+        ));
+    }
+}
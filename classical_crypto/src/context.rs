@@ -0,0 +1,237 @@
+//! Context for keys: which cipher a key belongs to, when it was created, what
+//! it is called, and where it came from.
+//!
+//! A bare key, e.g., a [`shift::Key`](crate::shift::Key), is just key
+//! material, and nothing stops it from being used with the wrong cipher or
+//! for the wrong purpose. A [`ContextKey`] carries a [`KeyContext`] along with
+//! the key. Its [`Debug`](fmt::Debug) and [`Display`](fmt::Display)
+//! implementations show the context but never the key, so that a key with
+//! context can be logged safely.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{context::{ContextKey, KeyOrigin}, shift::ShiftCipher};
+//! use rand::thread_rng;
+//!
+//! let key = ContextKey::<ShiftCipher>::new(&mut thread_rng()).with_label("alice");
+//! assert_eq!(key.context().cipher(), "shift");
+//! assert_eq!(key.context().origin(), KeyOrigin::Generated);
+//! assert!(key.to_string().starts_with("shift key \"alice\", generated at "));
+//! ```
+use crate::{errors::EncodingError, CipherTrait, KeyTrait};
+use rand::{CryptoRng, RngCore};
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Where a key came from.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum KeyOrigin {
+    /// The key was picked at random from the key space.
+    Generated,
+    /// The key was read from outside the program, e.g., typed in by a person
+    /// or loaded from a file.
+    Imported,
+}
+
+impl fmt::Display for KeyOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyOrigin::Generated => write!(f, "generated"),
+            KeyOrigin::Imported => write!(f, "imported"),
+        }
+    }
+}
+
+/// The context of a key: the name of its cipher, when it was created, an
+/// optional label for people, and where it came from.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct KeyContext {
+    cipher: &'static str,
+    created: SystemTime,
+    label: Option<String>,
+    origin: KeyOrigin,
+}
+
+impl KeyContext {
+    /// Create the context of a key of the named cipher that is created now,
+    /// without a label.
+    pub fn new(cipher: &'static str, origin: KeyOrigin) -> Self {
+        KeyContext {
+            cipher,
+            created: SystemTime::now(),
+            label: None,
+            origin,
+        }
+    }
+
+    /// Get the name of the cipher, as in
+    /// [`CipherInfo::name`](crate::CipherInfo::name).
+    pub fn cipher(&self) -> &'static str {
+        self.cipher
+    }
+
+    /// Get the time at which the key was created.
+    pub fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    /// Get the label, if any.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Get where the key came from.
+    pub fn origin(&self) -> KeyOrigin {
+        self.origin
+    }
+
+    /// Set the label.
+    pub fn set_label(&mut self, label: &str) {
+        self.label = Some(label.to_string());
+    }
+}
+
+impl fmt::Display for KeyContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} key", self.cipher)?;
+        if let Some(label) = &self.label {
+            write!(f, " {label:?}")?;
+        }
+
+        // Times before the epoch only arise from a badly set clock
+        let seconds = self
+            .created
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        write!(f, ", {} at {seconds} s after the Unix epoch", self.origin)
+    }
+}
+
+/// A key of the cipher `C` together with its [`KeyContext`].
+///
+/// The key is only reachable through [`key`](Self::key) and
+/// [`into_key`](Self::into_key): formatting a `ContextKey` shows the context
+/// and nothing about the key.
+pub struct ContextKey<C: CipherTrait> {
+    key: C::Key,
+    context: KeyContext,
+}
+
+impl<C: CipherTrait> ContextKey<C> {
+    /// Pick a new key uniformly at random, see [`KeyTrait::new`].
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        ContextKey {
+            key: C::Key::new(rng),
+            context: KeyContext::new(C::info().name, KeyOrigin::Generated),
+        }
+    }
+
+    /// Import a key, see [`KeyTrait::try_import`].
+    ///
+    /// # Errors
+    /// This function returns an error if the string does not represent a key.
+    pub fn try_import(s: &str) -> Result<Self, EncodingError> {
+        Ok(ContextKey {
+            key: C::Key::try_import(s)?,
+            context: KeyContext::new(C::info().name, KeyOrigin::Imported),
+        })
+    }
+
+    /// Attach context to a key that was made elsewhere, e.g., with
+    /// [`KeyTrait::derive_from_passphrase`].
+    pub fn from_key(key: C::Key, origin: KeyOrigin) -> Self {
+        ContextKey {
+            key,
+            context: KeyContext::new(C::info().name, origin),
+        }
+    }
+
+    /// Set the label of the key.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.context.set_label(label);
+        self
+    }
+
+    /// Get the key.
+    pub fn key(&self) -> &C::Key {
+        &self.key
+    }
+
+    /// Get the context of the key.
+    pub fn context(&self) -> &KeyContext {
+        &self.context
+    }
+
+    /// Drop the context and return the key.
+    pub fn into_key(self) -> C::Key {
+        self.key
+    }
+}
+
+impl<C: CipherTrait> fmt::Debug for ContextKey<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContextKey")
+            .field("context", &self.context)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: CipherTrait> fmt::Display for ContextKey<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.context.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        playfair::PlayfairCipher,
+        shift::{self, ShiftCipher},
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use std::time::Duration;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn context() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let key = ContextKey::<PlayfairCipher>::new(&mut rng);
+        assert_eq!(key.context().cipher(), "playfair");
+        assert_eq!(key.context().origin(), KeyOrigin::Generated);
+        assert_eq!(key.context().label(), None);
+        assert!(key.context().created() <= SystemTime::now());
+
+        let key = ContextKey::<ShiftCipher>::try_import("3")
+            .unwrap()
+            .with_label("bob");
+        assert_eq!(key.context().origin(), KeyOrigin::Imported);
+        assert_eq!(key.context().label(), Some("bob"));
+        assert_eq!(key.into_key(), shift::Key::try_import("3").unwrap());
+
+        assert!(ContextKey::<ShiftCipher>::try_import("26").is_err());
+    }
+
+    #[test]
+    fn formatting_hides_key() {
+        let mut key = ContextKey::<ShiftCipher>::from_key(
+            shift::Key::try_import("17").unwrap(),
+            KeyOrigin::Imported,
+        )
+        .with_label("carol");
+        key.context.created = UNIX_EPOCH + Duration::from_secs(1_000);
+
+        assert_eq!(
+            key.to_string(),
+            "shift key \"carol\", imported at 1000 s after the Unix epoch"
+        );
+        let debug = format!("{key:?}");
+        assert!(debug.starts_with("ContextKey { context: KeyContext { cipher: \"shift\""));
+        assert!(!debug.contains("17"));
+    }
+}
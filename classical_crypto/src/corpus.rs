@@ -0,0 +1,531 @@
+//! Statistics of natural languages, used as reference values by the tools in
+//! the [`analysis`](crate::analysis) module.
+//!
+//! We embed the English letter frequencies given by Stinson, as well as a short
+//! sample of English text from which we compute n-gram statistics. For other
+//! languages (or for other kinds of English text, say, text messages rather
+//! than novels), you can build your own [`LanguageModel`] or [`NgramModel`]
+//! from a sample of text, i.e., a _corpus_.
+use crate::{
+    alphabet::Latin,
+    analysis::{FrequencyTable, NgramTable},
+    errors::CorpusError,
+    RingElement,
+};
+use std::{collections::HashMap, fs::File, io::Read, path::Path};
+
+/// A short sample of English prose, used to compute the n-gram statistics of
+/// [`NgramModel::english`].
+pub const ENGLISH_SAMPLE: &str = include_str!("../data/english.txt");
+
+/// The probabilities of occurrence of the 26 letters of the Latin Alphabet in
+/// English text, as given in Table 2.1 of Stinson's _Cryptography: Theory and
+/// Practice_ (4th Edition), based on the work of Beker and Piper.
+pub const ENGLISH_LETTER_PROBABILITIES: [(char, f64); 26] = [
+    ('a', 0.082),
+    ('b', 0.015),
+    ('c', 0.028),
+    ('d', 0.043),
+    ('e', 0.127),
+    ('f', 0.022),
+    ('g', 0.020),
+    ('h', 0.061),
+    ('i', 0.070),
+    ('j', 0.002),
+    ('k', 0.008),
+    ('l', 0.040),
+    ('m', 0.024),
+    ('n', 0.067),
+    ('o', 0.075),
+    ('p', 0.019),
+    ('q', 0.001),
+    ('r', 0.060),
+    ('s', 0.063),
+    ('t', 0.091),
+    ('u', 0.028),
+    ('v', 0.010),
+    ('w', 0.023),
+    ('x', 0.001),
+    ('y', 0.020),
+    ('z', 0.001),
+];
+
+/// The letter probabilities of a language.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::corpus::LanguageModel;
+/// let english = LanguageModel::english();
+/// assert_eq!(english.probability('e'), 0.127);
+///
+/// // Build a model for another language from a sample text.
+/// let hawaiian = LanguageModel::from_text(
+///     "hawaiian",
+///     "Aloha mai kakou. Pehea oukou? Maikai no au, mahalo.",
+/// )
+/// .unwrap();
+///
+/// // Hawaiian has no letter 't', so text with many 't's looks more like English.
+/// assert!(english.log_likelihood("thatthing") > hawaiian.log_likelihood("thatthing"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageModel {
+    name: String,
+    // Sorted by symbol
+    probabilities: Vec<(char, f64)>,
+}
+
+impl LanguageModel {
+    /// The probability assigned to symbols that do not occur in the model when
+    /// computing log-likelihoods, so that a single unexpected symbol does not
+    /// rule out an otherwise plausible text.
+    const UNSEEN_PROBABILITY: f64 = 1e-5;
+
+    /// Get the model for English, using the letter probabilities in
+    /// [`ENGLISH_LETTER_PROBABILITIES`].
+    pub fn english() -> Self {
+        LanguageModel {
+            name: "english".to_string(),
+            probabilities: ENGLISH_LETTER_PROBABILITIES.to_vec(),
+        }
+    }
+
+    /// Build a model from a sample text.
+    ///
+    /// As with [`FrequencyTable::new`], letters are counted
+    /// case-insensitively, and all other characters are ignored.
+    ///
+    /// # Errors
+    /// This function returns an error if the text does not contain any
+    /// letters.
+    pub fn from_text(name: &str, text: &str) -> Result<Self, CorpusError> {
+        let table = FrequencyTable::new(text);
+        if table.total() == 0 {
+            return Err(CorpusError::Empty);
+        }
+
+        Ok(LanguageModel {
+            name: name.to_string(),
+            probabilities: table.proportions(),
+        })
+    }
+
+    /// Build a model from a sample text read from a reader.
+    ///
+    /// # Errors
+    /// This function returns an error if reading fails, if the text read is
+    /// not valid UTF-8, or if the text does not contain any letters.
+    pub fn from_reader(name: &str, mut reader: impl Read) -> Result<Self, CorpusError> {
+        let mut text = String::new();
+        let _ = reader.read_to_string(&mut text)?;
+        LanguageModel::from_text(name, &text)
+    }
+
+    /// Build a model from a sample text read from a file.
+    ///
+    /// # Errors
+    /// This function returns an error if the file cannot be read, if it is not
+    /// valid UTF-8, or if the text does not contain any letters.
+    pub fn from_file(name: &str, path: impl AsRef<Path>) -> Result<Self, CorpusError> {
+        LanguageModel::from_reader(name, File::open(path)?)
+    }
+
+    /// Get the name of the language.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the probability of a symbol, which is 0 for symbols that are not
+    /// part of the model.
+    pub fn probability(&self, symbol: char) -> f64 {
+        self.probabilities
+            .binary_search_by(|(c, _)| c.cmp(&symbol))
+            .map(|i| self.probabilities[i].1)
+            .unwrap_or_default()
+    }
+
+    /// Get the probabilities of all the symbols in the model, ordered by
+    /// symbol.
+    ///
+    /// This is the form expected by [`FrequencyTable::distance`].
+    pub fn probabilities(&self) -> &[(char, f64)] {
+        &self.probabilities
+    }
+
+    /// Compute the log-likelihood of a text, i.e., the natural logarithm of
+    /// the probability that the model assigns to the letters of the text, each
+    /// chosen independently.
+    ///
+    /// The higher the log-likelihood, the more the text resembles the
+    /// language. As with [`FrequencyTable::new`], letters are compared
+    /// case-insensitively and all other characters are ignored. Letters that
+    /// are not part of the model are assigned a small, nonzero probability.
+    pub fn log_likelihood(&self, text: &str) -> f64 {
+        text.chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_lowercase)
+            .map(|c| match self.probability(c) {
+                p if p > 0.0 => p.ln(),
+                _ => LanguageModel::UNSEEN_PROBABILITY.ln(),
+            })
+            .sum()
+    }
+}
+
+/// A classifier that detects which of several languages a text is most likely
+/// written in.
+///
+/// This is useful when brute forcing a ciphertext whose origin is unknown: we
+/// can decrypt under each key, and check whether any candidate plaintext
+/// resembles any of the languages we know about.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::corpus::{LanguageDetector, LanguageModel};
+/// let german = LanguageModel::from_text(
+///     "german",
+///     "Zwei flinke Boxer jagen die quirlige Eva und ihren Mops durch Sylt. \
+///      Franz jagt im komplett verwahrlosten Taxi quer durch Bayern.",
+/// )
+/// .unwrap();
+/// let detector = LanguageDetector::new(vec![LanguageModel::english(), german]);
+///
+/// assert_eq!(detector.detect("wewillmeetatmidnight").unwrap().name(), "english");
+/// assert_eq!(detector.detect("zwoelfboxkaempferjagenviktorquer").unwrap().name(), "german");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LanguageDetector {
+    models: Vec<LanguageModel>,
+}
+
+impl LanguageDetector {
+    /// Create a detector for the given languages.
+    pub fn new(models: Vec<LanguageModel>) -> Self {
+        LanguageDetector { models }
+    }
+
+    /// Add a language to the detector.
+    pub fn add(&mut self, model: LanguageModel) {
+        self.models.push(model);
+    }
+
+    /// Get the languages known to the detector.
+    pub fn models(&self) -> &[LanguageModel] {
+        &self.models
+    }
+
+    /// Score a text against each language, returning the languages together
+    /// with the log-likelihood of the text under each (see
+    /// [`LanguageModel::log_likelihood`]), from most to least likely.
+    ///
+    /// Languages that are equally likely are kept in the order they were added
+    /// to the detector.
+    pub fn scores(&self, text: &str) -> Vec<(&LanguageModel, f64)> {
+        let mut scores: Vec<(&LanguageModel, f64)> = self
+            .models
+            .iter()
+            .map(|model| (model, model.log_likelihood(text)))
+            .collect();
+        scores.sort_by(|(_, s0), (_, s1)| s1.total_cmp(s0));
+        scores
+    }
+
+    /// Get the language that the text is most likely written in, or `None` if
+    /// the detector does not know any languages.
+    pub fn detect(&self, text: &str) -> Option<&LanguageModel> {
+        self.scores(text).first().map(|&(model, _)| model)
+    }
+}
+
+/// The n-gram log-probabilities of a language.
+///
+/// Where a [`LanguageModel`] treats the letters of a text as independent, an
+/// n-gram model looks at runs of _n_ letters, so it can tell that "th" is far
+/// more likely in English than "ht", even though both contain the same
+/// letters. This makes it a much better measure of how closely a text
+/// resembles a language, which is what solvers for ciphers that scramble the
+/// order of letters, such as the Playfair Cipher, need.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::corpus::NgramModel;
+/// let bigrams = NgramModel::english(2);
+///
+/// assert!(bigrams.log_likelihood("the") > bigrams.log_likelihood("hte"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NgramModel {
+    n: usize,
+    log_probabilities: HashMap<String, f64>,
+    // The log-probability of n-grams that do not occur in the corpus.
+    floor: f64,
+    // The log-probabilities of all n-grams of the Latin Alphabet, indexed by reading their letters
+    // as the digits of a number in base 26, so that solvers can score texts without hashing
+    // strings. Empty if there are too many such n-grams to list.
+    latin: Vec<f64>,
+}
+
+impl NgramModel {
+    /// Get the n-gram model for English computed from [`ENGLISH_SAMPLE`].
+    ///
+    /// The sample is short, so the model is only reasonable for small `n`,
+    /// i.e., for bigrams and trigrams.
+    ///
+    /// # Panics
+    /// This function panics if `n` is 0.
+    pub fn english(n: usize) -> Self {
+        NgramModel::from_text(ENGLISH_SAMPLE, n).expect("The English sample should be long enough")
+    }
+
+    /// Build an n-gram model from a sample text.
+    ///
+    /// As with [`NgramTable::new`], letters are counted case-insensitively and
+    /// all other characters are ignored. N-grams that do not occur in the
+    /// sample are assigned a probability of 1/100 of an n-gram that occurs
+    /// once.
+    ///
+    /// # Errors
+    /// This function returns an error if the text does not contain at least
+    /// `n` letters.
+    ///
+    /// # Panics
+    /// This function panics if `n` is 0.
+    pub fn from_text(text: &str, n: usize) -> Result<Self, CorpusError> {
+        let table = NgramTable::new(text, n);
+        if table.total() == 0 {
+            return Err(CorpusError::Empty);
+        }
+
+        let total = table.total() as f64;
+        let log_probabilities: HashMap<String, f64> = table
+            .sorted()
+            .into_iter()
+            .map(|(ngram, count)| (ngram, (count as f64 / total).ln()))
+            .collect();
+        let floor = (0.01 / total).ln();
+
+        let mut latin = Vec::new();
+        if n <= MAX_LATIN_N {
+            latin = vec![floor; 26_usize.pow(n as u32)];
+            for (ngram, &log_probability) in &log_probabilities {
+                // N-grams with other letters, e.g., accented letters, are left out
+                let index = ngram.chars().try_fold(0, |index, c| {
+                    RingElement::<26>::from_char::<Latin>(c)
+                        .map(|x| 26 * index + usize::from(x.into_inner()))
+                });
+                if let Some(index) = index {
+                    latin[index] = log_probability;
+                }
+            }
+        }
+
+        Ok(NgramModel {
+            n,
+            log_probabilities,
+            floor,
+            latin,
+        })
+    }
+
+    /// Get the length of the n-grams in the model.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Compute the log-likelihood of a text, i.e., the sum of the
+    /// log-probabilities of its (overlapping) n-grams.
+    ///
+    /// The higher the log-likelihood, the more the text resembles the
+    /// language. Letters are compared case-insensitively and all other
+    /// characters are ignored.
+    pub fn log_likelihood(&self, text: &str) -> f64 {
+        let letters: Vec<char> = text
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        let mut ngram = String::with_capacity(4 * self.n);
+        letters
+            .windows(self.n)
+            .map(|window| {
+                ngram.clear();
+                ngram.extend(window);
+                self.log_probabilities
+                    .get(&ngram)
+                    .copied()
+                    .unwrap_or(self.floor)
+            })
+            .sum()
+    }
+
+    // Compute the log-likelihood of a text over the Latin Alphabet given by its symbols, which is
+    // the log-likelihood of the text written out, e.g., to score decryptions in a solver.
+    pub(crate) fn latin_log_likelihood(&self, symbols: &[RingElement]) -> f64 {
+        if self.latin.is_empty() {
+            let text: String = symbols.iter().map(|x| x.to_char_lossy::<Latin>()).collect();
+            return self.log_likelihood(&text);
+        }
+
+        // The index of each n-gram is computed from the index of the previous one, by dropping its
+        // first letter and appending the next letter
+        let mut index = 0;
+        let mut log_likelihood = 0.0;
+        for (i, x) in symbols.iter().enumerate() {
+            index = (26 * index + usize::from(x.into_inner())) % self.latin.len();
+            if i + 1 >= self.n {
+                log_likelihood += self.latin[index];
+            }
+        }
+        log_likelihood
+    }
+}
+
+// The largest n for which an n-gram model lists the log-probabilities of all n-grams of the Latin
+// Alphabet, i.e., 26^4 values.
+const MAX_LATIN_N: usize = 4;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english() {
+        let english = LanguageModel::english();
+
+        assert_eq!(english.name(), "english");
+        assert_eq!(english.probability('t'), 0.091);
+        assert_eq!(english.probability('?'), 0.0);
+        assert_eq!(english.probabilities().len(), 26);
+
+        // The probabilities sum to approximately 1
+        let sum: f64 = english.probabilities().iter().map(|(_, p)| p).sum();
+        assert!((sum - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn from_text() {
+        let model = LanguageModel::from_text("tiny", "Abba!").unwrap();
+
+        assert_eq!(model.name(), "tiny");
+        assert_eq!(model.probabilities(), &[('a', 0.5), ('b', 0.5)]);
+        assert_eq!(model.log_likelihood("ab ba"), 4.0 * 0.5_f64.ln());
+        // Unseen letters are unlikely, but not impossible
+        assert!(model.log_likelihood("c").is_finite());
+        assert!(model.log_likelihood("c") < model.log_likelihood("a"));
+    }
+
+    #[test]
+    fn from_text_error() {
+        assert!(matches!(
+            LanguageModel::from_text("empty", " 123 !"),
+            Err(CorpusError::Empty)
+        ));
+    }
+
+    #[test]
+    fn from_reader() {
+        let reader = "Ciao a tutti".as_bytes();
+        let model = LanguageModel::from_reader("italian", reader).unwrap();
+
+        assert_eq!(model.probability('t'), 0.3);
+        assert!(matches!(
+            LanguageModel::from_reader("italian", [0xff, 0xfe].as_slice()),
+            Err(CorpusError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn from_file_error() {
+        assert!(matches!(
+            LanguageModel::from_file("missing", "this/file/does/not/exist.txt"),
+            Err(CorpusError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn log_likelihood_prefers_english() {
+        let english = LanguageModel::english();
+
+        // Example 1.1, Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
+        assert!(
+            english.log_likelihood("wewillmeetatmidnight")
+                > english.log_likelihood("HPHTWWXPPELEXTOYTRSE")
+        );
+    }
+
+    #[test]
+    fn detect_language() {
+        let abc = LanguageModel::from_text("abc", "aaabbc").unwrap();
+        let xyz = LanguageModel::from_text("xyz", "xxxyyz").unwrap();
+        let detector = LanguageDetector::new(vec![abc.clone(), xyz.clone()]);
+
+        assert_eq!(detector.detect("abba"), Some(&abc));
+        assert_eq!(detector.detect("zyx"), Some(&xyz));
+
+        let scores = detector.scores("xyz");
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].0.name(), "xyz");
+        assert!(scores[0].1 > scores[1].1);
+    }
+
+    #[test]
+    fn detect_language_empty() {
+        let mut detector = LanguageDetector::default();
+        assert_eq!(detector.detect("abba"), None);
+
+        detector.add(LanguageModel::english());
+        assert_eq!(detector.models().len(), 1);
+        assert_eq!(detector.detect("abba").unwrap().name(), "english");
+    }
+
+    #[test]
+    fn ngram_model() {
+        let model = NgramModel::from_text("abab", 2).unwrap();
+
+        assert_eq!(model.n(), 2);
+        // "ab" occurs twice and "ba" once among three bigrams
+        let expected = (2.0_f64 / 3.0).ln() + (1.0_f64 / 3.0).ln();
+        assert!((model.log_likelihood("a-b-a") - expected).abs() < 1e-12);
+        // Unseen bigrams are unlikely, but not impossible
+        assert!(model.log_likelihood("aa").is_finite());
+        assert!(model.log_likelihood("aa") < model.log_likelihood("ba"));
+        // Texts that are too short have no n-grams
+        assert_eq!(model.log_likelihood("a"), 0.0);
+    }
+
+    #[test]
+    fn ngram_model_error() {
+        assert!(matches!(
+            NgramModel::from_text("ab", 3),
+            Err(CorpusError::Empty)
+        ));
+    }
+
+    #[test]
+    fn english_ngrams() {
+        let trigrams = NgramModel::english(3);
+        assert!(
+            trigrams.log_likelihood("wewillmeetatmidnight")
+                > trigrams.log_likelihood("HPHTWWXPPELEXTOYTRSE")
+        );
+    }
+
+    #[test]
+    fn latin_ngrams() {
+        let msg: Vec<RingElement> = "wewillmeetatmidnight"
+            .chars()
+            .flat_map(RingElement::from_char::<Latin>)
+            .collect();
+
+        // Models with and without the list of all n-grams agree with scoring the written text
+        for n in [1, 3, 5] {
+            let model = NgramModel::english(n);
+            assert_eq!(
+                model.latin_log_likelihood(&msg),
+                model.log_likelihood("wewillmeetatmidnight")
+            );
+        }
+        assert_eq!(NgramModel::english(3).latin_log_likelihood(&msg[..2]), 0.0);
+    }
+}
@@ -0,0 +1,170 @@
+//! Comparing two texts character by character, e.g., to show where a guessed
+//! decryption deviates from the right one.
+//!
+//! A [`TextDiff`] lists every position at which the texts differ, and its
+//! [`Display`](fmt::Display) implementation writes the two texts one above
+//! the other, marking the differences with `^`. The messages and ciphertexts
+//! of the ciphers provide a `diff` method, e.g.,
+//! [`shift::Message::diff`](crate::shift::Message::diff), and any two
+//! strings can be compared with [`TextDiff::new`].
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::shift::Message;
+//! let reference = Message::new("wewillmeetatmidnight").unwrap();
+//! let guess = Message::new("wewillmeetatmidday").unwrap();
+//!
+//! let diff = reference.diff(&guess);
+//! assert_eq!(diff.differences().len(), 5);
+//! assert_eq!(diff.differences()[0].expected, Some('n'));
+//! assert_eq!(diff.differences()[0].found, Some('d'));
+//! assert_eq!(
+//!     diff.to_string(),
+//!     "expected: wewillmeetatmidnight\nfound:    wewillmeetatmidday\n                         ^^^^^\n"
+//! );
+//! ```
+use std::fmt;
+
+/// A position at which two texts differ.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Difference {
+    /// The index of the character in the texts, counted in `char`s.
+    pub position: usize,
+    /// The character of the expected text, or `None` if the expected text is
+    /// shorter.
+    pub expected: Option<char>,
+    /// The character of the text found, or `None` if the text found is
+    /// shorter.
+    pub found: Option<char>,
+}
+
+/// The differences between an expected text and the text found, e.g., a
+/// reference plaintext and a guessed decryption.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TextDiff {
+    expected: String,
+    found: String,
+    differences: Vec<Difference>,
+}
+
+impl TextDiff {
+    /// Compare two texts character by character. Characters past the end of
+    /// the shorter text are all differences.
+    pub fn new(expected: &str, found: &str) -> Self {
+        let (mut expected_chars, mut found_chars) = (expected.chars(), found.chars());
+        let mut differences = Vec::new();
+        for position in 0.. {
+            let (e, f) = (expected_chars.next(), found_chars.next());
+            if e.is_none() && f.is_none() {
+                break;
+            }
+            if e != f {
+                differences.push(Difference {
+                    position,
+                    expected: e,
+                    found: f,
+                });
+            }
+        }
+
+        TextDiff {
+            expected: expected.to_string(),
+            found: found.to_string(),
+            differences,
+        }
+    }
+
+    /// Returns true if the texts are the same, and false otherwise.
+    pub fn is_match(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Get the positions at which the texts differ, in order.
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+
+    /// Get the expected text.
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// Get the text found.
+    pub fn found(&self) -> &str {
+        &self.found
+    }
+}
+
+/// Display the expected text and the text found on one line each, followed by
+/// a line that marks every position at which they differ with `^`.
+impl fmt::Display for TextDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "expected: {}", self.expected)?;
+        writeln!(f, "found:    {}", self.found)?;
+
+        // The markers line up with the texts, after the width of the labels
+        let mut next = 0;
+        for difference in &self.differences {
+            let indent = if next == 0 { 10 } else { 0 };
+            write!(f, "{:>1$}", '^', indent + difference.position - next + 1)?;
+            next = difference.position + 1;
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differences() {
+        let diff = TextDiff::new("HPHTWWXPPE", "HPHTWWXPPE");
+        assert!(diff.is_match());
+        assert_eq!(
+            diff.to_string(),
+            "expected: HPHTWWXPPE\nfound:    HPHTWWXPPE\n\n"
+        );
+
+        let diff = TextDiff::new("HPHTWWXPPE", "HQHTWWXPPA");
+        assert!(!diff.is_match());
+        assert_eq!(
+            diff.differences(),
+            [
+                Difference {
+                    position: 1,
+                    expected: Some('P'),
+                    found: Some('Q')
+                },
+                Difference {
+                    position: 9,
+                    expected: Some('E'),
+                    found: Some('A')
+                }
+            ]
+        );
+        assert_eq!(
+            diff.to_string().lines().last(),
+            Some("           ^       ^")
+        );
+    }
+
+    #[test]
+    fn different_lengths() {
+        let diff = TextDiff::new("abc", "abcde");
+        assert_eq!(diff.expected(), "abc");
+        assert_eq!(diff.found(), "abcde");
+        let found: Vec<_> = diff
+            .differences()
+            .iter()
+            .map(|d| (d.expected, d.found))
+            .collect();
+        assert_eq!(found, [(None, Some('d')), (None, Some('e'))]);
+        assert_eq!(diff.to_string().lines().last(), Some("             ^^"));
+
+        let diff = TextDiff::new("ab", "");
+        assert_eq!(diff.differences()[0].position, 0);
+        assert_eq!(diff.differences()[1].found, None);
+        assert!(TextDiff::default().is_match());
+    }
+}
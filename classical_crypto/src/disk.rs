@@ -0,0 +1,218 @@
+//! Cipher disks, i.e., two concentric rings of letters, where the inner ring
+//! rotates against the outer ring.
+//!
+//! Leon Battista Alberti described the cipher disk around 1467. The outer ring
+//! holds the plaintext alphabet and the inner ring a mixed ciphertext
+//! alphabet, so that each position of the disk is a substitution, and rotating
+//! the disk during encryption gives the first polyalphabetic cipher, see the
+//! [`alberti`](crate::alberti) module.
+use crate::{
+    alphabet::{CustomAlphabet, Latin},
+    errors::AlphabetError,
+};
+use std::fmt;
+
+/// A cipher disk with an outer ring, an inner ring, and the rotation of the
+/// inner ring against the outer ring.
+///
+/// At rotation (offset) `r`, the letter at index `i` of the outer ring sits
+/// over the letter at index `i + r` (mod the size of the rings) of the inner
+/// ring. Rotating the disk by one step moves each letter of the inner ring
+/// one place back, under the previous letter of the outer ring.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{alphabet::CustomAlphabet, disk::CipherDisk};
+/// let inner = CustomAlphabet::from_chars(&"zyxwvutsrqponmlkjihgfedcba".chars().collect::<Vec<_>>())
+///     .unwrap();
+/// let mut disk = CipherDisk::latin(inner).unwrap();
+///
+/// assert_eq!(disk.encode('a'), Some('z'));
+/// assert_eq!(disk.decode('z'), Some('a'));
+///
+/// disk.rotate(1);
+/// assert_eq!(disk.offset(), 1);
+/// assert_eq!(disk.encode('a'), Some('y'));
+///
+/// disk.align('a', 'm').unwrap();
+/// assert_eq!(disk.encode('a'), Some('m'));
+///
+/// println!("{disk}");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CipherDisk {
+    outer: CustomAlphabet,
+    inner: CustomAlphabet,
+    offset: usize,
+}
+
+impl CipherDisk {
+    /// Create a disk from its outer and inner rings, at rotation 0.
+    ///
+    /// # Errors
+    /// This function returns an error if the rings have different sizes.
+    pub fn new(outer: CustomAlphabet, inner: CustomAlphabet) -> Result<Self, AlphabetError> {
+        if outer.size() != inner.size() {
+            return Err(AlphabetError::WrongSize {
+                size: inner.size(),
+                expected: outer.size(),
+            });
+        }
+
+        Ok(CipherDisk {
+            outer,
+            inner,
+            offset: 0,
+        })
+    }
+
+    /// Create a disk with the Latin Alphabet as its outer ring.
+    ///
+    /// # Errors
+    /// This function returns an error if the inner ring does not have 26
+    /// letters.
+    pub fn latin(inner: CustomAlphabet) -> Result<Self, AlphabetError> {
+        Self::new(CustomAlphabet::from_alphabet::<Latin, 26>(), inner)
+    }
+
+    /// Get the outer ring.
+    pub fn outer(&self) -> &CustomAlphabet {
+        &self.outer
+    }
+
+    /// Get the inner ring.
+    pub fn inner(&self) -> &CustomAlphabet {
+        &self.inner
+    }
+
+    /// Get the number of letters on each ring.
+    pub fn size(&self) -> usize {
+        self.outer.size()
+    }
+
+    /// Get the rotation of the inner ring, between 0 and the size of the
+    /// rings.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Set the rotation of the inner ring, reduced mod the size of the rings.
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = offset % self.size();
+    }
+
+    /// Rotate the inner ring by `steps` steps.
+    pub fn rotate(&mut self, steps: usize) {
+        self.set_offset(self.offset + steps % self.size());
+    }
+
+    /// Rotate the inner ring so that the letter `inner` of the inner ring
+    /// sits under the letter `outer` of the outer ring.
+    ///
+    /// # Errors
+    /// This method returns an error if either letter is not on its ring.
+    pub fn align(&mut self, outer: char, inner: char) -> Result<(), AlphabetError> {
+        let outer = self
+            .outer
+            .index_of(outer)
+            .ok_or(AlphabetError::NotInAlphabet(outer))?;
+        let inner = self
+            .inner
+            .index_of(inner)
+            .ok_or(AlphabetError::NotInAlphabet(inner))?;
+        self.set_offset(inner + self.size() - outer);
+        Ok(())
+    }
+
+    /// Get the letter of the inner ring under a letter of the outer ring, if
+    /// it is on the outer ring.
+    pub fn encode(&self, outer: char) -> Option<char> {
+        let index = self.outer.index_of(outer)?;
+        self.inner.char_at((index + self.offset) % self.size())
+    }
+
+    /// Get the letter of the outer ring over a letter of the inner ring, if
+    /// it is on the inner ring.
+    pub fn decode(&self, inner: char) -> Option<char> {
+        let index = self.inner.index_of(inner)?;
+        self.outer
+            .char_at((index + self.size() - self.offset) % self.size())
+    }
+}
+
+/// Display the disk as two lines, the outer ring over the inner ring at its
+/// current rotation.
+impl fmt::Display for CipherDisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outer: Vec<String> = self.outer.chars().iter().map(char::to_string).collect();
+        let inner: Vec<String> = self
+            .outer
+            .chars()
+            .iter()
+            .filter_map(|&c| self.encode(c))
+            .map(|c| c.to_string())
+            .collect();
+        write!(f, "{}\n{}", outer.join(" "), inner.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reversed() -> CustomAlphabet {
+        CustomAlphabet::from_chars(&('a'..='z').rev().collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn rotation() {
+        let mut disk = CipherDisk::latin(reversed()).unwrap();
+        assert_eq!(disk.size(), 26);
+        assert_eq!(disk.encode('b'), Some('y'));
+        assert_eq!(disk.encode('B'), None);
+        assert_eq!(disk.decode('!'), None);
+
+        disk.rotate(25);
+        assert_eq!(disk.encode('a'), Some('a'));
+        disk.rotate(27);
+        assert_eq!(disk.offset(), 0);
+
+        disk.set_offset(30);
+        assert_eq!(disk.offset(), 4);
+
+        for outer in 'a'..='z' {
+            assert_eq!(disk.decode(disk.encode(outer).unwrap()), Some(outer));
+        }
+    }
+
+    #[test]
+    fn align() {
+        let mut disk = CipherDisk::latin(reversed()).unwrap();
+        disk.align('c', 'q').unwrap();
+        assert_eq!(disk.encode('c'), Some('q'));
+        assert_eq!(disk.decode('q'), Some('c'));
+        assert_eq!(disk.align('c', '1'), Err(AlphabetError::NotInAlphabet('1')));
+    }
+
+    #[test]
+    fn errors() {
+        let inner = CustomAlphabet::from_chars(&['a', 'b']).unwrap();
+        assert_eq!(
+            CipherDisk::latin(inner),
+            Err(AlphabetError::WrongSize {
+                size: 2,
+                expected: 26
+            })
+        );
+    }
+
+    #[test]
+    fn display() {
+        let outer = CustomAlphabet::from_chars(&['a', 'b', 'c']).unwrap();
+        let inner = CustomAlphabet::from_chars(&['x', 'y', 'z']).unwrap();
+        let mut disk = CipherDisk::new(outer, inner).unwrap();
+        assert_eq!(disk.to_string(), "a b c\nx y z");
+        disk.rotate(1);
+        assert_eq!(disk.to_string(), "a b c\ny z x");
+    }
+}
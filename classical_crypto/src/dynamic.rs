@@ -0,0 +1,263 @@
+//! An object-safe interface to the ciphers, so that a frontend can choose a
+//! cipher at runtime.
+//!
+//! [`CipherTrait`] has associated types and no receiver, so each cipher is a
+//! different type and code that uses a cipher is compiled once for each
+//! cipher. The [`DynCipher`] trait instead takes and returns strings and
+//! hides keys behind the opaque [`DynKey`] handle, so that a frontend can hold
+//! a `Box<dyn DynCipher>`. The [`registry`](crate::registry) creates ciphers by
+//! name.
+use crate::{
+    alberti::AlbertiCipher, errors::DynCipherError, playfair::PlayfairCipher, shift::ShiftCipher,
+    CipherInfo, CipherTrait, KeyTrait,
+};
+use rand::{CryptoRng, RngCore};
+use std::{any::Any, fmt};
+
+/// A random number generator that is suitable for generating keys and can be
+/// used as a trait object.
+pub trait KeyRng: RngCore + CryptoRng {}
+
+impl<R: RngCore + CryptoRng + ?Sized> KeyRng for R {}
+
+/// An opaque handle to a key of a [`DynCipher`].
+///
+/// A handle can only be used with the cipher that created it. Its [`Debug`]
+/// implementation names the cipher but does not show the key.
+pub struct DynKey {
+    cipher: &'static str,
+    key: Box<dyn Any + Send + Sync>,
+}
+
+impl DynKey {
+    /// Get the name of the cipher that the key belongs to.
+    pub fn cipher(&self) -> &'static str {
+        self.cipher
+    }
+}
+
+impl fmt::Debug for DynKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynKey")
+            .field("cipher", &self.cipher)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An object-safe cipher, which works on strings rather than on the message,
+/// ciphertext and key types of the cipher.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{dynamic::DynCipher, registry};
+/// let playfair: Box<dyn DynCipher> = registry::get("playfair").unwrap();
+/// let key = playfair.import_key("playfirexmbcdghknoqstuvwz").unwrap();
+///
+/// let ciphertxt = playfair.encrypt("hidethegold", &key).unwrap();
+/// assert_eq!(ciphertxt, "BMODZBXDNAGE");
+/// assert_eq!(playfair.decrypt(&ciphertxt, &key).unwrap(), "hidethegoldx");
+///
+/// // Keys only work with the cipher that made them
+/// let shift = registry::get("shift").unwrap();
+/// assert!(shift.encrypt("hidethegold", &key).is_err());
+/// ```
+pub trait DynCipher: fmt::Debug {
+    /// Get the name of the cipher, as accepted by
+    /// [`registry::get`](crate::registry::get).
+    fn name(&self) -> &'static str;
+
+    /// Describe the cipher, see [`CipherTrait::info`].
+    fn info(&self) -> CipherInfo;
+
+    /// Pick a new key uniformly at random.
+    fn generate_key(&self, rng: &mut dyn KeyRng) -> DynKey;
+
+    /// Derive a key from a passphrase, see
+    /// [`KeyTrait::derive_from_passphrase`], which is NOT secure.
+    fn derive_key(&self, phrase: &str) -> DynKey;
+
+    /// Import a key from a string, see [`KeyTrait::try_import`].
+    ///
+    /// # Errors
+    /// This method returns an error if the string does not represent a key.
+    fn import_key(&self, s: &str) -> Result<DynKey, DynCipherError>;
+
+    /// Export a key, insecurely, see [`KeyTrait::export`].
+    ///
+    /// # Errors
+    /// This method returns an error if the key belongs to another cipher.
+    fn export_key(&self, key: &DynKey) -> Result<String, DynCipherError>;
+
+    /// Encrypt a message given as a string, with the alphabet, settings and
+    /// padding of the instance, e.g.,
+    /// [`ShiftCipher::encrypt_text`].
+    ///
+    /// # Errors
+    /// This method returns an error if the string is not a message, if the
+    /// key belongs to another cipher, or if encryption fails.
+    fn encrypt(&self, msg: &str, key: &DynKey) -> Result<String, DynCipherError>;
+
+    /// Decrypt a ciphertext given as a string, e.g., with
+    /// [`ShiftCipher::decrypt_text`].
+    ///
+    /// # Errors
+    /// This method returns an error if the string is not a ciphertext, if the
+    /// key belongs to another cipher, or if decryption fails.
+    fn decrypt(&self, ciphertxt: &str, key: &DynKey) -> Result<String, DynCipherError>;
+}
+
+// Implement `DynCipher` for ciphers whose instances encrypt and decrypt strings with
+// `encrypt_text` and `decrypt_text`, under the name of their `CipherInfo`.
+macro_rules! impl_dyn_cipher {
+    ($($cipher:ty),+) => {
+        $(
+            impl DynCipher for $cipher {
+                fn name(&self) -> &'static str {
+                    <Self as CipherTrait>::info().name
+                }
+
+                fn info(&self) -> CipherInfo {
+                    <Self as CipherTrait>::info()
+                }
+
+                fn generate_key(&self, rng: &mut dyn KeyRng) -> DynKey {
+                    generate_key::<Self>(self.name(), rng)
+                }
+
+                fn derive_key(&self, phrase: &str) -> DynKey {
+                    derive_key::<Self>(self.name(), phrase)
+                }
+
+                fn import_key(&self, s: &str) -> Result<DynKey, DynCipherError> {
+                    import_key::<Self>(self.name(), s)
+                }
+
+                fn export_key(&self, key: &DynKey) -> Result<String, DynCipherError> {
+                    Ok(downcast::<Self>(self.name(), key)?.export())
+                }
+
+                fn encrypt(&self, msg: &str, key: &DynKey) -> Result<String, DynCipherError> {
+                    Ok(self.encrypt_text(msg, downcast::<Self>(self.name(), key)?)?)
+                }
+
+                fn decrypt(&self, ciphertxt: &str, key: &DynKey) -> Result<String, DynCipherError> {
+                    Ok(self.decrypt_text(ciphertxt, downcast::<Self>(self.name(), key)?)?)
+                }
+            }
+        )+
+    };
+}
+
+impl_dyn_cipher!(ShiftCipher, PlayfairCipher, AlbertiCipher);
+
+// The implementations of `DynCipher` share these helpers, which are generic over the cipher.
+fn generate_key<C: CipherTrait>(name: &'static str, mut rng: &mut dyn KeyRng) -> DynKey
+where
+    C::Key: Send + Sync + 'static,
+{
+    DynKey {
+        cipher: name,
+        key: Box::new(C::Key::new(&mut rng)),
+    }
+}
+
+fn derive_key<C: CipherTrait>(name: &'static str, phrase: &str) -> DynKey
+where
+    C::Key: Send + Sync + 'static,
+{
+    DynKey {
+        cipher: name,
+        key: Box::new(C::Key::derive_from_passphrase(phrase)),
+    }
+}
+
+fn import_key<C: CipherTrait>(name: &'static str, s: &str) -> Result<DynKey, DynCipherError>
+where
+    C::Key: Send + Sync + 'static,
+{
+    Ok(DynKey {
+        cipher: name,
+        key: Box::new(C::Key::try_import(s)?),
+    })
+}
+
+fn downcast<'a, C: CipherTrait>(
+    name: &'static str,
+    key: &'a DynKey,
+) -> Result<&'a C::Key, DynCipherError>
+where
+    C::Key: 'static,
+{
+    key.key.downcast_ref().ok_or(DynCipherError::WrongKey(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{registry::get, EncodingError};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn enc_dec_random_keys() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+
+        for name in ["shift", "playfair", "alberti"] {
+            let cipher = get(name).unwrap();
+            assert_eq!(cipher.name(), name);
+
+            let key = cipher.generate_key(&mut rng);
+            assert_eq!(key.cipher(), name);
+
+            let ciphertxt = cipher.encrypt("meetmeatnoon", &key).unwrap();
+            assert_eq!(cipher.decrypt(&ciphertxt, &key).unwrap(), "meetmeatnoon");
+
+            let derived = cipher.derive_key("correct horse battery staple");
+            assert_eq!(
+                cipher.export_key(&derived),
+                cipher.export_key(&cipher.derive_key("correct horse battery staple"))
+            );
+
+            let imported = cipher
+                .import_key(&cipher.export_key(&key).unwrap())
+                .unwrap();
+            assert_eq!(
+                cipher.decrypt(&ciphertxt, &imported).unwrap(),
+                "meetmeatnoon"
+            );
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let shift = get("shift").unwrap();
+        let key = shift.import_key("11").unwrap();
+        assert_eq!(format!("{key:?}"), "DynKey { cipher: \"shift\", .. }");
+
+        assert!(matches!(
+            shift.import_key("26").unwrap_err(),
+            DynCipherError::Encoding(EncodingError::KeyFormat {
+                cipher: "shift",
+                ..
+            })
+        ));
+        assert!(matches!(
+            shift.encrypt("Hello", &key),
+            Err(DynCipherError::Encoding(EncodingError::InvalidMessage(_)))
+        ));
+
+        let playfair = get("playfair").unwrap();
+        assert_eq!(
+            playfair.export_key(&key).unwrap_err(),
+            DynCipherError::WrongKey("playfair")
+        );
+
+        let key = playfair.import_key("playfirexmbcdghknoqstuvwz").unwrap();
+        assert!(matches!(
+            playfair.decrypt("BMODZ", &key),
+            Err(DynCipherError::Cipher(_))
+        ));
+    }
+}
@@ -1,27 +1,55 @@
 //! Contains custom error types.
+#[cfg(feature = "toml")]
+use std::time::Duration;
+use std::{convert::Infallible, fmt, io};
 use thiserror::Error;
 
-/// An opaque error type that hides the implementation details of internal
-/// errors.
-// This is a technique that is easy to use with the `thiserror` crate. The attribute
-// `error(transparent)` forwards the source and display methods straight through to the underlying
-// internal error representations.
-#[derive(Error, Debug, PartialEq)]
-#[error(transparent)]
-pub struct InternalError(#[from] ErrorRepr);
-
-/// Internal errors.
-#[derive(Clone, Debug, PartialEq, Error)]
-pub(super) enum ErrorRepr {
-    /// Thrown when a conversion between the Latin
-    /// Alphabet and the ring of integers modulo [`RingElement::MODULUS`] fails.
-    ///
-    /// This error should only be thrown if:
-    /// - There is a mistake in the definition of the constant
-    ///   [`RingElement::ALPH_ENCODING`];
-    /// - The input was not a lowercase letter from the Latin Alphabet.
-    #[error("Failed to encode the following characters as ring elements: {0}")]
-    RingElementEncodingError(String),
+/// The characters of a string that cannot be encoded as ring elements,
+/// together with their positions, as carried by an [`EncodingError`].
+///
+/// A string without any characters that can be encoded is also rejected, in
+/// which case the list of characters may be empty.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{errors::EncodingError, shift::Message};
+/// let Err(EncodingError::InvalidMessage(invalid)) = Message::new("meet me at 9!") else {
+///     panic!("The message is invalid");
+/// };
+///
+/// assert_eq!(invalid.to_string(), "Failed to encode the following characters as ring elements: 9!");
+/// assert_eq!(invalid.characters()[0].char_index, 11);
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Error)]
+#[error("Failed to encode the following characters as ring elements: {}", self.to_chars())]
+pub struct InvalidCharacters(pub(crate) Vec<InvalidCharacter>);
+
+impl InvalidCharacters {
+    /// Get the characters that cannot be encoded, in the order they appear in
+    /// the string.
+    pub fn characters(&self) -> &[InvalidCharacter] {
+        &self.0
+    }
+
+    // Find the characters of `s` for which `invalid` holds.
+    pub(crate) fn find(s: &str, mut invalid: impl FnMut(char) -> bool) -> Self {
+        InvalidCharacters(
+            s.char_indices()
+                .enumerate()
+                .filter(|&(_, (_, character))| invalid(character))
+                .map(|(char_index, (byte_index, character))| InvalidCharacter {
+                    character,
+                    byte_index,
+                    char_index,
+                })
+                .collect(),
+        )
+    }
+
+    // Write the characters, without their positions.
+    fn to_chars(&self) -> String {
+        self.0.iter().map(|c| c.character).collect()
+    }
 }
 
 // TODO: Are these usable for other ciphers?
@@ -30,20 +58,703 @@ pub(super) enum ErrorRepr {
 pub enum EncodingError {
     /// Error thrown when parsing a string as a message. This error is thrown
     /// when the string included one or more characters that are not
-    /// lowercase letters from the Latin Alphabet.
+    /// lowercase letters from the Latin Alphabet, and gives each of them with
+    /// its position.
     #[error("Invalid Message. {0}")]
-    InvalidMessage(InternalError),
+    InvalidMessage(InvalidCharacters),
     /// Error thrown when parsing a string as a ciphertext. This error is thrown
     /// when the string included one or more characters that are not letters
-    /// from the Latin Alphabet. We allow for strings containing both
-    /// capitalized and lowercase letters when parsing as string as a
-    /// ciphertext.
+    /// from the Latin Alphabet, and gives each of them with its position. We
+    /// allow for strings containing both capitalized and lowercase letters
+    /// when parsing as string as a ciphertext.
     #[error("Invalid Ciphertext. {0}")]
-    InvalidCiphertext(InternalError),
+    InvalidCiphertext(InvalidCharacters),
     /// Error thrown when parsing a string as a key. This error is thrown when
     /// the string does not represent a number in the appropriate
     /// range. e.g., for the Latin Shift Cipher, keys are in the range 0 to 25,
     /// inclusive.
     #[error("Input \"{0}\" does not represent a valid key")]
     InvalidKey(String),
+    /// Error thrown when parsing a string as a key of a cipher that accepts
+    /// keys in more than one format, naming the formats it accepts.
+    #[error("Input \"{key}\" does not represent a valid {cipher} key; expected {expected}")]
+    KeyFormat {
+        /// The string given.
+        key: String,
+        /// The name of the cipher, as in [`CipherInfo::name`](crate::CipherInfo::name).
+        cipher: &'static str,
+        /// The formats that the cipher accepts.
+        expected: &'static str,
+    },
+}
+
+/// A character that cannot be encoded, as found by `validate`, e.g.,
+/// [`shift::Message::validate`](crate::shift::Message::validate).
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct InvalidCharacter {
+    /// The character.
+    pub character: char,
+    /// The index of the first byte of the character in the string.
+    pub byte_index: usize,
+    /// The index of the character in the string, counted in `char`s.
+    pub char_index: usize,
+}
+
+/// A report of every character of a string that cannot be encoded as a
+/// message or ciphertext, as returned by `validate`, e.g.,
+/// [`shift::Message::validate`](crate::shift::Message::validate).
+///
+/// Unlike the [`EncodingError`] returned when parsing, the report gives the
+/// position of each invalid character, so that the input can be corrected.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ValidationReport {
+    pub(crate) invalid: Vec<InvalidCharacter>,
+    pub(crate) symbols: usize,
+}
+
+impl ValidationReport {
+    /// Returns true if the string can be parsed, i.e., it has no invalid
+    /// characters and at least one valid one, and false otherwise.
+    pub fn is_valid(&self) -> bool {
+        self.invalid.is_empty() && self.symbols > 0
+    }
+
+    /// Get the invalid characters, in the order they appear in the string.
+    pub fn invalid(&self) -> &[InvalidCharacter] {
+        &self.invalid
+    }
+
+    /// Get the number of characters that can be encoded. Spaces that are not
+    /// in the alphabet are skipped, and are neither valid nor invalid.
+    pub fn symbols(&self) -> usize {
+        self.symbols
+    }
+}
+
+/// Display one line per invalid character, or nothing if there are none.
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in &self.invalid {
+            writeln!(
+                f,
+                "invalid character {:?} at position {} (byte {})",
+                c.character, c.char_index, c.byte_index
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A report of every change made to a string to parse it as a message, as
+/// returned by `new_lossy`, e.g.,
+/// [`shift::Message::new_lossy`](crate::shift::Message::new_lossy).
+///
+/// Each change is listed with the position of the character in the original
+/// string: uppercase letters that were lowercased, and every other character
+/// that cannot be encoded, including spaces, which was dropped.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct StripReport {
+    pub(crate) lowercased: Vec<InvalidCharacter>,
+    pub(crate) dropped: Vec<InvalidCharacter>,
+}
+
+impl StripReport {
+    /// Returns true if the string was parsed without any changes, and false
+    /// otherwise.
+    pub fn is_unchanged(&self) -> bool {
+        self.lowercased.is_empty() && self.dropped.is_empty()
+    }
+
+    /// Get the letters that were lowercased, as they appear in the string.
+    pub fn lowercased(&self) -> &[InvalidCharacter] {
+        &self.lowercased
+    }
+
+    /// Get the characters that were dropped, in the order they appear in the
+    /// string.
+    pub fn dropped(&self) -> &[InvalidCharacter] {
+        &self.dropped
+    }
+}
+
+/// Display one line per change, in the order of the characters in the
+/// string, or nothing if there are none.
+impl fmt::Display for StripReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes: Vec<_> = self
+            .lowercased
+            .iter()
+            .map(|c| ("lowercased", c))
+            .chain(self.dropped.iter().map(|c| ("dropped", c)))
+            .collect();
+        changes.sort_by_key(|(_, c)| c.char_index);
+
+        for (change, c) in changes {
+            writeln!(
+                f,
+                "{change} {:?} at position {} (byte {})",
+                c.character, c.char_index, c.byte_index
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An error type that indicates a failure to read a message or ciphertext,
+/// e.g., with [`shift::Message::read_from`](crate::shift::Message::read_from).
+#[derive(Debug, Error)]
+pub enum ReadError {
+    /// Error thrown when the text could not be read, e.g., because the file
+    /// does not exist or does not contain valid UTF-8.
+    #[error("Failed to read text: {0}")]
+    Io(#[from] io::Error),
+
+    /// Error thrown when the text read is not a message or ciphertext.
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+}
+
+/// An error type that indicates a failure to encrypt or decrypt a stream, see
+/// the [`stream`](crate::stream) module.
+#[derive(Debug, Error)]
+pub enum StreamError {
+    /// Error thrown when the stream could not be read, e.g., because it does
+    /// not contain valid UTF-8.
+    #[error("Failed to read stream: {0}")]
+    Io(#[from] io::Error),
+
+    /// Error thrown when a character of the stream is not in the alphabet.
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+}
+
+/// An error type that indicates a failure to build a language model from a
+/// corpus.
+#[derive(Debug, Error)]
+pub enum CorpusError {
+    /// Error thrown when the corpus could not be read, e.g., because the file
+    /// does not exist or does not contain valid UTF-8.
+    #[error("Failed to read corpus: {0}")]
+    Io(#[from] io::Error),
+    /// Error thrown when the corpus does not contain any letters.
+    #[error("The corpus does not contain any letters")]
+    Empty,
+}
+
+/// An error type that indicates a letter assignment on a
+/// [`Workbench`](crate::analysis::Workbench) was rejected.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum WorkbenchError {
+    /// Error thrown when one of the given characters is not a letter.
+    #[error("'{0}' is not a letter")]
+    NotALetter(char),
+    /// Error thrown when trying to change the assignment of a ciphertext
+    /// letter that is pinned.
+    #[error("Ciphertext letter '{0}' is pinned")]
+    Pinned(char),
+    /// Error thrown when trying to assign a plaintext letter that is already
+    /// pinned to a different ciphertext letter.
+    #[error("Plaintext letter '{plaintext}' is pinned to ciphertext letter '{ciphertext}'")]
+    PlaintextPinned {
+        /// The plaintext letter.
+        plaintext: char,
+        /// The ciphertext letter it is pinned to.
+        ciphertext: char,
+    },
+}
+
+/// An error type that indicates a failure to encrypt or decrypt, see
+/// [`CipherTrait`](crate::CipherTrait).
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+#[non_exhaustive]
+pub enum CipherError {
+    /// Error thrown when a block cipher is given a text whose length is not a
+    /// multiple of the length of its blocks, e.g., a Playfair ciphertext with
+    /// an odd number of letters.
+    #[error("The text has {length} letters, which is not a multiple of the block length {block}")]
+    BlockLength {
+        /// The number of letters of the text.
+        length: usize,
+        /// The number of letters of each block.
+        block: usize,
+    },
+}
+
+/// An error type that indicates a failure to re-key a ciphertext, see
+/// [`CipherTrait::rekey`](crate::CipherTrait::rekey). `D` and `E` are the
+/// decryption and encryption errors of the cipher.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum RekeyError<D, E> {
+    /// Error thrown when the ciphertext could not be decrypted under the old
+    /// key.
+    #[error("Failed to decrypt under the old key: {0}")]
+    Decryption(D),
+
+    /// Error thrown when the plaintext could not be encrypted under the new
+    /// key.
+    #[error("Failed to encrypt under the new key: {0}")]
+    Encryption(E),
+}
+
+/// An error type that indicates that a cipher failed a round trip check, see
+/// [`verify_cipher`](crate::verify::verify_cipher). Each variant gives the
+/// index of the failing sample, counting from 0.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum RoundTripError {
+    /// Error thrown when a sample message could not be encrypted.
+    #[error("Sample {sample}: failed to encrypt {message}: {error}")]
+    Encryption {
+        /// The index of the sample.
+        sample: usize,
+        /// The message.
+        message: String,
+        /// The error returned by the cipher.
+        error: String,
+    },
+
+    /// Error thrown when the encryption of a sample message could not be
+    /// decrypted.
+    #[error("Sample {sample}: failed to decrypt {ciphertext}: {error}")]
+    Decryption {
+        /// The index of the sample.
+        sample: usize,
+        /// The ciphertext.
+        ciphertext: String,
+        /// The error returned by the cipher.
+        error: String,
+    },
+
+    /// Error thrown when decryption did not give back the sample message.
+    #[error("Sample {sample}: {message} decrypted to {decrypted}")]
+    Mismatch {
+        /// The index of the sample.
+        sample: usize,
+        /// The message.
+        message: String,
+        /// The result of decryption.
+        decrypted: String,
+    },
+}
+
+/// An error type that indicates a failure to encrypt or decrypt a string with
+/// an instance of a cipher, e.g., with
+/// [`PlayfairCipher::encrypt_text`](crate::playfair::PlayfairCipher::encrypt_text).
+#[derive(Debug, PartialEq, Error)]
+pub enum TextError {
+    /// Error thrown when the string cannot be parsed as a message or
+    /// ciphertext of the cipher.
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+
+    /// Error thrown when the cipher fails to encrypt or decrypt.
+    #[error(transparent)]
+    Cipher(#[from] CipherError),
+}
+
+/// An error type that indicates a failure to use a cipher through the
+/// object-safe interface, see [`DynCipher`](crate::dynamic::DynCipher).
+#[derive(Debug, PartialEq, Error)]
+pub enum DynCipherError {
+    /// Error thrown when a string cannot be parsed as a message, ciphertext,
+    /// or key of the cipher.
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+
+    /// Error thrown when a key handle was created by a different cipher.
+    #[error("The key is not a key of the {0} cipher")]
+    WrongKey(&'static str),
+
+    /// Error thrown when the cipher fails to encrypt or decrypt.
+    #[error(transparent)]
+    Cipher(#[from] CipherError),
+}
+
+impl From<Infallible> for DynCipherError {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+impl From<TextError> for DynCipherError {
+    fn from(e: TextError) -> Self {
+        match e {
+            TextError::Encoding(e) => DynCipherError::Encoding(e),
+            TextError::Cipher(e) => DynCipherError::Cipher(e),
+        }
+    }
+}
+
+/// An error type that indicates an invalid definition of a
+/// [`CustomAlphabet`](crate::alphabet::CustomAlphabet).
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum AlphabetError {
+    /// Error thrown when the alphabet has no characters.
+    #[error("An alphabet must have at least one character")]
+    Empty,
+    /// Error thrown when the alphabet has more characters than the ring
+    /// arithmetic supports.
+    #[error("An alphabet can have at most {max} characters, but {size} were given")]
+    TooLarge {
+        /// The number of characters given.
+        size: usize,
+        /// The maximum number of characters.
+        max: usize,
+    },
+    /// Error thrown when a character appears more than once.
+    #[error("The character '{0}' appears more than once")]
+    Repeated(char),
+    /// Error thrown when encoding a character that is not in the alphabet.
+    #[error("The character '{0}' is not in the alphabet")]
+    NotInAlphabet(char),
+    /// Error thrown when the number of characters does not fit a grid, e.g.,
+    /// of a [`PolybiusSquare`](crate::polybius::PolybiusSquare).
+    #[error("Expected {expected} characters, but {size} were given")]
+    WrongSize {
+        /// The number of characters given.
+        size: usize,
+        /// The number of characters expected.
+        expected: usize,
+    },
+}
+
+/// An error type that indicates a failure to load a
+/// [`Config`](crate::config::Config).
+#[cfg(feature = "toml")]
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// Error thrown when the configuration file could not be read.
+    #[error("Failed to read configuration: {0}")]
+    Io(#[from] io::Error),
+    /// Error thrown when the configuration is not valid TOML, or does not
+    /// have the expected fields.
+    #[error("Failed to parse configuration: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// Error thrown when the alphabet is not valid.
+    #[error("Invalid alphabet: {0}")]
+    Alphabet(#[from] AlphabetError),
+    /// Error thrown when the alphabet specifies both or neither of a preset
+    /// and a list of characters.
+    #[error("An alphabet must have exactly one of `preset` and `chars`")]
+    AlphabetSource,
+    /// Error thrown when the cipher cannot use the alphabet, e.g., the Shift
+    /// Cipher, whose keys are shifts from 0 to 25, with an alphabet that does
+    /// not have 26 letters, or the Playfair Cipher, which has its own square
+    /// instead of an alphabet.
+    #[error("The {cipher} cipher cannot use an alphabet of {size} characters")]
+    IncompatibleAlphabet {
+        /// The name of the cipher.
+        cipher: &'static str,
+        /// The number of characters of the alphabet.
+        size: usize,
+    },
+    /// Error thrown when the group size for displaying ciphertexts is 0.
+    #[error("The group size must be positive")]
+    GroupSize,
+    /// Error thrown when the key of a cipher is not valid.
+    #[error("Invalid cipher: {0}")]
+    Cipher(#[from] EncodingError),
+}
+
+/// An error type that indicates a failure to encode or decode Morse code.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum MorseError {
+    /// Error thrown when encoding a character that has no Morse code.
+    #[error("The character '{0}' has no Morse code")]
+    UnknownCharacter(char),
+    /// Error thrown when decoding a sequence of dots and dashes that is not
+    /// the Morse code of any character.
+    #[error("\"{0}\" is not a Morse code")]
+    UnknownCode(String),
+}
+
+/// An error type that indicates an invalid
+/// [`Permutation`](crate::permutation::Permutation).
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum PermutationError {
+    /// Error thrown when a point is out of range, i.e., not between 1 and the
+    /// size of the permutation in one-line or cycle notation.
+    #[error("The point {point} is out of range for a permutation of size {size}")]
+    OutOfRange {
+        /// The point given.
+        point: usize,
+        /// The size of the permutation.
+        size: usize,
+    },
+    /// Error thrown when a point appears more than once.
+    #[error("The point {0} appears more than once")]
+    Repeated(usize),
+    /// Error thrown when parsing a permutation from a string that is not in
+    /// one-line or cycle notation.
+    #[error(
+        "\"{0}\" is not a permutation; expected one-line notation, cycle notation or a permuted \
+         alphabet"
+    )]
+    Parse(String),
+}
+
+/// An error type that indicates an invalid matrix, or an operation that is
+/// not defined for a matrix, see [`Matrix`](crate::matrix::Matrix).
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum MatrixError {
+    /// Error thrown when creating a matrix with no rows or no columns.
+    #[error("A matrix must have at least one row and one column")]
+    Empty,
+    /// Error thrown when the rows of a matrix have different lengths.
+    #[error("Row {row} has {len} entries, but the first row has {expected}")]
+    Ragged {
+        /// The index of the row, counting from 0.
+        row: usize,
+        /// The number of entries in the row.
+        len: usize,
+        /// The number of entries in the first row.
+        expected: usize,
+    },
+    /// Error thrown when an operation that needs a square matrix, such as the
+    /// determinant, is applied to a matrix that is not square.
+    #[error("The matrix has {rows} rows and {cols} columns, but it must be square")]
+    NotSquare {
+        /// The number of rows.
+        rows: usize,
+        /// The number of columns.
+        cols: usize,
+    },
+    /// Error thrown when inverting a matrix whose determinant is not a unit.
+    #[error("The matrix is not invertible, since its determinant {0} is not a unit")]
+    NotInvertible(u16),
+}
+
+/// An error type that indicates an invalid key or text for the toy RSA
+/// Cryptosystem, see [`toy_rsa`](crate::toy_rsa).
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum RsaError {
+    /// Error thrown when a factor of the modulus is not prime, or is too
+    /// large for the toy cryptosystem.
+    #[error("{0} is not a prime between 2 and {max}", max = crate::toy_rsa::MAX_PRIME)]
+    InvalidPrime(i64),
+    /// Error thrown when the two factors of the modulus are equal.
+    #[error("The primes must be distinct, but both are {0}")]
+    RepeatedPrime(i64),
+    /// Error thrown when the encryption exponent is not invertible modulo
+    /// the order of the multiplicative group.
+    #[error("The exponent {exponent} is not a unit modulo {phi}")]
+    InvalidExponent {
+        /// The encryption exponent.
+        exponent: i64,
+        /// The order of the multiplicative group, i.e., `(p - 1) * (q - 1)`.
+        phi: i64,
+    },
+    /// Error thrown when a plaintext or ciphertext is not between 0 and the
+    /// modulus.
+    #[error("{value} is not between 0 and the modulus {n}")]
+    OutOfRange {
+        /// The plaintext or ciphertext.
+        value: i64,
+        /// The modulus.
+        n: i64,
+    },
+}
+
+/// An error type that indicates an invalid group or public value for the toy
+/// Diffie-Hellman key exchange, see [`toy_dh`](crate::toy_dh).
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum DhError {
+    /// Error thrown when the modulus is not a prime of at most
+    /// [`MAX_MODULUS`](crate::toy_dh::MAX_MODULUS).
+    #[error("{0} is not a prime between 3 and {max}", max = crate::toy_dh::MAX_MODULUS)]
+    InvalidModulus(i64),
+    /// Error thrown when the generator does not generate the multiplicative
+    /// group modulo the prime.
+    #[error("{generator} is not a primitive element modulo {p}")]
+    InvalidGenerator {
+        /// The proposed generator.
+        generator: i64,
+        /// The prime modulus.
+        p: i64,
+    },
+    /// Error thrown when a public value or secret exponent is not between 1
+    /// and `p - 1`.
+    #[error("{value} is not between 1 and {max}", max = p - 1)]
+    OutOfRange {
+        /// The public value or secret exponent.
+        value: i64,
+        /// The prime modulus.
+        p: i64,
+    },
+}
+
+/// An error type that indicates an invalid scheme, secret or set of shares
+/// for the toy Shamir Threshold Scheme, see [`toy_shamir`](crate::toy_shamir).
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum SharingError {
+    /// Error thrown when the modulus is not a prime of at most
+    /// [`MAX_PRIME`](crate::toy_shamir::MAX_PRIME).
+    #[error("{0} is not a prime between 2 and {max}", max = crate::toy_shamir::MAX_PRIME)]
+    InvalidModulus(i64),
+    /// Error thrown when the threshold is 0 or greater than the number of
+    /// shares, or when there are too many shares for the field.
+    #[error("Cannot split a secret into {shares} shares with threshold {threshold} modulo {p}")]
+    InvalidThreshold {
+        /// The number of shares needed to reconstruct the secret.
+        threshold: usize,
+        /// The number of shares.
+        shares: usize,
+        /// The prime modulus.
+        p: i64,
+    },
+    /// Error thrown when a secret or a share is not between 0 and `p - 1`.
+    #[error("{value} is not between 0 and {max}", max = p - 1)]
+    OutOfRange {
+        /// The secret or share.
+        value: i64,
+        /// The prime modulus.
+        p: i64,
+    },
+    /// Error thrown when reconstructing from fewer shares than the
+    /// threshold.
+    #[error("Expected at least {threshold} shares, but {given} were given")]
+    TooFewShares {
+        /// The number of shares given.
+        given: usize,
+        /// The number of shares needed.
+        threshold: usize,
+    },
+    /// Error thrown when two shares have the same `x`-coordinate.
+    #[error("More than one share has the x-coordinate {0}")]
+    RepeatedShare(i64),
+    /// Error thrown when the reconstructed secret is not a key, e.g., because
+    /// one of the shares was altered.
+    #[error("The reconstructed secret {0} is not a key")]
+    InvalidKey(i64),
+}
+
+/// An error type that indicates a failure to load a key from a file, see
+/// [`KeyFile`](crate::keyfile::KeyFile).
+#[derive(Debug, Error)]
+pub enum KeyFileError {
+    /// Error thrown when the file could not be read.
+    #[error("Failed to read the keyfile: {0}")]
+    Io(#[from] io::Error),
+    /// Error thrown when the file does not start with the magic bytes, or is
+    /// cut short.
+    #[error("The file is not a keyfile")]
+    NotAKeyfile,
+    /// Error thrown when the keyfile was written in a version of the format
+    /// that this version of the library cannot read.
+    #[error("Unsupported keyfile version {0}, expected {expected}", expected = crate::keyfile::KEYFILE_VERSION)]
+    UnsupportedVersion(u8),
+    /// Error thrown when the keyfile holds a key for a different cipher.
+    #[error("Expected a key for the {expected} cipher, found a key for the {found} cipher")]
+    WrongCipher {
+        /// The name of the cipher of the key being loaded.
+        expected: String,
+        /// The name of the cipher in the keyfile.
+        found: String,
+    },
+    /// Error thrown when the key material is not a key.
+    #[error("Invalid key material: {0}")]
+    Key(#[from] EncodingError),
+    /// Error thrown when loading a protected keyfile without a passphrase.
+    #[error("The keyfile is protected by a passphrase")]
+    PassphraseRequired,
+    /// Error thrown when the passphrase of a protected keyfile is wrong.
+    #[error("Wrong passphrase for the keyfile")]
+    WrongPassphrase,
+}
+
+/// An error type that indicates a failure to read an armored key, see the
+/// [`armor`](crate::armor) module.
+#[derive(Debug, PartialEq, Error)]
+pub enum ArmorError {
+    /// Error thrown when the text does not start with a header line.
+    #[error("The armored key has no header")]
+    MissingHeader,
+    /// Error thrown when the body is not followed by a checksum line, or the
+    /// checksum is not a hexadecimal number.
+    #[error("The armored key has no checksum")]
+    MissingChecksum,
+    /// Error thrown when the checksum is not followed by the footer of the
+    /// header.
+    #[error("The armored key has no footer")]
+    MissingFooter,
+    /// Error thrown when the armored key is for a different cipher.
+    #[error("Expected a key for the {expected} cipher, found a key for the {found} cipher")]
+    WrongCipher {
+        /// The name of the cipher of the key being read.
+        expected: String,
+        /// The name of the cipher in the header.
+        found: String,
+    },
+    /// Error thrown when the body is not base32, or does not decode to UTF-8.
+    #[error("The body of the armored key is not valid base32")]
+    InvalidBody,
+    /// Error thrown when the checksum does not match the body, e.g., because
+    /// the key was corrupted.
+    #[error("Checksum mismatch: expected {expected:08X}, found {found:08X}")]
+    Checksum {
+        /// The checksum in the armored key.
+        expected: u32,
+        /// The checksum of the body.
+        found: u32,
+    },
+    /// Error thrown when the key material is not a key.
+    #[error("Invalid key material: {0}")]
+    Key(#[from] EncodingError),
+}
+
+/// An error type that indicates a failure to add a key to a
+/// [`Keyring`](crate::keyring::Keyring), or to load a keyring.
+#[cfg(feature = "toml")]
+#[derive(Debug, Error)]
+pub enum KeyringError {
+    /// Error thrown when the keyring file could not be read.
+    #[error("Failed to read keyring: {0}")]
+    Io(#[from] io::Error),
+    /// Error thrown when the keyring is not valid TOML, or does not have the
+    /// expected fields.
+    #[error("Failed to parse keyring: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// Error thrown when the keyring was written in a version of the format
+    /// that this version of the library cannot read.
+    #[error("Unsupported keyring version {0}, expected {expected}", expected = crate::keyring::KEYRING_VERSION)]
+    UnsupportedVersion(u32),
+    /// Error thrown when a label is empty or has surrounding whitespace.
+    #[error(
+        "Invalid label {0:?}: labels must be nonempty and must not start or end with whitespace"
+    )]
+    InvalidLabel(String),
+    /// Error thrown when adding a key under a label that is already in use.
+    #[error("The keyring already has a key labeled {0:?}")]
+    DuplicateLabel(String),
+    /// Error thrown when there is no key under a label.
+    #[error("The keyring has no key labeled {0:?}")]
+    UnknownLabel(String),
+    /// Error thrown when a key would expire too far in the future for the
+    /// system clock to represent.
+    #[error("The lifetime {0:?} is too long")]
+    Lifetime(Duration),
+    /// Error thrown when a key belongs to a cipher that is not in the
+    /// [`registry`](crate::registry).
+    #[error("Unknown cipher {0:?}")]
+    UnknownCipher(String),
+    /// Error thrown when the key material of a key is not a key of its cipher.
+    #[error("Invalid key {label:?}: {source}")]
+    Key {
+        /// The label of the key.
+        label: String,
+        /// The error returned by the cipher.
+        source: DynCipherError,
+    },
+}
+
+/// An error type that indicates a failure to decode a key with a
+/// [`KeyCodec`](crate::codec::KeyCodec).
+#[derive(Debug, PartialEq, Error)]
+pub enum CodecError {
+    /// Error thrown when the text is not a key in the format of the codec.
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+    /// Error thrown when the text is not an armored key, see the
+    /// [`armor`](crate::armor) module.
+    #[error(transparent)]
+    Armor(#[from] ArmorError),
 }
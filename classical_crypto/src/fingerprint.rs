@@ -0,0 +1,106 @@
+//! Fingerprints of keys: short digests that people can compare, e.g., read
+//! out over the phone, to check that they hold the same key without reading
+//! out the key itself.
+//!
+//! A fingerprint is [`FINGERPRINT_LEN`] words from the list [`WORDS`] of 256
+//! words, one word for each of the first bytes of the 64-bit FNV-1a hash of
+//! the key as written by [`KeyTrait::export`](crate::KeyTrait::export).
+//!
+//! This is for teaching and exercises only. FNV-1a is not a cryptographic
+//! hash, so keys with the same fingerprint are easy to find. Also, the
+//! fingerprint does not hide a key from a small key space: anyone can
+//! compute the fingerprints of all 26 keys of the Shift Cipher and look the
+//! key up.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{fingerprint::FINGERPRINT_LEN, playfair::Key, KeyTrait};
+//! # use std::str::FromStr;
+//! let alice = Key::from_str("playfair example").unwrap();
+//! let bob = Key::try_import("playfirexmbcdghknoqstuvwz").unwrap();
+//! let eve = Key::from_str("charles").unwrap();
+//!
+//! assert_eq!(alice.fingerprint(), bob.fingerprint());
+//! assert_ne!(alice.fingerprint(), eve.fingerprint());
+//! assert_eq!(alice.fingerprint().split(' ').count(), FINGERPRINT_LEN);
+//! ```
+use crate::fnv1a;
+
+/// The number of words of a fingerprint.
+pub const FINGERPRINT_LEN: usize = 4;
+
+/// The words of fingerprints, in alphabetical order. The `i`th word stands
+/// for the byte `i`.
+pub const WORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "adobe", "agent", "alarm", "album", "alley", "amber", "anchor",
+    "angle", "ankle", "apple", "apron", "arena", "armor", "arrow", "aspen", "atlas", "attic",
+    "award", "axis", "bacon", "badge", "bagel", "baker", "bamboo", "banjo", "barn", "basil",
+    "basin", "beach", "beard", "bench", "berry", "bison", "blade", "blanket", "bloom", "board",
+    "bonus", "boot", "bread", "brick", "bridge", "brook", "brush", "bucket", "buffalo", "bugle",
+    "cabin", "cactus", "camel", "candle", "canoe", "canyon", "carpet", "castle", "cedar", "cello",
+    "chalk", "charm", "cherry", "chess", "cider", "circle", "clock", "cloud", "clover", "coast",
+    "cobra", "comet", "coral", "cotton", "crane", "crayon", "cricket", "crown", "cymbal", "daisy",
+    "dance", "delta", "denim", "desert", "diamond", "dinner", "dolphin", "donkey", "dragon",
+    "dream", "drum", "eagle", "easel", "echo", "eclipse", "elbow", "ember", "engine", "envoy",
+    "falcon", "feather", "fern", "ferry", "fiddle", "field", "flag", "flute", "forest", "fossil",
+    "fox", "frost", "garden", "garlic", "geyser", "ginger", "glacier", "globe", "goat", "gravel",
+    "guitar", "hammer", "harbor", "harp", "hazel", "helmet", "heron", "hill", "honey", "horizon",
+    "hotel", "igloo", "island", "ivory", "jacket", "jaguar", "jasmine", "jelly", "jewel", "jungle",
+    "kayak", "kettle", "kitten", "koala", "ladder", "lagoon", "lantern", "lemon", "lily", "lizard",
+    "lobster", "locket", "lotus", "magnet", "mango", "maple", "marble", "meadow", "melon",
+    "mirror", "mitten", "monkey", "moose", "mosaic", "motor", "muffin", "nectar", "needle", "nest",
+    "noodle", "oasis", "ocean", "olive", "onion", "orbit", "orchid", "otter", "owl", "paddle",
+    "palace", "panda", "parrot", "peach", "pebble", "pepper", "piano", "pilot", "planet", "plum",
+    "pocket", "pony", "prism", "puzzle", "quartz", "quill", "rabbit", "radar", "raven", "ribbon",
+    "river", "robin", "rocket", "saddle", "salmon", "sandal", "satin", "scarf", "shell", "silver",
+    "sketch", "sled", "socket", "spoon", "squid", "stamp", "statue", "summit", "sunset", "swan",
+    "table", "tango", "temple", "thistle", "thunder", "tiger", "timber", "toast", "tomato",
+    "topaz", "torch", "tractor", "trumpet", "tulip", "tunnel", "turtle", "umbrella", "unicorn",
+    "valley", "velvet", "violin", "volcano", "wagon", "walnut", "walrus", "whale", "wheat",
+    "whistle", "willow", "window", "wizard", "yacht", "yarn", "yogurt", "zebra", "zephyr", "zinc",
+    "zipper",
+];
+
+/// Compute the fingerprint of bytes, as words separated by spaces.
+pub fn fingerprint(bytes: &[u8]) -> String {
+    let hash = fnv1a(b"fingerprint:".iter().chain(bytes).copied());
+    hash.to_be_bytes()[..FINGERPRINT_LEN]
+        .iter()
+        .map(|&byte| WORDS[usize::from(byte)])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shift, KeyTrait};
+    use std::collections::HashSet;
+
+    #[test]
+    fn words() {
+        assert!(WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(WORDS
+            .iter()
+            .all(|word| word.chars().all(|c| c.is_ascii_lowercase())));
+    }
+
+    #[test]
+    fn shift_keys() {
+        // The 26 keys of the Shift Cipher have distinct fingerprints
+        let fingerprints: HashSet<String> = (0..26)
+            .map(|k| {
+                shift::Key::try_import(&k.to_string())
+                    .unwrap()
+                    .fingerprint()
+            })
+            .collect();
+        assert_eq!(fingerprints.len(), 26);
+
+        assert_eq!(
+            fingerprint(b"11"),
+            shift::Key::try_import("11").unwrap().fingerprint()
+        );
+        assert_eq!(fingerprint(b"11"), "bison clock board locket");
+    }
+}
@@ -0,0 +1,349 @@
+//! Storing keys in files, in a small versioned format.
+//!
+//! A keyfile consists of, in order:
+//! - the magic bytes [`MAGIC`],
+//! - the version of the format, [`KEYFILE_VERSION`], as one byte,
+//! - the length of the name of the cipher, as one byte, followed by the name
+//!   in UTF-8, as in [`CipherInfo::name`](crate::CipherInfo::name),
+//! - the key material, i.e., the key as written by [`KeyTrait::export`], in
+//!   UTF-8.
+//!
+//! Loading a key checks the magic bytes, the version and the name of the
+//! cipher, so that a key of one cipher is never loaded as a key of another.
+//! Like [`export`](KeyTrait::export), this does nothing to protect the key:
+//! anyone who can read the file can read the key.
+//!
+//! # Protected keyfiles
+//! [`KeyFile::save_protected`] wraps the keyfile in a toy passphrase
+//! encryption, so that the key is not in plaintext on disk. A protected
+//! keyfile consists of the magic bytes [`PROTECTED_MAGIC`], a random salt of
+//! [`SALT_LEN`] bytes, and the keyfile XORed with a keystream. The keystream
+//! is made of the 64-bit FNV-1a hashes of the salt, the passphrase and a
+//! block counter. A wrong passphrase is detected because the decrypted bytes
+//! are not a keyfile.
+//!
+//! This is for learning only and is NOT secure: FNV-1a is not a cryptographic
+//! hash, nothing slows down an attacker who guesses passphrases, and nothing
+//! detects a file that was altered. Real systems derive the key with a
+//! password-based key derivation function such as Argon2 and encrypt it with
+//! an authenticated cipher such as AES-GCM.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{keyfile::KeyFile, shift::Key, KeyTrait};
+//! # use rand::thread_rng;
+//! let key = Key::new(&mut thread_rng());
+//! let path = std::env::temp_dir().join("classical_crypto_doc.key");
+//!
+//! key.save_to(&path).unwrap();
+//! assert_eq!(Key::load_from(&path).unwrap(), key);
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+use crate::{errors::KeyFileError, fnv1a, CipherTrait, KeyTrait};
+use rand::{rngs::OsRng, RngCore};
+use std::{fs, io, path::Path};
+
+/// The bytes at the start of every keyfile.
+pub const MAGIC: &[u8; 5] = b"CCKEY";
+
+/// The version of the keyfile format. It changes only if a keyfile written in
+/// the format could no longer be read.
+pub const KEYFILE_VERSION: u8 = 1;
+
+/// The bytes at the start of every protected keyfile.
+pub const PROTECTED_MAGIC: &[u8; 5] = b"CCPKY";
+
+/// The number of bytes of the random salt of a protected keyfile.
+pub const SALT_LEN: usize = 16;
+
+/// A key that can be saved to and loaded from a keyfile.
+pub trait KeyFile: KeyTrait {
+    /// The cipher of the key, whose name is written to the keyfile.
+    type Cipher: CipherTrait;
+
+    /// Write the key to a keyfile at `path`, replacing any file there.
+    ///
+    /// # Errors
+    /// This method returns an error if the file could not be written.
+    fn save_to<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, encode(Self::Cipher::info().name, &self.export()))
+    }
+
+    /// Read the key from the keyfile at `path`.
+    ///
+    /// # Errors
+    /// This function returns an error if the file could not be read, if it is
+    /// not a keyfile of a supported version, if it holds a key of a different
+    /// cipher, or if the key material is not a key.
+    fn load_from<P: AsRef<Path>>(path: P) -> Result<Self, KeyFileError> {
+        let material = decode(Self::Cipher::info().name, &fs::read(path)?)?;
+        Ok(Self::try_import(&material)?)
+    }
+
+    /// Write the key to a protected keyfile at `path`, replacing any file
+    /// there. See the [module documentation](self) for why this toy
+    /// protection is NOT secure.
+    ///
+    /// # Errors
+    /// This method returns an error if the file could not be written.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{errors::KeyFileError, keyfile::KeyFile, shift::Key, KeyTrait};
+    /// # use rand::thread_rng;
+    /// let key = Key::new(&mut thread_rng());
+    /// let path = std::env::temp_dir().join("classical_crypto_doc_protected.key");
+    ///
+    /// key.save_protected(&path, "correct horse").unwrap();
+    /// assert_eq!(Key::load_protected(&path, "correct horse").unwrap(), key);
+    /// assert!(matches!(Key::load_protected(&path, "wrong horse"), Err(KeyFileError::WrongPassphrase)));
+    /// assert!(matches!(Key::load_from(&path), Err(KeyFileError::PassphraseRequired)));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn save_protected<P: AsRef<Path>>(&self, path: P, passphrase: &str) -> io::Result<()> {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let keyfile = encode(Self::Cipher::info().name, &self.export());
+        fs::write(path, protect(&keyfile, passphrase, &salt))
+    }
+
+    /// Read the key from the protected keyfile at `path`.
+    ///
+    /// # Errors
+    /// This function returns an error if the file could not be read, if it is
+    /// not a protected keyfile, if the passphrase is wrong, or for any of the
+    /// reasons that [`load_from`](KeyFile::load_from) fails.
+    fn load_protected<P: AsRef<Path>>(path: P, passphrase: &str) -> Result<Self, KeyFileError> {
+        let keyfile = unprotect(&fs::read(path)?, passphrase)?;
+        let material = decode(Self::Cipher::info().name, &keyfile)?;
+        Ok(Self::try_import(&material)?)
+    }
+}
+
+// Write a keyfile holding the key `material` of the cipher `name`.
+fn encode(name: &str, material: &str) -> Vec<u8> {
+    let len = u8::try_from(name.len()).expect("Cipher names are short");
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend([KEYFILE_VERSION, len]);
+    bytes.extend(name.as_bytes());
+    bytes.extend(material.as_bytes());
+    bytes
+}
+
+// Read the key material of the cipher `name` from a keyfile.
+fn decode(name: &str, bytes: &[u8]) -> Result<String, KeyFileError> {
+    if bytes.starts_with(PROTECTED_MAGIC) {
+        return Err(KeyFileError::PassphraseRequired);
+    }
+    let rest = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or(KeyFileError::NotAKeyfile)?;
+    let (&version, rest) = rest.split_first().ok_or(KeyFileError::NotAKeyfile)?;
+    if version != KEYFILE_VERSION {
+        return Err(KeyFileError::UnsupportedVersion(version));
+    }
+
+    let (&len, rest) = rest.split_first().ok_or(KeyFileError::NotAKeyfile)?;
+    if rest.len() < len as usize {
+        return Err(KeyFileError::NotAKeyfile);
+    }
+    let (found, material) = rest.split_at(len as usize);
+    let found = String::from_utf8_lossy(found);
+    if found != name {
+        return Err(KeyFileError::WrongCipher {
+            expected: name.to_string(),
+            found: found.into_owned(),
+        });
+    }
+
+    String::from_utf8(material.to_vec()).map_err(|_| KeyFileError::NotAKeyfile)
+}
+
+// Encrypt a keyfile under the passphrase, with the toy keystream.
+fn protect(keyfile: &[u8], passphrase: &str, salt: &[u8; SALT_LEN]) -> Vec<u8> {
+    let mut bytes = PROTECTED_MAGIC.to_vec();
+    bytes.extend(salt);
+    bytes.extend(
+        keyfile
+            .iter()
+            .zip(keystream(salt, passphrase))
+            .map(|(byte, key)| byte ^ key),
+    );
+    bytes
+}
+
+// Decrypt a protected keyfile, checking that the result is a keyfile.
+fn unprotect(bytes: &[u8], passphrase: &str) -> Result<Vec<u8>, KeyFileError> {
+    let rest = bytes
+        .strip_prefix(PROTECTED_MAGIC.as_slice())
+        .ok_or(KeyFileError::NotAKeyfile)?;
+    if rest.len() < SALT_LEN {
+        return Err(KeyFileError::NotAKeyfile);
+    }
+    let (salt, ciphertext) = rest.split_at(SALT_LEN);
+
+    let keyfile: Vec<u8> = ciphertext
+        .iter()
+        .zip(keystream(salt, passphrase))
+        .map(|(byte, key)| byte ^ key)
+        .collect();
+    if !keyfile.starts_with(MAGIC) {
+        return Err(KeyFileError::WrongPassphrase);
+    }
+    Ok(keyfile)
+}
+
+// The toy keystream: the FNV-1a hashes of the salt, the passphrase and a block counter, 8 bytes
+// per block.
+fn keystream<'a>(salt: &'a [u8], passphrase: &'a str) -> impl Iterator<Item = u8> + 'a {
+    (0_u64..).flat_map(move |block| {
+        let input = salt.iter().chain(passphrase.as_bytes()).copied();
+        fnv1a(input.chain(block.to_le_bytes())).to_le_bytes()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alberti, playfair, shift, EncodingError};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use std::path::PathBuf;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    // Get a path in the temporary directory that no other test uses.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "classical_crypto_{}_{name}.key",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(encode("shift", "3"), b"CCKEY\x01\x05shift3");
+        assert_eq!(decode("shift", b"CCKEY\x01\x05shift3").unwrap(), "3");
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let path = temp_path("round_trip");
+
+        let key = shift::Key::new(&mut rng);
+        key.save_to(&path).unwrap();
+        assert_eq!(shift::Key::load_from(&path).unwrap(), key);
+
+        let key = playfair::Key::new(&mut rng);
+        key.save_to(&path).unwrap();
+        assert_eq!(playfair::Key::load_from(&path).unwrap(), key);
+
+        let key = alberti::Key::new(&mut rng);
+        key.save_to(&path).unwrap();
+        assert_eq!(alberti::Key::load_from(&path).unwrap(), key);
+
+        // A key of one cipher is not loaded as a key of another
+        assert!(matches!(
+            shift::Key::load_from(&path),
+            Err(KeyFileError::WrongCipher { expected, found }) if expected == "shift" && found == "alberti"
+        ));
+
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            shift::Key::load_from(&path),
+            Err(KeyFileError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        for bytes in [
+            &b""[..],
+            b"CCKE",
+            b"KEYCC\x01\x05shift3",
+            b"CCKEY",
+            b"CCKEY\x01",
+            b"CCKEY\x01\x06shift",
+            b"CCKEY\x01\x05shift\xff",
+        ] {
+            assert!(matches!(
+                decode("shift", bytes),
+                Err(KeyFileError::NotAKeyfile)
+            ));
+        }
+        assert!(matches!(
+            decode("shift", b"CCKEY\x02\x05shift3"),
+            Err(KeyFileError::UnsupportedVersion(2))
+        ));
+
+        let path = temp_path("errors");
+        fs::write(&path, b"CCKEY\x01\x05shift26").unwrap();
+        assert!(matches!(
+            shift::Key::load_from(&path),
+            Err(KeyFileError::Key(EncodingError::KeyFormat { .. }))
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn protected() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let path = temp_path("protected");
+
+        let key = playfair::Key::new(&mut rng);
+        key.save_protected(&path, "hunter2").unwrap();
+        let bytes = fs::read(&path).unwrap();
+        assert!(bytes.starts_with(PROTECTED_MAGIC));
+        assert!(!bytes.windows(8).any(|window| window == b"playfair"));
+        assert!(!String::from_utf8_lossy(&bytes).contains(&key.export()));
+
+        assert_eq!(
+            playfair::Key::load_protected(&path, "hunter2").unwrap(),
+            key
+        );
+        assert!(matches!(
+            playfair::Key::load_protected(&path, "hunter3"),
+            Err(KeyFileError::WrongPassphrase)
+        ));
+        assert!(matches!(
+            playfair::Key::load_from(&path),
+            Err(KeyFileError::PassphraseRequired)
+        ));
+        assert!(matches!(
+            shift::Key::load_protected(&path, "hunter2"),
+            Err(KeyFileError::WrongCipher { .. })
+        ));
+
+        // A plain keyfile is not a protected keyfile
+        key.save_to(&path).unwrap();
+        assert!(matches!(
+            playfair::Key::load_protected(&path, "hunter2"),
+            Err(KeyFileError::NotAKeyfile)
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn protected_format() {
+        let salt = [7; SALT_LEN];
+        let keyfile = encode("shift", "3");
+        let bytes = protect(&keyfile, "passphrase", &salt);
+        assert_eq!(
+            bytes.len(),
+            PROTECTED_MAGIC.len() + SALT_LEN + keyfile.len()
+        );
+        assert_eq!(&bytes[5..5 + SALT_LEN], &salt);
+
+        // The salt changes the ciphertext
+        assert_ne!(bytes, protect(&keyfile, "passphrase", &[8; SALT_LEN]));
+        assert_eq!(unprotect(&bytes, "passphrase").unwrap(), keyfile);
+        assert!(matches!(
+            unprotect(&bytes[..10], "passphrase"),
+            Err(KeyFileError::NotAKeyfile)
+        ));
+    }
+}
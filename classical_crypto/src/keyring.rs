@@ -0,0 +1,569 @@
+//! A keyring, i.e., a collection of keys of any of the ciphers in the
+//! [`registry`], each stored under a label.
+//!
+//! # Expiry and rotation
+//! A key may have an expiry time, after which it should no longer be used to
+//! encrypt, although it may still be needed to decrypt old messages.
+//! [`rotate`](Keyring::rotate) replaces a key by a fresh key of the same
+//! cipher under a new label: the old key expires at once, and the new key
+//! records the label of the key it replaces, so that the
+//! [`lineage`](Keyring::lineage) of a key can be traced back.
+//!
+//! # Format
+//! A keyring is stored as TOML, with the version of the format and one entry
+//! per key, giving its label, the name of its cipher and the key as written
+//! by [`DynCipher::export_key`](crate::dynamic::DynCipher::export_key), and
+//! optionally its expiry time, in seconds after the Unix epoch, and the label
+//! of the key it replaces:
+//! ```toml
+//! version = 1
+//!
+//! [[keys]]
+//! label = "alice"
+//! cipher = "shift"
+//! key = "11"
+//! expires = 1700000000
+//!
+//! [[keys]]
+//! label = "alice v2"
+//! cipher = "shift"
+//! key = "4"
+//! replaces = "alice"
+//! ```
+//! Like [`export_key`](crate::dynamic::DynCipher::export_key), this does nothing to protect
+//! the keys: anyone who can read the file can read the keys.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{keyring::Keyring, registry};
+//! let shift = registry::get("shift").unwrap();
+//! let mut keyring = Keyring::new();
+//! keyring.add("alice", shift.import_key("11").unwrap()).unwrap();
+//!
+//! let keyring = Keyring::from_toml(&keyring.to_toml()).unwrap();
+//! let key = keyring.get("alice").unwrap();
+//! assert_eq!(key.cipher(), "shift");
+//! assert_eq!(shift.encrypt("wewillmeetatmidnight", key).unwrap(), "HPHTWWXPPELEXTOYTRSE");
+//! ```
+use crate::{dynamic::DynKey, errors::KeyringError, registry};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The version of the keyring format. It changes only if a keyring written in
+/// the format could no longer be read.
+pub const KEYRING_VERSION: u32 = 1;
+
+/// A collection of keys, each stored under a distinct label. The keys are
+/// listed in the order of their labels.
+#[derive(Debug, Default)]
+pub struct Keyring {
+    keys: BTreeMap<String, Entry>,
+}
+
+// A key together with its expiry time and the label of the key it replaces.
+#[derive(Debug)]
+struct Entry {
+    key: DynKey,
+    expires: Option<SystemTime>,
+    replaces: Option<String>,
+}
+
+// The TOML representation of a keyring.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct RawKeyring {
+    version: u32,
+    #[serde(default)]
+    keys: Vec<RawEntry>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct RawEntry {
+    label: String,
+    cipher: String,
+    key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    replaces: Option<String>,
+}
+
+impl Keyring {
+    /// Create an empty keyring.
+    pub fn new() -> Self {
+        Keyring::default()
+    }
+
+    /// Add a key under `label`, without an expiry time.
+    ///
+    /// # Errors
+    /// This method returns an error if the label is empty or starts or ends
+    /// with whitespace, or if there is already a key under the label.
+    pub fn add(&mut self, label: &str, key: DynKey) -> Result<(), KeyringError> {
+        if label.is_empty() || label.trim() != label {
+            return Err(KeyringError::InvalidLabel(label.to_string()));
+        }
+        if self.keys.contains_key(label) {
+            return Err(KeyringError::DuplicateLabel(label.to_string()));
+        }
+
+        let entry = Entry {
+            key,
+            expires: None,
+            replaces: None,
+        };
+        let _ = self.keys.insert(label.to_string(), entry);
+        Ok(())
+    }
+
+    /// Remove the key under `label`, and return it, or `None` if there is no
+    /// key under the label.
+    pub fn remove(&mut self, label: &str) -> Option<DynKey> {
+        self.keys.remove(label).map(|entry| entry.key)
+    }
+
+    /// Get the key under `label`, or `None` if there is no key under the
+    /// label. Expired keys are returned too, see
+    /// [`is_expired`](Self::is_expired).
+    pub fn get(&self, label: &str) -> Option<&DynKey> {
+        self.keys.get(label).map(|entry| &entry.key)
+    }
+
+    /// Set or clear the expiry time of the key under `label`.
+    ///
+    /// # Errors
+    /// This method returns an error if there is no key under the label.
+    pub fn set_expiry(
+        &mut self,
+        label: &str,
+        expires: Option<SystemTime>,
+    ) -> Result<(), KeyringError> {
+        let entry = self
+            .keys
+            .get_mut(label)
+            .ok_or_else(|| KeyringError::UnknownLabel(label.to_string()))?;
+        entry.expires = expires;
+        Ok(())
+    }
+
+    /// Get the expiry time of the key under `label`, or `None` if there is no
+    /// key under the label or it does not expire.
+    pub fn expiry(&self, label: &str) -> Option<SystemTime> {
+        self.keys.get(label).and_then(|entry| entry.expires)
+    }
+
+    /// Returns true if there is a key under `label` whose expiry time has
+    /// passed and false otherwise.
+    pub fn is_expired(&self, label: &str) -> bool {
+        self.expiry(label)
+            .is_some_and(|expires| expires <= SystemTime::now())
+    }
+
+    /// Get the label of the key that the key under `label` replaces, or
+    /// `None` if it does not replace a key.
+    pub fn predecessor(&self, label: &str) -> Option<&str> {
+        self.keys.get(label)?.replaces.as_deref()
+    }
+
+    /// Get the label of a key that replaces the key under `label`, or `None` if
+    /// no key replaces it. If several keys replace it, the first in the order
+    /// of the labels is returned.
+    pub fn successor(&self, label: &str) -> Option<&str> {
+        self.keys
+            .iter()
+            .find(|(_, entry)| entry.replaces.as_deref() == Some(label))
+            .map(|(successor, _)| successor.as_str())
+    }
+
+    /// Get the labels of the key under `label` and of the keys it replaces, in
+    /// turn, newest first. The lineage stops at a key that does not replace a
+    /// key, or whose predecessor has been removed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{keyring::Keyring, registry};
+    /// use rand::thread_rng;
+    ///
+    /// let mut keyring = Keyring::new();
+    /// keyring.add("alice", registry::get("shift").unwrap().import_key("11").unwrap()).unwrap();
+    /// keyring.rotate("alice", "alice v2", None, &mut thread_rng()).unwrap();
+    /// keyring.rotate("alice v2", "alice v3", None, &mut thread_rng()).unwrap();
+    ///
+    /// assert_eq!(keyring.lineage("alice v3"), ["alice v3", "alice v2", "alice"]);
+    /// assert!(keyring.is_expired("alice v2"));
+    /// ```
+    pub fn lineage(&self, label: &str) -> Vec<&str> {
+        // A label may be reused after its key is removed, so the lineage may loop: stop at the first
+        // label that it has already visited.
+        let mut lineage = Vec::new();
+        let mut current = self.keys.get_key_value(label);
+        while let Some((label, entry)) = current {
+            if lineage.contains(&label.as_str()) {
+                break;
+            }
+            lineage.push(label.as_str());
+            current = entry
+                .replaces
+                .as_ref()
+                .and_then(|previous| self.keys.get_key_value(previous));
+        }
+        lineage
+    }
+
+    /// Rotate the key under `label`: add a new key of the same cipher, picked
+    /// uniformly at random, under `new_label`, recording that it replaces the
+    /// old key, and let the old key expire now, unless it has already
+    /// expired. The old key stays in the keyring, to decrypt old messages.
+    ///
+    /// The new key expires after `lifetime`, or never if `lifetime` is
+    /// `None`.
+    ///
+    /// # Errors
+    /// This method returns an error if there is no key under `label`, if the
+    /// new key would expire too far in the future, or for the reasons that
+    /// [`add`](Self::add) fails for `new_label`.
+    pub fn rotate<R: RngCore + CryptoRng>(
+        &mut self,
+        label: &str,
+        new_label: &str,
+        lifetime: Option<Duration>,
+        rng: &mut R,
+    ) -> Result<&DynKey, KeyringError> {
+        let cipher = self
+            .get(label)
+            .and_then(|key| registry::get(key.cipher()))
+            .ok_or_else(|| KeyringError::UnknownLabel(label.to_string()))?;
+        let now = SystemTime::now();
+        let expires = lifetime
+            .map(|lifetime| {
+                now.checked_add(lifetime)
+                    .ok_or(KeyringError::Lifetime(lifetime))
+            })
+            .transpose()?;
+        self.add(new_label, cipher.generate_key(rng))?;
+
+        let new = self.keys.get_mut(new_label).expect("The new key was added");
+        new.expires = expires;
+        new.replaces = Some(label.to_string());
+
+        let old = self.keys.get_mut(label).expect("The old key exists");
+        old.expires = Some(old.expires.map_or(now, |expires| expires.min(now)));
+
+        Ok(&self.keys[new_label].key)
+    }
+
+    /// Get the number of keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true if the keyring has no keys and false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Iterate over the labels and keys, in the order of the labels.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &DynKey)> {
+        self.keys
+            .iter()
+            .map(|(label, entry)| (label.as_str(), &entry.key))
+    }
+
+    /// Write the keyring as TOML.
+    pub fn to_toml(&self) -> String {
+        let raw = RawKeyring {
+            version: KEYRING_VERSION,
+            keys: self
+                .keys
+                .iter()
+                .map(|(label, entry)| RawEntry {
+                    label: label.to_string(),
+                    cipher: entry.key.cipher().to_string(),
+                    key: registry::get(entry.key.cipher())
+                        .and_then(|cipher| cipher.export_key(&entry.key).ok())
+                        .expect("Every key handle belongs to a registered cipher"),
+                    // Times before the epoch only arise from a badly set clock
+                    expires: entry.expires.map(|expires| {
+                        expires
+                            .duration_since(UNIX_EPOCH)
+                            .map_or(0, |elapsed| elapsed.as_secs())
+                    }),
+                    replaces: entry.replaces.clone(),
+                })
+                .collect(),
+        };
+
+        toml::to_string(&raw).expect("A keyring is always valid TOML")
+    }
+
+    /// Parse a keyring from TOML.
+    ///
+    /// # Errors
+    /// This function returns an error if the input is not valid TOML, has
+    /// unknown or missing fields, or is of an unsupported version, or for any
+    /// of the reasons that [`add`](Self::add) or
+    /// [`DynCipher::import_key`](crate::dynamic::DynCipher::import_key) fail.
+    pub fn from_toml(toml: &str) -> Result<Self, KeyringError> {
+        let raw: RawKeyring = toml::from_str(toml)?;
+        if raw.version != KEYRING_VERSION {
+            return Err(KeyringError::UnsupportedVersion(raw.version));
+        }
+
+        let mut keyring = Keyring::new();
+        for entry in raw.keys {
+            let cipher = registry::get(&entry.cipher)
+                .ok_or_else(|| KeyringError::UnknownCipher(entry.cipher.clone()))?;
+            let key = cipher
+                .import_key(&entry.key)
+                .map_err(|source| KeyringError::Key {
+                    label: entry.label.clone(),
+                    source,
+                })?;
+            keyring.add(&entry.label, key)?;
+
+            let added = keyring
+                .keys
+                .get_mut(&entry.label)
+                .expect("The key was added");
+            added.expires = entry
+                .expires
+                .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds));
+            added.replaces = entry.replaces;
+        }
+        Ok(keyring)
+    }
+
+    /// Load a keyring from a TOML file.
+    ///
+    /// # Errors
+    /// This function returns an error if the file cannot be read, or for the
+    /// reasons given in [`Keyring::from_toml`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, KeyringError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Save the keyring to a TOML file, replacing any file there.
+    ///
+    /// # Errors
+    /// This method returns an error if the file could not be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{DynCipherError, EncodingError};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn add_remove() {
+        let shift = registry::get("shift").unwrap();
+        let mut keyring = Keyring::new();
+        assert!(keyring.is_empty());
+
+        keyring.add("bob", shift.import_key("3").unwrap()).unwrap();
+        keyring
+            .add("alice", shift.import_key("5").unwrap())
+            .unwrap();
+        assert_eq!(keyring.len(), 2);
+        let labels: Vec<&str> = keyring.iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, ["alice", "bob"]);
+
+        assert!(matches!(
+            keyring.add("bob", shift.import_key("7").unwrap()),
+            Err(KeyringError::DuplicateLabel(label)) if label == "bob"
+        ));
+        for label in ["", " carol", "carol\n"] {
+            assert!(matches!(
+                keyring.add(label, shift.import_key("7").unwrap()),
+                Err(KeyringError::InvalidLabel(_))
+            ));
+        }
+
+        let key = keyring.remove("bob").unwrap();
+        assert_eq!(shift.export_key(&key).unwrap(), "3");
+        assert!(keyring.get("bob").is_none());
+        assert!(keyring.remove("bob").is_none());
+        assert_eq!(keyring.len(), 1);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let mut keyring = Keyring::new();
+        for entry in registry::list() {
+            let cipher = entry.create();
+            keyring
+                .add(
+                    &format!("{} key", entry.name()),
+                    cipher.generate_key(&mut rng),
+                )
+                .unwrap();
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "classical_crypto_{}_keyring.toml",
+            std::process::id()
+        ));
+        keyring.save(&path).unwrap();
+        let loaded = Keyring::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), registry::list().len());
+        for ((label, key), (loaded_label, loaded_key)) in keyring.iter().zip(loaded.iter()) {
+            let cipher = registry::get(key.cipher()).unwrap();
+            assert_eq!(label, loaded_label);
+            assert_eq!(
+                cipher.export_key(key).unwrap(),
+                cipher.export_key(loaded_key).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn format() {
+        let mut keyring = Keyring::new();
+        keyring
+            .add(
+                "alice",
+                registry::get("shift").unwrap().import_key("11").unwrap(),
+            )
+            .unwrap();
+        assert_eq!(
+            keyring.to_toml(),
+            "version = 1\n\n[[keys]]\nlabel = \"alice\"\ncipher = \"shift\"\nkey = \"11\"\n"
+        );
+        assert!(Keyring::from_toml("version = 1").unwrap().is_empty());
+
+        let toml = "version = 1\n\n[[keys]]\nlabel = \"alice\"\ncipher = \"shift\"\nkey = \"11\"\nexpires = 1700000000\n\n[[keys]]\nlabel = \"alice v2\"\ncipher = \"shift\"\nkey = \"4\"\nreplaces = \"alice\"\n";
+        let keyring = Keyring::from_toml(toml).unwrap();
+        assert_eq!(
+            keyring.expiry("alice"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert_eq!(keyring.predecessor("alice v2"), Some("alice"));
+        assert_eq!(keyring.to_toml(), toml);
+    }
+
+    #[test]
+    fn expiry_and_rotation() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let mut keyring = Keyring::new();
+        keyring
+            .add(
+                "alice",
+                registry::get("playfair").unwrap().generate_key(&mut rng),
+            )
+            .unwrap();
+        assert_eq!(keyring.expiry("alice"), None);
+        assert!(!keyring.is_expired("alice"));
+
+        let later = SystemTime::now() + Duration::from_secs(3_600);
+        keyring.set_expiry("alice", Some(later)).unwrap();
+        assert!(!keyring.is_expired("alice"));
+        keyring.set_expiry("alice", Some(UNIX_EPOCH)).unwrap();
+        assert!(keyring.is_expired("alice"));
+        keyring.set_expiry("alice", None).unwrap();
+
+        let day = Duration::from_secs(86_400);
+        let key = keyring
+            .rotate("alice", "alice v2", Some(day), &mut rng)
+            .unwrap();
+        assert_eq!(key.cipher(), "playfair");
+        assert!(keyring.is_expired("alice"));
+        assert!(!keyring.is_expired("alice v2"));
+        assert!(keyring.expiry("alice v2").unwrap() > later);
+        assert_eq!(keyring.predecessor("alice v2"), Some("alice"));
+        assert_eq!(keyring.predecessor("alice"), None);
+        assert_eq!(keyring.successor("alice"), Some("alice v2"));
+        assert_eq!(keyring.successor("alice v2"), None);
+
+        let playfair = registry::get("playfair").unwrap();
+        assert_ne!(
+            playfair.export_key(keyring.get("alice").unwrap()).unwrap(),
+            playfair
+                .export_key(keyring.get("alice v2").unwrap())
+                .unwrap()
+        );
+
+        // Rotating does not move an expiry time that has passed
+        let _ = keyring
+            .rotate("alice v2", "alice v3", None, &mut rng)
+            .unwrap();
+        keyring.set_expiry("alice", Some(UNIX_EPOCH)).unwrap();
+        let _ = keyring.rotate("alice", "alice v4", None, &mut rng).unwrap();
+        assert_eq!(keyring.expiry("alice"), Some(UNIX_EPOCH));
+        assert_eq!(keyring.expiry("alice v3"), None);
+        assert_eq!(
+            keyring.lineage("alice v3"),
+            ["alice v3", "alice v2", "alice"]
+        );
+        assert_eq!(keyring.lineage("alice v4"), ["alice v4", "alice"]);
+
+        // A removed key ends the lineage, and a reused label cannot make it loop
+        let _ = keyring.remove("alice").unwrap();
+        assert_eq!(keyring.lineage("alice v2"), ["alice v2"]);
+        let _ = keyring.rotate("alice v3", "alice", None, &mut rng).unwrap();
+        assert_eq!(keyring.lineage("alice"), ["alice", "alice v3", "alice v2"]);
+        assert_eq!(keyring.lineage("bob"), Vec::<&str>::new());
+
+        assert!(matches!(
+            keyring.rotate("bob", "bob v2", None, &mut rng),
+            Err(KeyringError::UnknownLabel(label)) if label == "bob"
+        ));
+        assert!(matches!(
+            keyring.rotate("alice", "alice v2", None, &mut rng),
+            Err(KeyringError::DuplicateLabel(_))
+        ));
+        assert!(matches!(
+            keyring.rotate("alice", "alice v5", Some(Duration::MAX), &mut rng),
+            Err(KeyringError::Lifetime(Duration::MAX))
+        ));
+        assert!(keyring.get("alice v5").is_none());
+        assert!(matches!(
+            keyring.set_expiry("bob", None),
+            Err(KeyringError::UnknownLabel(_))
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Keyring::from_toml("version = 2"),
+            Err(KeyringError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            Keyring::from_toml("keys = []"),
+            Err(KeyringError::Parse(_))
+        ));
+        assert!(matches!(
+            Keyring::from_toml("version = 1\n[[keys]]\nlabel = \"a\"\ncipher = \"enigma\"\nkey = \"1\""),
+            Err(KeyringError::UnknownCipher(cipher)) if cipher == "enigma"
+        ));
+        assert!(matches!(
+            Keyring::from_toml("version = 1\n[[keys]]\nlabel = \"a\"\ncipher = \"shift\"\nkey = \"26\""),
+            Err(KeyringError::Key { label, source: DynCipherError::Encoding(EncodingError::KeyFormat { .. }) }) if label == "a"
+        ));
+        assert!(matches!(
+            Keyring::from_toml("version = 1\n[[keys]]\nlabel = \"a\"\ncipher = \"shift\"\nkey = \"1\"\n[[keys]]\nlabel = \"a\"\ncipher = \"shift\"\nkey = \"2\""),
+            Err(KeyringError::DuplicateLabel(_))
+        ));
+        assert!(matches!(
+            Keyring::load("/nonexistent/keyring.toml"),
+            Err(KeyringError::Io(_))
+        ));
+    }
+}
@@ -15,9 +15,12 @@
 #![warn(rustdoc::unescaped_backticks)]
 #![warn(rustdoc::redundant_explicit_links)]
 
-//! Currently we implement the Shift Cipher using the Latin Alphabet. We plan to
-//! implement the other classical ciphers (also using the Latin Alphabet) as
-//! presented in Douglas R. Stinson's _Cryptography: Theory and Practice_.
+//! Currently we implement the Shift Cipher and the Playfair Cipher using the
+//! Latin Alphabet, although the underlying types are generic over the alphabet
+//! (see the [`alphabet`] module). We plan to implement the other classical ciphers (also
+//! using the Latin Alphabet) as presented in Douglas R. Stinson's
+//! _Cryptography: Theory and Practice_, together with tools for attacking them
+//! (see the [`analysis`] and [`attack`] modules).
 //!
 //! The Shift Cipher, Affine Cipher, and Substitution Cipher all make use of an
 //! encoding of the Latin Alphabet in the ring of integers modulo 26, which we
@@ -27,21 +30,104 @@
 //! We allow for messages (and, correspondingly, ciphertexts) of arbitrary
 //! length, because in practice we can encrypt (and decrypt) using ordered
 //! sequences of ring elements (i.e., plaintexts and ciphertexts, respectively).
+//!
+//! Enabling the `rayon` feature runs long searches, such as the restarts of
+//! [`playfair::SimulatedAnnealing`], in parallel. The results do not depend on
+//! whether the feature is enabled.
+//!
+//! Enabling the `rand-reexport` feature adds the `rand` module,
+//! which re-exports the RNG types that this crate is built against.
+//! Enabling the `serde-keys` feature adds the `serialize` module, which
+//! implements `Serialize` and `Deserialize` for the keys of the ciphers.
+//! Enabling the `serde-texts` feature adds the `serialize_texts` module,
+//! which does the same for messages and ciphertexts.
+//! Enabling the `toml` feature adds the `config` module, which loads
+//! alphabets, ciphers and policies from TOML, and the `keyring` module, which
+//! stores labeled keys in a TOML file.
+//! Enabling the `audit-log` feature adds the `audit` module, which records
+//! which key was used for which operation, and when.
 // (&#x2124; is Unicode for blackboard bold Z)
 
-use rand::{CryptoRng, Rng};
+use ::rand::{
+    rngs::{OsRng, StdRng},
+    CryptoRng, Rng, RngCore, SeedableRng,
+};
 use std::{
     fmt,
-    ops::{Add, Sub},
+    hash::Hash,
+    io::{self, Read, Write},
+    iter::Sum,
+    marker::PhantomData,
+    ops::{Add, AddAssign, Index, Mul, Neg, RangeBounds, Sub, SubAssign},
+    slice,
     str::FromStr,
+    vec,
 };
-
+use subtle::{Choice, ConstantTimeEq};
+
+pub mod alberti;
+pub mod alphabet;
+pub mod analysis;
+pub mod armor;
+pub mod attack;
+#[cfg(feature = "audit-log")]
+pub mod audit;
+pub mod block;
+pub mod bytes;
+pub mod codec;
+#[cfg(feature = "toml")]
+pub mod config;
+pub mod context;
+pub mod corpus;
+pub mod diff;
+pub mod disk;
+pub mod dynamic;
 pub mod errors;
+pub mod fingerprint;
+pub mod keyfile;
+#[cfg(feature = "toml")]
+pub mod keyring;
+pub mod math;
+pub mod matrix;
+pub mod morse;
+pub mod permutation;
+pub mod playfair;
+pub mod policy;
+pub mod polybius;
+#[cfg(feature = "rand-reexport")]
+pub mod rand;
+pub mod registry;
+pub mod schedule;
+pub mod secret;
+#[cfg(feature = "serde-keys")]
+pub mod serialize;
+#[cfg(feature = "serde-texts")]
+pub mod serialize_texts;
+pub mod session;
 pub mod shift;
+pub mod stream;
+pub mod tabula;
+pub mod toy_dh;
+pub mod toy_rsa;
+pub mod toy_shamir;
+pub mod trace;
+pub mod transliteration;
+pub mod verify;
+
+use crate::{
+    alphabet::{Alphabet, Latin},
+    analysis::FrequencyTable,
+    diff::TextDiff,
+    errors::{
+        EncodingError, InvalidCharacter, InvalidCharacters, ReadError, RekeyError, StreamError,
+        StripReport, ValidationReport,
+    },
+    policy::{CasePolicy, CipherSettings, MessagePolicy, ParsePolicy},
+};
 
-use crate::errors::{EncodingError, ErrorRepr};
-
-/// This trait represents a deterministic cipher.
+/// This trait represents a deterministic cipher. An instance of the cipher
+/// carries its configuration, e.g., its alphabet or its padding, so that the
+/// cipher encrypts and decrypts through `&self`.
 pub trait CipherTrait {
     /// The message space (plaintext space) of the cipher.
     type Message;
@@ -52,96 +138,564 @@ pub trait CipherTrait {
     /// The keyspace of the cipher, which must implement the [`KeyTrait`] trait.
     type Key: KeyTrait;
 
+    /// The error returned when encryption fails. Ciphers that can encrypt
+    /// every message under every key use [`Infallible`](std::convert::Infallible).
+    type EncryptionError: std::error::Error;
+
+    /// The error returned when decryption fails, e.g., a
+    /// [`CipherError`](errors::CipherError) for a ciphertext that cannot have
+    /// been produced by the encryption function.
+    type DecryptionError: std::error::Error;
+
     /// The encryption function of the cipher.
     /// Invariant: For each key `k` in the keyspace, we have decrypt(encrypt(m,
     /// k), k) = m for every message `m` in the message space.
-    fn encrypt(msg: &Self::Message, key: &Self::Key) -> Self::Ciphertext;
+    ///
+    /// # Errors
+    /// This method returns an error if the message cannot be encrypted
+    /// under the key.
+    fn encrypt(
+        &self,
+        msg: &Self::Message,
+        key: &Self::Key,
+    ) -> Result<Self::Ciphertext, Self::EncryptionError>;
 
     /// The decryption function of the cipher.
     /// Invariant: For each key `k` in the keyspace, we have decrypt(encrypt(m,
     /// k), k) = m for every message `m` in the message space.
-    fn decrypt(ciphertxt: &Self::Ciphertext, key: &Self::Key) -> Self::Message;
+    ///
+    /// # Errors
+    /// This method returns an error if the ciphertext cannot be decrypted
+    /// under the key.
+    fn decrypt(
+        &self,
+        ciphertxt: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Result<Self::Message, Self::DecryptionError>;
+
+    /// Describe the cipher.
+    fn info() -> CipherInfo;
+
+    /// Decrypt a ciphertext under `old_key` and encrypt the result under
+    /// `new_key` in one call, e.g., to rotate keys. Only the new ciphertext is
+    /// returned, so the caller never holds the plaintext.
+    ///
+    /// # Errors
+    /// This method returns an error if the ciphertext cannot be decrypted
+    /// under the old key, or the plaintext cannot be encrypted under the new
+    /// key.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{shift::{Ciphertext, Key, ShiftCipher}, CipherTrait};
+    /// # use std::str::FromStr;
+    /// let old_key = Key::from_str("11").unwrap();
+    /// let new_key = Key::from_str("3").unwrap();
+    ///
+    /// let ciphertxt = Ciphertext::from_str("HPHTWWXPPELEXTOYTRSE").unwrap();
+    /// let ciphertxt = ShiftCipher::new().rekey(&ciphertxt, &old_key, &new_key).unwrap();
+    /// assert_eq!(ciphertxt.to_string(), "ZHZLOOPHHWDWPLGQLJKW");
+    /// ```
+    fn rekey(
+        &self,
+        ciphertxt: &Self::Ciphertext,
+        old_key: &Self::Key,
+        new_key: &Self::Key,
+    ) -> Result<Self::Ciphertext, RekeyError<Self::DecryptionError, Self::EncryptionError>> {
+        let msg = self
+            .decrypt(ciphertxt, old_key)
+            .map_err(RekeyError::Decryption)?;
+        self.encrypt(&msg, new_key).map_err(RekeyError::Encryption)
+    }
 }
 
 /// A trait for cryptographic keys.
-pub trait KeyTrait {
+pub trait KeyTrait: Sized {
     /// Pick a new key from the key space uniformly at random.
-    fn new<R: Rng + CryptoRng>(rng: &mut R) -> Self;
+    ///
+    /// Any cryptographically secure RNG from the `rand_core` traits works
+    /// here, so callers need not depend on the full `rand` crate.
+    fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self;
+
+    /// Pick a new key from the key space uniformly at random, using the
+    /// random number generator of the operating system.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{shift::Key, KeyTrait};
+    /// let key = Key::generate();
+    ///
+    /// assert!(Key::try_import(&key.export()).is_ok());
+    /// ```
+    fn generate() -> Self {
+        Self::new(&mut OsRng)
+    }
+
+    /// Export the key, insecurely, as a string that
+    /// [`try_import`](KeyTrait::try_import) accepts.
+    ///
+    /// This method does not do anything special for secure key handling,
+    /// which is another, more complicated and error-prone topic. Use caution.
+    fn export(&self) -> String;
+
+    /// Import a key from a string in the format written by
+    /// [`export`](KeyTrait::export).
+    ///
+    /// # Errors
+    /// This function returns an error if the string does not represent a key.
+    fn try_import(s: &str) -> Result<Self, EncodingError>;
+
+    /// Derive a key deterministically from a passphrase, so that the same
+    /// phrase always gives the same key.
+    ///
+    /// The phrase is hashed with the 64-bit FNV-1a hash, and the hash seeds
+    /// the RNG that picks the key with [`new`](KeyTrait::new). The phrase is
+    /// used exactly as given, so case and spacing matter.
+    ///
+    /// This is for teaching and demos only and is NOT secure: FNV-1a is not a
+    /// cryptographic hash, there are only 2<sup>64</sup> seeds, and nothing
+    /// slows down an attacker who guesses phrases. Real systems use a
+    /// password-based key derivation function such as Argon2 with a random
+    /// salt. Also note that the key is picked with [`StdRng`],
+    /// whose algorithm may change with new versions of `rand`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{shift::Key, KeyTrait};
+    /// let key = Key::derive_from_passphrase("correct horse battery staple");
+    ///
+    /// assert_eq!(key, Key::derive_from_passphrase("correct horse battery staple"));
+    /// ```
+    fn derive_from_passphrase(phrase: &str) -> Self {
+        Self::new(&mut StdRng::seed_from_u64(fnv1a(phrase.bytes())))
+    }
+
+    /// Check whether the key is weak, i.e., whether a ciphertext under the
+    /// key is much easier to break than under a typical key, and if so, say
+    /// why. By default, no key is weak.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{shift::Key, KeyTrait, WeakKey};
+    /// # use std::str::FromStr;
+    /// assert_eq!(Key::from_str("0").unwrap().is_weak(), Some(WeakKey::Identity));
+    /// assert_eq!(Key::from_str("3").unwrap().is_weak(), None);
+    /// ```
+    fn is_weak(&self) -> Option<WeakKey> {
+        None
+    }
+
+    /// Get the fingerprint of the key: a few words that people can compare
+    /// to check that they hold the same key, see the
+    /// [`fingerprint`] module for the caveats.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{shift::Key, KeyTrait};
+    /// # use std::str::FromStr;
+    /// let key = Key::from_str("11").unwrap();
+    ///
+    /// assert_eq!(key.fingerprint(), Key::from_str("l").unwrap().fingerprint());
+    /// assert_ne!(key.fingerprint(), Key::from_str("12").unwrap().fingerprint());
+    /// ```
+    fn fingerprint(&self) -> String {
+        fingerprint::fingerprint(self.export().as_bytes())
+    }
+
+    /// Pick a new key uniformly at random from the keys that are not weak, by
+    /// picking keys with [`new`](KeyTrait::new) until one is not weak.
+    ///
+    /// Note that leaving out the weak keys makes the key space smaller, and
+    /// so tells an attacker which keys were not used.
+    fn new_strong<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        loop {
+            let key = Self::new(rng);
+            if key.is_weak().is_none() {
+                return key;
+            }
+        }
+    }
+}
+
+/// The reason that a key is weak, see [`KeyTrait::is_weak`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum WeakKey {
+    /// Encryption leaves every message unchanged, e.g., the shift by 0 or
+    /// the identity permutation.
+    Identity,
+    /// The mixed alphabet of the key is the alphabet in order, e.g., the inner
+    /// ring of a cipher disk, so that the key reduces to a few shifts.
+    UnmixedAlphabet,
+}
+
+impl fmt::Display for WeakKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeakKey::Identity => write!(f, "Encryption under the key leaves messages unchanged"),
+            WeakKey::UnmixedAlphabet => {
+                write!(
+                    f,
+                    "The alphabet of the key is not mixed, so it reduces to shifts"
+                )
+            }
+        }
+    }
+}
+
+// Hash bytes with 64-bit FNV-1a, which (unlike the hashers of the standard library) is stable.
+// It is NOT a cryptographic hash.
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes
+        .into_iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// A key space in which keys compose, i.e., in which encrypting with one key
+/// and then with another is the same as encrypting with a single key, and
+/// every key has an inverse key. Such a key space is a group, so that
+/// encrypting twice is no more secure than encrypting once.
+///
+/// For example, shifting by 3 and then by 5 is shifting by 8, and permuting
+/// the letters of the alphabet twice, as with two keys of the Substitution
+/// Cipher, is permuting them once.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{shift::{Key, Message, ShiftCipher}, CipherTrait, ComposableKey};
+/// # use std::str::FromStr;
+/// let k1 = Key::from_str("3").unwrap();
+/// let k2 = Key::from_str("5").unwrap();
+/// let msg = Message::new("double shift").unwrap();
+///
+/// // A double shift is a single shift
+/// let once = ShiftCipher::new().encrypt(&msg, &k1).unwrap().to_string().to_lowercase();
+/// let twice = ShiftCipher::new().encrypt(&Message::new(&once).unwrap(), &k2).unwrap();
+/// assert_eq!(twice, ShiftCipher::new().encrypt(&msg, &k2.compose(&k1)).unwrap());
+///
+/// // Encrypting with the inverse key decrypts
+/// let undone = ShiftCipher::new().encrypt(&Message::new(&once).unwrap(), &k1.inverse()).unwrap();
+/// assert_eq!(undone.to_string().to_lowercase(), msg.to_string());
+/// ```
+pub trait ComposableKey: Sized {
+    /// Compose two keys, encrypting with `other` first and then with `self`,
+    /// following the composition of functions.
+    ///
+    /// # Panics
+    /// Implementations may panic if the keys cannot be composed, e.g.,
+    /// permutations of different sizes.
+    fn compose(&self, other: &Self) -> Self;
+
+    /// Get the inverse key, i.e., the key whose encryption function is the
+    /// decryption function of `self`.
+    fn inverse(&self) -> Self;
+
+    /// Get the key that relates `other` to `self`, i.e., the key `d` such that
+    /// encrypting with `other` and then with `d` is encrypting with `self`.
+    /// Related keys, whose relative key is known, are the subject of
+    /// related-key attacks.
+    ///
+    /// # Panics
+    /// Implementations may panic if the keys cannot be composed, see
+    /// [`compose`](ComposableKey::compose).
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{shift::Key, ComposableKey};
+    /// # use std::str::FromStr;
+    /// let k1 = Key::from_str("3").unwrap();
+    /// let k2 = Key::from_str("5").unwrap();
+    ///
+    /// let d = k2.relative_to(&k1);
+    /// assert_eq!(d, Key::from_str("2").unwrap());
+    /// assert_eq!(d.compose(&k1), k2);
+    /// ```
+    fn relative_to(&self, other: &Self) -> Self {
+        self.compose(&other.inverse())
+    }
+}
+
+/// A description of a cipher, e.g., for a user interface, see
+/// [`CipherTrait::info`].
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{shift::ShiftCipher, CipherTrait};
+/// let info = ShiftCipher::info();
+///
+/// assert_eq!(info.name, "shift");
+/// assert_eq!(info.key_count, Some(26));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct CipherInfo {
+    /// The name of the cipher, as used by the [`registry`].
+    pub name: &'static str,
+    /// Who invented, or first described, the cipher.
+    pub inventor: &'static str,
+    /// When the cipher was invented, or first described.
+    pub era: &'static str,
+    /// The alphabet of the messages and ciphertexts.
+    pub alphabet: &'static str,
+    /// The number of letters of the alphabet.
+    pub alphabet_size: usize,
+    /// The number of letters that are encrypted together, i.e., 1 for ciphers
+    /// that encrypt letter by letter.
+    pub block_size: usize,
+    /// The number of keys, or `None` if there is no bound on the number of
+    /// keys.
+    pub key_count: Option<u128>,
+}
+
+impl CipherInfo {
+    /// Get the number of keys, or `None` if there is no bound on the number of
+    /// keys.
+    pub fn key_count(&self) -> Option<u128> {
+        self.key_count
+    }
+
+    /// Get the size of the keyspace in bits, i.e., the base 2 logarithm of the
+    /// number of keys, or `None` if there is no bound on the number of keys.
+    ///
+    /// This is the work of an exhaustive key search, which makes it easy to
+    /// compare ciphers, but it is only an upper bound on their security. The
+    /// Playfair Cipher has a far larger keyspace than the Shift Cipher, but
+    /// both fall to frequency analysis without a search over keys.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{playfair::PlayfairCipher, shift::ShiftCipher, CipherTrait};
+    /// let shift = ShiftCipher::info().security_bits().unwrap();
+    /// let playfair = PlayfairCipher::info().security_bits().unwrap();
+    ///
+    /// assert_eq!(format!("shift: {shift:.1} bits"), "shift: 4.7 bits");
+    /// assert_eq!(format!("playfair: {playfair:.1} bits"), "playfair: 83.7 bits");
+    /// ```
+    pub fn security_bits(&self) -> Option<f64> {
+        // The conversion rounds large counts, which is far below the precision
+        // of the logarithm that we report
+        self.key_count.map(|count| (count as f64).log2())
+    }
+}
+
+/// A [`Display`](fmt::Display) adapter that writes a text in groups of
+/// characters, such as the traditional groups of five letters of a ciphertext,
+/// which hide the lengths of the words of the plaintext.
+///
+/// # Panics
+/// Creating the adapter panics if the group size is 0.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{shift::Ciphertext, Grouped};
+/// # use std::str::FromStr;
+/// let ciphertxt = Ciphertext::from_str("HPHTWWXPPELEXTOYTRSE").unwrap();
+///
+/// assert_eq!(ciphertxt.format_grouped(5), "HPHTW WXPPE LEXTO YTRSE");
+/// assert_eq!(
+///     Grouped::new(&ciphertxt, 4).with_separator("-").to_string(),
+///     "HPHT-WWXP-PELE-XTOY-TRSE"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Grouped<'a, T: ?Sized> {
+    text: &'a T,
+    size: usize,
+    separator: &'a str,
+}
+
+impl<'a, T: fmt::Display + ?Sized> Grouped<'a, T> {
+    /// Write `text` in groups of `size` characters, separated by spaces.
+    pub fn new(text: &'a T, size: usize) -> Self {
+        assert!(size > 0, "The group size must be positive");
+        Grouped {
+            text,
+            size,
+            separator: " ",
+        }
+    }
+
+    /// Set the separator written between groups.
+    pub fn with_separator(self, separator: &'a str) -> Self {
+        Grouped { separator, ..self }
+    }
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for Grouped<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.text.to_string();
+        for (i, c) in text.chars().enumerate() {
+            if i > 0 && i % self.size == 0 {
+                f.write_str(self.separator)?;
+            }
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
 }
-/// This trait represents an encoding of the characters of an alphabet.
-trait AlphabetEncoding: Sized {
-    /// The associated error type.
-    type Error;
 
-    /// Convert an encoded element to a character.
-    fn to_char(self) -> char;
+/// An adapter for writing a message or ciphertext in a chosen format, as
+/// returned by the `display` methods of the messages and ciphertexts of each
+/// cipher, e.g., [`shift::Ciphertext::display`].
+///
+/// By default, the adapter writes the text as its [`Display`](fmt::Display)
+/// implementation does: with the letters cased by [`CasePolicy::Stinson`] and
+/// without groups. The format is only applied when the text is written, so
+/// the adapter borrows the text rather than copying it.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{policy::CasePolicy, shift::Ciphertext};
+/// # use std::str::FromStr;
+/// let ciphertxt = Ciphertext::from_str("HPHTWWXPPELEXTOYTRSE").unwrap();
+///
+/// assert_eq!(ciphertxt.display().to_string(), ciphertxt.to_string());
+/// assert_eq!(
+///     ciphertxt.display().grouped(5).lowercase().to_string(),
+///     "hphtw wxppe lexto ytrse"
+/// );
+/// assert_eq!(
+///     format!("{}", ciphertxt.display().grouped(4).with_separator("-")),
+///     "HPHT-WWXP-PELE-XTOY-TRSE"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct TextDisplay<'a, const M: u16 = 26> {
+    symbols: &'a [RingElement<M>],
+    to_char: fn(RingElement<M>) -> char,
+    uppercase: bool,
+    case: CasePolicy,
+    group: Option<usize>,
+    separator: &'a str,
+}
+
+impl<'a, const M: u16> TextDisplay<'a, M> {
+    /// Write the text in groups of `size` characters, separated by spaces
+    /// unless [`with_separator`](Self::with_separator) sets another separator.
+    ///
+    /// # Panics
+    /// This method panics if `size` is 0.
+    pub fn grouped(self, size: usize) -> Self {
+        assert!(size > 0, "The group size must be positive");
+        TextDisplay {
+            group: Some(size),
+            ..self
+        }
+    }
+
+    /// Set the separator written between groups.
+    pub fn with_separator(self, separator: &'a str) -> Self {
+        TextDisplay { separator, ..self }
+    }
+
+    /// Case the letters of the text according to `case`.
+    pub fn with_case(self, case: CasePolicy) -> Self {
+        TextDisplay { case, ..self }
+    }
+
+    /// Write every letter in lowercase, i.e., use [`CasePolicy::Lowercase`].
+    pub fn lowercase(self) -> Self {
+        self.with_case(CasePolicy::Lowercase)
+    }
+}
 
-    /// Encode a character.
-    fn from_char(ltr: char) -> Result<Self, Self::Error>;
+impl<const M: u16> fmt::Display for TextDisplay<'_, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let txt: String = self.symbols.iter().map(|&x| (self.to_char)(x)).collect();
+        let txt = self.case.apply_to(&txt, self.uppercase);
+
+        match self.group {
+            Some(size) => Grouped::new(txt.as_ref(), size)
+                .with_separator(self.separator)
+                .fmt(f),
+            None => f.write_str(&txt),
+        }
+    }
 }
 
 /// This trait represents the element of a ring.
 trait Ring:
-    Add<Output = Self> + Sub<Output = Self> + Eq + Copy + Clone + Default + fmt::Debug + Sized
+    Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Eq
+    + Copy
+    + Clone
+    + Default
+    + fmt::Debug
+    + Sized
 {
     /// Zero, the additive identity.
     const ZERO: Self;
 
     /// Returns true if zero and false otherwise.
+    #[allow(dead_code)]
     fn is_zero(&self) -> bool;
 
     /// Chooses a ring element uniformly at random using an RNG provided by
     /// caller.
-    fn random<R: Rng + CryptoRng>(rng: &mut R) -> Self;
-}
-
-/// An implementation of the ring &#x2124;/_m_&#x2124; for modulus _m_.
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
-struct RingElement(i8);
-
-impl RingElement {
-    /// The default alphabet encoding for the Latin Shift Cipher.
-    const ALPH_ENCODING: [(char, i8); 26] = [
-        ('a', 0),
-        ('b', 1),
-        ('c', 2),
-        ('d', 3),
-        ('e', 4),
-        ('f', 5),
-        ('g', 6),
-        ('h', 7),
-        ('i', 8),
-        ('j', 9),
-        ('k', 10),
-        ('l', 11),
-        ('m', 12),
-        ('n', 13),
-        ('o', 14),
-        ('p', 15),
-        ('q', 16),
-        ('r', 17),
-        ('s', 18),
-        ('t', 19),
-        ('u', 20),
-        ('v', 21),
-        ('w', 22),
-        ('x', 23),
-        ('y', 24),
-        ('z', 25),
-    ];
+    fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self;
+}
+
+/// An implementation of the ring &#x2124;/_m_&#x2124; for modulus _m_ = `M`.
+///
+/// The modulus is a type parameter, so ring elements with different moduli,
+/// e.g., the elements of &#x2124;/26&#x2124; encoding the Latin Alphabet and the
+/// elements of &#x2124;/36&#x2124; encoding a 6x6 Polybius square, are different
+/// types and cannot be mixed up. By default, the modulus is 26.
+///
+/// Ring elements are the symbols of messages and ciphertexts, e.g., when
+/// walking a [`shift::Message`] with [`iter`](shift::Message::iter). Crate
+/// users can only construct ring elements with
+/// [`from_char`](RingElement::from_char), which checks the character, and
+/// [`from_i64`](RingElement::from_i64), which reduces the integer, so every
+/// ring element is in canonical form.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{alphabet::Latin, shift::Message};
+/// let msg = Message::new("hello").unwrap();
+///
+/// assert_eq!(msg[1].into_inner(), 4);
+/// assert_eq!(msg[1].to_char::<Latin>(), Some('e'));
+/// ```
+#[derive(Copy, Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Debug)]
+pub struct RingElement<const M: u16 = 26>(u16);
 
+impl<const M: u16> RingElement<M> {
     /// The modulus used to construct the ring of integers used in the given
-    /// Shift Cipher as the plaintext space, ciphertext space, and key
+    /// cipher as the plaintext space, ciphertext space, and key
     /// space, i.e., the ring of integers modulo _m_, denoted by
-    /// &#x2124;/_m_&#x2124;, where the modulus _m_ is drawn directly from
-    /// [`RingElement::ALPH_ENCODING`].
+    /// &#x2124;/_m_&#x2124;.
     // The modulus m for the ring Z/mZ.
-    // Note that the longest alphabet is Khmer, which has 74 characters, so this
-    // casting should be OK even if this code is used for a different alphabet
-    // later.
-    const MODULUS: i8 = RingElement::ALPH_ENCODING.len() as i8;
+    // Inner values are stored in canonical form as a `u16`, and the arithmetic below is computed
+    // in a `u32`, so any modulus that fits in a `u16` works: the Latin Alphabet (26), printable
+    // ASCII (95), bytes (256), or larger alphabets. The empty ring is ruled out at compile time.
+    const MODULUS: u16 = {
+        assert!(M > 0, "The modulus must be positive");
+        M
+    };
+
+    /// Create a ring element from its inner value, which must already be in
+    /// canonical form, i.e., between 0 and [`RingElement::MODULUS`] - 1.
+    ///
+    /// Library devs: This constructor is unchecked! Use
+    /// [`from_i32`](RingElement::from_i32) unless the value is known to be in
+    /// range.
+    const fn new(int: u16) -> Self {
+        Self(int)
+    }
+
+    /// Create a ring element from its inner value, or return `None` if the
+    /// value is not in canonical form. Unlike [`new`](RingElement::new), this
+    /// constructor checks the invariant of the type.
+    fn try_new(int: u16) -> Option<Self> {
+        (int < Self::MODULUS).then(|| Self::new(int))
+    }
 
-    /// Convert from an `i8` to a ring element.
+    /// Convert from an `i32` to a ring element.
     ///
     /// This function will compute the canonical form of the inner value, i.e.,
     /// it will compute and use the least nonnegative remainder modulo
@@ -149,338 +703,1511 @@ impl RingElement {
     /// future library developers constructing and using values of ring
     /// elements for which the unchecked routines [`add`](RingElement::add)
     /// and [`sub`](RingElement::sub) will fail.
-    fn from_i8(int: i8) -> Self {
-        Self(int.rem_euclid(RingElement::MODULUS))
+    fn from_i32(int: i32) -> Self {
+        Self::from_i64(int.into())
+    }
+
+    /// Returns true if the inner value is in canonical form, i.e., less than
+    /// the modulus, and false otherwise.
+    fn is_canonical(self) -> bool {
+        self.0 < Self::MODULUS
+    }
+
+    /// Computes the sum of `self` and `other`, or returns `None` if either is
+    /// not in canonical form.
+    ///
+    /// Ring elements created by the library are always canonical, so this
+    /// only returns `None` when a library developer has made an error, e.g.,
+    /// created an element with the unchecked constructor.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        (self.is_canonical() && other.is_canonical()).then(|| self + other)
+    }
+
+    /// Computes the difference of `self` and `other`, or returns `None` if
+    /// either is not in canonical form, see
+    /// [`checked_add`](RingElement::checked_add).
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        (self.is_canonical() && other.is_canonical()).then(|| self - other)
     }
 
-    /// Get the inner value of the ring element.
-    fn into_inner(self) -> i8 {
+    /// Get the inner value of the ring element, between 0 and `M` - 1.
+    pub fn into_inner(self) -> u16 {
         self.0
     }
-}
 
-impl AlphabetEncoding for RingElement {
-    type Error = ErrorRepr;
+    /// Get the multiplicative inverse of the ring element, or `None` if the
+    /// element is not a unit, i.e., if it is not coprime to the modulus.
+    ///
+    /// The inverse is computed with the Extended Euclidean Algorithm, see
+    /// [`math::mod_inverse`].
+    pub fn inverse(self) -> Option<Self> {
+        // The inverse is reduced mod the modulus, so it fits in a `u16`
+        math::mod_inverse(i64::from(self.0), i64::from(Self::MODULUS)).map(|x| Self::new(x as u16))
+    }
 
-    /// Convert from a character.
+    /// Iterate over the units of the ring, i.e., the elements that have a
+    /// multiplicative inverse, in increasing order. For the modulus 26, these
+    /// are the 12 possible multipliers of the Affine Cipher, see Stinson,
+    /// Section 2.1.3.
     ///
-    /// # Errors
-    /// This method will return a custom internal error if the constant
-    /// [`RingElement::ALPH_ENCODING`] does not specify a mapping to the ring of
-    /// integers for the given input. This happens if the input is not from the
-    /// lowercase Latin Alphabet. For crate users, this error type will get
-    /// "lifted" to the public error type [`EncodingError`] by the caller, e.g.,
-    /// when parsing a [`Message`] from a string.
-    fn from_char(ltr: char) -> Result<Self, ErrorRepr> {
-        // This constructor uses the encoding defined in `RingElement::ALPH_ENCODING`.
-        RingElement::ALPH_ENCODING
-            .into_iter()
-            .find_map(|(x, y)| if x == ltr { Some(RingElement(y)) } else { None })
-            .ok_or(ErrorRepr::RingElementEncodingError(ltr.to_string()))
-    }
-
-    /// Convert from a ring element to a character.
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::RingElement;
+    /// let units: Vec<u16> = RingElement::<26>::units().map(RingElement::into_inner).collect();
     ///
-    /// # Panics
-    /// This method will never panic unless the library developer has made an
-    /// error. For example,
+    /// assert_eq!(units, [1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25]);
+    /// ```
+    pub fn units() -> impl Iterator<Item = Self> {
+        (0..Self::MODULUS)
+            .map(Self::new)
+            .filter(|x| x.inverse().is_some())
+    }
+
+    /// Encode a character of the alphabet `A`, or return `None` if the
+    /// character is not in the alphabet. Together with
+    /// [`from_i64`](RingElement::from_i64), this lets crate users build
+    /// messages and ciphertexts symbol by symbol.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{alphabet::Latin, shift::Message, RingElement};
+    /// let msg: Message = "hello"
+    ///     .chars()
+    ///     .map(|c| RingElement::from_char::<Latin>(c).unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(msg, Message::new("hello").unwrap());
+    /// assert_eq!(RingElement::<26>::from_char::<Latin>('H'), None);
+    /// ```
+    pub fn from_char<A: Alphabet<M>>(ltr: char) -> Option<Self> {
+        A::index_of(ltr)
+            .and_then(|i| u16::try_from(i).ok())
+            .and_then(Self::try_new)
+    }
+
+    /// Reduce an integer modulo `M` to a ring element, e.g., -1 to `M` - 1.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{shift::Message, RingElement};
+    /// let msg: Message = [7, 30, -15, 11, 14].into_iter().map(RingElement::from_i64).collect();
+    ///
+    /// assert_eq!(msg.to_string(), "hello");
+    /// ```
+    pub fn from_i64(int: i64) -> Self {
+        // The remainder is less than the modulus, so it fits in a `u16`
+        Self::new(int.rem_euclid(i64::from(Self::MODULUS)) as u16)
+    }
+
+    /// Encode a character of the alphabet `A`, after lowercasing it if
+    /// `ignore_case` is true and it has a single lowercase character, as
+    /// ciphertexts are parsed.
+    fn encode<A: Alphabet<M>>(ltr: char, ignore_case: bool) -> Option<Self> {
+        let mut lower = ltr.to_lowercase();
+        match (ignore_case, lower.next(), lower.next()) {
+            (true, Some(lower), None) => Self::from_char::<A>(lower),
+            _ => Self::from_char::<A>(ltr),
+        }
+    }
+
+    /// Convert from a ring element to a character of the alphabet `A`.
+    ///
+    /// This method returns `None` only if the library developer (or the
+    /// implementer of `A`) has made an error. For example,
     /// if the library developer does not use a constructor to create a ring
-    /// element and creates an invalid element such as `RingElement(26)` when
-    /// representing the Latin Alphabet.
-    fn to_char(self) -> char {
-        RingElement::ALPH_ENCODING
-            .into_iter()
-            .find_map(|(x, y)| if y == self.0 { Some(x) } else { None })
-            .expect(
-                "Could not map to `char`: The definition of `RingElement::ALPH_ENCODING` must have an error or there is an invalid `RingElement`.",
-            )
+    /// element and creates an invalid element such as `RingElement::new(26)`
+    /// when representing the Latin Alphabet.
+    pub fn to_char<A: Alphabet<M>>(self) -> Option<char> {
+        A::char_at(usize::from(self.0))
+    }
+
+    /// Convert from a ring element to a character of the alphabet `A`, or to
+    /// the replacement character `U+FFFD` if [`to_char`](RingElement::to_char)
+    /// fails, so that writing out a text never panics, even if there is a bug.
+    fn to_char_lossy<A: Alphabet<M>>(self) -> char {
+        self.to_char::<A>().unwrap_or(char::REPLACEMENT_CHARACTER)
     }
 }
 
-impl Ring for RingElement {
-    const ZERO: Self = RingElement(0);
+impl<const M: u16> Ring for RingElement<M> {
+    const ZERO: Self = Self::new(0);
 
     fn is_zero(&self) -> bool {
-        self.eq(&RingElement::ZERO)
+        self.eq(&Self::ZERO)
     }
 
     /// Generate a ring element uniformly at random.
     ///
     /// Implementation notes:
-    /// 1. This is easy here because we used `i8` as the underlying   type for
+    /// 1. This is easy here because we used `u16` as the underlying   type for
     ///    `RingElement` and choosing uniformly from a range is already
-    ///    implemented for `i8` in `rand`. But note that in general you must be
+    ///    implemented for `u16` in `rand`. But note that in general you must be
     ///    careful, e.g., if you pick a `u8` from the uniform distribution and
     ///    then reduce mod 26, you will pick each of {24, 25} with probability
     ///    4/128 and all other elements with probability 5/128
     /// 2. `CryptoRng` is a marker trait to indicate generators suitable for
     ///    crypto, but user beware.
-    fn random<R: Rng + CryptoRng>(rng: &mut R) -> Self {
-        let elmt: i8 = rng.gen_range(0..RingElement::MODULUS);
-        Self(elmt)
+    fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let elmt: u16 = rng.gen_range(0..Self::MODULUS);
+        Self::new(elmt)
     }
 }
 
-impl Default for RingElement {
+impl<const M: u16> Default for RingElement<M> {
     fn default() -> Self {
-        RingElement::ZERO
+        Self::ZERO
+    }
+}
+
+// Keys made of ring elements compare them in constant time, see the `secret` module.
+impl<const M: u16> ConstantTimeEq for RingElement<M> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
     }
 }
 
-impl Add for RingElement {
+// The message of the debug assertions of the unchecked operators.
+const INVALID_OPERANDS: &str =
+    "Arithmetic on an invalid `RingElement`: the operands must be in canonical form.";
+
+impl<const M: u16> Add for RingElement<M> {
     type Output = Self;
 
     /// Computes the sum of `self` and `other`.
     ///
-    /// Library devs: This operation is unchecked!
+    /// Library devs: This operation is unchecked in release builds! Debug
+    /// builds assert that the operands are canonical, see
+    /// [`checked_add`](RingElement::checked_add).
     fn add(self, other: Self) -> Self {
-        Self(if (self.0 + other.0) >= RingElement::MODULUS {
-            self.0 + other.0 - RingElement::MODULUS
-        } else {
-            self.0 + other.0
-        })
+        debug_assert!(
+            self.is_canonical() && other.is_canonical(),
+            "{}",
+            INVALID_OPERANDS
+        );
+        // Widen so that the sum cannot overflow, even for a modulus close to `u16::MAX`
+        let sum = u32::from(self.0) + u32::from(other.0);
+        let modulus = u32::from(Self::MODULUS);
+        Self::new(if sum >= modulus { sum - modulus } else { sum } as u16)
     }
 }
 
-impl Sub for RingElement {
+impl<const M: u16> Sub for RingElement<M> {
     type Output = Self;
 
     /// Computes the difference of `self` and `other`.
     ///
-    /// Library devs: This operation is unchecked!
+    /// Library devs: This operation is unchecked in release builds! Debug
+    /// builds assert that the operands are canonical, see
+    /// [`checked_sub`](RingElement::checked_sub).
     fn sub(self, other: Self) -> Self {
-        Self(if (self.0 - other.0) < 0 {
-            self.0 - other.0 + RingElement::MODULUS
+        debug_assert!(
+            self.is_canonical() && other.is_canonical(),
+            "{}",
+            INVALID_OPERANDS
+        );
+        Self::new(if self.0 < other.0 {
+            Self::MODULUS - (other.0 - self.0)
         } else {
             self.0 - other.0
         })
     }
 }
 
-impl fmt::Display for RingElement {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+impl<const M: u16> Mul for RingElement<M> {
+    type Output = Self;
+
+    /// Computes the product of `self` and `other`.
+    fn mul(self, other: Self) -> Self {
+        // The product of two `u16`s fits in a `u32`
+        let product = u32::from(self.0) * u32::from(other.0);
+        Self::new((product % u32::from(Self::MODULUS)) as u16)
     }
 }
 
-/// A plaintext of arbitrary length.
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
-struct Message(Vec<RingElement>);
+/// Multiplication by an integer, i.e., repeated addition.
+impl<const M: u16> Mul<i8> for RingElement<M> {
+    type Output = Self;
 
-impl Message {
-    /// Create a new message from a string.
-    fn new(str: &str) -> Result<Message, EncodingError> {
-        Message::from_str(str)
+    fn mul(self, other: i8) -> Self {
+        self * Self::from_i32(i32::from(other))
     }
 }
 
-/// Parse a message from a string.
-///
-/// # Errors
-/// This trait implementation returns an error when parsing a string that
-/// contains an invalid character, i.e., if there is some `char` that is not
-/// from the lowercase Latin Alphabet.
-impl FromStr for Message {
-    type Err = EncodingError;
+impl<const M: u16> Neg for RingElement<M> {
+    type Output = Self;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match from_str(s) {
-            Ok(msg) => Ok(Message(msg)),
-            Err(e) => Err(EncodingError::InvalidMessage(e.into())),
-        }
+    /// Computes the additive inverse of `self`.
+    fn neg(self) -> Self {
+        Self::ZERO - self
     }
 }
 
-impl fmt::Display for Message {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let txt: String = self.0.iter().map(|i| i.to_char()).collect();
-
-        write!(f, "{txt}")
+impl<const M: u16> AddAssign for RingElement<M> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
     }
 }
 
-impl FromIterator<RingElement> for Message {
-    fn from_iter<I: IntoIterator<Item = RingElement>>(iter: I) -> Self {
-        Message(iter.into_iter().collect())
+impl<const M: u16> SubAssign for RingElement<M> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
     }
 }
 
-/// A ciphertext of arbitrary length.
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
-struct Ciphertext(Vec<RingElement>);
-/// Parse a ciphertext from a string.
-///
-/// # Errors
-/// This trait implementation returns an error when parsing a string that
-/// contains an invalid character, i.e., if there is some `char` that is not
-/// from the Latin Alphabet. Although the library generally follows the
-/// convention that ciphertexts are represented as ALL CAPS strings, this
-/// implementation ignores case, so parsing a string that includes lowercase
-/// letters may succeed.
-impl FromStr for Ciphertext {
-    type Err = EncodingError;
+impl<const M: u16> Sum for RingElement<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match from_str(&s.to_lowercase()) {
-            Ok(msg) => Ok(Ciphertext(msg)),
-            Err(e) => Err(EncodingError::InvalidCiphertext(e.into())),
-        }
+impl<'a, const M: u16> Sum<&'a RingElement<M>> for RingElement<M> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
     }
 }
 
-impl fmt::Display for Ciphertext {
+impl<const M: u16> fmt::Display for RingElement<M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let txt: String = self.0.iter().map(|i| RingElement::to_char(*i)).collect();
-
-        // Following Stinson's convention,
-        // ciphertexts are ALL CAPS
-        write!(f, "{ }", txt.to_uppercase())
+        write!(f, "{}", self.0)
     }
 }
 
-impl FromIterator<RingElement> for Ciphertext {
-    fn from_iter<I: IntoIterator<Item = RingElement>>(iter: I) -> Self {
-        Ciphertext(iter.into_iter().collect())
-    }
+/// The role of a [`Text`], i.e., whether it is a plaintext or a ciphertext.
+trait Role: Copy + Clone + fmt::Debug + Default + Eq + Hash + 'static {
+    /// Whether texts in this role are ciphertexts, which ignore case when
+    /// parsed and are written in ALL CAPS by default, for alphabets that opt
+    /// in (see [`Alphabet::UPPERCASE_CIPHERTEXT`]).
+    const CIPHERTEXT: bool;
+
+    /// Report that a string could not be parsed as a text in this role.
+    fn error(e: InvalidCharacters) -> EncodingError;
 }
 
-// Parse a string as a `Vec<RingElement>`
-// We cannot implement `FromStr` for `Vec<RingElement>` (since both `FromStr`
-// and `Vec` are external to our crate), but we need similar functionality in
-// order to avoid code duplication when converting from Strings to Wrapper types
-// around `Vec<RingElement>``
-fn from_str(s: &str) -> Result<Vec<RingElement>, ErrorRepr> {
-    let (msg, errors): (Vec<_>, Vec<_>) = s
-        .chars()
-        .map(|i: char| RingElement::from_char(i))
-        .partition(Result::is_ok);
+/// The role of plaintexts.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+struct Plain;
 
-    let msg: Vec<RingElement> = msg.into_iter().map(|i| i.unwrap()).collect();
+impl Role for Plain {
+    const CIPHERTEXT: bool = false;
 
-    let errors: String = errors
-        .into_iter()
-        .map(|i| i.unwrap_err())
-        .map(|ErrorRepr::RingElementEncodingError(i)| i)
-        // Filters out errors caused by spaces in input string
-        .filter(|i| i != " ")
-        .collect();
-
-    if errors.is_empty() && !msg.is_empty() {
-        Ok(msg)
-    } else {
-        Err(ErrorRepr::RingElementEncodingError(errors))
+    fn error(e: InvalidCharacters) -> EncodingError {
+        EncodingError::InvalidMessage(e)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The role of ciphertexts.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+struct Cipher;
 
-    // Data for our running example/test.
-    // Note: This is an attempt at global constants for the tests. If it would be
-    // better to use std::cell::OnceCell, I'm not sure I understand how to do
-    // that properly. Encoded "wewillmeetatmidnight" message from Example 1.1,
-    // Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
-    thread_local! (static MSG0: Message = Message(vec![RingElement(22), RingElement(4),
-            RingElement(22), RingElement(8), RingElement(11), RingElement(11),
-            RingElement(12), RingElement(4), RingElement(4), RingElement(19),
-            RingElement(0), RingElement(19),
-            RingElement(12), RingElement(8), RingElement(3), RingElement(13), RingElement(8), RingElement(6), RingElement(7), RingElement(19)]));
+impl Role for Cipher {
+    const CIPHERTEXT: bool = true;
 
-    // Encrypted "wewillmeetatmidnight" message with key=11, from Example 1.1,
-    // Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
-    thread_local! (static CIPH0: Ciphertext = Ciphertext(vec![RingElement(7), RingElement(15),
-            RingElement(7), RingElement(19), RingElement(22), RingElement(22),
-            RingElement(23), RingElement(15), RingElement(15), RingElement(4),
-            RingElement(11), RingElement(4),
-            RingElement(23), RingElement(19), RingElement(14), RingElement(24), RingElement(19), RingElement(17), RingElement(18), RingElement(4)]));
+    fn error(e: InvalidCharacters) -> EncodingError {
+        EncodingError::InvalidCiphertext(e)
+    }
+}
 
-    // Encrypted "wewillmeetatmidnight" as a string, from Example 1.1 Stinson 3rd
-    // Edition, Example 2.1 Stinson 4th Edition
-    thread_local!(static CIPH0_STR: String = "HPHTWWXPPELEXTOYTRSE".to_string());
+/// A text of arbitrary length in the role `R`, written in the alphabet `A`
+/// with `M` characters.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+struct Text<R: Role, A: Alphabet<M> = Latin, const M: u16 = 26>(
+    Vec<RingElement<M>>,
+    PhantomData<(R, A)>,
+);
+
+/// A plaintext of arbitrary length, written in the alphabet `A` with `M`
+/// characters.
+type Message<A = Latin, const M: u16 = 26> = Text<Plain, A, M>;
+
+/// A ciphertext of arbitrary length, written in the alphabet `A` with `M`
+/// characters.
+type Ciphertext<A = Latin, const M: u16 = 26> = Text<Cipher, A, M>;
+
+impl<R: Role, A: Alphabet<M>, const M: u16> Text<R, A, M> {
+    // Whether case is ignored when parsing the text.
+    const IGNORE_CASE: bool = R::CIPHERTEXT && A::UPPERCASE_CIPHERTEXT;
+
+    /// Report every character of a string that cannot be encoded as a text,
+    /// ignoring case for ciphertexts over alphabets with
+    /// [`Alphabet::UPPERCASE_CIPHERTEXT`].
+    fn validate(str: &str) -> ValidationReport {
+        validate::<A, M>(str, Self::IGNORE_CASE)
+    }
 
-    #[test]
-    fn ring_elmnt_default() {
-        assert_eq!(RingElement::default(), RingElement(0));
-        assert!(RingElement::default().is_zero())
+    /// Get an adapter that writes the text in a chosen format.
+    fn display(&self) -> TextDisplay<'_, M> {
+        TextDisplay {
+            symbols: &self.0,
+            to_char: RingElement::to_char_lossy::<A>,
+            uppercase: R::CIPHERTEXT && A::UPPERCASE_CIPHERTEXT,
+            case: CasePolicy::default(),
+            group: None,
+            separator: " ",
+        }
     }
 
-    #[test]
-    fn ring_elmnt_into_inner() {
-        let x = RingElement(5);
-        assert_eq!(x.into_inner(), 5)
+    /// Write the text with its letters cased according to `case`.
+    fn format_with_case(&self, case: CasePolicy) -> String {
+        self.display().with_case(case).to_string()
     }
-    #[test]
-    fn ring_elmt_display() {
-        // Test Display impl
-        let x = RingElement(3);
-        assert_eq!(
-            format!("The ring element value is {x}"),
-            "The ring element value is 3"
-        );
+
+    /// Iterate over the symbols of the text.
+    fn iter(&self) -> slice::Iter<'_, RingElement<M>> {
+        self.0.iter()
     }
 
-    #[test]
-    fn ring_elmt_encoding_basics() {
-        assert_eq!(RingElement::from_char('g').unwrap().0, 6); // Sanity check `from_char`
-        assert_eq!(RingElement::from_char('w').unwrap().0, 22); // Sanity check `from_char`
-        assert_eq!(RingElement(5).to_char(), 'f'); // Sanity check `to_char`
-        assert_eq!(RingElement(0).to_char(), 'a') // Sanity check to `to_char`
+    /// Get the symbol at `index`, or `None` if the index is out of bounds.
+    fn get(&self, index: usize) -> Option<&RingElement<M>> {
+        self.0.get(index)
     }
 
-    #[test]
-    fn ring_elmt_arithmetic() {
-        assert_eq!(RingElement(5) + RingElement(11), RingElement(16)); // Basic addition test
-        assert_eq!(RingElement(22) + RingElement(11), RingElement(7)); // Addition test with overflow
-        assert_eq!(RingElement(20) + RingElement(6), RingElement(0)); // Addition boundary check
+    /// Get the number of symbols in the text.
+    fn len(&self) -> usize {
+        self.0.len()
+    }
 
-        assert_eq!(RingElement(11) - RingElement(3), RingElement(8)); // Basic subtraction test
-        assert_eq!(RingElement(4) - RingElement(11), RingElement(19)); // Subtraction test with overflow
-        assert_eq!(RingElement(15) - RingElement(15), RingElement(0)); // Subtraction boundary check
+    /// Returns true if the text has no symbols and false otherwise.
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
-    #[test]
-    fn ring_elmt_from_i8() {
-        // `from_i8` works as expected
-        assert_eq!(RingElement::from_i8(37), RingElement(11));
-        assert_eq!(RingElement::from_i8(-28), RingElement(24));
-        assert_eq!(RingElement::from_i8(26), RingElement(0));
-        assert_eq!(RingElement::from_i8(-3), RingElement(23));
-        assert_eq!(RingElement::from_i8(5), RingElement(5));
+    /// Iterate over the blocks of `size` symbols of the text, borrowed from
+    /// the text, where the last block may be shorter.
+    ///
+    /// # Panics
+    /// This method panics if `size` is 0.
+    fn chunks(&self, size: usize) -> slice::Chunks<'_, RingElement<M>> {
+        self.0.chunks(size)
     }
 
-    #[test]
-    fn ring_elmt_encoding_errors() {
-        assert_eq!(
-            RingElement::from_char('_'),
-            Err(ErrorRepr::RingElementEncodingError('_'.to_string()))
-        );
-        assert_eq!(
-            RingElement::from_char('A'),
-            Err(ErrorRepr::RingElementEncodingError('A'.to_string()))
-        );
+    /// Count the positions at which two texts of the same length have
+    /// different symbols, i.e., their Hamming distance, or return `None` if
+    /// the texts have different lengths.
+    fn distance(&self, other: &Self) -> Option<usize> {
+        (self.len() == other.len())
+            .then(|| self.0.iter().zip(&other.0).filter(|(x, y)| x != y).count())
+    }
 
-        assert_eq!(
-            from_str("asd;lkasdfEnk0").unwrap_err(),
-            ErrorRepr::RingElementEncodingError(";E0".to_string())
-        )
+    /// Compare the text, as the expected text, with another text, and list
+    /// the positions at which they differ.
+    fn diff(&self, other: &Self) -> TextDiff {
+        TextDiff::new(&self.to_string(), &other.to_string())
     }
 
-    #[test]
-    #[should_panic(
-        expected = "Could not map to `char`: The definition of `RingElement::ALPH_ENCODING` must have an error or there is an invalid `RingElement`."
-    )]
-    fn ring_elmt_encoding_panic() {
-        // Sometimes you google to find out how to prevent things like backtraces
-        // appearing in your output for tests that should panic
-        let f = |_: &std::panic::PanicHookInfo| {};
-        std::panic::set_hook(Box::new(f));
-        let _fail = RingElement(26).to_char();
+    /// Iterate over the overlapping runs of `size` consecutive symbols of the
+    /// text, borrowed from the text.
+    ///
+    /// # Panics
+    /// This method panics if `size` is 0.
+    fn windows(&self, size: usize) -> slice::Windows<'_, RingElement<M>> {
+        self.0.windows(size)
     }
 
-    #[test]
-    fn msg_default() {
-        assert_eq!(Message::default(), Message(vec![]))
+    /// Parse the symbols of a text from a stream of characters, one at a
+    /// time, following the rules of [`FromStr`] except that all whitespace,
+    /// e.g., the line breaks of a file, is skipped. The position of an
+    /// invalid character is its position in the stream.
+    fn parse_stream<I: Iterator<Item = Result<char, StreamError>>>(
+        chars: I,
+    ) -> impl Iterator<Item = Result<RingElement<M>, StreamError>> {
+        let (mut char_index, mut byte_index) = (0, 0);
+        chars
+            .map(move |c| {
+                let c = c?;
+                let position = InvalidCharacter {
+                    character: c,
+                    byte_index,
+                    char_index,
+                };
+                char_index += 1;
+                byte_index += c.len_utf8();
+                Ok::<_, StreamError>(position)
+            })
+            .filter(|c| {
+                !matches!(c, Ok(c) if c.character.is_whitespace() && A::index_of(c.character).is_none())
+            })
+            .map(|c| {
+                let c = c?;
+                RingElement::encode::<A>(c.character, Self::IGNORE_CASE)
+                    .ok_or_else(|| R::error(InvalidCharacters(vec![c])).into())
+            })
+    }
+
+    /// Create a text from a sequence of characters, following the rules of
+    /// [`FromStr`] without collecting the characters into a string first. The
+    /// positions of invalid characters are their positions in the sequence, as
+    /// if it were a string.
+    fn from_chars<I: IntoIterator<Item = char>>(chars: I) -> Result<Self, EncodingError> {
+        match from_chars::<A, M>(chars, Self::IGNORE_CASE) {
+            Ok(txt) => Ok(Text(txt, PhantomData)),
+            Err(e) => Err(R::error(e)),
+        }
+    }
+
+    /// Get the text made of the symbols in `range`.
+    ///
+    /// # Panics
+    /// This method panics if the range is out of bounds, as for slices.
+    fn slice<B: RangeBounds<usize>>(&self, range: B) -> Self {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        Text(self.0[bounds].to_vec(), PhantomData)
+    }
+
+    /// Append a character to the text, following the rules of [`FromStr`]: a
+    /// space that is not in the alphabet is skipped.
+    ///
+    /// # Errors
+    /// This method returns an error, and leaves the text unchanged, if the
+    /// character is not in the alphabet. The position of the character is the
+    /// end of the text as displayed, where it would have been appended.
+    fn push_char(&mut self, character: char) -> Result<(), EncodingError> {
+        match RingElement::encode::<A>(character, Self::IGNORE_CASE) {
+            Some(x) => self.0.push(x),
+            None if character == ' ' => (),
+            None => {
+                return Err(R::error(InvalidCharacters(vec![InvalidCharacter {
+                    character,
+                    byte_index: self
+                        .0
+                        .iter()
+                        .map(|x| x.to_char_lossy::<A>().len_utf8())
+                        .sum(),
+                    char_index: self.len(),
+                }])))
+            }
+        }
+        Ok(())
+    }
+
+    /// Append the characters of a string to the text, following the rules of
+    /// [`FromStr`], except that a string without any symbols, e.g., an empty
+    /// string, leaves the text unchanged.
+    ///
+    /// # Errors
+    /// This method returns an error, and leaves the text unchanged, if the
+    /// string contains an invalid character. The positions of the invalid
+    /// characters are their positions in `str`.
+    fn extend_from_str(&mut self, str: &str) -> Result<(), EncodingError> {
+        match from_chars::<A, M>(str.chars(), Self::IGNORE_CASE) {
+            Ok(txt) => self.0.extend(txt),
+            Err(invalid) if invalid.0.is_empty() => (),
+            Err(invalid) => return Err(R::error(invalid)),
+        }
+        Ok(())
+    }
+
+    /// Shorten the text to its first `len` symbols, or leave it unchanged if
+    /// it has no more than `len` symbols.
+    fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    /// Write the text, followed by a line break, with its letters cased
+    /// according to `settings` and, for ciphertexts, in the groups of the
+    /// settings.
+    ///
+    /// # Errors
+    /// This method returns an error if writing fails.
+    fn write_to(&self, mut writer: impl Write, settings: &CipherSettings) -> io::Result<()> {
+        let display = self.display().with_case(settings.case());
+        match settings.group_size() {
+            Some(size) if R::CIPHERTEXT => writeln!(writer, "{}", display.grouped(size)),
+            _ => writeln!(writer, "{display}"),
+        }
+    }
+
+    /// Count the symbols of the text, recording letters in lowercase as
+    /// [`FrequencyTable::new`] does, whatever case the text is written in.
+    fn letter_counts(&self) -> FrequencyTable {
+        self.0
+            .iter()
+            .flat_map(|x| x.to_char_lossy::<A>().to_lowercase())
+            .collect()
+    }
+}
+
+impl<A: Alphabet<M>, const M: u16> Message<A, M> {
+    /// Create a new message from a string.
+    fn new(str: &str) -> Result<Self, EncodingError> {
+        Self::from_str(str)
+    }
+
+    /// Create a new message from a string after preparing it according to
+    /// `policy`.
+    ///
+    /// # Errors
+    /// This method returns an error if the policy rejects the string, or if
+    /// the prepared string contains an invalid character.
+    fn with_policy(str: &str, policy: &MessagePolicy) -> Result<Self, EncodingError> {
+        let prepared = policy.prepare(str).map_err(EncodingError::InvalidMessage)?;
+
+        Self::from_str(&prepared)
+    }
+
+    /// Read a message, e.g., from a file, after preparing it according to the
+    /// policy of `settings`. Line breaks are read as spaces.
+    ///
+    /// # Errors
+    /// This method returns an error if reading fails, or if the text read is
+    /// not a message.
+    fn read_from(reader: impl Read, settings: &CipherSettings) -> Result<Self, ReadError> {
+        Ok(Self::with_policy(&read_lines(reader)?, &settings.policy())?)
+    }
+
+    /// Create a new message from a string, lowercasing the letters that are
+    /// only in the alphabet in lowercase and dropping every other character
+    /// that cannot be encoded, and report the changes. The message is empty
+    /// if nothing in the string can be encoded.
+    fn new_lossy(str: &str) -> (Self, StripReport) {
+        let (mut txt, mut report) = (Vec::with_capacity(str.len()), StripReport::default());
+        for (char_index, (byte_index, character)) in str.char_indices().enumerate() {
+            let position = InvalidCharacter {
+                character,
+                byte_index,
+                char_index,
+            };
+
+            if let Some(x) = RingElement::encode::<A>(character, false) {
+                txt.push(x);
+            } else if let Some(x) = RingElement::encode::<A>(character, true) {
+                txt.push(x);
+                report.lowercased.push(position);
+            } else {
+                report.dropped.push(position);
+            }
+        }
+
+        (Text(txt, PhantomData), report)
+    }
+
+    /// Returns true if the message is the message that
+    /// [`new_lossy`](Self::new_lossy) makes from a string, i.e., if they are
+    /// equal up to case and the characters that cannot be encoded, and false
+    /// otherwise.
+    fn eq_ignore_format(&self, str: &str) -> bool {
+        let mut symbols = str.chars().filter_map(|c| {
+            RingElement::encode::<A>(c, false).or_else(|| RingElement::encode::<A>(c, true))
+        });
+
+        self.0.iter().all(|&x| symbols.next() == Some(x)) && symbols.next().is_none()
+    }
+}
+
+impl<A: Alphabet<M>, const M: u16> Ciphertext<A, M> {
+    /// Create a new ciphertext from a string, parsed according to `policy`.
+    ///
+    /// # Errors
+    /// This method returns an error if the policy rejects the string, or if
+    /// the prepared string contains an invalid character.
+    fn with_parsing(str: &str, policy: ParsePolicy) -> Result<Self, EncodingError> {
+        let prepared = policy
+            .prepare(str, Self::IGNORE_CASE, |c| {
+                RingElement::<M>::encode::<A>(c, Self::IGNORE_CASE).is_some()
+            })
+            .map_err(EncodingError::InvalidCiphertext)?;
+
+        Self::from_str(&prepared)
+    }
+
+    /// Read a ciphertext, e.g., from a file, parsed according to the parse
+    /// policy of `settings`. Line breaks are read as spaces.
+    ///
+    /// # Errors
+    /// This method returns an error if reading fails, or if the text read is
+    /// not a ciphertext.
+    fn read_from(reader: impl Read, settings: &CipherSettings) -> Result<Self, ReadError> {
+        Ok(Self::with_parsing(
+            &read_lines(reader)?,
+            settings.parsing(),
+        )?)
+    }
+}
+
+// Read the text of `reader`, joining its lines with spaces, so that a text that is wrapped over
+// several lines, or ends with a line break as files do, reads as a single line.
+fn read_lines(mut reader: impl Read) -> io::Result<String> {
+    let mut text = String::new();
+    let _ = reader.read_to_string(&mut text)?;
+    Ok(text.lines().collect::<Vec<_>>().join(" "))
+}
+
+/// Parse a text from a string.
+///
+/// # Errors
+/// This trait implementation returns an error when parsing a string that
+/// contains an invalid character, i.e., if there is some `char` that is not
+/// from the alphabet, e.g., not from the lowercase Latin Alphabet. Although
+/// the library generally follows the convention that ciphertexts over the
+/// Latin Alphabet are represented as ALL CAPS strings, ciphertexts ignore case
+/// for such alphabets (see [`Alphabet::UPPERCASE_CIPHERTEXT`]), so parsing a
+/// string that includes lowercase letters as a ciphertext may succeed. This
+/// is the [`Lenient`](ParsePolicy::Lenient) policy; the `with_parsing`
+/// constructors of the ciphertexts take other policies.
+impl<R: Role, A: Alphabet<M>, const M: u16> FromStr for Text<R, A, M> {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_chars(s.chars())
+    }
+}
+
+impl<R: Role, A: Alphabet<M>, const M: u16> TryFrom<&str> for Text<R, A, M> {
+    type Error = EncodingError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::from_str(s)
+    }
+}
+
+impl<R: Role, A: Alphabet<M>, const M: u16> TryFrom<String> for Text<R, A, M> {
+    type Error = EncodingError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+impl<R: Role, A: Alphabet<M>, const M: u16> fmt::Display for Text<R, A, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display().fmt(f)
+    }
+}
+
+impl<R: Role, A: Alphabet<M>, const M: u16> FromIterator<RingElement<M>> for Text<R, A, M> {
+    fn from_iter<I: IntoIterator<Item = RingElement<M>>>(iter: I) -> Self {
+        Text(iter.into_iter().collect(), PhantomData)
+    }
+}
+
+impl<R: Role, A: Alphabet<M>, const M: u16> Index<usize> for Text<R, A, M> {
+    type Output = RingElement<M>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+/// Concatenate two texts.
+impl<R: Role, A: Alphabet<M>, const M: u16> Add for Text<R, A, M> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+}
+
+impl<R: Role, A: Alphabet<M>, const M: u16> Extend<RingElement<M>> for Text<R, A, M> {
+    fn extend<I: IntoIterator<Item = RingElement<M>>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<R: Role, A: Alphabet<M>, const M: u16> IntoIterator for Text<R, A, M> {
+    type Item = RingElement<M>;
+    type IntoIter = vec::IntoIter<RingElement<M>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<R: Role, A: Alphabet<256>> Text<R, A, 256> {
+    /// Create a text from bytes, whose values are the ring elements.
+    fn from_bytes(bytes: &[u8]) -> Self {
+        bytes.iter().map(|&b| RingElement::new(b.into())).collect()
+    }
+
+    /// Get the ring elements of the text as bytes. Elements are canonical, so
+    /// every value fits in a byte.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.iter().map(|x| x.into_inner() as u8).collect()
+    }
+}
+
+// Write the public API of a cipher's message or ciphertext, which wraps a `Text`, by forwarding to
+// the text. Every cipher has its own wrapper types, so that the texts of different ciphers cannot
+// be mixed up, but their API is written once, here. For instance,
+// `text_api!(message Message in "shift")` writes the API of `shift::Message`, where "shift" is
+// the module used by the examples.
+macro_rules! text_api {
+    (message $name:ident in $module:literal) => {
+        $crate::text_api!(
+            $name($crate::Message),
+            $crate::RingElement,
+            "message",
+            "a lowercase letter or a space"
+        );
+
+        impl $name {
+            /// Report every character of a string that is not a lowercase letter or
+            /// a space, together with its position, e.g., to point out the mistakes
+            /// when [`new`](Self::new) fails.
+            ///
+            /// # Examples
+            /// ```
+            #[doc = concat!("# use classical_crypto::", $module, "::Message;")]
+            /// let report = Message::validate("We will meet at midnight!");
+            ///
+            /// assert!(!report.is_valid());
+            /// assert_eq!(report.invalid()[0].character, 'W');
+            /// assert_eq!(report.invalid()[1].char_index, 24);
+            /// ```
+            pub fn validate(str: &str) -> $crate::ValidationReport {
+                <$crate::Message>::validate(str)
+            }
+
+            /// Create a new message from a string after preparing it according to
+            /// `policy`, e.g., to spell out digits.
+            ///
+            /// # Errors
+            /// This method returns an error if the policy rejects the string, or if
+            /// the prepared string contains a character that is not a lowercase
+            /// letter or a space.
+            pub fn with_policy(
+                str: &str,
+                policy: &$crate::policy::MessagePolicy,
+            ) -> Result<Self, $crate::EncodingError> {
+                Ok($name(<$crate::Message>::with_policy(str, policy)?))
+            }
+
+            /// Create a new message from a string by lowercasing its capital letters
+            /// and dropping everything else that is not a lowercase letter, such as
+            /// spaces, digits and punctuation, and report exactly what was changed.
+            /// The message is empty if the string has no letters at all.
+            ///
+            /// # Examples
+            /// ```
+            #[doc = concat!("# use classical_crypto::", $module, "::Message;")]
+            /// let (msg, report) = Message::new_lossy("We will meet at midnight!");
+            ///
+            /// assert_eq!(msg.to_string(), "wewillmeetatmidnight");
+            /// assert_eq!(report.lowercased()[0].character, 'W');
+            /// assert_eq!(report.dropped().len(), 5);
+            /// ```
+            pub fn new_lossy(str: &str) -> (Self, $crate::errors::StripReport) {
+                let (msg, report) = <$crate::Message>::new_lossy(str);
+                ($name(msg), report)
+            }
+
+            /// Returns true if the message has the same letters as a string, ignoring
+            /// case and every character that is not a letter, e.g., to check that a
+            /// decryption gives back the original text, and false otherwise. The
+            /// string need not be a valid message: this is the same as comparing with
+            /// the message that [`new_lossy`](Self::new_lossy) makes from it.
+            ///
+            /// # Examples
+            /// ```
+            #[doc = concat!("# use classical_crypto::", $module, "::Message;")]
+            /// let msg = Message::new("wewillmeetatmidnight").unwrap();
+            ///
+            /// assert!(msg.eq_ignore_format("We will meet at midnight!"));
+            /// assert!(!msg.eq_ignore_format("We will meet at noon!"));
+            /// ```
+            pub fn eq_ignore_format(&self, str: &str) -> bool {
+                self.0.eq_ignore_format(str)
+            }
+
+            /// Write the message with its letters cased according to `case`.
+            pub fn format_with_case(&self, case: $crate::policy::CasePolicy) -> String {
+                self.0.format_with_case(case)
+            }
+
+            /// Get an adapter that writes the message in a chosen format, e.g., in
+            /// groups. See [`TextDisplay`](crate::TextDisplay).
+            pub fn display(&self) -> $crate::TextDisplay<'_> {
+                self.0.display()
+            }
+
+            /// Read a message from a reader, e.g., a file, prepared according to
+            /// the policy of `settings`. Line breaks are read as spaces.
+            ///
+            /// # Errors
+            /// This method returns an error if reading fails, if the text read is
+            /// not valid UTF-8, or if it is not a message.
+            pub fn read_from(
+                reader: impl ::std::io::Read,
+                settings: &$crate::policy::CipherSettings,
+            ) -> Result<Self, $crate::errors::ReadError> {
+                Ok($name(<$crate::Message>::read_from(reader, settings)?))
+            }
+
+            /// Read a message from a file, see [`read_from`](Self::read_from).
+            ///
+            /// # Errors
+            /// This method returns an error if the file cannot be read, if it is not
+            /// valid UTF-8, or if its text is not a message.
+            pub fn read_from_file(
+                path: impl AsRef<::std::path::Path>,
+                settings: &$crate::policy::CipherSettings,
+            ) -> Result<Self, $crate::errors::ReadError> {
+                Self::read_from(::std::fs::File::open(path)?, settings)
+            }
+
+            /// Write the message to a writer, e.g., a file, followed by a line
+            /// break, cased according to `settings`.
+            ///
+            /// # Errors
+            /// This method returns an error if writing fails.
+            pub fn write_to(
+                &self,
+                writer: impl ::std::io::Write,
+                settings: &$crate::policy::CipherSettings,
+            ) -> ::std::io::Result<()> {
+                self.0.write_to(writer, settings)
+            }
+
+            /// Write the message to a file, replacing its contents, see
+            /// [`write_to`](Self::write_to).
+            ///
+            /// # Errors
+            /// This method returns an error if the file cannot be written.
+            pub fn write_to_file(
+                &self,
+                path: impl AsRef<::std::path::Path>,
+                settings: &$crate::policy::CipherSettings,
+            ) -> ::std::io::Result<()> {
+                self.write_to(::std::fs::File::create(path)?, settings)
+            }
+        }
+    };
+    (ciphertext $name:ident in $module:literal) => {
+        $crate::text_api!(
+            $name($crate::Ciphertext),
+            $crate::RingElement,
+            "ciphertext",
+            "a letter or a space"
+        );
+        $crate::text_api!(parsing $name($crate::Ciphertext));
+
+        impl $name {
+            /// Report every character of a string that is not a letter or a space,
+            /// together with its position.
+            pub fn validate(str: &str) -> $crate::ValidationReport {
+                <$crate::Ciphertext>::validate(str)
+            }
+
+            /// Write the ciphertext in groups of `size` letters separated by spaces,
+            /// e.g., in the traditional groups of five letters. See
+            /// [`Grouped`](crate::Grouped) for other separators.
+            ///
+            /// # Panics
+            /// This method panics if `size` is 0.
+            pub fn format_grouped(&self, size: usize) -> String {
+                $crate::Grouped::new(self, size).to_string()
+            }
+
+            /// Write the ciphertext with its letters cased according to `case`, e.g.,
+            /// in lowercase rather than in the ALL CAPS used by
+            /// [`Display`](std::fmt::Display).
+            pub fn format_with_case(&self, case: $crate::policy::CasePolicy) -> String {
+                self.0.format_with_case(case)
+            }
+
+            /// Get an adapter that writes the ciphertext in a chosen format, e.g.,
+            /// `ciphertxt.display().grouped(5).lowercase()` for lowercase groups of
+            /// five letters. See [`TextDisplay`](crate::TextDisplay).
+            pub fn display(&self) -> $crate::TextDisplay<'_> {
+                self.0.display()
+            }
+
+            /// Read a ciphertext from a reader, e.g., a file, prepared according to
+            /// the parse policy of `settings`. Line breaks are read as spaces.
+            ///
+            /// # Errors
+            /// This method returns an error if reading fails, if the text read is
+            /// not valid UTF-8, or if it is not a ciphertext.
+            pub fn read_from(
+                reader: impl ::std::io::Read,
+                settings: &$crate::policy::CipherSettings,
+            ) -> Result<Self, $crate::errors::ReadError> {
+                Ok($name(<$crate::Ciphertext>::read_from(reader, settings)?))
+            }
+
+            /// Read a ciphertext from a file, see [`read_from`](Self::read_from).
+            ///
+            /// # Errors
+            /// This method returns an error if the file cannot be read, if it is not
+            /// valid UTF-8, or if its text is not a ciphertext.
+            pub fn read_from_file(
+                path: impl AsRef<::std::path::Path>,
+                settings: &$crate::policy::CipherSettings,
+            ) -> Result<Self, $crate::errors::ReadError> {
+                Self::read_from(::std::fs::File::open(path)?, settings)
+            }
+
+            /// Write the ciphertext to a writer, e.g., a file, followed by a line
+            /// break, cased according to `settings`, and in its groups.
+            ///
+            /// # Errors
+            /// This method returns an error if writing fails.
+            pub fn write_to(
+                &self,
+                writer: impl ::std::io::Write,
+                settings: &$crate::policy::CipherSettings,
+            ) -> ::std::io::Result<()> {
+                self.0.write_to(writer, settings)
+            }
+
+            /// Write the ciphertext to a file, replacing its contents, see
+            /// [`write_to`](Self::write_to).
+            ///
+            /// # Errors
+            /// This method returns an error if the file cannot be written.
+            pub fn write_to_file(
+                &self,
+                path: impl AsRef<::std::path::Path>,
+                settings: &$crate::policy::CipherSettings,
+            ) -> ::std::io::Result<()> {
+                self.write_to(::std::fs::File::create(path)?, settings)
+            }
+        }
+    };
+    (bytes message $name:ident) => {
+        $crate::text_api!(
+            $name($crate::Message<$crate::alphabet::Byte, 256>),
+            $crate::RingElement<256>,
+            "message",
+            "a byte, i.e., a character up to `U+00FF`"
+        );
+    };
+    (bytes ciphertext $name:ident) => {
+        $crate::text_api!(
+            $name($crate::Ciphertext<$crate::alphabet::Byte, 256>),
+            $crate::RingElement<256>,
+            "ciphertext",
+            "a byte, i.e., a character up to `U+00FF`"
+        );
+        $crate::text_api!(parsing $name($crate::Ciphertext<$crate::alphabet::Byte, 256>));
+    };
+    (parsing $name:ident($inner:ty)) => {
+        impl $name {
+            /// Create a new ciphertext from a string, parsed according to `policy`,
+            /// e.g., to reject lowercase letters. [`from_str`](#method.from_str)
+            /// parses with the [`Lenient`](crate::policy::ParsePolicy::Lenient)
+            /// policy.
+            ///
+            /// # Errors
+            /// This method returns an error if the policy rejects the string, or if
+            /// the prepared string contains an invalid character.
+            pub fn with_parsing(
+                str: &str,
+                policy: $crate::policy::ParsePolicy,
+            ) -> Result<Self, $crate::EncodingError> {
+                Ok($name(<$inner>::with_parsing(str, policy)?))
+            }
+        }
+    };
+    // The API of every text, where `$valid` describes the characters of a text.
+    ($name:ident($inner:ty), $symbol:ty, $noun:literal, $valid:literal) => {
+        impl $name {
+            #[doc = concat!(" Create a new ", $noun, " from a sequence of characters, e.g., the output")]
+            /// of another iterator, without collecting them into a string first. The
+            /// rules are those of [`from_str`](#method.from_str).
+            ///
+            /// # Errors
+            /// This method returns an error if the sequence is empty or contains a
+            #[doc = concat!(" character that is not ", $valid, ", reporting the position")]
+            /// of every such character in the sequence.
+            pub fn from_chars<I: IntoIterator<Item = char>>(
+                chars: I,
+            ) -> Result<Self, $crate::EncodingError> {
+                Ok($name(<$inner>::from_chars(chars)?))
+            }
+
+            #[doc = concat!(" Get the number of symbols in the ", $noun, ".")]
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            #[doc = concat!(" Returns true if the ", $noun, " has no symbols and false otherwise.")]
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+
+            #[doc = concat!(" Iterate over the blocks of `size` symbols of the ", $noun, ", borrowed")]
+            #[doc = concat!(" from the ", $noun, ", where the last block may be shorter.")]
+            ///
+            /// # Panics
+            /// This method panics if `size` is 0.
+            pub fn chunks(&self, size: usize) -> ::std::slice::Chunks<'_, $symbol> {
+                self.0.chunks(size)
+            }
+
+            /// Iterate over the overlapping runs of `size` consecutive symbols of the
+            #[doc = concat!(" ", $noun, ", e.g., its digraphs for `size` 2, borrowed from the ", $noun, ".")]
+            ///
+            /// # Panics
+            /// This method panics if `size` is 0.
+            pub fn windows(&self, size: usize) -> ::std::slice::Windows<'_, $symbol> {
+                self.0.windows(size)
+            }
+
+            #[doc = concat!(" Count the positions at which two ", $noun, "s of the same length have")]
+            /// different symbols, i.e., their Hamming distance, or return `None` if
+            /// they have different lengths.
+            pub fn distance(&self, other: &Self) -> Option<usize> {
+                self.0.distance(&other.0)
+            }
+
+            #[doc = concat!(" Compare the ", $noun, ", as the expected text, with another ", $noun, ", e.g.,")]
+            /// a guess, and list the positions at which they differ. See
+            /// [`TextDiff`](crate::diff::TextDiff).
+            pub fn diff(&self, other: &Self) -> $crate::diff::TextDiff {
+                self.0.diff(&other.0)
+            }
+
+            #[doc = concat!(" Iterate over the symbols of the ", $noun, ".")]
+            pub fn iter(&self) -> ::std::slice::Iter<'_, $symbol> {
+                self.0.iter()
+            }
+
+            /// Get the symbol at `index`, or `None` if the index is out of bounds.
+            pub fn get(&self, index: usize) -> Option<&$symbol> {
+                self.0.get(index)
+            }
+
+            #[doc = concat!(" Get the ", $noun, " made of the symbols in `range`, e.g., a block of")]
+            #[doc = concat!(" the ", $noun, ".")]
+            ///
+            /// # Panics
+            /// This method panics if the range is out of bounds, as for slices.
+            pub fn slice<B: ::std::ops::RangeBounds<usize>>(&self, range: B) -> Self {
+                $name(self.0.slice(range))
+            }
+
+            #[doc = concat!(" Count the symbols of the ", $noun, ", e.g., to compare them with the")]
+            /// letter frequencies of a language. Letters are recorded in lowercase.
+            pub fn letter_counts(&self) -> $crate::analysis::FrequencyTable {
+                self.0.letter_counts()
+            }
+
+            #[doc = concat!(" Append a character to the ", $noun, ", e.g., as it is typed, without")]
+            #[doc = concat!(" parsing the whole ", $noun, " again. The character is read as by")]
+            /// [`from_str`](#method.from_str), so a space is skipped unless it is a
+            /// symbol.
+            ///
+            /// # Errors
+            #[doc = concat!(" This method returns an error, and leaves the ", $noun, " unchanged, if")]
+            #[doc = concat!(" the character is not ", $valid, ". The character is")]
+            #[doc = concat!(" reported at the end of the ", $noun, ", i.e., at the position it would")]
+            /// have taken.
+            pub fn push_char(&mut self, character: char) -> Result<(), $crate::EncodingError> {
+                self.0.push_char(character)
+            }
+
+            #[doc = concat!(" Append the characters of a string to the ", $noun, ", read as by")]
+            /// [`from_str`](#method.from_str), except that a string without any
+            #[doc = concat!(" symbols, e.g., an empty string, leaves the ", $noun, " unchanged.")]
+            ///
+            /// # Errors
+            #[doc = concat!(" This method returns an error, and leaves the ", $noun, " unchanged, if")]
+            #[doc = concat!(" the string contains a character that is not ", $valid, ".")]
+            pub fn extend_from_str(&mut self, str: &str) -> Result<(), $crate::EncodingError> {
+                self.0.extend_from_str(str)
+            }
+
+            #[doc = concat!(" Shorten the ", $noun, " to its first `len` symbols, or leave it")]
+            /// unchanged if it has no more than `len` symbols.
+            pub fn truncate(&mut self, len: usize) {
+                self.0.truncate(len)
+            }
+        }
+
+        #[doc = concat!(" Parse a ", $noun, " from a string.")]
+        ///
+        /// # Errors
+        /// This trait implementation returns an error if the string is empty or
+        #[doc = concat!(" contains a character that is not ", $valid, ".")]
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::EncodingError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name(<$inner as ::std::str::FromStr>::from_str(s)?))
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = $crate::EncodingError;
+
+            fn try_from(s: &str) -> Result<Self, Self::Error> {
+                Ok($name(<$inner>::try_from(s)?))
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = $crate::EncodingError;
+
+            fn try_from(s: String) -> Result<Self, Self::Error> {
+                Ok($name(<$inner>::try_from(s)?))
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl ::std::ops::Index<usize> for $name {
+            type Output = $symbol;
+
+            fn index(&self, index: usize) -> &Self::Output {
+                &self.0[index]
+            }
+        }
+
+        impl IntoIterator for $name {
+            type Item = $symbol;
+            type IntoIter = ::std::vec::IntoIter<$symbol>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.into_iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $name {
+            type Item = &'a $symbol;
+            type IntoIter = ::std::slice::Iter<'a, $symbol>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+
+        #[doc = concat!(" Concatenate two ", $noun, "s.")]
+        impl ::std::ops::Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $name(self.0 + other.0)
+            }
+        }
+
+        impl Extend<$symbol> for $name {
+            fn extend<I: IntoIterator<Item = $symbol>>(&mut self, iter: I) {
+                self.0.extend(iter)
+            }
+        }
+
+        impl FromIterator<$symbol> for $name {
+            fn from_iter<I: IntoIterator<Item = $symbol>>(iter: I) -> Self {
+                $name(iter.into_iter().collect())
+            }
+        }
+    };
+}
+pub(crate) use text_api;
+
+// Parse a sequence of characters, e.g., the characters of a string, as a `Vec<RingElement>`
+// We cannot implement `FromStr` for `Vec<RingElement>` (since both `FromStr`
+// and `Vec` are external to our crate), but we need similar functionality in
+// order to avoid code duplication when converting from Strings to Wrapper types
+// around `Vec<RingElement>``
+fn from_chars<A: Alphabet<M>, const M: u16>(
+    chars: impl IntoIterator<Item = char>,
+    ignore_case: bool,
+) -> Result<Vec<RingElement<M>>, InvalidCharacters> {
+    let chars = chars.into_iter();
+    let (mut txt, mut invalid) = (Vec::with_capacity(chars.size_hint().0), Vec::new());
+    let mut byte_index = 0;
+    for (char_index, character) in chars.enumerate() {
+        match RingElement::encode::<A>(character, ignore_case) {
+            Some(x) => txt.push(x),
+            // Spaces are skipped
+            None if character == ' ' => (),
+            None => invalid.push(InvalidCharacter {
+                character,
+                byte_index,
+                char_index,
+            }),
+        }
+        byte_index += character.len_utf8();
+    }
+
+    if invalid.is_empty() && !txt.is_empty() {
+        Ok(txt)
+    } else {
+        Err(InvalidCharacters(invalid))
+    }
+}
+
+// Find every character of a string that is not in the alphabet `A`, following the rules of
+// `from_str`: spaces that are not in the alphabet are skipped, and letters are lowercased first if
+// `ignore_case` is true.
+fn validate<A: Alphabet<M>, const M: u16>(s: &str, ignore_case: bool) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for (char_index, (byte_index, character)) in s.char_indices().enumerate() {
+        let valid = if ignore_case {
+            character.to_lowercase().all(|c| A::index_of(c).is_some())
+        } else {
+            A::index_of(character).is_some()
+        };
+
+        if valid {
+            report.symbols += 1;
+        } else if character != ' ' {
+            report.invalid.push(InvalidCharacter {
+                character,
+                byte_index,
+                char_index,
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The tests use the Latin Alphabet.
+    type RingElement = super::RingElement<26>;
+    type Message = super::Message<Latin>;
+    type Ciphertext = super::Ciphertext<Latin>;
+
+    // Data for our running example/test.
+    // Note: This is an attempt at global constants for the tests. If it would be
+    // better to use std::cell::OnceCell, I'm not sure I understand how to do
+    // that properly. Encoded "wewillmeetatmidnight" message from Example 1.1,
+    // Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
+    thread_local! (static MSG0: Message = Message::from_iter(vec![RingElement::new(22), RingElement::new(4),
+            RingElement::new(22), RingElement::new(8), RingElement::new(11), RingElement::new(11),
+            RingElement::new(12), RingElement::new(4), RingElement::new(4), RingElement::new(19),
+            RingElement::new(0), RingElement::new(19),
+            RingElement::new(12), RingElement::new(8), RingElement::new(3), RingElement::new(13), RingElement::new(8), RingElement::new(6), RingElement::new(7), RingElement::new(19)]));
+
+    // Encrypted "wewillmeetatmidnight" message with key=11, from Example 1.1,
+    // Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
+    thread_local! (static CIPH0: Ciphertext = Ciphertext::from_iter(vec![RingElement::new(7), RingElement::new(15),
+            RingElement::new(7), RingElement::new(19), RingElement::new(22), RingElement::new(22),
+            RingElement::new(23), RingElement::new(15), RingElement::new(15), RingElement::new(4),
+            RingElement::new(11), RingElement::new(4),
+            RingElement::new(23), RingElement::new(19), RingElement::new(14), RingElement::new(24), RingElement::new(19), RingElement::new(17), RingElement::new(18), RingElement::new(4)]));
+
+    // Encrypted "wewillmeetatmidnight" as a string, from Example 1.1 Stinson 3rd
+    // Edition, Example 2.1 Stinson 4th Edition
+    thread_local!(static CIPH0_STR: String = "HPHTWWXPPELEXTOYTRSE".to_string());
+
+    #[test]
+    fn ring_elmnt_default() {
+        assert_eq!(RingElement::default(), RingElement::new(0));
+        assert!(RingElement::default().is_zero())
+    }
+
+    #[test]
+    fn ring_elmnt_into_inner() {
+        let x = RingElement::new(5);
+        assert_eq!(x.into_inner(), 5)
+    }
+    #[test]
+    fn ring_elmt_display() {
+        // Test Display impl
+        let x = RingElement::new(3);
+        assert_eq!(
+            format!("The ring element value is {x}"),
+            "The ring element value is 3"
+        );
+    }
+
+    #[test]
+    fn ring_elmt_encoding_basics() {
+        assert_eq!(RingElement::from_char::<Latin>('g').unwrap().0, 6); // Sanity check `from_char`
+        assert_eq!(RingElement::from_char::<Latin>('w').unwrap().0, 22); // Sanity check `from_char`
+        assert_eq!(RingElement::new(5).to_char::<Latin>(), Some('f')); // Sanity check `to_char`
+        assert_eq!(RingElement::new(0).to_char::<Latin>(), Some('a')) // Sanity check to `to_char`
+    }
+
+    #[test]
+    fn ring_elmt_arithmetic() {
+        assert_eq!(
+            RingElement::new(5) + RingElement::new(11),
+            RingElement::new(16)
+        ); // Basic addition test
+        assert_eq!(
+            RingElement::new(22) + RingElement::new(11),
+            RingElement::new(7)
+        ); // Addition test with overflow
+        assert_eq!(
+            RingElement::new(20) + RingElement::new(6),
+            RingElement::new(0)
+        ); // Addition boundary check
+
+        assert_eq!(
+            RingElement::new(11) - RingElement::new(3),
+            RingElement::new(8)
+        ); // Basic subtraction test
+        assert_eq!(
+            RingElement::new(4) - RingElement::new(11),
+            RingElement::new(19)
+        ); // Subtraction test with overflow
+        assert_eq!(
+            RingElement::new(15) - RingElement::new(15),
+            RingElement::new(0)
+        ); // Subtraction boundary check
+    }
+
+    #[test]
+    fn ring_elmt_multiplication() {
+        assert_eq!(
+            RingElement::new(5) * RingElement::new(7),
+            RingElement::new(9)
+        );
+        assert_eq!(
+            RingElement::new(13) * RingElement::new(2),
+            RingElement::new(0)
+        ); // Zero divisors
+
+        // Large moduli do not overflow
+        assert_eq!(
+            super::RingElement::<65535>::new(65534) * super::RingElement::<65535>::new(65534),
+            super::RingElement::<65535>::new(1)
+        );
+    }
+
+    #[test]
+    fn ring_elmt_arithmetic_traits() {
+        let mut x = RingElement::new(20);
+        x += RingElement::new(10);
+        assert_eq!(x, RingElement::new(4));
+        x -= RingElement::new(5);
+        assert_eq!(x, RingElement::new(25));
+
+        assert_eq!(-RingElement::new(3), RingElement::new(23));
+        assert_eq!(-RingElement::new(0), RingElement::new(0));
+
+        assert_eq!(RingElement::new(5) * 3i8, RingElement::new(15));
+        assert_eq!(RingElement::new(5) * -1i8, RingElement::new(21));
+        assert_eq!(RingElement::new(25) * i8::MIN, RingElement::new(24));
+
+        let elements: Vec<_> = (0..26).map(RingElement::new).collect();
+        assert_eq!(elements.iter().sum::<RingElement>(), RingElement::new(13));
+        assert_eq!(
+            elements.into_iter().sum::<RingElement>(),
+            RingElement::new(13)
+        );
+        assert_eq!(
+            Vec::<RingElement>::new().iter().sum::<RingElement>(),
+            RingElement::new(0)
+        );
+    }
+
+    #[test]
+    fn ring_elmt_inverse() {
+        // Stinson, Section 2.1.3
+        assert_eq!(RingElement::new(7).inverse(), Some(RingElement::new(15)));
+        assert_eq!(RingElement::new(25).inverse(), Some(RingElement::new(25)));
+        assert_eq!(RingElement::new(13).inverse(), None);
+        assert_eq!(RingElement::new(0).inverse(), None);
+
+        assert_eq!(RingElement::units().count(), 12);
+        for x in RingElement::units() {
+            assert_eq!(x * x.inverse().unwrap(), RingElement::new(1));
+        }
+        assert_eq!(super::RingElement::<29>::units().count(), 28);
+        assert_eq!(
+            super::RingElement::<1>::units().collect::<Vec<_>>(),
+            [super::RingElement::<1>::new(0)]
+        );
+    }
+
+    #[test]
+    fn ring_elmt_checked_arithmetic() {
+        assert_eq!(
+            RingElement::new(22).checked_add(RingElement::new(11)),
+            Some(RingElement::new(7))
+        );
+        assert_eq!(
+            RingElement::new(4).checked_sub(RingElement::new(11)),
+            Some(RingElement::new(19))
+        );
+        assert_eq!(RingElement::new(26).checked_add(RingElement::new(0)), None);
+        assert_eq!(RingElement::new(0).checked_sub(RingElement::new(65)), None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "Arithmetic on an invalid `RingElement`: the operands must be in canonical form."
+    )]
+    fn ring_elmt_invalid_operands() {
+        let f = |_: &std::panic::PanicHookInfo| {};
+        std::panic::set_hook(Box::new(f));
+        let _fail = RingElement::new(30) + RingElement::new(1);
+    }
+
+    #[test]
+    fn ring_elmt_from_i32() {
+        // `from_i32` works as expected
+        assert_eq!(RingElement::from_i32(37), RingElement::new(11));
+        assert_eq!(RingElement::from_i32(-28), RingElement::new(24));
+        assert_eq!(RingElement::from_i32(26), RingElement::new(0));
+        assert_eq!(RingElement::from_i32(-3), RingElement::new(23));
+        assert_eq!(RingElement::from_i32(5), RingElement::new(5));
+    }
+
+    #[test]
+    fn ring_elmt_public_constructors() {
+        assert_eq!(RingElement::from_i64(-1), RingElement::new(25));
+        assert_eq!(RingElement::from_i64(52), RingElement::new(0));
+        assert!(RingElement::from_i64(i64::MIN).is_canonical());
+        assert!(RingElement::from_i64(i64::MAX).is_canonical());
+        assert_eq!(
+            RingElement::from_char::<Latin>('z'),
+            Some(RingElement::new(25))
+        );
+        assert_eq!(RingElement::from_char::<Latin>(' '), None);
+    }
+
+    // The invalid characters of `s` that are in `chars`.
+    fn invalid(s: &str, chars: &str) -> InvalidCharacters {
+        InvalidCharacters::find(s, |c| chars.contains(c))
+    }
+
+    #[test]
+    fn ring_elmt_encoding_errors() {
+        assert_eq!(RingElement::encode::<Latin>('_', false), None);
+        assert_eq!(RingElement::encode::<Latin>('A', false), None);
+        assert_eq!(
+            RingElement::encode::<Latin>('A', true),
+            Some(RingElement::new(0))
+        );
+
+        let err = from_chars::<Latin, 26>("asd;lkasdfEnk0".chars(), false).unwrap_err();
+        let positions: Vec<_> = err
+            .characters()
+            .iter()
+            .map(|c| (c.character, c.char_index, c.byte_index))
+            .collect();
+        assert_eq!(positions, [(';', 3, 3), ('E', 10, 10), ('0', 13, 13)]);
+        assert_eq!(
+            err.to_string(),
+            "Failed to encode the following characters as ring elements: ;E0"
+        );
+
+        // Positions count characters and bytes separately
+        let err = from_chars::<Latin, 26>("é!".chars(), false).unwrap_err();
+        assert_eq!(err.characters()[1].char_index, 1);
+        assert_eq!(err.characters()[1].byte_index, 2);
+    }
+
+    #[test]
+    fn ring_elmt_encoding_invalid() {
+        // Invalid elements are never created by the constructors, and writing them out does
+        // not panic
+        assert_eq!(RingElement::try_new(26), None);
+        assert_eq!(RingElement::try_new(25), Some(RingElement::new(25)));
+        assert_eq!(RingElement::new(26).to_char::<Latin>(), None);
+        assert_eq!(
+            Message::from_iter(vec![RingElement::new(0), RingElement::new(26)]).to_string(),
+            "a\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn msg_default() {
+        assert_eq!(Message::default(), Message::from_iter(vec![]))
     }
     #[test]
     // Example 1.1, Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
@@ -503,16 +2230,16 @@ mod tests {
         // Allow spaces
         assert_eq!(
             Message::new("i love cats"),
-            Ok(Message(vec![
-                RingElement(8),
-                RingElement(11),
-                RingElement(14),
-                RingElement(21),
-                RingElement(4),
-                RingElement(2),
-                RingElement(0),
-                RingElement(19),
-                RingElement(18)
+            Ok(Message::from_iter(vec![
+                RingElement::new(8),
+                RingElement::new(11),
+                RingElement::new(14),
+                RingElement::new(21),
+                RingElement::new(4),
+                RingElement::new(2),
+                RingElement::new(0),
+                RingElement::new(19),
+                RingElement::new(18)
             ]))
         );
     }
@@ -522,16 +2249,15 @@ mod tests {
     fn msg_encoding_error() {
         assert_eq!(
             Message::new("we~ will Meet at midnight;"),
-            Err(EncodingError::InvalidMessage(
-                ErrorRepr::RingElementEncodingError("~M;".to_string()).into()
-            ))
+            Err(EncodingError::InvalidMessage(invalid(
+                "we~ will Meet at midnight;",
+                "~M;"
+            )))
         );
 
         assert_eq!(
             Message::new(""),
-            Err(EncodingError::InvalidMessage(
-                ErrorRepr::RingElementEncodingError("".to_string()).into()
-            ))
+            Err(EncodingError::InvalidMessage(InvalidCharacters::default()))
         )
     }
 
@@ -545,7 +2271,7 @@ mod tests {
 
     #[test]
     fn ciphertxt_default() {
-        assert_eq!(Ciphertext::default(), Ciphertext(vec![]));
+        assert_eq!(Ciphertext::default(), Ciphertext::from_iter(vec![]));
     }
 
     #[test]
@@ -564,13 +2290,614 @@ mod tests {
         )
     }
 
+    #[test]
+    fn iterate() {
+        let msg = MSG0.with(|msg| msg.clone());
+        assert_eq!(msg.get(2), Some(&RingElement::new(22)));
+        assert_eq!(msg.get(20), None);
+        assert_eq!(msg[0], RingElement::new(22));
+
+        // Iterating by reference and by value visit the same symbols
+        let ciphertxt = CIPH0.with(|ciph| ciph.clone());
+        assert!(ciphertxt.iter().copied().eq(ciphertxt.clone()));
+        assert_eq!(
+            ciphertxt.into_iter().collect::<Ciphertext>().to_string(),
+            CIPH0_STR.with(|ciph| ciph.clone())
+        );
+    }
+
+    #[test]
+    fn concatenate() {
+        let msg = Message::new("wewillmeetatmidnight").unwrap();
+        let (start, end) = (msg.slice(..8), msg.slice(8..));
+        assert_eq!(start.to_string(), "wewillme");
+        assert_eq!(end.to_string(), "etatmidnight");
+        assert_eq!(start.clone() + end.clone(), msg);
+
+        let mut extended = start;
+        extended.extend(end);
+        assert_eq!(extended, msg);
+
+        let ciphertxt = CIPH0.with(|ciph| ciph.clone());
+        assert_eq!(ciphertxt.slice(2..=4).to_string(), "HTW");
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_out_of_bounds() {
+        let _ = Message::new("short").unwrap().slice(3..6);
+    }
+
+    #[test]
+    fn chunks() {
+        let msg = MSG0.with(|msg| msg.clone());
+        assert_eq!(msg.len(), 20);
+        assert!(!msg.is_empty());
+        assert!(Message::default().is_empty());
+
+        let chunks: Vec<_> = msg.chunks(8).collect();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2].len(), 4);
+        assert_eq!(chunks.concat(), msg.0);
+
+        // The chunks are borrowed from the text
+        assert!(std::ptr::eq(&chunks[0][0], &msg[0]));
+    }
+
+    #[test]
+    fn windows() {
+        let ciphertxt = CIPH0.with(|ciph| ciph.clone());
+        assert_eq!(ciphertxt.windows(3).count(), 18);
+        assert!(ciphertxt.windows(21).next().is_none());
+
+        // Each window is a run of consecutive symbols
+        let windows: Vec<_> = ciphertxt.windows(2).collect();
+        assert_eq!(windows[0], [RingElement::new(7), RingElement::new(15)]);
+        assert!(windows.windows(2).all(|pair| pair[0][1] == pair[1][0]));
+
+        // The chunks of the ciphertext are the windows that do not overlap
+        for (chunk, window) in ciphertxt.chunks(4).zip(ciphertxt.windows(4).step_by(4)) {
+            assert!(chunk.iter().eq(window));
+        }
+    }
+
+    #[test]
+    fn distance() {
+        let msg1 = MSG0.with(|msg| msg.clone());
+        let msg2 = Message::from_str("wewillmeetatdaybreak").unwrap();
+        assert_eq!(msg1.distance(&msg1), Some(0));
+        assert_eq!(msg1.distance(&msg2), Some(8));
+        assert_eq!(msg1.distance(&Message::from_str("wewill").unwrap()), None);
+    }
+
+    #[test]
+    fn diff() {
+        let msg = MSG0.with(|msg| msg.clone());
+        assert!(msg.diff(&msg).differences().is_empty());
+
+        let diff = msg.diff(&Message::from_str("wewillmeetatdaybreak").unwrap());
+        assert_eq!(diff.differences().len(), 8);
+        assert_eq!(diff.differences()[0].position, 12);
+        assert_eq!(diff.differences()[0].expected, Some('m'));
+        assert_eq!(diff.differences()[0].found, Some('d'));
+    }
+
+    #[test]
+    fn try_from() {
+        // Conversions work through generic bounds, and agree with `FromStr`
+        fn convert<T: TryFrom<&'static str>>(s: &'static str) -> Option<T> {
+            s.try_into().ok()
+        }
+
+        let msg: Message = "wewillmeetatmidnight".try_into().unwrap();
+        assert_eq!(msg, MSG0.with(|msg| msg.clone()));
+        assert_eq!(convert::<Message>("Hello"), None);
+        let ciphertxt = Ciphertext::try_from("HPHTWWXPPELEXTOYTRSE".to_string()).unwrap();
+        assert_eq!(ciphertxt, CIPH0.with(|ciph| ciph.clone()));
+        assert_eq!(
+            Ciphertext::try_from("HELLO!").unwrap_err(),
+            Ciphertext::from_str("HELLO!").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn text_from_chars() {
+        // Characters from an iterator parse like the string they would make
+        let msg = Message::from_chars("we will meet".chars().rev()).unwrap();
+        assert_eq!(msg, Message::from_str("teemlliwew").unwrap());
+        let ciphertxt = Ciphertext::from_chars(['H', 'p', ' ', 'H']).unwrap();
+        assert_eq!(ciphertxt, Ciphertext::from_str("HPH").unwrap());
+
+        // Every invalid character is reported at its position in the sequence
+        let Err(EncodingError::InvalidMessage(invalid)) = Message::from_chars(['é', 'a', 'B', '1'])
+        else {
+            panic!("expected an invalid message");
+        };
+        let positions: Vec<_> = invalid
+            .characters()
+            .iter()
+            .map(|c| (c.character, c.char_index, c.byte_index))
+            .collect();
+        assert_eq!(positions, [('é', 0, 0), ('B', 2, 3), ('1', 3, 4)]);
+        assert_eq!(
+            Message::from_chars("Hi!".chars()).unwrap_err(),
+            Message::from_str("Hi!").unwrap_err()
+        );
+
+        assert!(Ciphertext::from_chars([]).is_err());
+        assert!(Ciphertext::from_chars("HELLO!".chars()).is_err());
+    }
+
+    #[test]
+    fn new_lossy() {
+        let (msg, report) = Message::new_lossy("wewillmeetatmidnight");
+        assert_eq!(msg, Message::new("wewillmeetatmidnight").unwrap());
+        assert!(report.is_unchanged());
+        assert_eq!(report.to_string(), "");
+
+        let (msg, report) = Message::new_lossy("Meet Zoë at 9!");
+        assert_eq!(msg.to_string(), "meetzoat");
+        let lowercased: Vec<_> = report.lowercased().iter().map(|c| c.character).collect();
+        assert_eq!(lowercased, ['M', 'Z']);
+        let dropped: Vec<_> = report
+            .dropped()
+            .iter()
+            .map(|c| (c.character, c.char_index))
+            .collect();
+        assert_eq!(
+            dropped,
+            [
+                (' ', 4),
+                ('ë', 7),
+                (' ', 8),
+                (' ', 11),
+                ('9', 12),
+                ('!', 13)
+            ]
+        );
+        assert_eq!(
+            report.to_string().lines().take(3).collect::<Vec<_>>(),
+            [
+                "lowercased 'M' at position 0 (byte 0)",
+                "dropped ' ' at position 4 (byte 4)",
+                "lowercased 'Z' at position 5 (byte 5)"
+            ]
+        );
+
+        let (msg, report) = Message::new_lossy("1984");
+        assert!(msg.is_empty());
+        assert_eq!(report.dropped().len(), 4);
+    }
+
+    #[test]
+    fn eq_ignore_format() {
+        let msg = MSG0.with(|msg| msg.clone());
+        assert!(msg.eq_ignore_format("We will meet at midnight!"));
+        assert!(msg.eq_ignore_format("WEWILLMEETATMIDNIGHT"));
+        assert!(msg.eq_ignore_format("we-will-meet-at-midnight"));
+
+        // The letters must match exactly, with nothing left over on either side
+        assert!(!msg.eq_ignore_format("We will meet at midnigh"));
+        assert!(!msg.eq_ignore_format("We will meet at midnight, Zoë"));
+        assert!(!msg.eq_ignore_format(""));
+        assert!(Message::default().eq_ignore_format("1, 2, 3!"));
+    }
+
+    #[test]
+    fn letter_counts() {
+        let counts = CIPH0.with(|ciph| ciph.letter_counts());
+        assert_eq!(counts, FrequencyTable::new("HPHTWWXPPELEXTOYTRSE"));
+        assert_eq!(counts.count('p'), 3);
+        assert_eq!(counts.count('P'), 0);
+        assert_eq!(MSG0.with(|msg| msg.letter_counts().total()), 20);
+        assert_eq!(Message::default().letter_counts().total(), 0);
+    }
+
+    #[test]
+    fn editing() {
+        let mut msg = Message::default();
+        for c in "we will".chars() {
+            msg.push_char(c).unwrap();
+        }
+        msg.extend_from_str("meetat midnight").unwrap();
+        assert_eq!(msg, MSG0.with(|msg| msg.clone()));
+
+        // Invalid input is rejected without changing the message, and reported where it was typed
+        let Err(EncodingError::InvalidMessage(invalid)) = msg.push_char('!') else {
+            panic!("expected an invalid message");
+        };
+        assert_eq!(
+            invalid.characters(),
+            [InvalidCharacter {
+                character: '!',
+                byte_index: 20,
+                char_index: 20
+            }]
+        );
+        let Err(EncodingError::InvalidMessage(invalid)) = msg.extend_from_str("at 1 am") else {
+            panic!("expected an invalid message");
+        };
+        assert_eq!(invalid.characters()[0].char_index, 3);
+        assert_eq!(msg.len(), 20);
+        msg.extend_from_str("  ").unwrap();
+        msg.extend_from_str("").unwrap();
+        assert_eq!(msg.len(), 20);
+
+        msg.truncate(7);
+        assert_eq!(msg.to_string(), "wewillm");
+        msg.truncate(100);
+        assert_eq!(msg.len(), 7);
+
+        // Ciphertexts ignore case as they do when parsed
+        let mut ciphertxt = Ciphertext::from_str("HP").unwrap();
+        ciphertxt.push_char('h').unwrap();
+        ciphertxt.extend_from_str("TW wxppelextoytrse").unwrap();
+        assert_eq!(ciphertxt, CIPH0.with(|ciph| ciph.clone()));
+        let Err(EncodingError::InvalidCiphertext(invalid)) = ciphertxt.push_char('é') else {
+            panic!("expected an invalid ciphertext");
+        };
+        assert_eq!(invalid.characters()[0].char_index, 20);
+    }
+
+    #[test]
+    fn read_and_write() {
+        let settings = CipherSettings::new();
+        let msg = Message::read_from("we will meet\nat midnight\n".as_bytes(), &settings).unwrap();
+        assert_eq!(msg, MSG0.with(|msg| msg.clone()));
+
+        let mut written = Vec::new();
+        msg.write_to(&mut written, &settings.with_group_size(5))
+            .unwrap();
+        // Messages are not grouped
+        assert_eq!(written, b"wewillmeetatmidnight\n");
+
+        let ciphertxt = CIPH0.with(|ciph| ciph.clone());
+        let mut written = Vec::new();
+        let grouped = settings.with_case(CasePolicy::Lowercase).with_group_size(5);
+        ciphertxt.write_to(&mut written, &grouped).unwrap();
+        assert_eq!(written, b"hphtw wxppe lexto ytrse\n");
+        assert_eq!(
+            Ciphertext::read_from(written.as_slice(), &settings).unwrap(),
+            ciphertxt
+        );
+
+        // The settings normalize the text that is read
+        let strict = settings.with_parsing(ParsePolicy::Strict);
+        assert!(matches!(
+            Ciphertext::read_from(written.as_slice(), &strict),
+            Err(ReadError::Encoding(EncodingError::InvalidCiphertext(_)))
+        ));
+        let spelled =
+            settings.with_policy(MessagePolicy::new().with_digits(policy::DigitPolicy::SpellOut));
+        let msg = Message::read_from("at 9\n".as_bytes(), &spelled).unwrap();
+        assert_eq!(msg.to_string(), "atnine");
+        assert!(matches!(
+            Message::read_from(b"\xff".as_slice(), &settings),
+            Err(ReadError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn case_policy() {
+        let ciphertxt = Ciphertext::from_str("hPhT").unwrap();
+        assert_eq!(ciphertxt.format_with_case(CasePolicy::Stinson), "HPHT");
+        assert_eq!(
+            ciphertxt.format_with_case(CasePolicy::PreserveInput),
+            "hpht"
+        );
+        assert_eq!(ciphertxt.format_with_case(CasePolicy::Lowercase), "hpht");
+
+        let msg = Message::from_str("hpht").unwrap();
+        assert_eq!(msg.format_with_case(CasePolicy::Stinson), "hpht");
+    }
+
+    // An alphabet of the four DNA bases
+    #[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct Dna;
+
+    impl Alphabet<4> for Dna {
+        fn index_of(symbol: char) -> Option<usize> {
+            "acgt".find(symbol)
+        }
+
+        fn char_at(index: usize) -> Option<char> {
+            "acgt".chars().nth(index)
+        }
+    }
+
+    #[test]
+    fn other_alphabet() {
+        type Base = super::RingElement<4>;
+
+        assert_eq!(Base::MODULUS, 4);
+        assert_eq!(Base::from_char::<Dna>('g'), Some(Base::new(2)));
+        assert_eq!(Base::new(3).to_char::<Dna>(), Some('t'));
+        assert_eq!(Base::new(3) + Base::new(2), Base::new(1));
+        assert_eq!(Base::from_i32(-1), Base::new(3));
+
+        let msg = super::Message::<Dna, 4>::new("gat taca").unwrap();
+        assert_eq!(msg.to_string(), "gattaca");
+        assert!(super::Message::<Dna, 4>::new("gattacx").is_err());
+
+        // Ciphertexts are only capitalized for alphabets that ask for it
+        let ciphertxt = super::Ciphertext::<Dna, 4>::from_str("gattaca").unwrap();
+        assert_eq!(ciphertxt.to_string(), "gattaca");
+        assert!(super::Ciphertext::<Dna, 4>::from_str("GATTACA").is_err());
+    }
+
+    #[test]
+    fn other_moduli() {
+        // Z/27Z, e.g., for the Trifid Cipher
+        type Trit = super::RingElement<27>;
+        assert_eq!(Trit::MODULUS, 27);
+        assert_eq!(Trit::new(20) + Trit::new(10), Trit::new(3));
+        assert_eq!(Trit::new(2) - Trit::new(10), Trit::new(19));
+        assert_eq!(Trit::from_i32(-1), Trit::new(26));
+
+        // Z/36Z, e.g., for a 6x6 Polybius square
+        type Cell = super::RingElement<36>;
+        assert_eq!(Cell::MODULUS, 36);
+        assert_eq!(Cell::new(30) + Cell::new(10), Cell::new(4));
+        assert_eq!(Cell::new(0) - Cell::new(1), Cell::new(35));
+        assert_eq!(Cell::from_i32(100), Cell::new(28));
+    }
+
+    #[test]
+    fn alphanumeric() {
+        use crate::alphabet::Alphanumeric;
+
+        let msg = super::Message::<Alphanumeric, 36>::new("agent 007").unwrap();
+        assert_eq!(msg.0.len(), 8);
+        assert_eq!(msg.0[7], super::RingElement::new(33));
+        assert_eq!(msg.to_string(), "agent007");
+
+        // Digits are written as they are in ciphertexts
+        let ciphertxt = super::Ciphertext::<Alphanumeric, 36>::from_str("x7k2").unwrap();
+        assert_eq!(ciphertxt.to_string(), "X7K2");
+        assert_eq!(
+            super::Ciphertext::<Alphanumeric, 36>::from_str("X7K2"),
+            Ok(ciphertxt)
+        );
+    }
+
+    #[test]
+    fn msg_policy() {
+        use crate::{alphabet::Alphanumeric, policy::DigitPolicy};
+
+        // Digits are kept by default, and encoded if the alphabet has them
+        let policy = MessagePolicy::default();
+        assert!(Message::with_policy("agent007", &policy).is_err());
+        assert_eq!(
+            super::Message::<Alphanumeric, 36>::with_policy("agent007", &policy)
+                .unwrap()
+                .to_string(),
+            "agent007"
+        );
+
+        let policy = policy.with_digits(DigitPolicy::Reject);
+        assert_eq!(
+            super::Message::<Alphanumeric, 36>::with_policy("agent007", &policy),
+            Err(EncodingError::InvalidMessage(invalid("agent007", "07")))
+        );
+
+        let policy = policy.with_digits(DigitPolicy::Strip);
+        assert_eq!(
+            Message::with_policy("agent007", &policy),
+            Message::new("agent")
+        );
+    }
+
+    #[test]
+    fn validation() {
+        let report = Message::validate("we~ will Meet at midnight;");
+        assert!(!report.is_valid());
+        assert_eq!(report.symbols(), 19);
+        assert_eq!(
+            report.invalid(),
+            [
+                InvalidCharacter {
+                    character: '~',
+                    byte_index: 2,
+                    char_index: 2
+                },
+                InvalidCharacter {
+                    character: 'M',
+                    byte_index: 9,
+                    char_index: 9
+                },
+                InvalidCharacter {
+                    character: ';',
+                    byte_index: 25,
+                    char_index: 25
+                }
+            ]
+        );
+
+        // Byte and character indices differ after multi-byte characters
+        let report = Message::validate("café au lait!");
+        assert_eq!(report.invalid()[0].char_index, 3);
+        assert_eq!(report.invalid()[1].byte_index, 13);
+        assert_eq!(report.invalid()[1].char_index, 12);
+
+        // Ciphertexts ignore case
+        assert!(Ciphertext::validate("HPHT wwxp").is_valid());
+        assert_eq!(Ciphertext::validate("HPHT-").invalid().len(), 1);
+
+        // Validation agrees with parsing
+        assert!(!Message::validate("").is_valid());
+        assert!(!Message::validate("   ").is_valid());
+        assert!(Message::validate("we will meet").is_valid());
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(
+            Ciphertext::with_parsing("WKLV LV", ParsePolicy::Strict),
+            Ciphertext::from_str("WKLVLV")
+        );
+        assert!(matches!(
+            Ciphertext::with_parsing("WKLV lv", ParsePolicy::Strict),
+            Err(EncodingError::InvalidCiphertext(invalid)) if invalid.characters().len() == 2
+        ));
+        assert_eq!(
+            Ciphertext::with_parsing("WKLV lv", ParsePolicy::Lenient),
+            Ciphertext::from_str("WKLV lv")
+        );
+        assert!(Ciphertext::with_parsing("WKLV!", ParsePolicy::Lenient).is_err());
+        assert_eq!(
+            Ciphertext::with_parsing("WKLV! lv?", ParsePolicy::Permissive),
+            Ciphertext::from_str("WKLVLV")
+        );
+        assert!(Ciphertext::with_parsing("!?", ParsePolicy::Permissive).is_err());
+    }
+
+    #[test]
+    fn bytes() {
+        use crate::alphabet::Byte;
+        type Bytes = super::Message<Byte, 256>;
+
+        let data = [0x00, 0x7f, 0x80, 0xff, b'a'];
+        let msg = Bytes::from_bytes(&data);
+        assert_eq!(msg.0.len(), 5);
+        assert_eq!(msg.to_bytes(), data);
+        assert_eq!(msg.to_string(), "\u{0}\u{7f}\u{80}ÿa");
+        assert_eq!(Bytes::from_str(&msg.to_string()), Ok(msg.clone()));
+
+        // A shift of every byte wraps around
+        let key = super::RingElement::<256>::new(1);
+        let ciphertxt: super::Ciphertext<Byte, 256> = msg.0.iter().map(|&x| x + key).collect();
+        assert_eq!(ciphertxt.to_bytes(), [0x01, 0x80, 0x81, 0x00, b'b']);
+        assert_eq!(
+            super::Ciphertext::<Byte, 256>::from_bytes(&ciphertxt.to_bytes()),
+            ciphertxt
+        );
+    }
+
+    #[test]
+    fn classical_latin() {
+        use crate::{alphabet::ClassicalLatin, policy::Folding};
+        type Roman = super::Message<ClassicalLatin, 23>;
+
+        assert!(Roman::new("julius").is_err());
+
+        let policy = MessagePolicy::new().with_folding(Folding::ClassicalLatin);
+        let msg = Roman::with_policy("julius caesar", &policy).unwrap();
+        assert_eq!(msg.to_string(), "ivlivscaesar");
+        assert_eq!(msg.0[1], super::RingElement::new(19));
+
+        let ciphertxt = super::Ciphertext::<ClassicalLatin, 23>::from_str("lyolyv").unwrap();
+        assert_eq!(ciphertxt.to_string(), "LYOLYV");
+    }
+
+    #[test]
+    fn grouped() {
+        let ciphertxt = Ciphertext::from_str("HPHTWWXPPELEXTOYTRSE").unwrap();
+        assert_eq!(
+            Grouped::new(&ciphertxt, 5).to_string(),
+            "HPHTW WXPPE LEXTO YTRSE"
+        );
+        assert_eq!(
+            Grouped::new(&ciphertxt, 6)
+                .with_separator(" / ")
+                .to_string(),
+            "HPHTWW / XPPELE / XTOYTR / SE"
+        );
+        assert_eq!(
+            Grouped::new(&ciphertxt, 20).to_string(),
+            "HPHTWWXPPELEXTOYTRSE"
+        );
+        assert_eq!(Grouped::new("", 5).to_string(), "");
+        assert_eq!(Grouped::new("αβγδ", 2).to_string(), "αβ γδ");
+    }
+
+    #[test]
+    #[should_panic(expected = "The group size must be positive")]
+    fn grouped_zero() {
+        let _ = Grouped::new("abc", 0);
+    }
+
+    #[test]
+    fn text_display() {
+        let ciphertxt = Ciphertext::from_str("hphtwwxppelextoytrse").unwrap();
+        assert_eq!(ciphertxt.display().to_string(), "HPHTWWXPPELEXTOYTRSE");
+        assert_eq!(
+            ciphertxt.display().grouped(5).to_string(),
+            Grouped::new(&ciphertxt, 5).to_string()
+        );
+        assert_eq!(
+            ciphertxt
+                .display()
+                .lowercase()
+                .grouped(6)
+                .with_separator(" / ")
+                .to_string(),
+            "hphtww / xppele / xtoytr / se"
+        );
+        assert_eq!(
+            ciphertxt
+                .display()
+                .with_case(CasePolicy::PreserveInput)
+                .to_string(),
+            "hphtwwxppelextoytrse"
+        );
+
+        // The separator is only written between groups
+        let msg = Message::from_str("hpht").unwrap();
+        assert_eq!(msg.display().grouped(2).to_string(), "hp ht");
+        assert_eq!(msg.display().with_separator("-").to_string(), "hpht");
+        assert_eq!(Message::default().display().grouped(5).to_string(), "");
+    }
+
+    #[test]
+    fn printable_ascii() {
+        use crate::alphabet::PrintableAscii;
+        type Ascii = super::RingElement<95>;
+
+        // The arithmetic of Z/95Z overflowed the original `i8` representation
+        assert_eq!(Ascii::new(90) + Ascii::new(90), Ascii::new(85));
+        assert_eq!(Ascii::new(3) - Ascii::new(94), Ascii::new(4));
+
+        // Spaces, punctuation and case are kept
+        let msg = super::Message::<PrintableAscii, 95>::new("We will meet, at 12!").unwrap();
+        assert_eq!(msg.0.len(), 20);
+        assert_eq!(msg.to_string(), "We will meet, at 12!");
+
+        // Ciphertexts are not capitalized, since that would change them
+        let ciphertxt = super::Ciphertext::<PrintableAscii, 95>::from_str("Hp ht!").unwrap();
+        assert_eq!(ciphertxt.to_string(), "Hp ht!");
+        assert_eq!(ciphertxt.format_with_case(CasePolicy::Lowercase), "hp ht!");
+        assert!(super::Ciphertext::<PrintableAscii, 95>::from_str("tab\t").is_err());
+    }
+
+    #[test]
+    fn large_moduli() {
+        // Z/256Z, e.g., for bytes
+        type Byte = super::RingElement<256>;
+        assert_eq!(Byte::MODULUS, 256);
+        assert_eq!(Byte::new(200) + Byte::new(100), Byte::new(44));
+        assert_eq!(Byte::new(100) - Byte::new(200), Byte::new(156));
+        assert_eq!(Byte::from_i32(-1), Byte::new(255));
+
+        // The largest modulus does not overflow
+        type Big = super::RingElement<{ u16::MAX }>;
+        assert_eq!(Big::new(65534) + Big::new(65534), Big::new(65533));
+        assert_eq!(Big::new(0) - Big::new(65534), Big::new(1));
+        assert_eq!(Big::from_i32(-65536), Big::new(65534));
+    }
+
     #[test]
     fn ciphertxt_encoding_error() {
         assert_eq!(
             Ciphertext::from_str("a;k"),
-            Err(EncodingError::InvalidCiphertext(
-                ErrorRepr::RingElementEncodingError(";".to_string()).into()
-            ))
+            Err(EncodingError::InvalidCiphertext(invalid("a;k", ";")))
         )
     }
+
+    #[test]
+    fn security_bits() {
+        let info = shift::ShiftCipher::info();
+        assert_eq!(info.key_count(), Some(26));
+        assert!((info.security_bits().unwrap() - 26f64.log2()).abs() < 1e-12);
+        assert_eq!(alberti::AlbertiCipher::info().security_bits(), None);
+    }
 }
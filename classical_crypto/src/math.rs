@@ -0,0 +1,181 @@
+//! Elementary number theory over the integers, following the algorithms of
+//! Stinson, Chapter 6: the greatest common divisor, the Extended Euclidean
+//! Algorithm, multiplicative inverses modulo _m_, and modular exponentiation.
+//!
+//! These are the building blocks of the Affine and Hill Ciphers, whose keys
+//! must be invertible modulo 26, and of RSA and the Diffie-Hellman key
+//! exchange.
+
+/// Compute the greatest common divisor of `a` and `b` with the Euclidean
+/// Algorithm (Stinson, Algorithm 6.1). The result is nonnegative, and
+/// `gcd(0, 0)` is 0.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::math::gcd;
+/// assert_eq!(gcd(1547, 560), 7);
+/// assert_eq!(gcd(-12, 18), 6);
+/// ```
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut r0, mut r1) = (a, b);
+    while r1 != 0 {
+        (r0, r1) = (r1, r0 % r1);
+    }
+    r0.abs()
+}
+
+/// Compute the greatest common divisor `g` of `a` and `b` together with
+/// integers `s` and `t` such that `s * a + t * b == g`, with the Extended
+/// Euclidean Algorithm (Stinson, Algorithm 6.2). Returns `(g, s, t)`, where
+/// `g` is nonnegative.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::math::extended_gcd;
+/// let (g, s, t) = extended_gcd(75, 28);
+///
+/// assert_eq!(g, 1);
+/// assert_eq!(s * 75 + t * 28, 1);
+/// ```
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut s0, mut s1) = (1, 0);
+    let (mut t0, mut t1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+
+    if r0 < 0 {
+        (-r0, -s0, -t0)
+    } else {
+        (r0, s0, t0)
+    }
+}
+
+/// Compute the multiplicative inverse of `a` modulo `m`, between 0 and
+/// `m - 1`, or return `None` if `a` and `m` are not coprime (Stinson,
+/// Algorithm 6.3).
+///
+/// # Panics
+/// This function panics if `m` is not positive.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::math::mod_inverse;
+/// assert_eq!(mod_inverse(28, 75), Some(67));
+/// assert_eq!(mod_inverse(-7, 26), Some(11));
+/// assert_eq!(mod_inverse(13, 26), None);
+/// ```
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    assert!(m > 0, "The modulus must be positive");
+
+    let (g, s, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| s.rem_euclid(m))
+}
+
+/// Compute `base` to the power `exp` modulo `m`, between 0 and `m - 1`, with
+/// the Square-and-Multiply Algorithm (Stinson, Algorithm 6.5).
+///
+/// # Panics
+/// This function panics if `m` is not positive.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::math::mod_pow;
+/// assert_eq!(mod_pow(9726, 3533, 11413), 5761);
+/// assert_eq!(mod_pow(-2, 3, 5), 2);
+/// ```
+pub fn mod_pow(base: i64, exp: u64, m: i64) -> i64 {
+    assert!(m > 0, "The modulus must be positive");
+
+    // Widen so that the products cannot overflow
+    let m = i128::from(m);
+    let base = i128::from(base).rem_euclid(m);
+    let mut z = 1 % m;
+    for i in (0..u64::BITS - exp.leading_zeros()).rev() {
+        z = z * z % m;
+        if exp >> i & 1 == 1 {
+            z = z * base % m;
+        }
+    }
+    z as i64
+}
+
+/// Test whether `n` is prime by trial division, which is fast enough for the
+/// small moduli of the toy cryptosystems, e.g., [`toy_rsa`](crate::toy_rsa).
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::math::is_prime;
+/// assert!(is_prime(467));
+/// assert!(!is_prime(11413));
+/// ```
+pub fn is_prime(n: i64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_basics() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(-5, 0), 5);
+        assert_eq!(gcd(26, 7), 1);
+        assert_eq!(gcd(26, -13), 13);
+    }
+
+    #[test]
+    fn extended_gcd_bezout() {
+        for (a, b) in [(75, 28), (0, 4), (-12, 18), (12, -18), (1547, 560), (0, 0)] {
+            let (g, s, t) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(s * a + t * b, g);
+        }
+    }
+
+    #[test]
+    fn inverses() {
+        for a in 0..26 {
+            match mod_inverse(a, 26) {
+                Some(inv) => {
+                    assert!((0..26).contains(&inv));
+                    assert_eq!(a * inv % 26, 1);
+                }
+                None => assert_ne!(gcd(a, 26), 1),
+            }
+        }
+        assert_eq!(mod_inverse(5, 1), Some(0));
+    }
+
+    #[test]
+    fn powers() {
+        assert_eq!(mod_pow(2, 0, 7), 1);
+        assert_eq!(mod_pow(2, 0, 1), 0);
+        assert_eq!(mod_pow(3, 5, 7), 5);
+        assert_eq!(mod_pow(0, 5, 7), 0);
+
+        // Fermat's Little Theorem for a large prime
+        let p = 2_147_483_647;
+        assert_eq!(mod_pow(16_807, p as u64 - 1, p), 1);
+    }
+
+    #[test]
+    fn primes() {
+        let primes: Vec<i64> = (-5..30).filter(|&n| is_prime(n)).collect();
+        assert_eq!(primes, [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert!(is_prime(2_147_483_647));
+        assert!(!is_prime(49));
+    }
+
+    #[test]
+    #[should_panic(expected = "The modulus must be positive")]
+    fn zero_modulus() {
+        let _ = mod_inverse(3, 0);
+    }
+}
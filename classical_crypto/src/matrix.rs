@@ -0,0 +1,383 @@
+//! Matrices over the ring &#x2124;/_m_&#x2124;, as used by the Hill Cipher
+//! and its known-plaintext attack, see Stinson, Section 2.1.5.
+//!
+//! Vectors are row vectors, following Stinson, so a matrix acts on a vector
+//! by multiplication on the right, see [`Matrix::apply`].
+use crate::{errors::MatrixError, Ring, RingElement};
+use std::{fmt, ops::Mul};
+
+/// A dense matrix over &#x2124;/_m_&#x2124; for the modulus _m_ = `M`, which
+/// is 26 by default.
+///
+/// Determinants are computed by cofactor expansion, which is meant for the
+/// small matrices, up to 4x4 or so, of the classical ciphers.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::matrix::Matrix;
+/// // Stinson, Example 2.5
+/// let key: Matrix = Matrix::from_rows(&[vec![11, 8], vec![3, 7]]).unwrap();
+/// let inverse = key.inverse().unwrap();
+///
+/// assert_eq!(inverse, Matrix::from_rows(&[vec![7, 18], vec![23, 11]]).unwrap());
+/// assert_eq!(&key * &inverse, Matrix::identity(2));
+/// assert_eq!(key.determinant().unwrap().into_inner(), 1);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Matrix<const M: u16 = 26> {
+    rows: usize,
+    cols: usize,
+    // The entries, row by row
+    entries: Vec<RingElement<M>>,
+}
+
+impl<const M: u16> Matrix<M> {
+    /// Create a matrix from its rows. Each entry is reduced mod `M`.
+    ///
+    /// # Errors
+    /// This function returns an error if there are no rows, if the rows are
+    /// empty, or if the rows have different lengths.
+    pub fn from_rows(rows: &[Vec<i64>]) -> Result<Self, MatrixError> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if cols == 0 {
+            return Err(MatrixError::Empty);
+        }
+        if let Some((row, entries)) = rows.iter().enumerate().find(|(_, r)| r.len() != cols) {
+            return Err(MatrixError::Ragged {
+                row,
+                len: entries.len(),
+                expected: cols,
+            });
+        }
+
+        Ok(Matrix {
+            rows: rows.len(),
+            cols,
+            entries: rows.iter().flatten().map(|&x| reduce(x)).collect(),
+        })
+    }
+
+    /// Create the `size` by `size` identity matrix.
+    ///
+    /// # Panics
+    /// This function panics if `size` is 0.
+    pub fn identity(size: usize) -> Self {
+        assert!(
+            size > 0,
+            "A matrix must have at least one row and one column"
+        );
+
+        let mut entries = vec![RingElement::ZERO; size * size];
+        for i in 0..size {
+            entries[i * size + i] = reduce(1);
+        }
+        Matrix {
+            rows: size,
+            cols: size,
+            entries,
+        }
+    }
+
+    /// Get the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Get the number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Get the entry in the given row and column, counting from 0, if it is
+    /// in the matrix.
+    pub fn get(&self, row: usize, col: usize) -> Option<RingElement<M>> {
+        (row < self.rows && col < self.cols).then(|| self.entries[row * self.cols + col])
+    }
+
+    /// Get the transpose of the matrix.
+    pub fn transpose(&self) -> Self {
+        Matrix {
+            rows: self.cols,
+            cols: self.rows,
+            entries: (0..self.cols)
+                .flat_map(|j| (0..self.rows).map(move |i| self.entries[i * self.cols + j]))
+                .collect(),
+        }
+    }
+
+    /// Multiply the row vector `x` by the matrix, i.e., compute `xK` for the
+    /// matrix `K`.
+    ///
+    /// # Panics
+    /// This method panics if the length of `x` is not the number of rows.
+    pub fn apply(&self, x: &[RingElement<M>]) -> Vec<RingElement<M>> {
+        assert_eq!(
+            x.len(),
+            self.rows,
+            "The vector must have one entry for each row of the matrix"
+        );
+
+        (0..self.cols)
+            .map(|j| {
+                x.iter()
+                    .enumerate()
+                    .map(|(i, &xi)| xi * self.entries[i * self.cols + j])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Compute the determinant of the matrix.
+    ///
+    /// # Errors
+    /// This method returns an error if the matrix is not square.
+    pub fn determinant(&self) -> Result<RingElement<M>, MatrixError> {
+        self.check_square()?;
+        Ok(self.det())
+    }
+
+    /// Compute the adjugate of the matrix, i.e., the transpose of its matrix
+    /// of cofactors, which satisfies `K * adj(K) = det(K) * I`.
+    ///
+    /// # Errors
+    /// This method returns an error if the matrix is not square.
+    pub fn adjugate(&self) -> Result<Self, MatrixError> {
+        self.check_square()?;
+
+        let n = self.rows;
+        if n == 1 {
+            return Ok(Self::identity(1));
+        }
+        let entries = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                // The entry in row i and column j is the cofactor of the entry in row j and
+                // column i
+                let minor = self.minor(j, i).det();
+                if (i + j) % 2 == 0 {
+                    minor
+                } else {
+                    -minor
+                }
+            })
+            .collect();
+        Ok(Matrix {
+            rows: n,
+            cols: n,
+            entries,
+        })
+    }
+
+    /// Compute the inverse of the matrix modulo `M`, as the product of the
+    /// inverse of the determinant and the adjugate (Stinson, Section 2.1.5).
+    ///
+    /// # Errors
+    /// This method returns an error if the matrix is not square, or if its
+    /// determinant is not a unit, i.e., not coprime to `M`.
+    pub fn inverse(&self) -> Result<Self, MatrixError> {
+        let det = self.determinant()?;
+        let det_inverse = det
+            .inverse()
+            .ok_or(MatrixError::NotInvertible(det.into_inner()))?;
+
+        let mut adjugate = self.adjugate()?;
+        for entry in &mut adjugate.entries {
+            *entry = det_inverse * *entry;
+        }
+        Ok(adjugate)
+    }
+
+    fn check_square(&self) -> Result<(), MatrixError> {
+        if self.rows == self.cols {
+            Ok(())
+        } else {
+            Err(MatrixError::NotSquare {
+                rows: self.rows,
+                cols: self.cols,
+            })
+        }
+    }
+
+    // The determinant of a square matrix, by cofactor expansion along the first row.
+    fn det(&self) -> RingElement<M> {
+        let n = self.rows;
+        if n == 1 {
+            return self.entries[0];
+        }
+        (0..n)
+            .map(|j| {
+                let term = self.entries[j] * self.minor(0, j).det();
+                if j % 2 == 0 {
+                    term
+                } else {
+                    -term
+                }
+            })
+            .sum()
+    }
+
+    // The matrix with the given row and column removed.
+    fn minor(&self, row: usize, col: usize) -> Self {
+        let entries = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| k / self.cols != row && k % self.cols != col)
+            .map(|(_, &x)| x)
+            .collect();
+        Matrix {
+            rows: self.rows - 1,
+            cols: self.cols - 1,
+            entries,
+        }
+    }
+}
+
+// Reduce an integer mod `M`.
+fn reduce<const M: u16>(x: i64) -> RingElement<M> {
+    // The remainder is less than the modulus, so it fits in a `u16`
+    RingElement::new(x.rem_euclid(i64::from(M)) as u16)
+}
+
+/// Multiply two matrices.
+///
+/// # Panics
+/// Multiplication panics if the number of columns of the left matrix is not
+/// the number of rows of the right matrix.
+impl<const M: u16> Mul for &Matrix<M> {
+    type Output = Matrix<M>;
+
+    fn mul(self, other: Self) -> Matrix<M> {
+        assert_eq!(
+            self.cols, other.rows,
+            "Only matrices of compatible sizes can be multiplied"
+        );
+
+        let entries = (0..self.rows)
+            .flat_map(|i| other.apply(&self.entries[i * self.cols..(i + 1) * self.cols]))
+            .collect();
+        Matrix {
+            rows: self.rows,
+            cols: other.cols,
+            entries,
+        }
+    }
+}
+
+/// Display the matrix one row per line, with the entries of each row
+/// separated by spaces.
+impl<const M: u16> fmt::Display for Matrix<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.entries.chunks(self.cols).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let row: Vec<String> = row.iter().map(ToString::to_string).collect();
+            write!(f, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[Vec<i64>]) -> Matrix {
+        Matrix::from_rows(rows).unwrap()
+    }
+
+    #[test]
+    fn hill_example() {
+        // Stinson, Example 2.5
+        let key = matrix(&[vec![11, 8], vec![3, 7]]);
+        let msg: Vec<RingElement> = [9, 20].map(RingElement::new).to_vec();
+        let ciphertxt = key.apply(&msg);
+        assert_eq!(ciphertxt, [3, 4].map(RingElement::new));
+        assert_eq!(key.inverse().unwrap().apply(&ciphertxt), msg);
+    }
+
+    #[test]
+    fn determinant_and_adjugate() {
+        let m = matrix(&[vec![1, 2, 3], vec![0, 1, 4], vec![5, 6, 0]]);
+        assert_eq!(m.determinant().unwrap(), RingElement::new(1));
+        assert_eq!(
+            m.adjugate().unwrap(),
+            matrix(&[vec![-24, 18, 5], vec![20, -15, -4], vec![-5, 4, 1]])
+        );
+
+        let m = matrix(&[
+            vec![3, 1, 4, 1],
+            vec![5, 9, 2, 6],
+            vec![5, 3, 5, 8],
+            vec![9, 7, 9, 3],
+        ]);
+        // The determinant is 98 over the integers
+        assert_eq!(m.determinant().unwrap(), RingElement::new(98 % 26));
+        let det_identity = &m * &m.adjugate().unwrap();
+        for i in 0..4 {
+            for j in 0..4 {
+                let expected = if i == j { 98 % 26 } else { 0 };
+                assert_eq!(det_identity.get(i, j), Some(RingElement::new(expected)));
+            }
+        }
+
+        assert_eq!(matrix(&[vec![7]]).inverse().unwrap(), matrix(&[vec![15]]));
+    }
+
+    #[test]
+    fn inverse_mod_m() {
+        let m = matrix(&[
+            vec![1, 2, 0, 0],
+            vec![0, 1, 3, 0],
+            vec![0, 0, 1, 5],
+            vec![7, 0, 0, 1],
+        ]);
+        let inverse = m.inverse().unwrap();
+        assert_eq!(&m * &inverse, Matrix::identity(4));
+        assert_eq!(&inverse * &m, Matrix::identity(4));
+
+        // The determinant 2 is not a unit mod 26
+        assert_eq!(
+            matrix(&[vec![2, 0], vec![0, 1]]).inverse(),
+            Err(MatrixError::NotInvertible(2))
+        );
+        // Over Z/29Z, every nonzero determinant is a unit
+        assert!(Matrix::<29>::from_rows(&[vec![2, 0], vec![0, 1]])
+            .unwrap()
+            .inverse()
+            .is_ok());
+    }
+
+    #[test]
+    fn shapes() {
+        assert_eq!(Matrix::<26>::from_rows(&[]), Err(MatrixError::Empty));
+        assert_eq!(Matrix::<26>::from_rows(&[vec![]]), Err(MatrixError::Empty));
+        assert_eq!(
+            Matrix::<26>::from_rows(&[vec![1, 2], vec![3]]),
+            Err(MatrixError::Ragged {
+                row: 1,
+                len: 1,
+                expected: 2
+            })
+        );
+
+        let m = matrix(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!(
+            m.determinant(),
+            Err(MatrixError::NotSquare { rows: 2, cols: 3 })
+        );
+        assert_eq!(m.transpose().rows(), 3);
+        assert_eq!(m.get(1, 2), Some(RingElement::new(6)));
+        assert_eq!(m.get(2, 0), None);
+        assert_eq!(&m * &m.transpose(), matrix(&[vec![14, 32], vec![32, 77]]));
+        assert_eq!(m.to_string(), "1 2 3\n4 5 6");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only matrices of compatible sizes can be multiplied")]
+    fn incompatible_sizes() {
+        let m = matrix(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        let _ = &m * &m;
+    }
+}
@@ -0,0 +1,254 @@
+//! Morse code, i.e., the encoding of letters, digits and punctuation as
+//! sequences of dots (`.`) and dashes (`-`).
+//!
+//! Morse code is not a cipher, but some ciphers, such as the Fractionated
+//! Morse Cipher, encrypt the Morse code of a message rather than its letters.
+use crate::errors::MorseError;
+
+// The Morse codes of the letters, digits and punctuation marks, following the International
+// Telecommunication Union (ITU) standard.
+const CODES: [(char, &str); 54] = [
+    ('a', ".-"),
+    ('b', "-..."),
+    ('c', "-.-."),
+    ('d', "-.."),
+    ('e', "."),
+    ('f', "..-."),
+    ('g', "--."),
+    ('h', "...."),
+    ('i', ".."),
+    ('j', ".---"),
+    ('k', "-.-"),
+    ('l', ".-.."),
+    ('m', "--"),
+    ('n', "-."),
+    ('o', "---"),
+    ('p', ".--."),
+    ('q', "--.-"),
+    ('r', ".-."),
+    ('s', "..."),
+    ('t', "-"),
+    ('u', "..-"),
+    ('v', "...-"),
+    ('w', ".--"),
+    ('x', "-..-"),
+    ('y', "-.--"),
+    ('z', "--.."),
+    ('0', "-----"),
+    ('1', ".----"),
+    ('2', "..---"),
+    ('3', "...--"),
+    ('4', "....-"),
+    ('5', "....."),
+    ('6', "-...."),
+    ('7', "--..."),
+    ('8', "---.."),
+    ('9', "----."),
+    ('.', ".-.-.-"),
+    (',', "--..--"),
+    ('?', "..--.."),
+    ('\'', ".----."),
+    ('!', "-.-.--"),
+    ('/', "-..-."),
+    ('(', "-.--."),
+    (')', "-.--.-"),
+    ('&', ".-..."),
+    (':', "---..."),
+    (';', "-.-.-."),
+    ('=', "-...-"),
+    ('+', ".-.-."),
+    ('-', "-....-"),
+    ('_', "..--.-"),
+    ('"', ".-..-."),
+    ('$', "...-..-"),
+    ('@', ".--.-."),
+];
+
+/// Get the Morse code of a character, ignoring case.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::morse;
+/// assert_eq!(morse::code('S'), Some("..."));
+/// assert_eq!(morse::code('#'), None);
+/// ```
+pub fn code(symbol: char) -> Option<&'static str> {
+    let symbol = symbol.to_ascii_lowercase();
+    CODES
+        .iter()
+        .find(|(c, _)| *c == symbol)
+        .map(|(_, code)| *code)
+}
+
+/// Get the character with the given Morse code, in lowercase.
+pub fn symbol(code: &str) -> Option<char> {
+    CODES.iter().find(|(_, m)| *m == code).map(|(c, _)| *c)
+}
+
+/// An encoder of text as Morse code, with configurable separators between
+/// letters and between words.
+///
+/// By default, letters are separated by a space and words by `" / "`. The
+/// [`fractionated`](Morse::fractionated) format instead separates letters by
+/// `x` and words by `xx`, so that the encoding only uses the three symbols
+/// `.`, `-` and `x`, as in the Fractionated Morse Cipher.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::morse::Morse;
+/// let morse = Morse::default();
+/// assert_eq!(morse.encode("SOS help").unwrap(), "... --- ... / .... . .-.. .--.");
+/// assert_eq!(morse.decode("... --- ...").unwrap(), "sos");
+///
+/// let fractionated = Morse::fractionated();
+/// assert_eq!(fractionated.encode("hi there").unwrap(), "....x..xx-x....x.x.-.x.");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Morse {
+    letter_separator: String,
+    word_separator: String,
+}
+
+impl Default for Morse {
+    fn default() -> Self {
+        Morse::new(" ", " / ")
+    }
+}
+
+impl Morse {
+    /// Create an encoder with the given separators between letters and
+    /// between words.
+    ///
+    /// # Panics
+    /// This function panics if a separator is empty or contains a dot or a
+    /// dash, or if the separators are equal, since the encoding could then
+    /// not be decoded.
+    pub fn new(letter_separator: &str, word_separator: &str) -> Self {
+        for separator in [letter_separator, word_separator] {
+            assert!(
+                !separator.is_empty() && !separator.contains(['.', '-']),
+                "Separators must be nonempty and must not contain dots or dashes"
+            );
+        }
+        assert_ne!(
+            letter_separator, word_separator,
+            "The separators must be different"
+        );
+
+        Morse {
+            letter_separator: letter_separator.to_string(),
+            word_separator: word_separator.to_string(),
+        }
+    }
+
+    /// Create an encoder that separates letters by `x` and words by `xx`, as
+    /// in the Fractionated Morse Cipher.
+    pub fn fractionated() -> Self {
+        Morse::new("x", "xx")
+    }
+
+    /// Encode a text as Morse code. Letters are read case-insensitively, and
+    /// words are separated by whitespace.
+    ///
+    /// # Errors
+    /// This method returns an error for the first character without a Morse
+    /// code.
+    pub fn encode(&self, text: &str) -> Result<String, MorseError> {
+        let words = text
+            .split_whitespace()
+            .map(|word| {
+                word.chars()
+                    .map(|c| code(c).ok_or(MorseError::UnknownCharacter(c)))
+                    .collect::<Result<Vec<&str>, _>>()
+                    .map(|codes| codes.join(&self.letter_separator))
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+
+        Ok(words.join(&self.word_separator))
+    }
+
+    /// Decode Morse code, written with this encoder's separators, as a
+    /// lowercase text with words separated by spaces.
+    ///
+    /// # Errors
+    /// This method returns an error for the first code that is not the Morse
+    /// code of any character.
+    pub fn decode(&self, morse: &str) -> Result<String, MorseError> {
+        let words = self
+            .split(morse.trim(), &self.word_separator)
+            .into_iter()
+            .map(|word| {
+                self.split(word, &self.letter_separator)
+                    .into_iter()
+                    .map(|code| {
+                        symbol(code).ok_or_else(|| MorseError::UnknownCode(code.to_string()))
+                    })
+                    .collect::<Result<String, _>>()
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+
+        Ok(words.join(" "))
+    }
+
+    // Split on a separator, skipping empty parts, e.g., from repeated separators.
+    fn split<'a>(&self, text: &'a str, separator: &str) -> Vec<&'a str> {
+        text.split(separator)
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table() {
+        for (c, code) in CODES {
+            assert_eq!(symbol(code), Some(c));
+            assert!(code.chars().all(|s| s == '.' || s == '-'));
+        }
+        // Every code is unique
+        for (i, (_, a)) in CODES.iter().enumerate() {
+            assert!(CODES[i + 1..].iter().all(|(_, b)| a != b));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "we will meet at midnight, 12 o'clock!";
+        for morse in [
+            Morse::default(),
+            Morse::fractionated(),
+            Morse::new("|", "||"),
+        ] {
+            assert_eq!(morse.decode(&morse.encode(text).unwrap()).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn fractionated_uses_three_symbols() {
+        let encoded = Morse::fractionated().encode("attack at dawn").unwrap();
+        assert!(encoded.chars().all(|c| ".-x".contains(c)));
+        assert_eq!(encoded, ".-x-x-x.-x-.-.x-.-xx.-x-xx-..x.-x.--x-.");
+    }
+
+    #[test]
+    fn errors() {
+        let morse = Morse::default();
+        assert_eq!(morse.encode("a#b"), Err(MorseError::UnknownCharacter('#')));
+        assert_eq!(
+            morse.decode(".- ......."),
+            Err(MorseError::UnknownCode(".......".to_string()))
+        );
+        assert_eq!(morse.encode("").unwrap(), "");
+        assert_eq!(morse.decode("").unwrap(), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "The separators must be different")]
+    fn equal_separators() {
+        let _ = Morse::new(" ", " ");
+    }
+}
@@ -0,0 +1,506 @@
+//! Permutations, i.e., bijections of the set {1, ..., n} to itself.
+//!
+//! Permutations are the keys of the Substitution Cipher, which permutes the
+//! letters of the alphabet, and of the Permutation (transposition) Cipher,
+//! which permutes the positions of the letters in each block, see Stinson,
+//! Sections 2.1.2 and 2.1.6.
+use crate::{
+    alphabet::{Alphabet, Latin},
+    errors::PermutationError,
+    ComposableKey, WeakKey,
+};
+use rand::{seq::SliceRandom, CryptoRng, RngCore};
+use std::{fmt, str::FromStr};
+
+/// A permutation of the points 1 to `size`.
+///
+/// Points are numbered from 1 when parsed or displayed, following Stinson,
+/// but from 0 by the methods that take or return points, so that they can
+/// index a slice or an alphabet.
+///
+/// A permutation is written either in _one-line notation_, listing the images
+/// of 1, 2, ... in turn, e.g., `3 1 4 2` sends 1 to 3, 2 to 1, and so on, or
+/// in _cycle notation_, where each cycle `(a b c)` sends `a` to `b`, `b` to
+/// `c` and `c` back to `a`, and the points missing from every cycle are
+/// fixed, e.g., `(1 3 4 2)`. The [`Display`](fmt::Display) implementation
+/// writes the one-line notation, or the cycle notation with the alternate
+/// flag `{:#}`.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::permutation::Permutation;
+/// # use std::str::FromStr;
+/// let pi = Permutation::from_str("3 1 4 2").unwrap();
+/// assert_eq!(pi.apply(0), Some(2));
+/// assert_eq!(format!("{pi:#}"), "(1 3 4 2)");
+///
+/// let sigma = Permutation::from_cycles(4, "(1 2)").unwrap();
+/// assert_eq!(pi.compose(&sigma).to_string(), "1 3 4 2");
+/// assert_eq!(pi.compose(&pi.inverse()), Permutation::identity(4));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Permutation(Vec<usize>);
+
+impl Permutation {
+    /// Create the identity permutation of the given size.
+    pub fn identity(size: usize) -> Self {
+        Permutation((0..size).collect())
+    }
+
+    /// Check whether the permutation is weak as the key of a Substitution
+    /// Cipher, i.e., whether it is the identity, see [`KeyTrait::is_weak`](crate::KeyTrait::is_weak).
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{permutation::Permutation, WeakKey};
+    /// assert_eq!(Permutation::identity(26).is_weak(), Some(WeakKey::Identity));
+    /// assert_eq!(Permutation::from_images(vec![1, 0]).unwrap().is_weak(), None);
+    /// ```
+    pub fn is_weak(&self) -> Option<WeakKey> {
+        self.0
+            .iter()
+            .enumerate()
+            .all(|(i, &image)| i == image)
+            .then_some(WeakKey::Identity)
+    }
+
+    /// Create a permutation from the images of the points 0, 1, ... in turn.
+    ///
+    /// # Errors
+    /// This function returns an error if an image is out of range or repeated.
+    pub fn from_images(images: Vec<usize>) -> Result<Self, PermutationError> {
+        let size = images.len();
+        let mut seen = vec![false; size];
+
+        for &image in &images {
+            match seen.get_mut(image) {
+                None => {
+                    return Err(PermutationError::OutOfRange {
+                        point: image + 1,
+                        size,
+                    })
+                }
+                Some(true) => return Err(PermutationError::Repeated(image + 1)),
+                Some(seen) => *seen = true,
+            }
+        }
+
+        Ok(Permutation(images))
+    }
+
+    /// Parse a permutation of the given size from its cycle notation, e.g.,
+    /// `(1 3)(2 4 5)`. The identity is written `()`, or as the empty string.
+    ///
+    /// # Errors
+    /// This function returns an error if the string is not in cycle notation,
+    /// or if a point is out of range or appears more than once.
+    pub fn from_cycles(size: usize, cycles: &str) -> Result<Self, PermutationError> {
+        let mut images: Vec<usize> = (0..size).collect();
+        let mut seen = vec![false; size];
+
+        for cycle in parse_cycles(cycles)? {
+            for (i, &point) in cycle.iter().enumerate() {
+                if point == 0 || point > size {
+                    return Err(PermutationError::OutOfRange { point, size });
+                }
+                if std::mem::replace(&mut seen[point - 1], true) {
+                    return Err(PermutationError::Repeated(point));
+                }
+                images[point - 1] = cycle[(i + 1) % cycle.len()] - 1;
+            }
+        }
+
+        Ok(Permutation(images))
+    }
+
+    /// Create the permutation of the alphabet `A` that sends the `i`th letter
+    /// to the `i`th letter of `letters`, e.g., the key of a Substitution
+    /// Cipher written as the ciphertext alphabet.
+    ///
+    /// # Errors
+    /// This function returns an error if `letters` contains a character that
+    /// is not in the alphabet, or is not a permutation of the alphabet.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{alphabet::Latin, permutation::Permutation};
+    /// let key = Permutation::from_letters::<Latin, 26>("bcdefghijklmnopqrstuvwxyza").unwrap();
+    /// assert_eq!(key.apply(25), Some(0));
+    /// assert_eq!(key.inverse().to_letters::<Latin, 26>().unwrap(), "zabcdefghijklmnopqrstuvwxy");
+    /// ```
+    pub fn from_letters<A: Alphabet<M>, const M: u16>(
+        letters: &str,
+    ) -> Result<Self, PermutationError> {
+        let images = letters
+            .chars()
+            .map(|c| A::index_of(c).ok_or_else(|| PermutationError::Parse(letters.to_string())))
+            .collect::<Result<Vec<usize>, _>>()?;
+
+        if images.len() != usize::from(M) {
+            return Err(PermutationError::Parse(letters.to_string()));
+        }
+        Self::from_images(images)
+    }
+
+    /// Write the images of the letters of the alphabet `A` in turn, or return
+    /// `None` if the permutation is larger than the alphabet.
+    pub fn to_letters<A: Alphabet<M>, const M: u16>(&self) -> Option<String> {
+        self.0.iter().map(|&i| A::char_at(i)).collect()
+    }
+
+    /// Pick a permutation of the given size uniformly at random.
+    pub fn random<R: RngCore + CryptoRng>(size: usize, rng: &mut R) -> Self {
+        let mut images: Vec<usize> = (0..size).collect();
+        images.shuffle(rng);
+        Permutation(images)
+    }
+
+    /// Get the number of points.
+    pub fn size(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Get the images of the points 0, 1, ... in turn.
+    pub fn images(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// Get the image of a point, if it is in range.
+    pub fn apply(&self, point: usize) -> Option<usize> {
+        self.0.get(point).copied()
+    }
+
+    /// Compose two permutations, applying `other` first and then `self`,
+    /// i.e., the result sends `x` to `self(other(x))`.
+    ///
+    /// # Panics
+    /// This method panics if the permutations have different sizes.
+    pub fn compose(&self, other: &Permutation) -> Permutation {
+        assert_eq!(
+            self.size(),
+            other.size(),
+            "Only permutations of the same size can be composed"
+        );
+        Permutation(other.0.iter().map(|&x| self.0[x]).collect())
+    }
+
+    /// Get the inverse permutation.
+    pub fn inverse(&self) -> Permutation {
+        let mut images = vec![0; self.size()];
+        for (x, &y) in self.0.iter().enumerate() {
+            images[y] = x;
+        }
+        Permutation(images)
+    }
+
+    /// Get the Cayley distance to `other`, i.e., the least number of
+    /// transpositions (swaps of two points) that turn `other` into `self`.
+    /// For keys of the Substitution Cipher, this is the number of swaps of
+    /// two ciphertext letters between the keys.
+    ///
+    /// # Panics
+    /// This method panics if the permutations have different sizes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::permutation::Permutation;
+    /// # use std::str::FromStr;
+    /// let pi = Permutation::from_str("2 3 1 4").unwrap();
+    ///
+    /// assert_eq!(pi.distance(&pi), 0);
+    /// assert_eq!(pi.distance(&Permutation::identity(4)), 2);
+    /// assert_eq!(pi.distance(&Permutation::from_str("3 2 1 4").unwrap()), 1);
+    /// ```
+    pub fn distance(&self, other: &Permutation) -> usize {
+        let relative = self.compose(&other.inverse());
+        relative.cycles().iter().map(|cycle| cycle.len() - 1).sum()
+    }
+
+    /// Get the cycles of the permutation, omitting the fixed points. Each
+    /// cycle starts with its smallest point, and the cycles are ordered by
+    /// their first points.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.size()];
+        let mut cycles = Vec::new();
+
+        for start in 0..self.size() {
+            if seen[start] || self.0[start] == start {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut point = start;
+            while !seen[point] {
+                seen[point] = true;
+                cycle.push(point);
+                point = self.0[point];
+            }
+            cycles.push(cycle);
+        }
+
+        cycles
+    }
+}
+
+// Parse cycle notation into lists of points, numbered from 1.
+fn parse_cycles(s: &str) -> Result<Vec<Vec<usize>>, PermutationError> {
+    let error = || PermutationError::Parse(s.to_string());
+    let mut cycles = Vec::new();
+    let mut rest = s.trim();
+
+    while !rest.is_empty() {
+        let (cycle, tail) = rest
+            .strip_prefix('(')
+            .and_then(|r| r.split_once(')'))
+            .ok_or_else(error)?;
+        let cycle = cycle
+            .split_whitespace()
+            .map(|point| usize::from_str(point).map_err(|_| error()))
+            .collect::<Result<Vec<usize>, _>>()?;
+        if !cycle.is_empty() {
+            cycles.push(cycle);
+        }
+        rest = tail.trim_start();
+    }
+
+    Ok(cycles)
+}
+
+/// Parse a permutation from its one-line notation, e.g., `3 1 4 2`, from its
+/// cycle notation, e.g., `(1 3 4 2)`, or, for the key of a Substitution Cipher,
+/// from the 26 lowercase letters of the ciphertext alphabet, see
+/// [`Permutation::from_letters`]. The size of a permutation in cycle notation
+/// is the largest point of its cycles; use [`Permutation::from_cycles`] to give
+/// the size explicitly.
+///
+/// # Errors
+/// This implementation returns an error if the string is in neither
+/// notation, or if a point is out of range or appears more than once.
+impl FromStr for Permutation {
+    type Err = PermutationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim_start().starts_with('(') {
+            let size = parse_cycles(s)?.into_iter().flatten().max().unwrap_or(0);
+            return Self::from_cycles(size, s);
+        }
+        if s.chars().any(|c| c.is_ascii_alphabetic()) {
+            return Self::from_letters::<Latin, 26>(s.trim());
+        }
+
+        let images = s
+            .split_whitespace()
+            .map(|point| match usize::from_str(point) {
+                Ok(0) => Err(PermutationError::OutOfRange {
+                    point: 0,
+                    size: s.split_whitespace().count(),
+                }),
+                Ok(point) => Ok(point - 1),
+                Err(_) => Err(PermutationError::Parse(s.to_string())),
+            })
+            .collect::<Result<Vec<usize>, _>>()?;
+        Self::from_images(images)
+    }
+}
+
+impl TryFrom<&str> for Permutation {
+    type Error = PermutationError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<String> for Permutation {
+    type Error = PermutationError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// Permutations of a given size form the symmetric group, e.g., the keys of
+/// the Substitution Cipher compose as permutations of the alphabet.
+///
+/// # Panics
+/// [`compose`](ComposableKey::compose) panics if the permutations have
+/// different sizes.
+impl ComposableKey for Permutation {
+    fn compose(&self, other: &Self) -> Self {
+        Permutation::compose(self, other)
+    }
+
+    fn inverse(&self) -> Self {
+        Permutation::inverse(self)
+    }
+}
+
+/// Write the permutation in one-line notation, or in cycle notation with the
+/// alternate flag `{:#}`.
+impl fmt::Display for Permutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let cycles = self.cycles();
+            if cycles.is_empty() {
+                return write!(f, "()");
+            }
+            for cycle in cycles {
+                let points: Vec<String> = cycle.iter().map(|x| (x + 1).to_string()).collect();
+                write!(f, "({})", points.join(" "))?;
+            }
+            Ok(())
+        } else {
+            let points: Vec<String> = self.0.iter().map(|x| (x + 1).to_string()).collect();
+            write!(f, "{}", points.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn notation() {
+        // Stinson, Example 2.7
+        let pi = Permutation::from_str("3 5 1 6 4 2").unwrap();
+        assert_eq!(pi.to_string(), "3 5 1 6 4 2");
+        assert_eq!(format!("{pi:#}"), "(1 3)(2 5 4 6)");
+        assert_eq!(pi.inverse().to_string(), "3 6 1 5 2 4");
+
+        let sigma = Permutation::from_cycles(5, "(1 3)(2 5)").unwrap();
+        assert_eq!(sigma.to_string(), "3 5 1 4 2");
+        assert_eq!(format!("{sigma:#}"), "(1 3)(2 5)");
+        assert_eq!(Permutation::from_str("(1 3)(2 5)").unwrap(), sigma);
+
+        assert_eq!(format!("{:#}", Permutation::identity(3)), "()");
+        assert_eq!(
+            Permutation::from_cycles(3, "()").unwrap(),
+            Permutation::identity(3)
+        );
+    }
+
+    #[test]
+    fn compose() {
+        let pi = Permutation::from_str("2 3 1").unwrap();
+        let sigma = Permutation::from_str("2 1 3").unwrap();
+
+        // x -> pi(sigma(x))
+        assert_eq!(pi.compose(&sigma).to_string(), "3 2 1");
+        assert_eq!(sigma.compose(&pi).to_string(), "1 3 2");
+        assert_eq!(pi.compose(&pi).compose(&pi), Permutation::identity(3));
+        assert_eq!(pi.inverse(), pi.compose(&pi));
+    }
+
+    #[test]
+    fn weak() {
+        assert_eq!(Permutation::identity(5).is_weak(), Some(WeakKey::Identity));
+        assert_eq!(Permutation::identity(0).is_weak(), Some(WeakKey::Identity));
+        assert_eq!(Permutation::from_str("(1 2)").unwrap().is_weak(), None);
+    }
+
+    #[test]
+    fn composable() {
+        let mut rng = ChaCha12Rng::from_seed(*b"MY DISTRIBUTION IS NOT UNIFORM!!");
+        for _ in 0..20 {
+            let pi = Permutation::random(26, &mut rng);
+            let sigma = Permutation::random(26, &mut rng);
+
+            // Substituting twice is substituting once with the composed key
+            let composed = ComposableKey::compose(&pi, &sigma);
+            for x in 0..26 {
+                assert_eq!(sigma.apply(x).and_then(|y| pi.apply(y)), composed.apply(x));
+            }
+            assert_eq!(
+                ComposableKey::compose(&pi, &ComposableKey::inverse(&pi)),
+                Permutation::identity(26)
+            );
+        }
+    }
+
+    #[test]
+    fn distance() {
+        let mut rng = ChaCha12Rng::from_seed(*b"MY DISTRIBUTION IS NOT UNIFORM!!");
+        for _ in 0..20 {
+            let pi = Permutation::random(26, &mut rng);
+            let sigma = Permutation::random(26, &mut rng);
+            let tau = Permutation::random(26, &mut rng);
+
+            assert_eq!(pi.distance(&pi), 0);
+            assert_eq!(pi.distance(&sigma), sigma.distance(&pi));
+            assert!(pi.distance(&tau) <= pi.distance(&sigma) + sigma.distance(&tau));
+            assert!(pi.distance(&sigma) < 26);
+
+            // Swapping two ciphertext letters moves the key by 1
+            let mut images = pi.images().to_vec();
+            images.swap(3, 17);
+            assert_eq!(Permutation::from_images(images).unwrap().distance(&pi), 1);
+
+            // The relative key takes sigma to pi
+            let relative = ComposableKey::relative_to(&pi, &sigma);
+            assert_eq!(relative.compose(&sigma), pi);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Only permutations of the same size can be composed")]
+    fn compose_sizes() {
+        let _ = Permutation::identity(2).compose(&Permutation::identity(3));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Permutation::from_str("1 2 2"),
+            Err(PermutationError::Repeated(2))
+        );
+        assert_eq!(
+            Permutation::from_str("1 4 2"),
+            Err(PermutationError::OutOfRange { point: 4, size: 3 })
+        );
+        assert_eq!(
+            Permutation::from_str("0 1"),
+            Err(PermutationError::OutOfRange { point: 0, size: 2 })
+        );
+        assert!(matches!(
+            Permutation::from_str("1 b"),
+            Err(PermutationError::Parse(_))
+        ));
+        assert!(matches!(
+            Permutation::from_str("(1 2"),
+            Err(PermutationError::Parse(_))
+        ));
+        assert_eq!(
+            Permutation::from_cycles(3, "(1 2)(2 3)"),
+            Err(PermutationError::Repeated(2))
+        );
+        assert!(Permutation::from_letters::<Latin, 26>("abc").is_err());
+        assert_eq!(
+            Permutation::from_str("abc").unwrap_err().to_string(),
+            "\"abc\" is not a permutation; expected one-line notation, cycle notation or a \
+             permuted alphabet"
+        );
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = ChaCha12Rng::from_seed(*b"MY DISTRIBUTION IS NOT UNIFORM!!");
+
+        // Each of the 6 permutations of 3 points appears about equally often
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..6000 {
+            *counts.entry(Permutation::random(3, &mut rng)).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 6);
+        assert!(counts.values().all(|&count| (900..1100).contains(&count)));
+
+        let pi = Permutation::random(26, &mut rng);
+        let letters = pi.to_letters::<Latin, 26>().unwrap();
+        assert_eq!(
+            Permutation::from_letters::<Latin, 26>(&letters).unwrap(),
+            pi
+        );
+        assert_eq!(Permutation::from_str(&letters).unwrap(), pi);
+    }
+}
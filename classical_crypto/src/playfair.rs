@@ -0,0 +1,1144 @@
+//! This is an implementation of the Playfair Cipher.
+//!
+//! The Playfair Cipher encrypts pairs of letters (_digraphs_) rather than
+//! single letters. The key is a 5&times;5 square containing 25 letters of the
+//! Latin Alphabet, where the letters `i` and `j` are identified. Each digraph
+//! of the plaintext is encrypted according to the positions of its two letters
+//! in the square:
+//! - If the letters are in the same row, each is replaced by the letter to its
+//!   right (wrapping around at the end of the row).
+//! - If the letters are in the same column, each is replaced by the letter
+//!   below it (wrapping around at the bottom of the column).
+//! - Otherwise, the letters are at opposite corners of a rectangle, and each is
+//!   replaced by the letter at the other corner in the same row.
+//!
+//! Before encryption, the plaintext is split into digraphs. Any `j` is replaced
+//! by `i`, an `x` is inserted between the letters of a digraph that would
+//! otherwise consist of a repeated letter, and an `x` is appended if the
+//! plaintext has an odd number of letters (a `q` is used instead whenever the
+//! letter to be separated or padded is itself an `x`). So, unlike the Shift
+//! Cipher, decryption gives back the plaintext _as it was prepared for
+//! encryption_, and the reader must remove the extra letters.
+use crate::{
+    alphabet::{keyword_mixed, Latin},
+    analysis::Annealer,
+    attack::{
+        map_items, Attack, AttackModel, CancellationToken, Candidate, Candidates,
+        CiphertextOnlyAttack, Monitor, ProgressSink,
+    },
+    block::{BlockCipher, Padding},
+    corpus::NgramModel,
+    errors::{CipherError, TextError},
+    keyfile::KeyFile,
+    policy::CipherSettings,
+    secret::Secret,
+    verify::SampleMessage,
+    CipherInfo, CipherTrait, Ciphertext as Ciphtxt, EncodingError, KeyTrait, Message as Msg, Ring,
+    RingElement,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, CryptoRng, Rng, RngCore, SeedableRng};
+use std::{convert::Infallible, str::FromStr};
+use subtle::{Choice, ConstantTimeEq};
+
+/// The ciphertext space for the Playfair Cipher.
+// Notes: This is a wrapper type around the library's private representation of a ciphertext,
+// following the Latin Shift Cipher.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Ciphertext(Ciphtxt);
+
+crate::text_api!(ciphertext Ciphertext in "playfair");
+
+/// The message space of the Playfair Cipher.
+// Notes: This is a wrapper type around the library's private representation of a message,
+// following the Latin Shift Cipher.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Message(Msg);
+
+crate::text_api!(message Message in "playfair");
+
+impl Message {
+    /// Create a new message from a string.
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::playfair::Message;
+    /// let msg = Message::new("playfair example").expect("This example is hardcoded; it should work!");
+    ///
+    /// // Spaces are dropped.
+    /// assert_eq!(msg.to_string(), "playfairexample");
+    /// ```
+    pub fn new(str: &str) -> Result<Message, EncodingError> {
+        Ok(Message(Msg::new(str)?))
+    }
+}
+
+// The letters with special roles in the Playfair Cipher.
+const I: RingElement = RingElement::new(8);
+const J: RingElement = RingElement::new(9);
+const Q: RingElement = RingElement::new(16);
+const X: RingElement = RingElement::new(23);
+
+/// A 5x5 Playfair square.
+// This is separate from `Key` so that we can freely copy squares around while searching the key
+// space, while still discouraging copies of actual keys.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Square {
+    // The letters of the square, row by row.
+    letters: [RingElement; 25],
+    // The position of each letter of the alphabet in the square, with `j` at the position of `i`.
+    positions: [usize; 26],
+}
+
+impl Square {
+    /// Create a square from its letters, row by row.
+    ///
+    /// The caller must ensure that `letters` contains each letter of the
+    /// alphabet other than `j` exactly once.
+    fn new(letters: [RingElement; 25]) -> Self {
+        let mut positions = [0; 26];
+        for (i, letter) in letters.iter().enumerate() {
+            positions[letter.into_inner() as usize] = i;
+        }
+        positions[J.into_inner() as usize] = positions[I.into_inner() as usize];
+
+        Square { letters, positions }
+    }
+
+    /// Create a square from a keyword.
+    ///
+    /// The square is filled with the letters of the keyword, skipping
+    /// repeated letters, followed by the remaining letters of the alphabet
+    /// in order.
+    fn from_keyword(keyword: &[RingElement]) -> Self {
+        let keyword = keyword
+            .iter()
+            .map(|&letter| if letter == J { I } else { letter });
+        let alphabet = (0..<RingElement>::MODULUS)
+            .map(RingElement::new)
+            .filter(|&letter| letter != J);
+        let letters = keyword_mixed(keyword, alphabet);
+
+        Square::new(
+            letters
+                .try_into()
+                .expect("There are exactly 25 letters other than j"),
+        )
+    }
+
+    /// Get the row and column of a letter.
+    fn position(&self, letter: RingElement) -> (usize, usize) {
+        let i = self.positions[letter.into_inner() as usize];
+        (i / 5, i % 5)
+    }
+
+    /// Get the letter at a row and column, wrapping around as needed.
+    fn letter(&self, row: usize, col: usize) -> RingElement {
+        self.letters[5 * (row % 5) + col % 5]
+    }
+
+    /// Encrypt (if `step` is 1) or decrypt (if `step` is 4) a digraph.
+    fn apply(&self, a: RingElement, b: RingElement, step: usize) -> (RingElement, RingElement) {
+        let (ra, ca) = self.position(a);
+        let (rb, cb) = self.position(b);
+
+        if ra == rb {
+            (self.letter(ra, ca + step), self.letter(rb, cb + step))
+        } else if ca == cb {
+            (self.letter(ra + step, ca), self.letter(rb + step, cb))
+        } else {
+            (self.letter(ra, cb), self.letter(rb, ca))
+        }
+    }
+
+    /// Decrypt a sequence of letters.
+    fn decrypt(&self, ciphertxt: &[RingElement]) -> Vec<RingElement> {
+        let mut msg = Vec::with_capacity(ciphertxt.len());
+        let mut pairs = ciphertxt.chunks_exact(2);
+        for pair in pairs.by_ref() {
+            let (a, b) = self.apply(pair[0], pair[1], 4);
+            msg.push(a);
+            msg.push(b);
+        }
+        // A trailing unpaired letter is left as is.
+        msg.extend_from_slice(pairs.remainder());
+        msg
+    }
+
+    /// Make a small random change to the square: usually swap two letters,
+    /// and occasionally swap two rows or two columns.
+    fn mutate<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
+        let mut letters = self.letters;
+        let (x, y) = (rng.gen_range(0..5), rng.gen_range(0..5));
+        match rng.gen_range(0..20) {
+            0 => {
+                for col in 0..5 {
+                    letters.swap(5 * x + col, 5 * y + col);
+                }
+            }
+            1 => {
+                for row in 0..5 {
+                    letters.swap(5 * row + x, 5 * row + y);
+                }
+            }
+            _ => letters.swap(rng.gen_range(0..25), rng.gen_range(0..25)),
+        }
+        Square::new(letters)
+    }
+}
+
+/// A cryptographic key for the Playfair Cipher, i.e., a 5x5 square of letters.
+// We do not implement `Copy` and `Clone` here, to discourage making copies of secrets.
+#[derive(Debug)]
+pub struct Key(Secret<Square>);
+
+// Keys are compared in constant time, see the `secret` module. The positions of a square follow
+// from its letters, so comparing the letters is enough.
+impl ConstantTimeEq for Key {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.square().letters[..].ct_eq(&other.square().letters[..])
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Key {}
+
+impl Key {
+    // Get the square.
+    fn square(&self) -> &Square {
+        self.0.expose_secret()
+    }
+}
+
+impl KeyTrait for Key {
+    /// Generate a cryptographic key uniformly at random from the key space.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{KeyTrait, playfair::Key};
+    /// use rand::thread_rng;
+    ///
+    /// let key = Key::new(&mut thread_rng());
+    /// ```
+    fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut letters: Vec<RingElement> = (0..<RingElement>::MODULUS)
+            .map(RingElement::new)
+            .filter(|&letter| letter != J)
+            .collect();
+        letters.shuffle(rng);
+
+        Key(Secret::new(Square::new(
+            letters
+                .try_into()
+                .expect("There are exactly 25 letters other than j"),
+        )))
+    }
+
+    /// Export the cryptographic key, insecurely, as the 25 letters of the
+    /// square, row by row.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{KeyTrait, playfair::Key};
+    /// # use std::str::FromStr;
+    /// let key = Key::from_str("playfair example").unwrap();
+    ///
+    /// assert_eq!(key.export(), "playfirexmbcdghknoqstuvwz");
+    /// ```
+    fn export(&self) -> String {
+        self.square()
+            .letters
+            .iter()
+            .map(|&i| i.to_char_lossy::<Latin>())
+            .collect()
+    }
+
+    /// Import a key from the 25 letters of the square, row by row. Unlike
+    /// [`Key::from_str`], this does not accept shorter keywords.
+    fn try_import(s: &str) -> Result<Self, EncodingError> {
+        match Key::from_str(s) {
+            Ok(key) if key.export() == s => Ok(key),
+            _ => Err(EncodingError::KeyFormat {
+                key: s.to_string(),
+                cipher: "playfair",
+                expected: "the 25 letters of the square other than j, row by row",
+            }),
+        }
+    }
+}
+
+/// Parse a key from a keyword.
+///
+/// The square is filled row by row with the letters of the keyword, skipping
+/// repeated letters and treating `j` as `i`, followed by the remaining letters
+/// of the alphabet in order. In particular, a 25-letter keyword without
+/// repeated letters specifies the square directly. Spaces are ignored.
+///
+/// # Errors
+/// This implementation will produce an error if the keyword is empty or
+/// contains characters other than lowercase letters and spaces.
+impl FromStr for Key {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match <Msg>::from_str(s) {
+            Ok(keyword) => Ok(Key(Secret::new(Square::from_keyword(&keyword.0)))),
+            Err(_) => Err(EncodingError::KeyFormat {
+                key: s.to_string(),
+                cipher: "playfair",
+                expected: "a keyword of lowercase letters and spaces",
+            }),
+        }
+    }
+}
+
+impl TryFrom<&str> for Key {
+    type Error = EncodingError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = EncodingError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// An implementation of the Playfair Cipher.
+///
+/// The methods of [`CipherTrait`] always pad a message with an odd number
+/// of letters with `x`. An instance of the cipher also carries a [`Padding`]
+/// for that last letter and [`CipherSettings`], which
+/// [`encrypt_text`](Self::encrypt_text) and
+/// [`decrypt_text`](Self::decrypt_text) use to work on strings.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{block::Padding, playfair::{Key, PlayfairCipher}, policy::CipherSettings};
+/// # use std::str::FromStr;
+/// let key = Key::from_str("playfairexample").unwrap();
+/// let cipher = PlayfairCipher::new().with_settings(CipherSettings::new().with_group_size(4));
+///
+/// assert_eq!(cipher.encrypt_text("hide the gold", &key).unwrap(), "BMOD ZBXD NAGE");
+///
+/// let strict = cipher.with_padding(Padding::Reject);
+/// assert!(strict.encrypt_text("hide the gold", &key).is_err());
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PlayfairCipher {
+    padding: Padding,
+    settings: CipherSettings,
+}
+
+impl PlayfairCipher {
+    /// Create the cipher, padding with `x` and with the default settings.
+    pub fn new() -> Self {
+        PlayfairCipher::default()
+    }
+
+    /// Set how a message with an odd number of letters is padded. Repeated
+    /// letters are still separated by `x`, or by `q` if the letter is `x`.
+    pub fn with_padding(self, padding: Padding) -> Self {
+        PlayfairCipher { padding, ..self }
+    }
+
+    /// Set the settings of the cipher.
+    pub fn with_settings(self, settings: CipherSettings) -> Self {
+        PlayfairCipher { settings, ..self }
+    }
+
+    /// Get how a message with an odd number of letters is padded.
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Get the settings of the cipher.
+    pub fn settings(&self) -> CipherSettings {
+        self.settings
+    }
+
+    /// Encrypt a message given as a string, after preparing it according to
+    /// the settings of the cipher. Random padding is picked with
+    /// [`thread_rng`](rand::thread_rng).
+    ///
+    /// # Errors
+    /// This method returns an error if the string is not a message, or if
+    /// the padding is [`Padding::Reject`] and the prepared message has an odd
+    /// number of letters.
+    pub fn encrypt_text(&self, msg: &str, key: &Key) -> Result<String, TextError> {
+        let msg = Message::with_policy(msg, &self.settings.policy())?;
+        let ciphertxt = Self::encrypt_with(&msg, key, |a, filler, length| match self.padding {
+            Padding::PadX => Ok(filler),
+            Padding::PadRandom => {
+                let mut rng = rand::thread_rng();
+                // A random letter must not repeat the last letter, nor be the j that is merged
+                // into i
+                Ok(loop {
+                    let b = RingElement::random(&mut rng);
+                    if b != a && b != J {
+                        break b;
+                    }
+                })
+            }
+            Padding::Reject => Err(CipherError::BlockLength { length, block: 2 }),
+        })?;
+
+        Ok(self
+            .settings
+            .group(ciphertxt.format_with_case(self.settings.case())))
+    }
+
+    /// Decrypt a ciphertext given as a string. Any padding, and any letter
+    /// that separates a repeated letter, is left in the message.
+    ///
+    /// # Errors
+    /// This method returns an error if the string is not a ciphertext, or if
+    /// it has an odd number of letters.
+    pub fn decrypt_text(&self, ciphertxt: &str, key: &Key) -> Result<String, TextError> {
+        let ciphertxt = Ciphertext::with_parsing(ciphertxt, self.settings.parsing())?;
+        let msg = Self::decrypt_blocks(&ciphertxt, key)?;
+
+        Ok(msg.format_with_case(self.settings.case()))
+    }
+
+    // Encrypt a message, where `pad` picks the letter that pads a message with an odd number of
+    // letters, given the last letter, the traditional filler and the number of letters of the
+    // prepared message, i.e., with the letters that separate repeated letters.
+    fn encrypt_with<F>(msg: &Message, key: &Key, pad: F) -> Result<Ciphertext, CipherError>
+    where
+        F: FnOnce(RingElement, RingElement, usize) -> Result<RingElement, CipherError>,
+    {
+        let mut pad = Some(pad);
+        let mut ciphertxt = Vec::with_capacity(msg.0 .0.len() + 2);
+        let mut letters = msg.0 .0.iter().map(|&i| if i == J { I } else { i });
+        let mut next = letters.next();
+        while let Some(a) = next {
+            let filler = if a == X { Q } else { X };
+            let b = match letters.next() {
+                // A repeated letter is separated, and the second occurrence
+                // starts the next digraph.
+                Some(b) if b == a => {
+                    next = Some(b);
+                    filler
+                }
+                Some(b) => {
+                    next = letters.next();
+                    b
+                }
+                None => {
+                    next = None;
+                    let length = ciphertxt.len() + 1;
+                    pad.take()
+                        .map_or(Ok(filler), |pad| pad(a, filler, length))?
+                }
+            };
+            let (a, b) = key.square().apply(a, b, 1);
+            ciphertxt.push(a);
+            ciphertxt.push(b);
+        }
+
+        Ok(ciphertxt.into_iter().collect())
+    }
+}
+
+impl CipherTrait for PlayfairCipher {
+    type Message = Message;
+    type Ciphertext = Ciphertext;
+    type Key = Key;
+    type EncryptionError = Infallible;
+    type DecryptionError = CipherError;
+
+    /// Encrypt a message. This never fails.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{CipherTrait, playfair::{PlayfairCipher, Key, Message}};
+    /// # use std::str::FromStr;
+    /// let key = Key::from_str("playfairexample").unwrap();
+    /// let msg = Message::new("hidethegoldinthetreestump").unwrap();
+    ///
+    /// assert_eq!(
+    ///     PlayfairCipher::new().encrypt(&msg, &key).unwrap().to_string(),
+    ///     "BMODZBXDNABEKUDMUIXMMOUVIF"
+    /// );
+    /// ```
+    fn encrypt(
+        &self,
+        msg: &Self::Message,
+        key: &Self::Key,
+    ) -> Result<Self::Ciphertext, Infallible> {
+        Ok(Self::encrypt_with(msg, key, |_, filler, _| Ok(filler))
+            .expect("Padding with the filler never fails"))
+    }
+
+    /// Decrypt a ciphertext with a given key.
+    ///
+    /// Any `J` in the ciphertext is treated as `I`.
+    ///
+    /// # Errors
+    /// Ciphertexts produced by [`PlayfairCipher::encrypt`] have an even number
+    /// of letters, so this method returns [`CipherError::BlockLength`] if
+    /// the ciphertext has an odd number of letters.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{CipherTrait, playfair::{PlayfairCipher, Key, Ciphertext}};
+    /// # use std::str::FromStr;
+    /// let key = Key::from_str("playfairexample").unwrap();
+    /// let ciphertxt = Ciphertext::from_str("BMODZBXDNABEKUDMUIXMMOUVIF").unwrap();
+    ///
+    /// // Note the `x` separating the double `e`.
+    /// assert_eq!(
+    ///     PlayfairCipher::new().decrypt(&ciphertxt, &key).unwrap().to_string(),
+    ///     "hidethegoldinthetrexestump"
+    /// );
+    /// ```
+    fn decrypt(
+        &self,
+        ciphertxt: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Result<Self::Message, CipherError> {
+        Self::decrypt_blocks(ciphertxt, key)
+    }
+
+    /// Describe the Playfair Cipher. There is one key for each arrangement of
+    /// the square, i.e., 25! keys, although the 25 cyclic shifts of the rows
+    /// and columns of a square give the same cipher.
+    fn info() -> CipherInfo {
+        CipherInfo {
+            name: "playfair",
+            inventor: "Charles Wheatstone",
+            era: "1854",
+            alphabet: "Latin, with j merged into i",
+            alphabet_size: 26,
+            block_size: 2,
+            key_count: Some(15_511_210_043_330_985_984_000_000),
+        }
+    }
+}
+
+impl KeyFile for Key {
+    type Cipher = PlayfairCipher;
+}
+
+impl SampleMessage for PlayfairCipher {
+    /// Pick a prepared message at random, i.e., a message of digraphs of two
+    /// different letters other than `j`. An odd length is rounded up.
+    fn sample_message<R: RngCore + CryptoRng>(rng: &mut R, len: usize) -> Message {
+        let mut letter = |other: Option<RingElement>| loop {
+            let letter = RingElement::random(rng);
+            if letter != J && Some(letter) != other {
+                break letter;
+            }
+        };
+
+        (0..len.div_ceil(2))
+            .flat_map(|_| {
+                let a = letter(None);
+                [a, letter(Some(a))]
+            })
+            .collect()
+    }
+}
+
+/// Encrypt digraphs directly, without the preparation of
+/// [`encrypt`](PlayfairCipher::encrypt): a digraph of repeated letters is
+/// encrypted as if the letters were in the same row, and j is replaced by i.
+impl BlockCipher for PlayfairCipher {
+    const BLOCK_SIZE: usize = 2;
+
+    fn encrypt_block(block: &[RingElement], key: &Self::Key) -> Vec<RingElement> {
+        let [a, b] = [block[0], block[1]].map(|i| if i == J { I } else { i });
+        let (a, b) = key.square().apply(a, b, 1);
+        vec![a, b]
+    }
+
+    fn decrypt_block(block: &[RingElement], key: &Self::Key) -> Vec<RingElement> {
+        key.square().decrypt(block)
+    }
+}
+
+/// A ciphertext-only attack on the Playfair Cipher using simulated annealing.
+///
+/// Starting from a random square, we repeatedly mutate the square by swapping
+/// letters, rows or columns, and score each square by how closely the
+/// resulting decryption resembles English (or another language), according to
+/// an [`NgramModel`]. See [`Annealer`] for the details of the search. Since the
+/// search is randomized, it is restarted several times, and each restart
+/// contributes one candidate.
+///
+/// The key space of the Playfair Cipher is huge, so each restart needs many
+/// iterations and, to have a good chance of success, the ciphertext should be
+/// several hundred letters long. Even then, a restart from a random square
+/// only finds the key some of the time, so use several restarts. The n-gram
+/// statistics matter, too: the default model is built from the short
+/// [`ENGLISH_SAMPLE`], and is good enough to finish off a partially correct
+/// square (see [`SimulatedAnnealing::refine`]), but a model built from a much
+/// larger corpus works far better when starting from scratch. Note that the recovered
+/// square may differ from the original key by a rotation of its rows or
+/// columns, since such squares encrypt identically.
+///
+/// [`ENGLISH_SAMPLE`]: crate::corpus::ENGLISH_SAMPLE
+///
+/// # Examples
+/// ```no_run
+/// # use classical_crypto::{attack::CiphertextOnlyAttack, playfair::{Ciphertext, SimulatedAnnealing}};
+/// # use std::str::FromStr;
+/// # let ciphertxt = Ciphertext::from_str("BMODZBXDNABEKUDMUIXMMOUVIF").unwrap();
+/// let candidates = SimulatedAnnealing::default().with_restarts(10).attack(&ciphertxt);
+///
+/// println!("Our best guess is {}", candidates.best().unwrap().plaintext());
+/// ```
+///
+/// The search can take a while, so progress can be reported (see
+/// [`SimulatedAnnealing::with_progress`]) and the search can be cancelled (see
+/// [`SimulatedAnnealing::with_cancellation`]), in which case the candidates
+/// found so far are returned. With the `rayon` feature, the restarts run in
+/// parallel; the results are the same either way.
+#[derive(Clone, Debug)]
+pub struct SimulatedAnnealing {
+    model: NgramModel,
+    annealer: Annealer,
+    restarts: usize,
+    seed: u64,
+    monitor: Monitor,
+}
+
+impl SimulatedAnnealing {
+    /// Use the given n-gram model to score decryptions.
+    pub fn with_model(self, model: NgramModel) -> Self {
+        SimulatedAnnealing { model, ..self }
+    }
+
+    /// Use the given annealing parameters for each restart.
+    pub fn with_annealer(self, annealer: Annealer) -> Self {
+        SimulatedAnnealing { annealer, ..self }
+    }
+
+    /// Set the number of restarts.
+    pub fn with_restarts(self, restarts: usize) -> Self {
+        SimulatedAnnealing { restarts, ..self }
+    }
+
+    /// Set the seed of the random number generator used by the search, which
+    /// makes the search reproducible.
+    pub fn with_seed(self, seed: u64) -> Self {
+        SimulatedAnnealing { seed, ..self }
+    }
+
+    /// Report progress to the given sink periodically. The number of
+    /// iterations counts all restarts.
+    pub fn with_progress(self, sink: impl ProgressSink + 'static) -> Self {
+        SimulatedAnnealing {
+            monitor: self.monitor.with_progress(sink),
+            ..self
+        }
+    }
+
+    /// Stop the search when the given token is cancelled.
+    pub fn with_cancellation(self, token: CancellationToken) -> Self {
+        SimulatedAnnealing {
+            monitor: self.monitor.with_cancellation(token),
+            ..self
+        }
+    }
+
+    /// Run a single search starting from the given key rather than a random
+    /// one, e.g., to improve on a partially correct square.
+    pub fn refine(&self, ciphertxt: &Ciphertext, key: &Key) -> Candidate<Key, Message> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        self.search(
+            &ciphertxt.0 .0,
+            key.square().clone(),
+            &mut rng,
+            &self.monitor,
+        )
+    }
+
+    fn search(
+        &self,
+        ciphertxt: &[RingElement],
+        initial: Square,
+        rng: &mut StdRng,
+        monitor: &Monitor,
+    ) -> Candidate<Key, Message> {
+        let score = |square: &Square| self.model.latin_log_likelihood(&square.decrypt(ciphertxt));
+        let (square, score) = self.annealer.run_monitored(
+            initial,
+            score,
+            |square, rng| square.mutate(rng),
+            rng,
+            monitor,
+        );
+
+        let msg = square.decrypt(ciphertxt).into_iter().collect();
+        Candidate::new(Key(Secret::new(square)), msg, score)
+    }
+}
+
+impl Default for SimulatedAnnealing {
+    /// Score decryptions using English trigrams, with 5 restarts of 50,000
+    /// iterations each, cooling from a temperature of 50 to 1.
+    fn default() -> Self {
+        SimulatedAnnealing {
+            model: NgramModel::english(3),
+            annealer: Annealer::new(50_000, 50.0, 1.0),
+            restarts: 5,
+            seed: 0,
+            monitor: Monitor::default(),
+        }
+    }
+}
+
+impl Attack for SimulatedAnnealing {
+    const NAME: &'static str = "simulated annealing";
+    const MODEL: AttackModel = AttackModel::CiphertextOnly;
+}
+
+impl CiphertextOnlyAttack<PlayfairCipher> for SimulatedAnnealing {
+    fn attack(&self, ciphertxt: &Ciphertext) -> Candidates<Key, Message> {
+        let monitor = self
+            .monitor
+            .begin(self.annealer.iterations() * self.restarts);
+        // Each restart has its own generator, so that the results do not depend on whether the
+        // restarts run in parallel.
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let seeds = (0..self.restarts).map(|_| rng.gen()).collect();
+
+        map_items(seeds, |seed: u64| {
+            if monitor.is_cancelled() {
+                return None;
+            }
+
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut letters: Vec<RingElement> = (0..<RingElement>::MODULUS)
+                .map(RingElement::new)
+                .filter(|&letter| letter != J)
+                .collect();
+            letters.shuffle(&mut rng);
+            let initial = Square::new(
+                letters
+                    .try_into()
+                    .expect("There are exactly 25 letters other than j"),
+            );
+
+            Some(
+                self.search(&ciphertxt.0 .0, initial, &mut rng, &monitor)
+                    .into_parts(),
+            )
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::Padding, errors::RekeyError};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    pub const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    // A (slightly) longer plaintext for testing the attack.
+    const LONG_MSG: &str =
+        "the playfair cipher was used by soldiers in the field because it needs \
+        no equipment other than a pencil and a piece of paper and a good officer could remember \
+        the keyword that builds the square yet it too can be broken because some pairs of letters \
+        are still far more common than others";
+
+    // More plaintext, so that the ciphertext is long enough to attack from scratch.
+    const MORE_MSG: &str =
+        "when the war began the officers of the signal corps were told that the cipher would keep \
+        their orders secret for as long as it took the enemy to read them and by then the orders \
+        would have been carried out so that the secret no longer mattered to anyone at all \
+        the enemy had clerks of their own however and they counted the pairs of letters in every \
+        message that they could intercept until the most common pairs gave away the square";
+
+    #[test]
+    fn key_from_keyword() {
+        let key = Key::from_str("playfair example").unwrap();
+        assert_eq!(key.export(), "playfirexmbcdghknoqstuvwz");
+
+        // The letter j is identified with i
+        let key = Key::from_str("jam").unwrap();
+        assert_eq!(key.export(), "iambcdefghklnopqrstuvwxyz");
+    }
+
+    #[test]
+    fn key_import() {
+        let key = Key::from_str("playfair example").unwrap();
+        assert_eq!(Key::try_import(&key.export()).unwrap(), key);
+
+        // Only complete squares are imported
+        assert!(Key::try_import("playfair example").is_err());
+        assert!(Key::try_import("playfirexmbcdghknoqstuvwzz").is_err());
+
+        // Conversions read keywords, as `FromStr` does
+        let converted: Key = "playfair example".try_into().unwrap();
+        assert_eq!(converted, key);
+        assert!(Key::try_from("Playfair".to_string()).is_err());
+    }
+
+    #[test]
+    fn key_from_keyword_err() {
+        assert!(matches!(
+            Key::from_str("").unwrap_err(),
+            EncodingError::KeyFormat { key, cipher: "playfair", .. } if key.is_empty()
+        ));
+        assert_eq!(
+            Key::from_str("Playfair").unwrap_err().to_string(),
+            "Input \"Playfair\" does not represent a valid playfair key; expected a keyword of \
+             lowercase letters and spaces"
+        );
+    }
+
+    #[test]
+    fn random_key_is_square() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let key = Key::new(&mut rng);
+
+        let mut letters: Vec<char> = key.export().chars().collect();
+        letters.sort();
+        assert_eq!(
+            letters.into_iter().collect::<String>(),
+            "abcdefghiklmnopqrstuvwxyz"
+        );
+    }
+
+    // The example from the original description of the cipher by Wheatstone,
+    // as given by Wikipedia.
+    #[test]
+    fn enc_dec_basic() {
+        let key = Key::from_str("playfairexample").unwrap();
+        let msg = Message::new("hidethegoldinthetreestump").unwrap();
+
+        let ciphertxt = PlayfairCipher::new().encrypt(&msg, &key).unwrap();
+        assert_eq!(ciphertxt.to_string(), "BMODZBXDNABEKUDMUIXMMOUVIF");
+        assert_eq!(
+            PlayfairCipher::new().decrypt(&ciphertxt, &key).unwrap(),
+            Message::new("hidethegoldinthetrexestump").unwrap()
+        );
+    }
+
+    #[test]
+    fn digraphs() {
+        let key = Key::from_str("playfairexample").unwrap();
+        let msg = Message::new("hidethegoldinthetreestump").unwrap();
+        let ciphertxt = PlayfairCipher::new().encrypt(&msg, &key).unwrap();
+        let digraphs: Vec<Ciphertext> = ciphertxt
+            .chunks(2)
+            .map(|d| d.iter().copied().collect())
+            .collect();
+        assert_eq!(digraphs.len(), 13);
+        assert_eq!(digraphs[0].to_string(), "BM");
+        assert_eq!(digraphs[2].to_string(), "ZB");
+
+        // Each digraph decrypts on its own
+        for (digraph, plain) in digraphs.iter().zip(["hi", "de", "th"]) {
+            assert_eq!(
+                PlayfairCipher::new()
+                    .decrypt(digraph, &key)
+                    .unwrap()
+                    .to_string(),
+                plain
+            );
+        }
+    }
+
+    #[test]
+    fn eq_ignore_format() {
+        // Decryption gives back the prepared plaintext, whose filler letters set it apart from
+        // the original
+        let key = Key::from_str("playfairexample").unwrap();
+        let original = "Hide the gold!";
+        let msg = Message::new_lossy(original).0;
+        let decrypted = PlayfairCipher::new()
+            .decrypt(&PlayfairCipher::new().encrypt(&msg, &key).unwrap(), &key)
+            .unwrap();
+        assert!(!decrypted.eq_ignore_format(original));
+        assert!(decrypted.eq_ignore_format("Hide the gold, x"));
+    }
+
+    #[test]
+    fn block_cipher() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let key = Key::from_str("playfairexample").unwrap();
+
+        // Without preparation, repeated letters stay in one digraph
+        let msg = Message::new("balloon").unwrap();
+        let ciphertxt =
+            PlayfairCipher::encrypt_padded(&msg, &key, Padding::PadX, &mut rng).unwrap();
+        assert_eq!(ciphertxt.len(), 8);
+        assert_eq!(
+            PlayfairCipher::decrypt_blocks(&ciphertxt, &key)
+                .unwrap()
+                .to_string(),
+            "balloonx"
+        );
+
+        let ciphertxt =
+            PlayfairCipher::encrypt_padded(&msg, &key, Padding::PadRandom, &mut rng).unwrap();
+        let decrypted = PlayfairCipher::new().decrypt(&ciphertxt, &key).unwrap();
+        assert_eq!(decrypted.slice(..7), msg);
+
+        assert_eq!(
+            PlayfairCipher::encrypt_padded(&msg, &key, Padding::Reject, &mut rng),
+            Err(CipherError::BlockLength {
+                length: 7,
+                block: 2
+            })
+        );
+    }
+
+    #[test]
+    fn rekey() {
+        let old_key = Key::from_str("playfairexample").unwrap();
+        let new_key = Key::from_str("charles").unwrap();
+        let msg = Message::new("hidethegold").unwrap();
+
+        let ciphertxt = PlayfairCipher::new().encrypt(&msg, &old_key).unwrap();
+        assert_eq!(
+            PlayfairCipher::new().rekey(&ciphertxt, &old_key, &new_key),
+            Ok(PlayfairCipher::new().encrypt(&msg, &new_key).unwrap())
+        );
+        assert_eq!(
+            PlayfairCipher::new().rekey(
+                &Ciphertext::from_str("BMODZ").unwrap(),
+                &old_key,
+                &new_key
+            ),
+            Err(RekeyError::Decryption(CipherError::BlockLength {
+                length: 5,
+                block: 2
+            }))
+        );
+    }
+
+    #[test]
+    fn instance() {
+        let key = Key::from_str("playfairexample").unwrap();
+        let cipher = PlayfairCipher::new();
+        assert_eq!(cipher.padding(), Padding::PadX);
+        assert_eq!(
+            cipher.encrypt_text("hide the gold", &key),
+            Ok("BMODZBXDNAGE".to_string())
+        );
+        assert_eq!(
+            cipher.decrypt_text("BMODZ", &key),
+            Err(TextError::Cipher(CipherError::BlockLength {
+                length: 5,
+                block: 2
+            }))
+        );
+
+        // Only the last letter is padded at random, repeated letters are still separated by x
+        let random = cipher.with_padding(Padding::PadRandom);
+        for _ in 0..10 {
+            let ciphertxt = random.encrypt_text("balloons", &key).unwrap();
+            let decrypted = random.decrypt_text(&ciphertxt, &key).unwrap();
+            assert_eq!(&decrypted[..9], "balxloons");
+            assert_ne!(&decrypted[9..], "s");
+        }
+
+        let strict = cipher.with_padding(Padding::Reject);
+        assert_eq!(
+            strict.encrypt_text("balloons", &key),
+            Err(TextError::Cipher(CipherError::BlockLength {
+                length: 9,
+                block: 2
+            }))
+        );
+        assert_eq!(strict.encrypt_text("hidethegoldx", &key).unwrap().len(), 12);
+
+        // The length counts the letters that separate repeated letters, i.e., "balxloonsxs"
+        let error = strict.encrypt_text("balloonss", &key).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The text has 11 letters, which is not a multiple of the block length 2"
+        );
+    }
+
+    #[test]
+    fn enc_preparation() {
+        let key = Key::from_str("playfairexample").unwrap();
+
+        // Odd-length messages are padded, repeated x's are separated by q,
+        // and j is replaced by i.
+        for (msg, prepared) in [("abc", "abcx"), ("xxx", "xqxqxq"), ("jj", "ixix")] {
+            let msg = Message::new(msg).unwrap();
+            assert_eq!(
+                PlayfairCipher::new()
+                    .decrypt(&PlayfairCipher::new().encrypt(&msg, &key).unwrap(), &key)
+                    .unwrap(),
+                Message::new(prepared).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn dec_odd_length() {
+        let key = Key::from_str("playfairexample").unwrap();
+        let ciphertxt = Ciphertext::from_str("BMODZ").unwrap();
+
+        assert_eq!(
+            PlayfairCipher::new().decrypt(&ciphertxt, &key),
+            Err(CipherError::BlockLength {
+                length: 5,
+                block: 2
+            })
+        );
+    }
+
+    #[test]
+    fn enc_dec_random_keys() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let msg = Message::new(LONG_MSG).unwrap();
+
+        for _ in 0..10 {
+            let key = Key::new(&mut rng);
+            let prepared = PlayfairCipher::new()
+                .decrypt(&PlayfairCipher::new().encrypt(&msg, &key).unwrap(), &key)
+                .unwrap();
+            // Encryption is deterministic, so re-encrypting the prepared
+            // message gives the same ciphertext.
+            assert_eq!(
+                PlayfairCipher::new().encrypt(&prepared, &key).unwrap(),
+                PlayfairCipher::new().encrypt(&msg, &key).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn mutations_preserve_squares() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let mut square = Key::from_str("playfairexample").unwrap().square().clone();
+        for _ in 0..1000 {
+            square = square.mutate(&mut rng);
+        }
+        // The positions are consistent with the letters
+        for (i, letter) in square.letters.iter().enumerate() {
+            assert_eq!(square.position(*letter), (i / 5, i % 5));
+        }
+        assert_eq!(square.position(J), square.position(I));
+    }
+
+    #[test]
+    fn annealing_refines_key() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let key = Key::new(&mut rng);
+        let msg = Message::new(LONG_MSG).unwrap();
+        let ciphertxt = PlayfairCipher::new().encrypt(&msg, &key).unwrap();
+        let prepared = PlayfairCipher::new().decrypt(&ciphertxt, &key).unwrap();
+
+        // Start from a square that is a few swaps away from the key
+        let mut letters = key.square().letters;
+        letters.swap(0, 7);
+        letters.swap(3, 19);
+        letters.swap(12, 24);
+        let start = Key(Secret::new(Square::new(letters)));
+        assert_ne!(
+            PlayfairCipher::new().decrypt(&ciphertxt, &start).unwrap(),
+            prepared
+        );
+
+        let solver = SimulatedAnnealing::default()
+            .with_annealer(Annealer::new(3000, 5.0, 0.2))
+            .with_seed(1);
+        let candidate = solver.refine(&ciphertxt, &start);
+        assert_eq!(candidate.plaintext(), &prepared);
+    }
+
+    #[test]
+    fn annealing_attack_recovers_key() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let key = Key::new(&mut rng);
+        let msg = Message::new(&format!("{LONG_MSG} {MORE_MSG}")).unwrap();
+        let ciphertxt = PlayfairCipher::new().encrypt(&msg, &key).unwrap();
+        let prepared = PlayfairCipher::new().decrypt(&ciphertxt, &key).unwrap();
+
+        // Start from a random square. A single restart does not always find the key, but this
+        // one does
+        let solver = SimulatedAnnealing::default()
+            .with_annealer(Annealer::new(30_000, 50.0, 1.0))
+            .with_restarts(1)
+            .with_seed(23);
+        let candidates = solver.attack(&ciphertxt);
+        let best = candidates.best().unwrap();
+        assert_eq!(best.plaintext(), &prepared);
+        assert_eq!(
+            PlayfairCipher::new()
+                .decrypt(&ciphertxt, best.key())
+                .unwrap(),
+            prepared
+        );
+    }
+
+    #[test]
+    fn annealing_attack_returns_restarts() {
+        let key = Key::from_str("playfairexample").unwrap();
+        let ciphertxt = PlayfairCipher::new()
+            .encrypt(&Message::new(LONG_MSG).unwrap(), &key)
+            .unwrap();
+
+        let solver = SimulatedAnnealing::default()
+            .with_annealer(Annealer::new(100, 5.0, 0.2))
+            .with_restarts(3);
+        let candidates = solver.attack(&ciphertxt);
+        assert_eq!(candidates.len(), 3);
+        // The search is reproducible
+        assert_eq!(candidates, solver.attack(&ciphertxt));
+    }
+
+    #[test]
+    fn annealing_progress_and_cancellation() {
+        use crate::attack::Progress;
+        use std::sync::{Arc, Mutex};
+
+        let key = Key::from_str("playfairexample").unwrap();
+        let ciphertxt = PlayfairCipher::new()
+            .encrypt(&Message::new(LONG_MSG).unwrap(), &key)
+            .unwrap();
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let token = CancellationToken::new();
+        let solver = SimulatedAnnealing::default()
+            .with_annealer(Annealer::new(1500, 5.0, 0.2))
+            .with_restarts(2)
+            .with_progress(move |p: Progress| {
+                sink.lock()
+                    .unwrap()
+                    .push((p.iterations(), p.total(), p.best_score()))
+            })
+            .with_cancellation(token.clone());
+        let candidates = solver.attack(&ciphertxt);
+
+        let reports = reports.lock().unwrap();
+        // Each restart reports at the start, after 1000 iterations and at the end
+        assert_eq!(reports.len(), 6);
+        assert_eq!(reports.last().unwrap().0, 3000);
+        assert!(reports.iter().all(|r| r.1 == 3000));
+        // Progress never goes backwards, even across restarts
+        assert!(reports
+            .windows(2)
+            .all(|w| w[0].0 <= w[1].0 && w[0].2 <= w[1].2));
+        assert_eq!(
+            reports.last().unwrap().2,
+            candidates.best().unwrap().score()
+        );
+
+        token.cancel();
+        assert!(solver.attack(&ciphertxt).is_empty());
+    }
+}
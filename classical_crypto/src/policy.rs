@@ -0,0 +1,464 @@
+//! Policies for preparing text before it is parsed as a message or a
+//! ciphertext, and for writing messages and ciphertexts.
+use crate::{errors::InvalidCharacters, Grouped};
+#[cfg(feature = "toml")]
+use serde::Deserialize;
+use std::borrow::Cow;
+
+// The names of the decimal digits, used to spell them out.
+const DIGIT_NAMES: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// How the (ASCII) digits of a plaintext are handled, see [`MessagePolicy`].
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "toml",
+    derive(Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DigitPolicy {
+    /// Keep the digits, so that they are encoded if the alphabet contains
+    /// them, as the [`Alphanumeric`](crate::alphabet::Alphanumeric) Alphabet
+    /// of the [`AlphabetShiftCipher`](crate::shift::AlphabetShiftCipher) does,
+    /// and rejected otherwise. This is the default.
+    #[default]
+    Keep,
+    /// Reject any message that contains a digit, even if the alphabet contains
+    /// them.
+    Reject,
+    /// Remove the digits.
+    Strip,
+    /// Replace each digit by its name in English, e.g., `4` by `four` and `42`
+    /// by `fourtwo`.
+    SpellOut,
+}
+
+/// How letters missing from the alphabet are folded into other letters, see
+/// [`MessagePolicy`].
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "toml",
+    derive(Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Folding {
+    /// Keep all letters. This is the default.
+    #[default]
+    None,
+    /// Fold the letters missing from the
+    /// [`ClassicalLatin`](crate::alphabet::ClassicalLatin) Alphabet: `j` into
+    /// `i`, `u` into `v` and `w` into `vv`.
+    ClassicalLatin,
+}
+
+/// How the letters of messages and ciphertexts are cased when they are
+/// written, e.g., by the `format_with_case` methods of the messages and
+/// ciphertexts of each cipher, such as
+/// [`shift::Ciphertext::format_with_case`](crate::shift::Ciphertext::format_with_case).
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{policy::CasePolicy, shift::Ciphertext};
+/// # use std::str::FromStr;
+/// let ciphertxt = Ciphertext::from_str("wklv").unwrap();
+///
+/// assert_eq!(ciphertxt.to_string(), "WKLV");
+/// assert_eq!(ciphertxt.format_with_case(CasePolicy::Lowercase), "wklv");
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "toml",
+    derive(Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum CasePolicy {
+    /// Follow Stinson's convention: plaintexts are written in lowercase and
+    /// ciphertexts in ALL CAPS, for alphabets that opt in (see
+    /// [`Alphabet::UPPERCASE_CIPHERTEXT`](crate::alphabet::Alphabet::UPPERCASE_CIPHERTEXT)).
+    /// This is the default, and is what the [`Display`](std::fmt::Display)
+    /// implementations use.
+    #[default]
+    Stinson,
+    /// Write each character as the alphabet encodes it, i.e., as it must be
+    /// written in a message. Ciphertexts are not uppercased.
+    PreserveInput,
+    /// Write every letter in lowercase.
+    Lowercase,
+}
+
+/// How strictly ciphertexts are parsed, used by the `with_parsing`
+/// constructors of the ciphertexts of each cipher, e.g.,
+/// [`shift::Ciphertext::with_parsing`](crate::shift::Ciphertext::with_parsing),
+/// and by [`CipherSettings`].
+///
+/// Spaces are skipped by every policy, so that ciphertexts may be written in
+/// groups.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{policy::ParsePolicy, shift::Ciphertext};
+/// # use std::str::FromStr;
+/// assert!(Ciphertext::with_parsing("WKLV lv", ParsePolicy::Strict).is_err());
+/// assert_eq!(
+///     Ciphertext::with_parsing("WKLV lv", ParsePolicy::Lenient),
+///     Ciphertext::from_str("WKLVLV")
+/// );
+/// assert_eq!(
+///     Ciphertext::with_parsing("WKLV-LV, 2!", ParsePolicy::Permissive),
+///     Ciphertext::from_str("WKLVLV")
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "toml",
+    derive(Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ParsePolicy {
+    /// Enforce Stinson's convention: reject lowercase letters in ciphertexts
+    /// over alphabets that write ciphertexts in ALL CAPS (see
+    /// [`Alphabet::UPPERCASE_CIPHERTEXT`](crate::alphabet::Alphabet::UPPERCASE_CIPHERTEXT)).
+    Strict,
+    /// Fold the case of ciphertexts over alphabets that write ciphertexts in
+    /// ALL CAPS, so that lowercase letters are accepted too. This is the
+    /// default, and is what the [`FromStr`](std::str::FromStr)
+    /// implementations use.
+    #[default]
+    Lenient,
+    /// Fold case as [`Lenient`](ParsePolicy::Lenient) does, and skip every
+    /// character that is not in the alphabet, e.g., punctuation and digits.
+    Permissive,
+}
+
+impl ParsePolicy {
+    // Prepare a ciphertext for parsing, where `fold_case` is true if the alphabet writes
+    // ciphertexts in ALL CAPS and `encodes` tells whether a character is in the alphabet, after
+    // folding case. A strict policy returns the lowercase letters as an error.
+    pub(crate) fn prepare<'a>(
+        self,
+        text: &'a str,
+        fold_case: bool,
+        encodes: impl Fn(char) -> bool,
+    ) -> Result<Cow<'a, str>, InvalidCharacters> {
+        match self {
+            ParsePolicy::Strict if fold_case && text.chars().any(char::is_lowercase) => {
+                Err(InvalidCharacters::find(text, char::is_lowercase))
+            }
+            ParsePolicy::Strict | ParsePolicy::Lenient => Ok(Cow::Borrowed(text)),
+            ParsePolicy::Permissive if text.chars().all(|c| c == ' ' || encodes(c)) => {
+                Ok(Cow::Borrowed(text))
+            }
+            ParsePolicy::Permissive => Ok(text.chars().filter(|&c| encodes(c)).collect()),
+        }
+    }
+}
+
+impl CasePolicy {
+    // Case a text, which Stinson's convention writes in ALL CAPS if `uppercase` is true.
+    pub(crate) fn apply_to(self, text: &str, uppercase: bool) -> Cow<'_, str> {
+        match self {
+            CasePolicy::Stinson if uppercase => Cow::Owned(text.to_uppercase()),
+            CasePolicy::Stinson | CasePolicy::PreserveInput => Cow::Borrowed(text),
+            CasePolicy::Lowercase => Cow::Owned(text.to_lowercase()),
+        }
+    }
+}
+
+/// A policy for preparing plaintexts before they are encoded, used by the
+/// `with_policy` constructors of the messages of each cipher, e.g.,
+/// [`shift::Message::with_policy`](crate::shift::Message::with_policy).
+///
+/// The default policy leaves the plaintext unchanged, which is what the `new`
+/// constructors of the messages use.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{policy::{DigitPolicy, MessagePolicy}, shift::Message};
+/// // The Latin Alphabet has no digits
+/// assert!(Message::new("meetatnine30").is_err());
+///
+/// let policy = MessagePolicy::new().with_digits(DigitPolicy::SpellOut);
+/// let msg = Message::with_policy("meetatnine30", &policy).unwrap();
+/// assert_eq!(msg.to_string(), "meetatninethreezero");
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MessagePolicy {
+    digits: DigitPolicy,
+    folding: Folding,
+}
+
+impl MessagePolicy {
+    /// Create the default policy, which leaves plaintexts unchanged.
+    pub fn new() -> Self {
+        MessagePolicy::default()
+    }
+
+    /// Set how digits are handled.
+    pub fn with_digits(self, digits: DigitPolicy) -> Self {
+        MessagePolicy { digits, ..self }
+    }
+
+    /// Set how letters are folded.
+    pub fn with_folding(self, folding: Folding) -> Self {
+        MessagePolicy { folding, ..self }
+    }
+
+    /// Get how digits are handled.
+    pub fn digits(&self) -> DigitPolicy {
+        self.digits
+    }
+
+    /// Get how letters are folded.
+    pub fn folding(&self) -> Folding {
+        self.folding
+    }
+
+    /// Prepare a plaintext for encoding according to the policy. Digits are
+    /// handled before letters are folded.
+    ///
+    /// # Errors
+    /// This method returns an error reporting every digit of the plaintext,
+    /// together with its position, if digits are rejected and the plaintext
+    /// contains any.
+    pub fn prepare<'a>(&self, text: &'a str) -> Result<Cow<'a, str>, InvalidCharacters> {
+        let text = self.prepare_digits(text)?;
+
+        match self.folding {
+            Folding::None => Ok(text),
+            Folding::ClassicalLatin if !text.contains(['j', 'u', 'w']) => Ok(text),
+            Folding::ClassicalLatin => Ok(Cow::Owned(
+                text.replace('j', "i").replace('u', "v").replace('w', "vv"),
+            )),
+        }
+    }
+
+    // Handle the digits of a plaintext.
+    fn prepare_digits<'a>(&self, text: &'a str) -> Result<Cow<'a, str>, InvalidCharacters> {
+        if !text.chars().any(|c| c.is_ascii_digit()) {
+            return Ok(Cow::Borrowed(text));
+        }
+
+        match self.digits {
+            DigitPolicy::Keep => Ok(Cow::Borrowed(text)),
+            DigitPolicy::Reject => Err(InvalidCharacters::find(text, |c| c.is_ascii_digit())),
+            DigitPolicy::Strip => Ok(text.chars().filter(|c| !c.is_ascii_digit()).collect()),
+            DigitPolicy::SpellOut => {
+                let mut spelled = String::with_capacity(text.len());
+                for c in text.chars() {
+                    match c.to_digit(10) {
+                        Some(d) => spelled.push_str(DIGIT_NAMES[d as usize]),
+                        None => spelled.push(c),
+                    }
+                }
+                Ok(Cow::Owned(spelled))
+            }
+        }
+    }
+}
+
+/// The settings of a cipher instance for the text that it reads and writes,
+/// e.g., [`ShiftCipher::with_settings`](crate::shift::ShiftCipher::with_settings):
+/// the [`MessagePolicy`] that prepares plaintexts, the [`ParsePolicy`] that
+/// parses ciphertexts, the [`CasePolicy`] of the output, and the size of the
+/// groups that ciphertexts are written in.
+///
+/// The default settings leave plaintexts unchanged, parse ciphertexts
+/// leniently, follow Stinson's convention, and do not group ciphertexts.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{policy::{CasePolicy, CipherSettings}, shift::{Key, ShiftCipher}};
+/// # use std::str::FromStr;
+/// let settings = CipherSettings::new()
+///     .with_case(CasePolicy::Lowercase)
+///     .with_group_size(5);
+/// let cipher = ShiftCipher::new().with_settings(settings);
+/// let key = Key::from_str("11").unwrap();
+///
+/// assert_eq!(
+///     cipher.encrypt_text("wewillmeetatmidnight", &key).unwrap(),
+///     "hphtw wxppe lexto ytrse"
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CipherSettings {
+    policy: MessagePolicy,
+    parsing: ParsePolicy,
+    case: CasePolicy,
+    group_size: Option<usize>,
+}
+
+impl CipherSettings {
+    /// Create the default settings.
+    pub fn new() -> Self {
+        CipherSettings::default()
+    }
+
+    /// Set the policy that prepares plaintexts.
+    pub fn with_policy(self, policy: MessagePolicy) -> Self {
+        CipherSettings { policy, ..self }
+    }
+
+    /// Set how strictly ciphertexts are parsed.
+    pub fn with_parsing(self, parsing: ParsePolicy) -> Self {
+        CipherSettings { parsing, ..self }
+    }
+
+    /// Set how the letters of the output are cased.
+    pub fn with_case(self, case: CasePolicy) -> Self {
+        CipherSettings { case, ..self }
+    }
+
+    /// Write ciphertexts in groups of `size` letters separated by spaces.
+    ///
+    /// # Panics
+    /// This method panics if `size` is 0.
+    pub fn with_group_size(self, size: usize) -> Self {
+        assert!(size > 0, "The group size must be positive");
+        CipherSettings {
+            group_size: Some(size),
+            ..self
+        }
+    }
+
+    /// Get the policy that prepares plaintexts.
+    pub fn policy(&self) -> MessagePolicy {
+        self.policy
+    }
+
+    /// Get how strictly ciphertexts are parsed.
+    pub fn parsing(&self) -> ParsePolicy {
+        self.parsing
+    }
+
+    /// Get how the letters of the output are cased.
+    pub fn case(&self) -> CasePolicy {
+        self.case
+    }
+
+    /// Get the size of the groups that ciphertexts are written in, if any.
+    pub fn group_size(&self) -> Option<usize> {
+        self.group_size
+    }
+
+    // Write a ciphertext in groups, if the settings ask for them.
+    pub(crate) fn group(&self, ciphertxt: String) -> String {
+        match self.group_size {
+            Some(size) => Grouped::new(ciphertxt.as_str(), size).to_string(),
+            None => ciphertxt,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits() {
+        let text = "room 101, floor 4";
+
+        assert_eq!(MessagePolicy::new().prepare(text).unwrap(), text);
+        let error = MessagePolicy::new()
+            .with_digits(DigitPolicy::Reject)
+            .prepare(text)
+            .unwrap_err();
+        let digits: Vec<_> = error
+            .characters()
+            .iter()
+            .map(|c| (c.character, c.char_index))
+            .collect();
+        assert_eq!(digits, [('1', 5), ('0', 6), ('1', 7), ('4', 16)]);
+        assert_eq!(
+            MessagePolicy::new()
+                .with_digits(DigitPolicy::Strip)
+                .prepare(text)
+                .unwrap(),
+            "room , floor "
+        );
+        assert_eq!(
+            MessagePolicy::new()
+                .with_digits(DigitPolicy::SpellOut)
+                .prepare(text)
+                .unwrap(),
+            "room onezeroone, floor four"
+        );
+    }
+
+    #[test]
+    fn no_digits() {
+        for digits in [
+            DigitPolicy::Keep,
+            DigitPolicy::Reject,
+            DigitPolicy::Strip,
+            DigitPolicy::SpellOut,
+        ] {
+            let policy = MessagePolicy::new().with_digits(digits);
+            assert_eq!(policy.digits(), digits);
+            assert!(matches!(
+                policy.prepare("no digits here"),
+                Ok(Cow::Borrowed("no digits here"))
+            ));
+        }
+    }
+
+    #[test]
+    fn classical_latin_folding() {
+        let policy = MessagePolicy::new().with_folding(Folding::ClassicalLatin);
+        assert_eq!(policy.folding(), Folding::ClassicalLatin);
+        assert_eq!(policy.prepare("julius wrote").unwrap(), "ivlivs vvrote");
+        assert!(matches!(policy.prepare("caesar"), Ok(Cow::Borrowed(_))));
+
+        // Digits are spelled out before folding
+        let policy = policy.with_digits(DigitPolicy::SpellOut);
+        assert_eq!(policy.prepare("2").unwrap(), "tvvo");
+    }
+
+    #[test]
+    fn case() {
+        assert_eq!(CasePolicy::default(), CasePolicy::Stinson);
+        assert_eq!(CasePolicy::Stinson.apply_to("abc", true), "ABC");
+        assert_eq!(CasePolicy::Stinson.apply_to("abc", false), "abc");
+        assert_eq!(CasePolicy::PreserveInput.apply_to("abc", true), "abc");
+
+        // Texts that Stinson's convention leaves alone, e.g., printable ASCII
+        // ciphertexts, are still lowercased on request
+        assert_eq!(CasePolicy::Stinson.apply_to("Hi!", false), "Hi!");
+        assert_eq!(CasePolicy::Lowercase.apply_to("Hi!", false), "hi!");
+    }
+
+    #[test]
+    fn parsing() {
+        let latin = |c: char| c.is_ascii_alphabetic();
+        assert_eq!(ParsePolicy::default(), ParsePolicy::Lenient);
+
+        let error = ParsePolicy::Strict
+            .prepare("WKLV lv", true, latin)
+            .unwrap_err();
+        assert_eq!(error.characters().len(), 2);
+        assert_eq!(error.characters()[0].char_index, 5);
+        assert!(matches!(
+            ParsePolicy::Strict.prepare("WKLV LV", true, latin),
+            Ok(Cow::Borrowed(_))
+        ));
+        // Alphabets that do not write ciphertexts in ALL CAPS have no case to enforce
+        assert!(ParsePolicy::Strict.prepare("Hi!", false, latin).is_ok());
+
+        assert!(matches!(
+            ParsePolicy::Lenient.prepare("WKLV lv!", true, latin),
+            Ok(Cow::Borrowed("WKLV lv!"))
+        ));
+
+        assert_eq!(
+            ParsePolicy::Permissive
+                .prepare("WKLV-lv, 2!", true, latin)
+                .unwrap(),
+            "WKLVlv"
+        );
+        assert!(matches!(
+            ParsePolicy::Permissive.prepare("WKLV LV", true, latin),
+            Ok(Cow::Borrowed(_))
+        ));
+    }
+}
@@ -0,0 +1,253 @@
+//! Polybius squares, i.e., grids of letters that encode each letter by its
+//! row and column.
+//!
+//! A Polybius square is the building block of several classical ciphers,
+//! such as the Polybius, Bifid, Nihilist and ADFGVX Ciphers, which encrypt the
+//! coordinates of the letters rather than the letters themselves.
+use crate::{
+    alphabet::{keyword_mixed, Alphabet, Alphanumeric, CustomAlphabet, Latin},
+    errors::AlphabetError,
+};
+use std::{collections::BTreeMap, fmt};
+
+/// A square grid of characters with row and column lookup.
+///
+/// The classical square is the 5x5 square of the Latin Alphabet, where `i`
+/// and `j` share a cell; the 6x6 square holds the Latin Alphabet and the
+/// decimal digits. Either square may be mixed with a keyword, as for the
+/// Playfair Cipher. Rows and columns are counted from 0, but are displayed
+/// from 1, following tradition.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::polybius::PolybiusSquare;
+/// let square = PolybiusSquare::latin();
+/// assert_eq!(square.position('c'), Some((0, 2)));
+/// assert_eq!(square.position('j'), square.position('i'));
+/// assert_eq!(square.letter(4, 4), Some('z'));
+///
+/// let mixed = PolybiusSquare::latin_keyword("playfair example").unwrap();
+/// assert_eq!(mixed.position('e'), Some((1, 2)));
+///
+/// let coordinates = square.encode("hello").unwrap();
+/// assert_eq!(coordinates, [(1, 2), (0, 4), (2, 0), (2, 0), (2, 3)]);
+/// assert_eq!(square.decode(&coordinates), Some("hello".to_string()));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PolybiusSquare {
+    size: usize,
+    cells: Vec<char>,
+    positions: BTreeMap<char, usize>,
+}
+
+impl PolybiusSquare {
+    /// Create a square of the given size from its characters, row by row.
+    ///
+    /// # Errors
+    /// This function returns an error if the number of characters is not
+    /// `size * size`, or if a character appears more than once.
+    pub fn new(size: usize, cells: &[char]) -> Result<Self, AlphabetError> {
+        if cells.len() != size * size || size == 0 {
+            return Err(AlphabetError::WrongSize {
+                size: cells.len(),
+                expected: size * size,
+            });
+        }
+
+        let alphabet = CustomAlphabet::from_chars(cells)?;
+        let positions = alphabet
+            .chars()
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (c, i))
+            .collect();
+
+        Ok(PolybiusSquare {
+            size,
+            cells: cells.to_vec(),
+            positions,
+        })
+    }
+
+    /// Create the 5x5 square of the Latin Alphabet in order, where `j` is
+    /// encoded as `i`.
+    pub fn latin() -> Self {
+        Self::latin_keyword("").expect("The empty keyword is valid")
+    }
+
+    /// Create the 5x5 square of the Latin Alphabet mixed with a keyword,
+    /// where `j` is encoded as `i`. The square is filled with the letters of
+    /// the keyword, skipping repeated letters and spaces, followed by the
+    /// remaining letters in order.
+    ///
+    /// # Errors
+    /// This function returns an error if the keyword contains a character
+    /// other than a lowercase letter or a space.
+    pub fn latin_keyword(keyword: &str) -> Result<Self, AlphabetError> {
+        let keyword =
+            letters::<Latin, 26>(keyword)?
+                .into_iter()
+                .map(|c| if c == 'j' { 'i' } else { c });
+        let alphabet = (0..26).filter_map(Latin::char_at).filter(|&c| c != 'j');
+        let mut square = Self::new(5, &keyword_mixed(keyword, alphabet))?;
+
+        let i = square.positions[&'i'];
+        let _ = square.positions.insert('j', i);
+        Ok(square)
+    }
+
+    /// Create the 6x6 square of the Latin Alphabet followed by the digits.
+    pub fn alphanumeric() -> Self {
+        Self::alphanumeric_keyword("").expect("The empty keyword is valid")
+    }
+
+    /// Create the 6x6 square of the Latin Alphabet and the digits mixed with
+    /// a keyword.
+    ///
+    /// # Errors
+    /// This function returns an error if the keyword contains a character
+    /// other than a lowercase letter, a digit or a space.
+    pub fn alphanumeric_keyword(keyword: &str) -> Result<Self, AlphabetError> {
+        let keyword = letters::<Alphanumeric, 36>(keyword)?;
+        let alphabet = (0..36).filter_map(Alphanumeric::char_at);
+        Self::new(6, &keyword_mixed(keyword, alphabet))
+    }
+
+    /// Get the number of rows (and columns) of the square.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Get the row and column of a character, if it is in the square.
+    pub fn position(&self, symbol: char) -> Option<(usize, usize)> {
+        self.positions
+            .get(&symbol)
+            .map(|&i| (i / self.size, i % self.size))
+    }
+
+    /// Get the character at a row and column, if they are in the square.
+    pub fn letter(&self, row: usize, col: usize) -> Option<char> {
+        (row < self.size && col < self.size).then(|| self.cells[row * self.size + col])
+    }
+
+    /// Encode a text as the rows and columns of its characters, skipping
+    /// spaces unless they are in the square.
+    ///
+    /// # Errors
+    /// This method returns an error for the first character that is not in
+    /// the square.
+    pub fn encode(&self, text: &str) -> Result<Vec<(usize, usize)>, AlphabetError> {
+        text.chars()
+            .filter(|&c| c != ' ' || self.positions.contains_key(&' '))
+            .map(|c| self.position(c).ok_or(AlphabetError::NotInAlphabet(c)))
+            .collect()
+    }
+
+    /// Decode rows and columns as a text, or return `None` if some row or
+    /// column is out of range.
+    pub fn decode(&self, coordinates: &[(usize, usize)]) -> Option<String> {
+        coordinates
+            .iter()
+            .map(|&(row, col)| self.letter(row, col))
+            .collect()
+    }
+}
+
+// Check that the characters of a keyword, other than spaces, are in the alphabet `A`.
+fn letters<A: Alphabet<M>, const M: u16>(keyword: &str) -> Result<Vec<char>, AlphabetError> {
+    keyword
+        .chars()
+        .filter(|&c| c != ' ')
+        .map(|c| {
+            A::index_of(c)
+                .map(|_| c)
+                .ok_or(AlphabetError::NotInAlphabet(c))
+        })
+        .collect()
+}
+
+/// Display the square as a grid, with rows and columns numbered from 1.
+impl fmt::Display for PolybiusSquare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, " ")?;
+        for col in 1..=self.size {
+            write!(f, " {col}")?;
+        }
+        for (row, cells) in self.cells.chunks(self.size).enumerate() {
+            write!(f, "\n{}", row + 1)?;
+            for c in cells {
+                write!(f, " {c}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latin() {
+        let square = PolybiusSquare::latin();
+        assert_eq!(square.size(), 5);
+        assert_eq!(square.position('a'), Some((0, 0)));
+        assert_eq!(square.position('k'), Some((1, 4)));
+        assert_eq!(square.position('l'), Some((2, 0)));
+        assert_eq!(square.position('A'), None);
+        assert_eq!(square.letter(1, 3), Some('i'));
+        assert_eq!(square.letter(5, 0), None);
+        assert_eq!(
+            square.decode(&square.encode("jam").unwrap()).unwrap(),
+            "iam"
+        );
+        assert_eq!(
+            square.to_string(),
+            "  1 2 3 4 5\n1 a b c d e\n2 f g h i k\n3 l m n o p\n4 q r s t u\n5 v w x y z"
+        );
+    }
+
+    #[test]
+    fn keyword() {
+        let square = PolybiusSquare::latin_keyword("playfair example").unwrap();
+        assert_eq!(
+            square.decode(&(0..5).map(|col| (0, col)).collect::<Vec<_>>()),
+            Some("playf".to_string())
+        );
+        assert_eq!(square.position('j'), square.position('i'));
+
+        let square = PolybiusSquare::alphanumeric_keyword("agent 007").unwrap();
+        assert_eq!(square.size(), 6);
+        assert_eq!(
+            square.decode(&[(0, 0), (0, 4), (0, 5)]),
+            Some("at0".to_string())
+        );
+        assert_eq!(square.position('9'), Some((5, 5)));
+
+        assert_eq!(
+            PolybiusSquare::latin_keyword("agent 007"),
+            Err(AlphabetError::NotInAlphabet('0'))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            PolybiusSquare::new(2, &['a', 'b', 'c']),
+            Err(AlphabetError::WrongSize {
+                size: 3,
+                expected: 4
+            })
+        );
+        assert_eq!(
+            PolybiusSquare::new(2, &['a', 'b', 'c', 'a']),
+            Err(AlphabetError::Repeated('a'))
+        );
+        assert!(PolybiusSquare::new(0, &[]).is_err());
+        assert_eq!(
+            PolybiusSquare::latin().encode("a!"),
+            Err(AlphabetError::NotInAlphabet('!'))
+        );
+        assert_eq!(PolybiusSquare::latin().decode(&[(0, 5)]), None);
+    }
+}
@@ -0,0 +1,20 @@
+//! Re-exports of the [`rand`] items that this crate is built against.
+//!
+//! Key generation is generic over [`RngCore`] and [`CryptoRng`], and these
+//! traits must come from the same version of `rand_core` as this crate uses.
+//! Depending on `rand` directly means keeping its version in step with ours;
+//! using these re-exports instead guarantees a compatible RNG.
+//!
+//! This module is only available with the `rand-reexport` feature.
+//!
+//! # Examples
+//! ```
+//! use classical_crypto::{rand::thread_rng, shift::Key, KeyTrait};
+//!
+//! let mut rng = thread_rng();
+//! let key = Key::new(&mut rng);
+//! ```
+pub use ::rand::{
+    rngs::{OsRng, ThreadRng},
+    thread_rng, CryptoRng, Rng, RngCore,
+};
@@ -0,0 +1,170 @@
+//! A registry of the ciphers that can be used through the object-safe
+//! [`DynCipher`] interface, keyed by name, so that frontends can discover the
+//! available ciphers at runtime, together with the attacks on them.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::registry;
+//! for entry in registry::list() {
+//!     println!("{}: {}", entry.name(), entry.description());
+//!     for attack in entry.attacks() {
+//!         println!("  {} ({})", attack.name(), attack.model());
+//!     }
+//! }
+//!
+//! let shift = registry::get("shift").unwrap();
+//! let key = shift.import_key("11").unwrap();
+//! assert_eq!(shift.encrypt("wewillmeetatmidnight", &key).unwrap(), "HPHTWWXPPELEXTOYTRSE");
+//! ```
+use crate::{
+    alberti::AlbertiCipher,
+    attack::{Attack, AttackModel},
+    dynamic::DynCipher,
+    playfair::{self, PlayfairCipher},
+    shift::{self, ShiftCipher},
+};
+
+/// An attack on a cipher in the registry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AttackEntry {
+    name: &'static str,
+    model: AttackModel,
+}
+
+impl AttackEntry {
+    // Describe the attack `A`.
+    const fn of<A: Attack>() -> Self {
+        AttackEntry {
+            name: A::NAME,
+            model: A::MODEL,
+        }
+    }
+
+    /// Get the name of the attack, e.g., `"brute force"`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the information available to the attacker.
+    pub fn model(&self) -> AttackModel {
+        self.model
+    }
+}
+
+/// A cipher in the registry, with a factory function that creates it and the
+/// attacks on it.
+#[derive(Clone, Copy, Debug)]
+pub struct CipherEntry {
+    name: &'static str,
+    description: &'static str,
+    factory: fn() -> Box<dyn DynCipher>,
+    attacks: &'static [AttackEntry],
+}
+
+impl CipherEntry {
+    /// Get the name of the cipher, e.g., `"shift"`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get a short description of the cipher.
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// List the attacks on the cipher that the crate implements.
+    pub fn attacks(&self) -> &'static [AttackEntry] {
+        self.attacks
+    }
+
+    /// Create the cipher.
+    pub fn create(&self) -> Box<dyn DynCipher> {
+        (self.factory)()
+    }
+}
+
+// The registered ciphers, in the order in which frontends list them. The names match the names
+// used by the `config` module.
+static CIPHERS: [CipherEntry; 3] = [
+    CipherEntry {
+        name: "shift",
+        description: "The Latin Shift Cipher, which shifts each letter by the key",
+        factory: || Box::new(ShiftCipher::new()),
+        attacks: &[
+            AttackEntry::of::<shift::BruteForce>(),
+            AttackEntry::of::<shift::KnownPlaintext>(),
+            AttackEntry::of::<shift::ChosenPlaintext>(),
+        ],
+    },
+    CipherEntry {
+        name: "playfair",
+        description: "The Playfair Cipher, which encrypts pairs of letters with a 5x5 square",
+        factory: || Box::new(PlayfairCipher::new()),
+        attacks: &[AttackEntry::of::<playfair::SimulatedAnnealing>()],
+    },
+    CipherEntry {
+        name: "alberti",
+        description: "The Alberti Cipher, which rotates a cipher disk as it encrypts",
+        factory: || Box::new(AlbertiCipher::new()),
+        attacks: &[],
+    },
+];
+
+/// List the registered ciphers.
+pub fn list() -> &'static [CipherEntry] {
+    &CIPHERS
+}
+
+/// Create the cipher with the given name, or return `None` if there is no
+/// such cipher.
+pub fn get(name: &str) -> Option<Box<dyn DynCipher>> {
+    CIPHERS
+        .iter()
+        .find(|entry| entry.name == name)
+        .map(CipherEntry::create)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let names: Vec<_> = list().iter().map(CipherEntry::name).collect();
+        assert_eq!(names, ["shift", "playfair", "alberti"]);
+
+        for entry in list() {
+            assert_eq!(entry.create().name(), entry.name());
+            assert_eq!(entry.create().info().name, entry.name());
+            assert_eq!(get(entry.name()).unwrap().name(), entry.name());
+        }
+        assert!(get("enigma").is_none());
+        assert!(get("Shift").is_none());
+    }
+
+    #[test]
+    fn attacks() {
+        let attacks: Vec<_> = list()
+            .iter()
+            .map(|entry| {
+                entry
+                    .attacks()
+                    .iter()
+                    .map(|attack| (attack.name(), attack.model()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            attacks,
+            [
+                vec![
+                    ("brute force", AttackModel::CiphertextOnly),
+                    ("known plaintext", AttackModel::KnownPlaintext),
+                    ("chosen plaintext", AttackModel::ChosenPlaintext),
+                ],
+                vec![("simulated annealing", AttackModel::CiphertextOnly)],
+                vec![],
+            ]
+        );
+    }
+}
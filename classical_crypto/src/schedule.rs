@@ -0,0 +1,176 @@
+//! Key schedules, which derive the keys of several ciphers from one master
+//! secret, so that ciphers applied one after the other can be keyed with a
+//! single string.
+//!
+//! A [`KeySchedule`] holds a secret. Its [`child`](KeySchedule::child)
+//! schedules hold secrets derived from it and an index, so schedules form a
+//! tree: a chain of ciphers takes the key of its _i_-th cipher from the
+//! _i_-th child, and a chain nested in another chain takes its own schedule
+//! from the child at its position. Keys are derived with
+//! [`KeyTrait::derive_from_passphrase`], so the same master secret always
+//! gives the same keys.
+//!
+//! This is NOT secure, for the reasons given in
+//! [`derive_from_passphrase`](KeyTrait::derive_from_passphrase): anyone who
+//! guesses the master secret has every key, and the keys are only as hard to
+//! guess as the master secret.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{dynamic::DynCipher, registry, schedule::KeySchedule};
+//! let shift = registry::get("shift").unwrap();
+//! let playfair = registry::get("playfair").unwrap();
+//! let ciphers = [shift.as_ref(), playfair.as_ref()];
+//!
+//! let schedule = KeySchedule::new("tempest");
+//! let keys = schedule.dyn_keys(&ciphers);
+//!
+//! let ciphertxt = ciphers
+//!     .iter()
+//!     .zip(&keys)
+//!     .try_fold("meetmeatmidnight".to_string(), |text, (cipher, key)| {
+//!         cipher.encrypt(&text.to_lowercase(), key)
+//!     })
+//!     .unwrap();
+//!
+//! // The other party derives the same keys from the same string
+//! let keys = KeySchedule::new("tempest").dyn_keys(&ciphers);
+//! let plaintxt = ciphers
+//!     .iter()
+//!     .zip(&keys)
+//!     .rev()
+//!     .try_fold(ciphertxt, |text, (cipher, key)| cipher.decrypt(&text, key))
+//!     .unwrap();
+//! assert_eq!(plaintxt.to_lowercase(), "meetmeatmidnight");
+//! ```
+use crate::{
+    dynamic::{DynCipher, DynKey},
+    secret::Secret,
+    KeyTrait,
+};
+
+/// A master secret from which the keys of several ciphers are derived.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeySchedule {
+    secret: Secret<String>,
+}
+
+impl KeySchedule {
+    /// Create a key schedule from a master secret.
+    pub fn new(master: &str) -> Self {
+        KeySchedule {
+            secret: Secret::new(master.to_string()),
+        }
+    }
+
+    /// Get the child schedule at `index`, whose secret is derived from the
+    /// secret of `self` and the index.
+    pub fn child(&self, index: usize) -> Self {
+        // Prefix the secret with its length, so that no two pairs of a secret and an index give the
+        // same string
+        let secret = self.secret.expose_secret();
+        KeySchedule {
+            secret: Secret::new(format!("{}:{secret}:{index}", secret.len())),
+        }
+    }
+
+    /// Derive the key at `index`, i.e., the key of the child schedule at
+    /// `index`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{playfair, schedule::KeySchedule, shift};
+    /// let schedule = KeySchedule::new("tempest");
+    /// let first: shift::Key = schedule.key(0);
+    /// let second: playfair::Key = schedule.key(1);
+    ///
+    /// assert_eq!(first, KeySchedule::new("tempest").key(0));
+    /// ```
+    pub fn key<K: KeyTrait>(&self, index: usize) -> K {
+        K::derive_from_passphrase(self.child(index).secret.expose_secret())
+    }
+
+    /// Derive the key at `index` for a cipher chosen at runtime.
+    pub fn dyn_key(&self, index: usize, cipher: &dyn DynCipher) -> DynKey {
+        cipher.derive_key(self.child(index).secret.expose_secret())
+    }
+
+    /// Derive a key for each of the ciphers, in order, taking the key of the
+    /// _i_-th cipher from index _i_.
+    pub fn dyn_keys(&self, ciphers: &[&dyn DynCipher]) -> Vec<DynKey> {
+        ciphers
+            .iter()
+            .enumerate()
+            .map(|(index, cipher)| self.dyn_key(index, *cipher))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alberti, playfair, registry};
+
+    #[test]
+    fn deterministic() {
+        let schedule = KeySchedule::new("tempest");
+        let other = KeySchedule::new("tempest");
+        assert_eq!(schedule, other);
+        assert_eq!(schedule.key::<alberti::Key>(2), other.key(2));
+        assert_eq!(
+            schedule.child(3).key::<playfair::Key>(0),
+            other.child(3).key(0)
+        );
+
+        let playfair = registry::get("playfair").unwrap();
+        assert_eq!(
+            playfair
+                .export_key(&schedule.dyn_key(1, playfair.as_ref()))
+                .unwrap(),
+            schedule.key::<playfair::Key>(1).export()
+        );
+    }
+
+    #[test]
+    fn independent() {
+        let schedule = KeySchedule::new("tempest");
+        let keys: Vec<playfair::Key> = (0..10).map(|index| schedule.key(index)).collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(keys[i + 1..].iter().all(|other| other != key));
+        }
+
+        assert_ne!(
+            schedule.key::<playfair::Key>(0),
+            KeySchedule::new("tempests").key(0)
+        );
+        assert_ne!(schedule.key::<playfair::Key>(0), schedule.child(0).key(0));
+
+        // The length prefix keeps a secret and an index apart
+        assert_ne!(
+            KeySchedule::new("a:1").child(2),
+            KeySchedule::new("a").child(1).child(2)
+        );
+    }
+
+    #[test]
+    fn redacted() {
+        let schedule = KeySchedule::new("tempest");
+        assert!(!format!("{schedule:?}").contains("tempest"));
+        assert!(!format!("{:?}", schedule.child(1)).contains("tempest"));
+    }
+
+    #[test]
+    fn dyn_keys() {
+        let ciphers: Vec<_> = registry::list()
+            .iter()
+            .map(|entry| entry.create())
+            .collect();
+        let ciphers: Vec<&dyn DynCipher> = ciphers.iter().map(|cipher| cipher.as_ref()).collect();
+
+        let keys = KeySchedule::new("tempest").dyn_keys(&ciphers);
+        assert_eq!(keys.len(), ciphers.len());
+        for (cipher, key) in ciphers.iter().zip(&keys) {
+            assert_eq!(key.cipher(), cipher.name());
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! A wrapper for secret values, such as key material, that keeps them out of
+//! logs.
+//!
+//! Formatting a [`Secret`] with `{:?}` or `{}` writes `<redacted>` rather
+//! than the value, so a key that is printed by mistake, e.g., in a debug log
+//! or a failed assertion, does not leak. The value is only reachable through
+//! [`expose_secret`](Secret::expose_secret), which makes every use of the
+//! secret easy to find.
+//!
+//! The keys of the ciphers in this crate hold their material in a `Secret`,
+//! so that they print as, e.g., `Key(<redacted>)`. This does not protect the
+//! key in any other way: it is still in memory, and
+//! [`export`](crate::KeyTrait::export) still writes it out.
+//!
+//! # Comparing secrets
+//! The keys of the ciphers in this crate implement
+//! [`ConstantTimeEq`](subtle::ConstantTimeEq), and `==` on keys uses it: the
+//! time it takes to compare two keys does not depend on where they first
+//! differ. A derived `==` stops at the first difference, so an attacker who
+//! can submit guesses to be compared with a secret, and time the answers, can
+//! learn the secret one letter at a time, which takes far fewer guesses than
+//! trying whole keys. This is a real attack on real systems, and classical
+//! keys are a good place to practise the habit, even though the key spaces of
+//! the ciphers here are small enough to search outright.
+//!
+//! The comparison of a [`Secret`] itself is the `==` of its value, which need
+//! not take constant time.
+//!
+//! # Redacting messages
+//! Plaintexts are sensitive too, but a program that hides them completely
+//! cannot tell from its logs which message it was working on. A
+//! [`Redacted`] value, e.g., a wrapped [`shift::Message`](crate::shift::Message),
+//! is formatted as its length and a hash, which are enough to tell messages
+//! apart, but not to read them.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{secret::Secret, shift::Key};
+//! # use std::str::FromStr;
+//! let secret = Secret::new(11);
+//! assert_eq!(format!("{secret:?}"), "<redacted>");
+//! assert_eq!(*secret.expose_secret(), 11);
+//!
+//! let key = Key::from_str("11").unwrap();
+//! assert_eq!(format!("{key:?}"), "Key(<redacted>)");
+//! ```
+use crate::fnv1a;
+use std::fmt;
+
+/// A secret value, which is redacted when formatted.
+#[derive(Clone, Default, Eq, Hash, PartialEq)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wrap a secret value.
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+
+    /// Get the secret value. Take care not to log or print it.
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+/// A value, such as a message, that is formatted as the length and a hash of
+/// its text rather than as the text itself.
+///
+/// The length is the number of `char`s that the value is written with, and
+/// the hash is the 64-bit FNV-1a hash of its text, so equal messages have
+/// equal hashes. The hash is fast rather than cryptographic, and is not
+/// salted: anyone who can guess a short message can check the guess against
+/// the log, so this keeps messages out of sight, not secret.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{secret::Redacted, shift::Message};
+/// let msg = Redacted::new(Message::new("wewillmeetatmidnight").unwrap());
+///
+/// let logged = format!("{msg:?}");
+/// assert!(logged.starts_with("Redacted { len: 20, hash: "));
+/// assert!(!logged.contains("midnight"));
+/// assert_eq!(msg.inner().to_string(), "wewillmeetatmidnight");
+/// ```
+#[derive(Clone, Default, Eq, Hash, PartialEq)]
+pub struct Redacted<T>(T);
+
+impl<T: fmt::Display> Redacted<T> {
+    /// Wrap a value.
+    pub fn new(value: T) -> Self {
+        Redacted(value)
+    }
+
+    /// Get the value. Take care not to log or print it.
+    pub fn inner(&self) -> &T {
+        &self.0
+    }
+
+    /// Drop the wrapper and return the value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: fmt::Display> From<T> for Redacted<T> {
+    fn from(value: T) -> Self {
+        Redacted(value)
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.0.to_string();
+        f.debug_struct("Redacted")
+            .field("len", &text.chars().count())
+            .field("hash", &format_args!("{:016x}", fnv1a(text.bytes())))
+            .finish()
+    }
+}
+
+/// Display the length and hash of the value, as [`Debug`](fmt::Debug) does.
+impl<T: fmt::Display> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alberti, playfair, shift, KeyTrait};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use subtle::ConstantTimeEq;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn redacted() {
+        let secret = Secret::new("hunter2");
+        assert_eq!(format!("{secret:?}"), "<redacted>");
+        assert_eq!(format!("{secret:#?}"), "<redacted>");
+        assert_eq!(secret.to_string(), "<redacted>");
+        assert_eq!(*secret.expose_secret(), "hunter2");
+        assert_eq!(Secret::from("hunter2"), secret);
+    }
+
+    #[test]
+    fn messages_are_redacted() {
+        let msg = shift::Message::new("attackatdawn").unwrap();
+        let redacted = Redacted::new(msg.clone());
+        let logged = format!("{redacted:?}");
+        assert_eq!(logged, redacted.to_string());
+        assert!(logged.starts_with("Redacted { len: 12, hash: "));
+        assert!(!logged.contains("attack"));
+
+        // Equal messages log the same way, and different ones do not
+        assert_eq!(format!("{:?}", Redacted::from(msg.clone())), logged);
+        let other = Redacted::new(shift::Message::new("attackatdusk").unwrap());
+        assert_ne!(format!("{other:?}"), logged);
+
+        assert_eq!(redacted.inner(), &msg);
+        assert_eq!(redacted.into_inner(), msg);
+    }
+
+    #[test]
+    fn keys_are_redacted() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        assert_eq!(
+            format!("{:?}", shift::Key::new(&mut rng)),
+            "Key(<redacted>)"
+        );
+        assert_eq!(
+            format!("{:?}", playfair::Key::new(&mut rng)),
+            "Key(<redacted>)"
+        );
+        assert_eq!(
+            format!("{:?}", alberti::Key::new(&mut rng)),
+            "Key(<redacted>)"
+        );
+    }
+
+    #[test]
+    fn keys_compare_in_constant_time() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        for _ in 0..10 {
+            let (key1, key2) = (shift::Key::new(&mut rng), shift::Key::new(&mut rng));
+            let copy = shift::Key::try_import(&key1.export()).unwrap();
+            assert!(bool::from(key1.ct_eq(&copy)) && key1 == copy);
+            assert_eq!(
+                bool::from(key1.ct_eq(&key2)),
+                key1.export() == key2.export()
+            );
+
+            let (key1, key2) = (playfair::Key::new(&mut rng), playfair::Key::new(&mut rng));
+            let copy = playfair::Key::try_import(&key1.export()).unwrap();
+            assert!(bool::from(key1.ct_eq(&copy)) && key1 == copy);
+            assert!(!bool::from(key1.ct_eq(&key2)) && key1 != key2);
+
+            let (key1, key2) = (alberti::Key::new(&mut rng), alberti::Key::new(&mut rng));
+            let copy = alberti::Key::try_import(&key1.export()).unwrap();
+            assert!(bool::from(key1.ct_eq(&copy)) && key1 == copy);
+            assert!(!bool::from(key1.ct_eq(&key2)) && key1 != key2);
+        }
+    }
+}
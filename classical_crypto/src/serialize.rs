@@ -0,0 +1,174 @@
+//! Serialization of keys with [`serde`], e.g., to store keys in JSON or TOML
+//! configuration files, or to exchange them between tools.
+//!
+//! Each key is written as a record with three fields, which do not depend on
+//! the layout of the key types:
+//! - `cipher`: the name of the cipher, as in [`CipherInfo::name`],
+//! - `version`: the version of the format, i.e., [`KEY_FORMAT_VERSION`],
+//! - `key`: the key as written by [`KeyTrait::export`].
+//!
+//! Deserializing checks the name of the cipher and the version, so that a key
+//! of one cipher is never read as a key of another. Like
+//! [`export`](KeyTrait::export), this does nothing to protect the key.
+//!
+//! This module is only available with the `serde-keys` feature.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::shift::Key;
+//! # use std::str::FromStr;
+//! let key = Key::from_str("3").unwrap();
+//!
+//! let toml = toml::to_string(&key).unwrap();
+//! assert_eq!(toml, "cipher = \"shift\"\nversion = 1\nkey = \"3\"\n");
+//! assert_eq!(toml::from_str::<Key>(&toml).unwrap(), key);
+//! ```
+use crate::{
+    alberti::{self, AlbertiCipher},
+    playfair::{self, PlayfairCipher},
+    shift::{self, ShiftCipher},
+    CipherInfo, CipherTrait, KeyTrait,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The version of the format of serialized keys. It changes only if a key
+/// written in the format could no longer be read.
+pub const KEY_FORMAT_VERSION: u32 = 1;
+
+// The record that each key is written as.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct KeyRecord {
+    cipher: String,
+    version: u32,
+    key: String,
+}
+
+// Write `key` as a record for the cipher `C`.
+fn serialize_key<C: CipherTrait, S: Serializer>(
+    key: &C::Key,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    KeyRecord {
+        cipher: C::info().name.to_string(),
+        version: KEY_FORMAT_VERSION,
+        key: key.export(),
+    }
+    .serialize(serializer)
+}
+
+// Read a key of the cipher `C` from a record, checking the cipher and the version.
+fn deserialize_key<'de, C: CipherTrait, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<C::Key, D::Error> {
+    let record = KeyRecord::deserialize(deserializer)?;
+    let CipherInfo { name, .. } = C::info();
+
+    if record.cipher != name {
+        return Err(de::Error::custom(format!(
+            "expected a key for the {name} cipher, found a key for the {} cipher",
+            record.cipher
+        )));
+    }
+    if record.version != KEY_FORMAT_VERSION {
+        return Err(de::Error::custom(format!(
+            "unsupported key format version {}, expected {KEY_FORMAT_VERSION}",
+            record.version
+        )));
+    }
+
+    C::Key::try_import(&record.key).map_err(de::Error::custom)
+}
+
+impl Serialize for shift::Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_key::<ShiftCipher, _>(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for shift::Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_key::<ShiftCipher, _>(deserializer)
+    }
+}
+
+impl Serialize for playfair::Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_key::<PlayfairCipher, _>(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for playfair::Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_key::<PlayfairCipher, _>(deserializer)
+    }
+}
+
+impl Serialize for alberti::Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_key::<AlbertiCipher, _>(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for alberti::Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_key::<AlbertiCipher, _>(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Keys {
+        shift: shift::Key,
+        playfair: playfair::Key,
+        alberti: alberti::Key,
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        for _ in 0..10 {
+            let keys = Keys {
+                shift: shift::Key::new(&mut rng),
+                playfair: playfair::Key::new(&mut rng),
+                alberti: alberti::Key::new(&mut rng),
+            };
+
+            let toml = toml::to_string(&keys).unwrap();
+            assert_eq!(toml::from_str::<Keys>(&toml).unwrap(), keys);
+        }
+    }
+
+    #[test]
+    fn format() {
+        let key = playfair::Key::try_import("playfirexmbcdghknoqstuvwz").unwrap();
+        assert_eq!(
+            toml::to_string(&key).unwrap(),
+            "cipher = \"playfair\"\nversion = 1\nkey = \"playfirexmbcdghknoqstuvwz\"\n"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |toml: &str| toml::from_str::<shift::Key>(toml).unwrap_err().to_string();
+
+        assert!(error("cipher = \"playfair\"\nversion = 1\nkey = \"3\"")
+            .contains("expected a key for the shift cipher, found a key for the playfair cipher"));
+        assert!(error("cipher = \"shift\"\nversion = 2\nkey = \"3\"")
+            .contains("unsupported key format version 2, expected 1"));
+        assert!(error("cipher = \"shift\"\nversion = 1\nkey = \"26\"").contains("26"));
+        assert!(error("cipher = \"shift\"\nversion = 1").contains("missing field `key`"));
+        assert!(
+            error("cipher = \"shift\"\nversion = 1\nkey = \"3\"\nextra = 0")
+                .contains("unknown field `extra`")
+        );
+    }
+}
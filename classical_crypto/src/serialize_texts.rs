@@ -0,0 +1,223 @@
+//! Serialization of messages and ciphertexts with [`serde`], e.g., for test
+//! fixtures in JSON, saved sessions, or sending ciphertexts over a network.
+//!
+//! Each text is written as a string in its canonical form, i.e., as written
+//! by its [`Display`] implementation, e.g., `wewillmeetatmidnight` for a
+//! message of the Shift Cipher and `HPHTWWXPPELEXTOYTRSE` for a ciphertext.
+//! Deserializing parses the string with the [`FromStr`] implementation of the
+//! text, so it accepts the same strings, e.g., spaces are skipped.
+//!
+//! An empty text, such as `Message::default()`, is written as the empty
+//! string, which does not parse as a text, so it cannot be read back.
+//!
+//! This module is only available with the `serde-texts` feature.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::shift::{Ciphertext, Key, Message, ShiftCipher};
+//! # use classical_crypto::CipherTrait;
+//! # use serde::{Deserialize, Serialize};
+//! # use std::str::FromStr;
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Fixture {
+//!     msg: Message,
+//!     ciphertxt: Ciphertext,
+//! }
+//!
+//! let msg = Message::new("we will meet at midnight").unwrap();
+//! let ciphertxt = ShiftCipher::new().encrypt(&msg, &Key::from_str("11").unwrap()).unwrap();
+//! let fixture = Fixture { msg, ciphertxt };
+//!
+//! let toml = toml::to_string(&fixture).unwrap();
+//! assert_eq!(toml, "msg = \"wewillmeetatmidnight\"\nciphertxt = \"HPHTWWXPPELEXTOYTRSE\"\n");
+//! assert_eq!(toml::from_str::<Fixture>(&toml).unwrap(), fixture);
+//! ```
+use crate::{alberti, bytes, playfair, shift};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt::Display, str::FromStr};
+
+// Write a text as its canonical string.
+fn serialize_text<T: Display, S: Serializer>(text: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(text)
+}
+
+// Read a text from a string.
+fn deserialize_text<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    T::from_str(&s).map_err(de::Error::custom)
+}
+
+impl Serialize for shift::Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for shift::Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_text(deserializer)
+    }
+}
+
+impl Serialize for shift::Ciphertext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for shift::Ciphertext {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_text(deserializer)
+    }
+}
+
+impl Serialize for playfair::Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for playfair::Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_text(deserializer)
+    }
+}
+
+impl Serialize for playfair::Ciphertext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for playfair::Ciphertext {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_text(deserializer)
+    }
+}
+
+impl Serialize for alberti::Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for alberti::Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_text(deserializer)
+    }
+}
+
+impl Serialize for alberti::Ciphertext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for alberti::Ciphertext {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_text(deserializer)
+    }
+}
+
+impl Serialize for bytes::Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for bytes::Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_text(deserializer)
+    }
+}
+
+impl Serialize for bytes::Ciphertext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for bytes::Ciphertext {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_text(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CipherTrait, KeyTrait};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Texts {
+        shift: (shift::Message, shift::Ciphertext),
+        playfair: (playfair::Message, playfair::Ciphertext),
+        alberti: (alberti::Message, alberti::Ciphertext),
+        bytes: (bytes::Message, bytes::Ciphertext),
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        for _ in 0..10 {
+            let msg = shift::Message::from_str("attackatdawn").unwrap();
+            let shift = (
+                msg.clone(),
+                shift::ShiftCipher::new()
+                    .encrypt(&msg, &shift::Key::new(&mut rng))
+                    .unwrap(),
+            );
+            let msg = playfair::Message::from_str("hidethegold").unwrap();
+            let playfair = (
+                msg.clone(),
+                playfair::PlayfairCipher::new()
+                    .encrypt(&msg, &playfair::Key::new(&mut rng))
+                    .unwrap(),
+            );
+            let msg = alberti::Message::from_str("decifris").unwrap();
+            let alberti = (
+                msg.clone(),
+                alberti::AlbertiCipher::new()
+                    .encrypt(&msg, &alberti::Key::new(&mut rng))
+                    .unwrap(),
+            );
+            let bytes = (
+                bytes::Message::from_bytes(b"\x00\xff\"binary\"\n"),
+                bytes::Ciphertext::from_bytes(b"\x7f\\"),
+            );
+            let texts = Texts {
+                shift,
+                playfair,
+                alberti,
+                bytes,
+            };
+
+            let toml = toml::to_string(&texts).unwrap();
+            assert_eq!(toml::from_str::<Texts>(&toml).unwrap(), texts);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        #[derive(Debug, Deserialize)]
+        struct Fixture {
+            #[allow(dead_code)]
+            ciphertxt: shift::Ciphertext,
+        }
+
+        let error = |toml: &str| toml::from_str::<Fixture>(toml).unwrap_err().to_string();
+        assert!(error("ciphertxt = \"HELLO!\"").contains("!"));
+        assert!(error("ciphertxt = 3").contains("invalid type"));
+        assert!(error("ciphertxt = \"\"").contains("ciphertxt"));
+    }
+}
@@ -0,0 +1,86 @@
+//! Sessions of ciphers whose internal state evolves with each symbol.
+//!
+//! [`CipherTrait`](crate::CipherTrait) describes encryption as a pure
+//! function of a whole message and a key. Many ciphers are more naturally
+//! described as machines that change state after every symbol, e.g., the
+//! Alberti Cipher rotates its disk, the Autokey Cipher feeds the message back
+//! into the key, and the Enigma steps its rotors. A [`CipherSession`] is such
+//! a machine: it encrypts or decrypts one symbol at a time, and its state can
+//! be reset to the start, or saved and restored to replay part of a text.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{alberti::{AlbertiCipher, Ciphertext, Key, Message, Session}, session::CipherSession, CipherTrait, KeyTrait};
+//! # use rand::thread_rng;
+//! let key = Key::new(&mut thread_rng());
+//! let msg = Message::new("de cifris").unwrap();
+//! let mut session = Session::new(&key);
+//!
+//! // Saving the state and restoring it replays the same encryption
+//! let first = session.encrypt_symbol(msg[0]);
+//! let state = session.snapshot();
+//! let second = session.encrypt_symbol(msg[1]);
+//! session.restore(state);
+//! assert_eq!(session.encrypt_symbol(msg[1]), second);
+//!
+//! // Resetting the session starts over, as encryption of a whole message does
+//! session.reset();
+//! let ciphertxt: Ciphertext = session.encrypt_all(msg.iter().copied());
+//! assert_eq!(ciphertxt[0], first);
+//! assert_eq!(ciphertxt, AlbertiCipher::new().encrypt(&msg, &key).unwrap());
+//! ```
+use crate::RingElement;
+
+/// A running encryption or decryption with a cipher whose state evolves with
+/// each symbol.
+///
+/// The same session should not be used to both encrypt and decrypt without a
+/// [`reset`](CipherSession::reset) in between, since both advance the state.
+pub trait CipherSession {
+    /// A snapshot of the state of the session.
+    type State: Clone;
+
+    /// Encrypt the next symbol of a message, and advance the state.
+    fn encrypt_symbol(&mut self, x: RingElement) -> RingElement;
+
+    /// Decrypt the next symbol of a ciphertext, and advance the state.
+    fn decrypt_symbol(&mut self, y: RingElement) -> RingElement;
+
+    /// Return the session to its state before the first symbol.
+    fn reset(&mut self);
+
+    /// Save the current state of the session.
+    fn snapshot(&self) -> Self::State;
+
+    /// Return the session to a state saved by
+    /// [`snapshot`](CipherSession::snapshot).
+    fn restore(&mut self, state: Self::State);
+
+    /// Encrypt each symbol in turn, starting from the current state, and
+    /// collect the results, e.g., into a ciphertext.
+    fn encrypt_all<I, T>(&mut self, symbols: I) -> T
+    where
+        Self: Sized,
+        I: IntoIterator<Item = RingElement>,
+        T: FromIterator<RingElement>,
+    {
+        symbols
+            .into_iter()
+            .map(|x| self.encrypt_symbol(x))
+            .collect()
+    }
+
+    /// Decrypt each symbol in turn, starting from the current state, and
+    /// collect the results, e.g., into a message.
+    fn decrypt_all<I, T>(&mut self, symbols: I) -> T
+    where
+        Self: Sized,
+        I: IntoIterator<Item = RingElement>,
+        T: FromIterator<RingElement>,
+    {
+        symbols
+            .into_iter()
+            .map(|y| self.decrypt_symbol(y))
+            .collect()
+    }
+}
@@ -4,10 +4,25 @@
 //! using modular arithmetic) of the corresponding plaintexts, so the _key
 //! space_ is &#x2124;/26&#x2124; as well.
 use crate::{
-    CipherTrait, Ciphertext as Ciphtxt, EncodingError, KeyTrait, Message as Msg, Ring, RingElement,
+    alphabet::{Alphabet, CustomAlphabet, Latin},
+    attack::{
+        Attack, AttackModel, CancellationToken, Candidates, ChosenPlaintextAttack,
+        CiphertextOnlyAttack, KnownPlaintextAttack, Monitor, ProgressSink,
+    },
+    corpus::LanguageModel,
+    errors::{AlphabetError, InvalidCharacter, InvalidCharacters, StreamError},
+    keyfile::KeyFile,
+    policy::{CipherSettings, MessagePolicy},
+    secret::Secret,
+    stream,
+    trace::{Step, Trace, TracedCipher},
+    verify::SampleMessage,
+    CipherInfo, CipherTrait, Ciphertext as Ciphtxt, ComposableKey, EncodingError, KeyTrait,
+    Message as Msg, Ring, RingElement, WeakKey,
 };
-use rand::{CryptoRng, Rng};
-use std::{fmt::Display, str::FromStr};
+use rand::{CryptoRng, RngCore};
+use std::{convert::Infallible, fmt::Display, io::Read, marker::PhantomData, str::FromStr};
+use subtle::{Choice, ConstantTimeEq};
 
 /// The ciphertext space for the Latin Shift Cipher.
 // Notes:
@@ -20,24 +35,7 @@ use std::{fmt::Display, str::FromStr};
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Ciphertext(Ciphtxt);
 
-impl FromStr for Ciphertext {
-    type Err = EncodingError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Ciphertext(Ciphtxt::from_str(s)?))
-    }
-}
-
-impl Display for Ciphertext {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Ciphtxt::fmt(&self.0, f)
-    }
-}
-
-impl FromIterator<RingElement> for Ciphertext {
-    fn from_iter<I: IntoIterator<Item = RingElement>>(iter: I) -> Self {
-        Ciphertext(Ciphtxt::from_iter(iter))
-    }
-}
+crate::text_api!(ciphertext Ciphertext in "shift");
 
 /// The message space of the Latin Shift Cipher.
 // Notes:
@@ -52,15 +50,18 @@ impl FromIterator<RingElement> for Ciphertext {
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Message(Msg);
 
+crate::text_api!(message Message in "shift");
+
 impl Message {
     /// Create a new message from a string.
     /// # Examples
     /// ```
     /// // Creating this example shows how awkward our API is.
-    /// // We can't use spaces, punctuation, or capital letters.
+    /// // We can't use punctuation or capital letters, and spaces are dropped.
     /// // That said, humans are very quick at understanding mashed up plaintexts
     /// // without punctuation and spacing.
     /// // Computers have to check dictionaries.
+    /// // `Message::new_lossy` cleans up ordinary text instead.
     /// # use classical_crypto::shift::Message;
     /// # use rand::thread_rng;
     /// let msg = Message::new("thisisanawkwardapichoice").expect("This example is hardcoded; it should work!");
@@ -73,33 +74,29 @@ impl Message {
     }
 }
 
-impl FromStr for Message {
-    type Err = EncodingError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Message(Msg::from_str(s)?))
-    }
-}
+/// A cryptographic key for the Latin Shift Cipher.
+// Keys should always carry context: see `context::ContextKey`.
+// We *could* implement `Copy` and `Clone` here.
+// We do not because we want to discourage making copies of secrets.
+// The shift is held in a `Secret`, so that the key prints as `Key(<redacted>)`.
+// However there is a lot more to best practices for handling keys than this.
+#[derive(Debug)]
+pub struct Key(Secret<RingElement>);
 
-impl Display for Message {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Msg::fmt(&self.0, f)
+// Keys are compared in constant time, see the `secret` module.
+impl ConstantTimeEq for Key {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.shift().ct_eq(&other.shift())
     }
 }
 
-impl FromIterator<RingElement> for Message {
-    fn from_iter<I: IntoIterator<Item = RingElement>>(iter: I) -> Self {
-        Message(Msg::from_iter(iter))
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
     }
 }
 
-/// A cryptographic key for the Latin Shift Cipher.
-// Crypto TODO: Keys should always contain context.
-// We *could* implement `Copy` and `Clone` here.
-// We do not because we want to discourage making copies of secrets.
-// However there is a lot more to best practices for handling keys than this.
-#[derive(Debug, Eq, PartialEq)]
-pub struct Key(RingElement);
+impl Eq for Key {}
 
 // TODO: refactor, prep for Substitution Cipher
 impl KeyTrait for Key {
@@ -129,48 +126,555 @@ impl KeyTrait for Key {
     /// ```
     // Note: Keys must always be chosen according to a uniform distribution on the
     // underlying key space, i.e., the ring Z/26Z for the Latin Alphabet cipher.
-    fn new<R: Rng + CryptoRng>(rng: &mut R) -> Self {
-        Self(RingElement::random(rng))
+    fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::from(RingElement::random(rng))
+    }
+
+    /// Export the cryptographic key, insecurely, as an integer between 0 and
+    /// 25.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{KeyTrait, shift::Key};
+    /// # // Don't forget to include the `rand` crate!
+    /// # use rand::thread_rng;
+    /// # //
+    /// # // Initialize a cryptographic rng.
+    /// # let mut rng = thread_rng();
+    /// # //
+    /// # // Generate a key
+    /// # let key = Key::new(&mut rng);
+    /// //
+    /// // We can export a key for external storage or other uses.
+    /// // This method does not do anything special for secure key
+    /// // handling, which is another, more complicated
+    /// // and error-prone topic.
+    /// // Use caution.
+    /// println!("Here is our key value: {}", key.export());
+    ///
+    /// assert_eq!(Key::try_import(&key.export()).unwrap(), key);
+    /// ```
+    fn export(&self) -> String {
+        self.shift().into_inner().to_string()
+    }
+
+    /// Import a key, i.e., parse it with [`Key::from_str`].
+    fn try_import(s: &str) -> Result<Self, EncodingError> {
+        Key::from_str(s)
+    }
+
+    /// The key 0 is weak, since it sends the message "in the clear".
+    fn is_weak(&self) -> Option<WeakKey> {
+        (self.shift() == RingElement::new(0)).then_some(WeakKey::Identity)
     }
 }
 
 // TODO: refactor, prep for Substitution Cipher
-/// Parse a key from a string.
+/// Parse a key from a string: a number between 0 and 25, inclusive, the
+/// lowercase letter that `a` is shifted to, or both, as written by
+/// [`Key::export_as`] with [`KeyDisplayMode::Both`]. E.g., `"3"`, `"d"` and
+/// `"3 (d)"` are the same key.
 ///
 /// # Errors
-/// This implementation will produce an error if the input string does not
-/// represent an integer in the key space, i.e., an integer between 0 and 25,
-/// inclusive. While it would be a simple matter to accept _any_ integer as
-/// input and map to the ring of integers, we chose not to do so for clarity of
-/// use.
+/// This implementation will produce an error if the input string is not in
+/// one of these formats, or gives a number and a letter that disagree. While
+/// it would be a simple matter to accept _any_ integer as input and map to
+/// the ring of integers, we chose not to do so for clarity of use.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::shift::Key;
+/// # use std::str::FromStr;
+/// assert_eq!(Key::from_str("d").unwrap(), Key::from_str("3").unwrap());
+/// assert_eq!(Key::from_str("3 (d)").unwrap(), Key::from_str("3").unwrap());
+/// assert!(Key::from_str("D").is_err());
+/// assert!(Key::from_str("3 (e)").is_err());
+/// ```
 impl FromStr for Key {
     type Err = EncodingError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match i8::from_str(s) {
-            Ok(x) if (0..=25).contains(&x) => Ok(Key::from(RingElement::from_i8(x))),
-            _ => Err(EncodingError::InvalidKey(s.to_string())),
+        let shift = match s.split_once(' ') {
+            Some((number, letter)) => letter
+                .strip_prefix('(')
+                .and_then(|letter| letter.strip_suffix(')'))
+                .and_then(parse_letter)
+                .filter(|&shift| parse_number(number) == Some(shift)),
+            None => parse_number(s).or_else(|| parse_letter(s)),
+        };
+
+        match shift {
+            Some(shift) => Ok(Key::from(RingElement::from_i32(shift))),
+            None => Err(EncodingError::KeyFormat {
+                key: s.to_string(),
+                cipher: "shift",
+                expected: KEY_FORMATS,
+            }),
+        }
+    }
+}
+
+impl TryFrom<&str> for Key {
+    type Error = EncodingError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::from_str(s)
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = EncodingError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+// The formats of a key that `Key::from_str` accepts, for its errors.
+const KEY_FORMATS: &str =
+    "a number between 0 and 25, a lowercase letter, or both, as in \"11 (l)\"";
+
+// Parse a shift written as a number between 0 and 25.
+pub(crate) fn parse_number(s: &str) -> Option<i32> {
+    i32::from_str(s).ok().filter(|x| (0..=25).contains(x))
+}
+
+// Parse a shift written as the lowercase letter that `a` is shifted to.
+pub(crate) fn parse_letter(s: &str) -> Option<i32> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_ascii_lowercase() => Some(letter as i32 - 'a' as i32),
+        _ => None,
+    }
+}
+
+/// How to write a key of the Shift Cipher for people, see [`Key::export_as`].
+///
+/// Stinson describes shift keys by number, but many other textbooks describe
+/// them by the letter that `a` is shifted to.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum KeyDisplayMode {
+    /// The number of the shift, e.g., `11`, as written by
+    /// [`export`](KeyTrait::export).
+    #[default]
+    Number,
+    /// The letter that `a` is shifted to, e.g., `l`.
+    Letter,
+    /// The number followed by the letter, e.g., `11 (l)`.
+    Both,
+}
+
+impl Key {
+    /// Export the key, insecurely, in the given mode. Every mode is accepted
+    /// by [`Key::from_str`], and so by [`try_import`](KeyTrait::try_import).
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::{shift::{Key, KeyDisplayMode}, KeyTrait};
+    /// # use std::str::FromStr;
+    /// let key = Key::from_str("11").unwrap();
+    ///
+    /// assert_eq!(key.export_as(KeyDisplayMode::Letter), "l");
+    /// assert_eq!(key.export_as(KeyDisplayMode::Both), "11 (l)");
+    /// assert_eq!(Key::try_import(&key.export_as(KeyDisplayMode::Both)).unwrap(), key);
+    /// ```
+    pub fn export_as(&self, mode: KeyDisplayMode) -> String {
+        let letter = self.shift().to_char_lossy::<Latin>();
+        match mode {
+            KeyDisplayMode::Number => self.export(),
+            KeyDisplayMode::Letter => letter.to_string(),
+            KeyDisplayMode::Both => format!("{} ({letter})", self.export()),
         }
     }
+
+    /// Get the difference of the shifts, i.e., the shift that takes `other`
+    /// to `self`, between 0 and 25. This is the number of the key
+    /// [`relative_to`](ComposableKey::relative_to) `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::shift::Key;
+    /// # use std::str::FromStr;
+    /// let k1 = Key::from_str("24").unwrap();
+    /// let k2 = Key::from_str("1").unwrap();
+    ///
+    /// assert_eq!(k2.difference(&k1), 3);
+    /// assert_eq!(k1.difference(&k2), 23);
+    /// ```
+    pub fn difference(&self, other: &Key) -> u16 {
+        self.relative_to(other).shift().into_inner()
+    }
+
+    // Get the shift, e.g., to split the key into shares.
+    pub(crate) fn shift(&self) -> RingElement {
+        *self.0.expose_secret()
+    }
 }
 
 // TODO: refactor, prep for Substitution Cipher
 impl From<RingElement> for Key {
     fn from(item: RingElement) -> Self {
-        Key(item)
+        Key(Secret::new(item))
+    }
+}
+
+/// The keys of the Shift Cipher form the group &#x2124;/26&#x2124;: shifts
+/// compose by addition, and the inverse of a shift is its negative.
+impl ComposableKey for Key {
+    fn compose(&self, other: &Self) -> Self {
+        Key::from(self.shift() + other.shift())
+    }
+
+    fn inverse(&self) -> Self {
+        Key::from(-self.shift())
     }
 }
 
 /// An implementation of the Latin Shift Cipher.
-#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
-pub struct ShiftCipher;
+///
+/// An instance of the cipher carries an alphabet and [`CipherSettings`]. The
+/// methods of [`CipherTrait`] shift the letters of the [`Message`] and
+/// [`Ciphertext`] types, i.e., their positions in the alphabet.
+/// [`encrypt_text`](Self::encrypt_text), [`decrypt_text`](Self::decrypt_text)
+/// and the streams, e.g., [`encrypt_stream`](Self::encrypt_stream), read
+/// these letters from the alphabet and write them back in it, following the
+/// settings.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{alphabet::CustomAlphabet, shift::{Key, ShiftCipher}};
+/// # use std::str::FromStr;
+/// let alphabet = CustomAlphabet::from_chars(&('a'..='z').collect::<Vec<_>>())
+///     .unwrap()
+///     .keyword_mixed("kryptos")
+///     .unwrap();
+/// let cipher = ShiftCipher::with_alphabet(alphabet).unwrap();
+/// let key = Key::from_str("1").unwrap();
+///
+/// let ciphertxt = cipher.encrypt_text("attack at dawn", &key).unwrap();
+/// assert_eq!(ciphertxt, "BOOBDRBOEBXQ");
+/// assert_eq!(cipher.decrypt_text(&ciphertxt, &key).unwrap(), "attackatdawn");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ShiftCipher {
+    alphabet: CustomAlphabet,
+    settings: CipherSettings,
+}
+
+impl ShiftCipher {
+    /// Create the cipher on the Latin Alphabet, with the default settings.
+    pub fn new() -> Self {
+        ShiftCipher {
+            alphabet: CustomAlphabet::from_alphabet::<Latin, 26>(),
+            settings: CipherSettings::default(),
+        }
+    }
+
+    /// Create the cipher on another alphabet of 26 letters, e.g., a mixed
+    /// alphabet, with the default settings.
+    ///
+    /// For an alphabet of another size, e.g., the
+    /// [`ClassicalLatin`](crate::alphabet::ClassicalLatin) Alphabet, use
+    /// [`AlphabetShiftCipher`] instead.
+    ///
+    /// # Errors
+    /// This function returns an error if the alphabet does not have 26
+    /// letters, since keys are shifts from 0 to 25.
+    pub fn with_alphabet(alphabet: CustomAlphabet) -> Result<Self, AlphabetError> {
+        if alphabet.size() != 26 {
+            return Err(AlphabetError::WrongSize {
+                size: alphabet.size(),
+                expected: 26,
+            });
+        }
+
+        Ok(ShiftCipher {
+            alphabet,
+            ..Self::new()
+        })
+    }
+
+    /// Set the settings of the cipher.
+    pub fn with_settings(self, settings: CipherSettings) -> Self {
+        ShiftCipher { settings, ..self }
+    }
+
+    /// Get the alphabet of the cipher.
+    pub fn alphabet(&self) -> &CustomAlphabet {
+        &self.alphabet
+    }
+
+    /// Get the settings of the cipher.
+    pub fn settings(&self) -> CipherSettings {
+        self.settings
+    }
+
+    /// Encrypt a message given as a string, after preparing it according to
+    /// the settings of the cipher. Whitespace is skipped, unless it is in the
+    /// alphabet.
+    ///
+    /// Following Stinson's convention, the ciphertext is in ALL CAPS unless
+    /// the alphabet has uppercase letters, which the case of the settings can
+    /// override.
+    ///
+    /// # Errors
+    /// This method returns an error if the policy of the settings rejects the
+    /// message, or if the prepared message has a character that is not in the
+    /// alphabet.
+    pub fn encrypt_text(&self, msg: &str, key: &Key) -> Result<String, EncodingError> {
+        let prepared = self
+            .settings
+            .policy()
+            .prepare(msg)
+            .map_err(EncodingError::InvalidMessage)?;
+        let msg = self
+            .read(&prepared, false)
+            .map_err(EncodingError::InvalidMessage)?;
+        let ciphertxt = self.encrypt(&msg, key).unwrap_or_else(|e| match e {});
+
+        Ok(self
+            .settings
+            .group(self.write(&ciphertxt, self.uppercase_ciphertext())))
+    }
+
+    /// Decrypt a ciphertext given as a string. Whitespace is skipped, unless
+    /// it is in the alphabet, and the case of the ciphertext is ignored
+    /// if the alphabet has no uppercase letters, as the
+    /// [`ParsePolicy`](crate::policy::ParsePolicy) of the settings allows.
+    ///
+    /// # Errors
+    /// This method returns an error if the parse policy rejects the
+    /// ciphertext, or if it has a character that is not in the alphabet.
+    pub fn decrypt_text(&self, ciphertxt: &str, key: &Key) -> Result<String, EncodingError> {
+        let fold_case = self.uppercase_ciphertext();
+        let prepared = self
+            .settings
+            .parsing()
+            .prepare(ciphertxt, fold_case, |c| {
+                self.index_of(c, fold_case).is_some()
+            })
+            .map_err(EncodingError::InvalidCiphertext)?;
+        let ciphertxt = self
+            .read(&prepared, fold_case)
+            .map_err(EncodingError::InvalidCiphertext)?;
+        let msg = self.decrypt(&ciphertxt, key).unwrap_or_else(|e| match e {});
+
+        Ok(self.write(&msg, false))
+    }
+
+    /// Encrypt a message given as a stream of characters, yielding each
+    /// letter of the ciphertext as soon as the corresponding letter of the
+    /// message is read. Whitespace is skipped, unless it is in the alphabet,
+    /// and the ciphertext is written as by
+    /// [`encrypt_text`](Self::encrypt_text), without groups.
+    ///
+    /// # Errors
+    /// The stream yields an error for each character that is not in the
+    /// alphabet or whitespace, and then carries on.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::shift::{Key, ShiftCipher};
+    /// # use std::str::FromStr;
+    /// let key = Key::from_str("11").unwrap();
+    /// let ciphertxt: String = ShiftCipher::new()
+    ///     .encrypt_stream("we will\nmeet".chars(), &key)
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(ciphertxt, "HPHTWWXPPE");
+    /// ```
+    pub fn encrypt_stream<'a, I>(
+        &'a self,
+        chars: I,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> + 'a
+    where
+        I: IntoIterator<Item = char>,
+        I::IntoIter: 'a,
+    {
+        self.encrypt_chars(chars.into_iter().map(Ok), key)
+    }
+
+    /// Encrypt a message read from `reader`, see
+    /// [`encrypt_stream`](Self::encrypt_stream).
+    ///
+    /// # Errors
+    /// The stream also yields an error if the reader fails or does not contain
+    /// valid UTF-8.
+    pub fn encrypt_reader<'a, R: Read + 'a>(
+        &'a self,
+        reader: R,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> + 'a {
+        self.encrypt_chars(stream::read_chars(reader), key)
+    }
+
+    /// Decrypt a ciphertext given as a stream of characters, yielding each
+    /// letter of the message as soon as the corresponding letter of the
+    /// ciphertext is read. Whitespace is skipped, unless it is in the
+    /// alphabet, and case is ignored if the alphabet has no uppercase
+    /// letters.
+    ///
+    /// # Errors
+    /// The stream yields an error for each character that is not in the
+    /// alphabet or whitespace, and then carries on.
+    pub fn decrypt_stream<'a, I>(
+        &'a self,
+        chars: I,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> + 'a
+    where
+        I: IntoIterator<Item = char>,
+        I::IntoIter: 'a,
+    {
+        self.decrypt_chars(chars.into_iter().map(Ok), key)
+    }
+
+    /// Decrypt a ciphertext read from `reader`, see
+    /// [`decrypt_stream`](Self::decrypt_stream).
+    ///
+    /// # Errors
+    /// The stream also yields an error if the reader fails or does not contain
+    /// valid UTF-8.
+    pub fn decrypt_reader<'a, R: Read + 'a>(
+        &'a self,
+        reader: R,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> + 'a {
+        self.decrypt_chars(stream::read_chars(reader), key)
+    }
+
+    fn encrypt_chars<'a, I: Iterator<Item = Result<char, StreamError>> + 'a>(
+        &'a self,
+        chars: I,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> + 'a {
+        let key = key.shift();
+        let uppercase = self.uppercase_ciphertext();
+        self.read_stream(chars, false, EncodingError::InvalidMessage)
+            .flat_map(move |x| self.write_stream(x.map(|x| x + key), uppercase))
+    }
+
+    fn decrypt_chars<'a, I: Iterator<Item = Result<char, StreamError>> + 'a>(
+        &'a self,
+        chars: I,
+        key: &Key,
+    ) -> impl Iterator<Item = Result<char, StreamError>> + 'a {
+        let key = key.shift();
+        let fold_case = self.uppercase_ciphertext();
+        self.read_stream(chars, fold_case, EncodingError::InvalidCiphertext)
+            .flat_map(move |y| self.write_stream(y.map(|y| y - key), false))
+    }
+
+    // Ciphertexts follow Stinson's convention if uppercasing does not change their letters.
+    fn uppercase_ciphertext(&self) -> bool {
+        !self.alphabet.chars().iter().any(|c| c.is_uppercase())
+    }
+
+    // Get the letter that `c` is in the alphabet, lowercasing `c` first if it is not in the
+    // alphabet and `ignore_case` is true.
+    fn index_of(&self, c: char, ignore_case: bool) -> Option<RingElement> {
+        self.alphabet
+            .index_of(c)
+            .or_else(|| {
+                ignore_case
+                    .then(|| c.to_lowercase().next())
+                    .flatten()
+                    .and_then(|c| self.alphabet.index_of(c))
+            })
+            // The alphabet has 26 letters, so the index fits in a `u16`
+            .map(|index| RingElement::new(index as u16))
+    }
+
+    // Read the letters of `text` in the alphabet, skipping whitespace that is not in the alphabet,
+    // or return the characters that are not in the alphabet.
+    fn read<T: FromIterator<RingElement>>(
+        &self,
+        text: &str,
+        ignore_case: bool,
+    ) -> Result<T, InvalidCharacters> {
+        let mut letters = Vec::with_capacity(text.len());
+        let invalid = InvalidCharacters::find(text, |c| match self.index_of(c, ignore_case) {
+            Some(x) => {
+                letters.push(x);
+                false
+            }
+            None => !c.is_whitespace(),
+        });
+
+        if invalid.characters().is_empty() {
+            Ok(letters.into_iter().collect())
+        } else {
+            Err(invalid)
+        }
+    }
+
+    // Read the letters of a stream in the alphabet, one at a time, as `read` does. The position of
+    // an invalid character, which `error` wraps, is its position in the stream.
+    fn read_stream<'a, I: Iterator<Item = Result<char, StreamError>> + 'a>(
+        &'a self,
+        chars: I,
+        ignore_case: bool,
+        error: fn(InvalidCharacters) -> EncodingError,
+    ) -> impl Iterator<Item = Result<RingElement, StreamError>> + 'a {
+        let (mut char_index, mut byte_index) = (0, 0);
+        chars.filter_map(move |c| {
+            let c = match c {
+                Ok(c) => c,
+                Err(e) => return Some(Err(e)),
+            };
+            let position = InvalidCharacter {
+                character: c,
+                byte_index,
+                char_index,
+            };
+            char_index += 1;
+            byte_index += c.len_utf8();
+            match self.index_of(c, ignore_case) {
+                Some(x) => Some(Ok(x)),
+                None if c.is_whitespace() => None,
+                None => Some(Err(error(InvalidCharacters(vec![position])).into())),
+            }
+        })
+    }
+
+    // Write letters in the alphabet, following the case of the settings, where `uppercase` tells
+    // whether Stinson's convention writes them in ALL CAPS.
+    fn write<'a>(
+        &self,
+        letters: impl IntoIterator<Item = &'a RingElement>,
+        uppercase: bool,
+    ) -> String {
+        let text: String = letters
+            .into_iter()
+            .filter_map(|x| self.alphabet.char_at(x.into_inner().into()))
+            .collect();
+
+        self.settings.case().apply_to(&text, uppercase).into_owned()
+    }
+
+    // Write a letter of a stream, as `write` does.
+    fn write_stream(
+        &self,
+        x: Result<RingElement, StreamError>,
+        uppercase: bool,
+    ) -> Vec<Result<char, StreamError>> {
+        match x {
+            Ok(x) => self.write([&x], uppercase).chars().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        }
+    }
+}
 
 impl CipherTrait for ShiftCipher {
     type Message = Message;
     type Ciphertext = Ciphertext;
     type Key = Key;
+    type EncryptionError = Infallible;
+    type DecryptionError = Infallible;
 
-    /// Encrypt a message.
+    /// Encrypt a message. This never fails.
     ///
     /// # Examples
     /// ```
@@ -179,14 +683,20 @@ impl CipherTrait for ShiftCipher {
     /// # let mut rng = thread_rng();
     /// # let key = Key::new(&mut rng);
     /// # let msg = Message::new("thisisanawkwardapichoice").expect("This example is hardcoded; it should work!");
-    /// let ciphertxt = ShiftCipher::encrypt(&msg, &key);
+    /// let ciphertxt = ShiftCipher::new().encrypt(&msg, &key).unwrap();
     /// ```
-    fn encrypt(msg: &Self::Message, key: &Self::Key) -> Self::Ciphertext {
-        Ciphertext(msg.0 .0.iter().map(|&i| i + key.0).collect())
+    fn encrypt(
+        &self,
+        msg: &Self::Message,
+        key: &Self::Key,
+    ) -> Result<Self::Ciphertext, Infallible> {
+        Ok(Ciphertext(
+            msg.0 .0.iter().map(|&i| i + key.shift()).collect(),
+        ))
     }
 
     // TODO! refactor, generalize
-    /// Decrypt a ciphertext with a given key.
+    /// Decrypt a ciphertext with a given key. This never fails.
     ///
     /// # Examples
     /// ```
@@ -196,8 +706,8 @@ impl CipherTrait for ShiftCipher {
     /// # let mut rng = thread_rng();
     /// # let key = Key::new(&mut rng);
     /// # let msg = Message::new("thisisanawkwardapichoice").expect("This example is hardcoded; it should work!");
-    /// # let ciphertxt = ShiftCipher::encrypt(&msg, &key);
-    /// let decrypted = ShiftCipher::decrypt(&ciphertxt, &key);
+    /// # let ciphertxt = ShiftCipher::new().encrypt(&msg, &key).unwrap();
+    /// let decrypted = ShiftCipher::new().decrypt(&ciphertxt, &key).unwrap();
     ///
     /// println!(
     ///    "If we decrypt using the correct key, we get our original
@@ -207,7 +717,7 @@ impl CipherTrait for ShiftCipher {
     /// if key != wrong_key {
     /// println!("If we decrypt using an incorrect key, we do not get
     ///  our original message back: {}",
-    /// ShiftCipher::decrypt(&ciphertxt, &wrong_key));
+    /// ShiftCipher::new().decrypt(&ciphertxt, &wrong_key).unwrap());
     /// }
     /// ```
     ///
@@ -227,11 +737,11 @@ impl CipherTrait for ShiftCipher {
     /// // break the system with a brute force attack. But likely there
     /// // is other context available to validate possible plaintexts.
     /// let small_msg = Message::new("dad").expect("This example is hardcoded; it should work!");
-    /// let small_ciphertext = ShiftCipher::encrypt(&small_msg, &key);
+    /// let small_ciphertext = ShiftCipher::new().encrypt(&small_msg, &key).unwrap();
     /// // This will also decrypt the message properly with probability 1/26
     /// // which is of course a huge probability of success.
-    /// let small_decryption = ShiftCipher::decrypt(&small_ciphertext,
-    ///  &Key::new(&mut rng));
+    /// let small_decryption = ShiftCipher::new().decrypt(&small_ciphertext,
+    ///  &Key::new(&mut rng)).unwrap();
     ///
     /// println!("Here is a small example, where we can more
     /// easily see the preservation of patterns:
@@ -240,42 +750,464 @@ impl CipherTrait for ShiftCipher {
     /// small_msg, small_ciphertext,
     /// small_decryption)
     /// ```
-    fn decrypt(ciphertxt: &Self::Ciphertext, key: &Self::Key) -> Self::Message {
-        ciphertxt.0 .0.iter().map(|&i| i - key.0).collect()
+    fn decrypt(
+        &self,
+        ciphertxt: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Result<Self::Message, Infallible> {
+        Ok(ciphertxt.0 .0.iter().map(|&i| i - key.shift()).collect())
+    }
+
+    /// Describe the Latin Shift Cipher. With a shift of 3, it is the Caesar
+    /// Cipher.
+    fn info() -> CipherInfo {
+        CipherInfo {
+            name: "shift",
+            inventor: "Julius Caesar",
+            era: "1st century BC",
+            alphabet: "Latin",
+            alphabet_size: 26,
+            block_size: 1,
+            key_count: Some(26),
+        }
     }
 }
 
-impl ShiftCipher {
-    /// Export the cryptographic key, insecurely.
+impl TracedCipher for ShiftCipher {
+    /// Encrypt a message and trace each letter. The key element of every step
+    /// is the key itself. This never fails.
+    fn encrypt_traced(&self, msg: &Message, key: &Key) -> Result<(Ciphertext, Trace), Infallible> {
+        let ciphertxt = self.encrypt(msg, key)?;
+        let trace = msg
+            .iter()
+            .zip(&ciphertxt)
+            .map(|(&x, &y)| Step::new(x, key.shift(), y))
+            .collect();
+
+        Ok((ciphertxt, trace))
+    }
+}
+
+impl KeyFile for Key {
+    type Cipher = ShiftCipher;
+}
+
+impl SampleMessage for ShiftCipher {
+    fn sample_message<R: RngCore + CryptoRng>(rng: &mut R, len: usize) -> Message {
+        (0..len).map(|_| RingElement::random(rng)).collect()
+    }
+}
+
+impl Default for ShiftCipher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A message of the Shift Cipher on the alphabet `A` of `M` characters, e.g.,
+/// the [`ClassicalLatin`](crate::alphabet::ClassicalLatin) Alphabet, see
+/// [`AlphabetShiftCipher`].
+// Notes: This is a wrapper type around the library's private representation of a message, like
+// `Message`, which is the message of the Latin Alphabet. They are different types so that the
+// constructors of `Message` need no type annotations.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AlphabetMessage<A: Alphabet<M>, const M: u16>(Msg<A, M>);
+
+impl<A: Alphabet<M>, const M: u16> AlphabetMessage<A, M> {
+    /// Create a new message from a string. Spaces are skipped, unless they
+    /// are in the alphabet.
     ///
-    /// # Examples
-    /// ```
-    /// # use classical_crypto::{CipherTrait, KeyTrait, shift::{ShiftCipher, Key, Message, Ciphertext}};
-    /// # // Don't forget to include the `rand` crate!
-    /// # use rand::thread_rng;
-    /// # //
-    /// # // Initialize a cryptographic rng.
-    /// # let mut rng = thread_rng();
-    /// # //
-    /// # // Generate a key
-    /// # let key = Key::new(&mut rng);
-    /// //
-    /// // We can export a key for external storage or other uses.
-    /// // This method does not do anything special for secure key
-    /// // handling, which is another, more complicated
-    /// // and error-prone topic.
-    /// // Use caution.
-    /// println!("Here is our key value: {}", ShiftCipher::insecure_key_export(&key));
-    /// ```
-    pub fn insecure_key_export(key: &<Self as CipherTrait>::Key) -> String {
-        key.0.into_inner().to_string()
+    /// # Errors
+    /// This method returns an error if the string is empty or contains a
+    /// character that is not in the alphabet, reporting the position of every
+    /// such character.
+    pub fn new(str: &str) -> Result<Self, EncodingError> {
+        Ok(AlphabetMessage(Msg::new(str)?))
+    }
+
+    /// Create a new message from a string after preparing it according to
+    /// `policy`, e.g., to fold the letters that are missing from the alphabet.
+    ///
+    /// # Errors
+    /// This method returns an error if the policy rejects the string, or if
+    /// the prepared string contains a character that is not in the alphabet.
+    pub fn with_policy(str: &str, policy: &MessagePolicy) -> Result<Self, EncodingError> {
+        Ok(AlphabetMessage(Msg::with_policy(str, policy)?))
+    }
+
+    /// Get the number of symbols in the message.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the message has no symbols and false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the symbols of the message.
+    pub fn iter(&self) -> std::slice::Iter<'_, RingElement<M>> {
+        self.0.iter()
+    }
+}
+
+impl<A: Alphabet<M>, const M: u16> FromStr for AlphabetMessage<A, M> {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl<A: Alphabet<M>, const M: u16> Display for AlphabetMessage<A, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A ciphertext of the Shift Cipher on the alphabet `A` of `M` characters,
+/// see [`AlphabetShiftCipher`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AlphabetCiphertext<A: Alphabet<M>, const M: u16>(Ciphtxt<A, M>);
+
+impl<A: Alphabet<M>, const M: u16> AlphabetCiphertext<A, M> {
+    /// Get the number of symbols in the ciphertext.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the ciphertext has no symbols and false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the symbols of the ciphertext.
+    pub fn iter(&self) -> std::slice::Iter<'_, RingElement<M>> {
+        self.0.iter()
+    }
+}
+
+/// Parse a ciphertext from a string. Spaces are skipped, unless they are in
+/// the alphabet, and case is ignored if the alphabet writes ciphertexts in
+/// ALL CAPS (see [`Alphabet::UPPERCASE_CIPHERTEXT`]).
+///
+/// # Errors
+/// This trait implementation returns an error if the string is empty or
+/// contains a character that is not in the alphabet.
+impl<A: Alphabet<M>, const M: u16> FromStr for AlphabetCiphertext<A, M> {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(AlphabetCiphertext(Ciphtxt::from_str(s)?))
+    }
+}
+
+impl<A: Alphabet<M>, const M: u16> Display for AlphabetCiphertext<A, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A cryptographic key for the Shift Cipher on an alphabet of `M`
+/// characters, i.e., a shift between 0 and `M` - 1, see
+/// [`AlphabetShiftCipher`].
+// As for `Key`, the shift is held in a `Secret`, and we do not implement `Copy` and `Clone`.
+#[derive(Debug)]
+pub struct AlphabetKey<const M: u16>(Secret<RingElement<M>>);
+
+impl<const M: u16> AlphabetKey<M> {
+    fn shift(&self) -> RingElement<M> {
+        *self.0.expose_secret()
+    }
+}
+
+// Keys are compared in constant time, see the `secret` module.
+impl<const M: u16> ConstantTimeEq for AlphabetKey<M> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.shift().ct_eq(&other.shift())
+    }
+}
+
+impl<const M: u16> PartialEq for AlphabetKey<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<const M: u16> Eq for AlphabetKey<M> {}
+
+impl<const M: u16> KeyTrait for AlphabetKey<M> {
+    /// Generate a key uniformly at random from the key space, i.e., the ring
+    /// &#x2124;/`M`&#x2124;.
+    fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        AlphabetKey(Secret::new(RingElement::random(rng)))
+    }
+
+    /// Export the key, insecurely, as an integer between 0 and `M` - 1.
+    fn export(&self) -> String {
+        self.shift().into_inner().to_string()
+    }
+
+    /// Import a key, i.e., parse it with [`AlphabetKey::from_str`].
+    fn try_import(s: &str) -> Result<Self, EncodingError> {
+        AlphabetKey::from_str(s)
+    }
+
+    /// The key 0 is weak, since it sends the message "in the clear".
+    fn is_weak(&self) -> Option<WeakKey> {
+        (self.shift() == RingElement::new(0)).then_some(WeakKey::Identity)
+    }
+}
+
+/// Parse a key from a string, i.e., a number between 0 and `M` - 1,
+/// inclusive.
+///
+/// # Errors
+/// This implementation returns an error if the string is not such a number.
+impl<const M: u16> FromStr for AlphabetKey<M> {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match i32::from_str(s) {
+            Ok(shift) if (0..i32::from(M)).contains(&shift) => {
+                Ok(AlphabetKey(Secret::new(RingElement::from_i32(shift))))
+            }
+            _ => Err(EncodingError::InvalidKey(s.to_string())),
+        }
+    }
+}
+
+/// The Shift Cipher on the alphabet `A` of `M` characters, e.g., the
+/// [`ClassicalLatin`](crate::alphabet::ClassicalLatin) Alphabet of the Romans,
+/// the [`Alphanumeric`](crate::alphabet::Alphanumeric) Alphabet, which
+/// encrypts digits too, or the [`PrintableAscii`](crate::alphabet::PrintableAscii)
+/// Alphabet, which encrypts whole sentences.
+///
+/// The plaintext, ciphertext and key spaces are the ring
+/// &#x2124;/`M`&#x2124;, so the alphabet is part of the type of the cipher, as
+/// well as of its messages, ciphertexts and keys. On the Latin Alphabet, use
+/// [`ShiftCipher`], whose [`Message`], [`Ciphertext`] and [`Key`] types have
+/// more features.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{
+/// #     alphabet::ClassicalLatin,
+/// #     shift::{AlphabetKey, AlphabetMessage, AlphabetShiftCipher},
+/// #     CipherTrait,
+/// # };
+/// # use std::str::FromStr;
+/// type Caesar = AlphabetShiftCipher<ClassicalLatin, 23>;
+///
+/// let msg = AlphabetMessage::new("gallia est omnis divisa in partes tres").unwrap();
+/// let key = AlphabetKey::from_str("3").unwrap();
+///
+/// let ciphertxt = Caesar::new().encrypt(&msg, &key).unwrap();
+/// assert_eq!(ciphertxt.to_string(), "KDOOMDHXYRPQMXGMZMXDMQSDVYHXYVHX");
+/// assert_eq!(Caesar::new().decrypt(&ciphertxt, &key).unwrap(), msg);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AlphabetShiftCipher<A: Alphabet<M>, const M: u16>(PhantomData<A>);
+
+impl<A: Alphabet<M>, const M: u16> AlphabetShiftCipher<A, M> {
+    /// Create the cipher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<A: Alphabet<M>, const M: u16> CipherTrait for AlphabetShiftCipher<A, M> {
+    type Message = AlphabetMessage<A, M>;
+    type Ciphertext = AlphabetCiphertext<A, M>;
+    type Key = AlphabetKey<M>;
+    type EncryptionError = Infallible;
+    type DecryptionError = Infallible;
+
+    /// Encrypt a message. This never fails.
+    fn encrypt(
+        &self,
+        msg: &Self::Message,
+        key: &Self::Key,
+    ) -> Result<Self::Ciphertext, Infallible> {
+        Ok(AlphabetCiphertext(
+            msg.iter().map(|&x| x + key.shift()).collect(),
+        ))
+    }
+
+    /// Decrypt a ciphertext. This never fails.
+    fn decrypt(
+        &self,
+        ciphertxt: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Result<Self::Message, Infallible> {
+        Ok(AlphabetMessage(
+            ciphertxt.iter().map(|&y| y - key.shift()).collect(),
+        ))
+    }
+
+    /// Describe the Shift Cipher on the alphabet, which is named after its
+    /// type, e.g., `ClassicalLatin`.
+    fn info() -> CipherInfo {
+        let alphabet = std::any::type_name::<A>();
+        CipherInfo {
+            alphabet: alphabet.rsplit("::").next().unwrap_or(alphabet),
+            alphabet_size: M.into(),
+            key_count: Some(M.into()),
+            ..ShiftCipher::info()
+        }
+    }
+}
+
+/// A brute force ciphertext-only attack on the Latin Shift Cipher.
+///
+/// The key space of the Latin Shift Cipher has only 26 elements, so we can
+/// simply try every key. Candidate decryptions are ranked by how closely their
+/// letter frequencies resemble those of a language, using
+/// [`FrequencyTable::distance`](crate::analysis::FrequencyTable::distance). By
+/// default the language is English, but any [`LanguageModel`] can be used.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{attack::CiphertextOnlyAttack, shift::{BruteForce, Ciphertext, Message}};
+/// # use std::str::FromStr;
+/// // Example 1.1, Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
+/// let ciphertxt = Ciphertext::from_str("HPHTWWXPPELEXTOYTRSE").unwrap();
+///
+/// let candidates = BruteForce::default().attack(&ciphertxt);
+///
+/// // Every key is a candidate.
+/// assert_eq!(candidates.len(), 26);
+/// // And, if we have enough ciphertext, the best candidate is usually correct.
+/// assert_eq!(
+///     candidates.best().unwrap().plaintext(),
+///     &Message::new("wewillmeetatmidnight").unwrap()
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct BruteForce {
+    model: LanguageModel,
+    monitor: Monitor,
+}
+
+impl BruteForce {
+    /// Create a brute force attack that ranks decryptions using the given
+    /// language model.
+    pub fn with_model(model: LanguageModel) -> Self {
+        BruteForce {
+            model,
+            monitor: Monitor::default(),
+        }
+    }
+
+    /// Report progress to the given sink after trying each key.
+    pub fn with_progress(self, sink: impl ProgressSink + 'static) -> Self {
+        BruteForce {
+            monitor: self.monitor.with_progress(sink),
+            ..self
+        }
+    }
+
+    /// Stop trying keys when the given token is cancelled.
+    pub fn with_cancellation(self, token: CancellationToken) -> Self {
+        BruteForce {
+            monitor: self.monitor.with_cancellation(token),
+            ..self
+        }
+    }
+}
+
+impl Default for BruteForce {
+    fn default() -> Self {
+        BruteForce::with_model(LanguageModel::english())
+    }
+}
+
+impl Attack for BruteForce {
+    const NAME: &'static str = "brute force";
+    const MODEL: AttackModel = AttackModel::CiphertextOnly;
+}
+
+impl CiphertextOnlyAttack<ShiftCipher> for BruteForce {
+    fn attack(&self, ciphertxt: &Ciphertext) -> Candidates<Key, Message> {
+        let monitor = self.monitor.begin(<RingElement>::MODULUS as usize);
+        let mut candidates = Candidates::new();
+
+        for i in 0..<RingElement>::MODULUS {
+            if monitor.is_cancelled() {
+                break;
+            }
+
+            let key = Key::from(RingElement::new(i));
+            let Ok(msg) = ShiftCipher::new().decrypt(ciphertxt, &key);
+            // Closer to the language is better
+            let score = -msg.letter_counts().distance(self.model.probabilities());
+            candidates.push(key, msg, score);
+
+            let best = candidates.best().map_or(f64::NEG_INFINITY, |c| c.score());
+            monitor.report(i as usize + 1, best);
+        }
+
+        candidates
+    }
+}
+
+/// A known plaintext attack on the Latin Shift Cipher.
+///
+/// The key is the difference between any letter of the ciphertext and the
+/// corresponding letter of the plaintext. The attack checks that the remaining
+/// letters agree, and fails if they do not.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct KnownPlaintext;
+
+impl Attack for KnownPlaintext {
+    const NAME: &'static str = "known plaintext";
+    const MODEL: AttackModel = AttackModel::KnownPlaintext;
+}
+
+impl KnownPlaintextAttack<ShiftCipher> for KnownPlaintext {
+    fn attack(&self, msg: &Message, ciphertxt: &Ciphertext) -> Option<Key> {
+        let (msg, ciphertxt) = (&msg.0 .0, &ciphertxt.0 .0);
+        if msg.len() != ciphertxt.len() {
+            return None;
+        }
+
+        let key = *ciphertxt.first()? - *msg.first()?;
+        msg.iter()
+            .zip(ciphertxt)
+            .all(|(&m, &c)| c - m == key)
+            .then_some(Key::from(key))
+    }
+}
+
+/// A chosen plaintext attack on the Latin Shift Cipher.
+///
+/// The encryption of the letter `a` is the key.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ChosenPlaintext;
+
+impl Attack for ChosenPlaintext {
+    const NAME: &'static str = "chosen plaintext";
+    const MODEL: AttackModel = AttackModel::ChosenPlaintext;
+}
+
+impl ChosenPlaintextAttack<ShiftCipher> for ChosenPlaintext {
+    fn attack<O>(&self, mut oracle: O) -> Option<Key>
+    where
+        O: FnMut(&Message) -> Ciphertext,
+    {
+        let msg = Message(Msg::from_iter(vec![RingElement::ZERO]));
+        oracle(&msg).0 .0.first().map(|&i| Key::from(i))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::RingElement;
+    use crate::{
+        errors::{ReadError, RekeyError},
+        policy::{CasePolicy, DigitPolicy, ParsePolicy},
+        RingElement,
+    };
     use rand::{Rng, SeedableRng};
     use rand_chacha::ChaCha12Rng;
 
@@ -298,19 +1230,19 @@ mod tests {
     // better to use std::cell::OnceCell, I'm not sure I understand how to do
     // that properly. Encoded "wewillmeetatmidnight" message from Example 1.1,
     // Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
-    thread_local! (static MSG0: Message = Message(Msg(vec![RingElement(22), RingElement(4),
-            RingElement(22), RingElement(8), RingElement(11), RingElement(11),
-            RingElement(12), RingElement(4), RingElement(4), RingElement(19),
-            RingElement(0), RingElement(19),
-            RingElement(12), RingElement(8), RingElement(3), RingElement(13), RingElement(8), RingElement(6), RingElement(7), RingElement(19)])));
+    thread_local! (static MSG0: Message = Message(Msg::from_iter(vec![RingElement::new(22), RingElement::new(4),
+            RingElement::new(22), RingElement::new(8), RingElement::new(11), RingElement::new(11),
+            RingElement::new(12), RingElement::new(4), RingElement::new(4), RingElement::new(19),
+            RingElement::new(0), RingElement::new(19),
+            RingElement::new(12), RingElement::new(8), RingElement::new(3), RingElement::new(13), RingElement::new(8), RingElement::new(6), RingElement::new(7), RingElement::new(19)])));
 
     // Encrypted "wewillmeetatmidnight" message with key=11, from Example 1.1,
     // Stinson 3rd Edition, Example 2.1 Stinson 4th Edition
-    thread_local! (static CIPH0: Ciphertext = Ciphertext(Ciphtxt(vec![RingElement(7), RingElement(15), 
-            RingElement(7), RingElement(19), RingElement(22), RingElement(22),
-            RingElement(23), RingElement(15), RingElement(15), RingElement(4),
-            RingElement(11), RingElement(4),
-            RingElement(23), RingElement(19), RingElement(14), RingElement(24), RingElement(19), RingElement(17), RingElement(18), RingElement(4)])));
+    thread_local! (static CIPH0: Ciphertext = Ciphertext(Ciphtxt::from_iter(vec![RingElement::new(7), RingElement::new(15), 
+            RingElement::new(7), RingElement::new(19), RingElement::new(22), RingElement::new(22),
+            RingElement::new(23), RingElement::new(15), RingElement::new(15), RingElement::new(4),
+            RingElement::new(11), RingElement::new(4),
+            RingElement::new(23), RingElement::new(19), RingElement::new(14), RingElement::new(24), RingElement::new(19), RingElement::new(17), RingElement::new(18), RingElement::new(4)])));
 
     // Encrypted "wewillmeetatmidnight" as a string, from Example 1.1 Stinson 3rd
     // Edition, Example 2.1 Stinson 4th Edition
@@ -319,41 +1251,46 @@ mod tests {
     // Example 1.1, Stinson 3rd Edition, Example 2.1 Stinson 4th Edition.
     #[test]
     fn enc_dec_basic() {
-        let key0 = Key(RingElement(11));
+        let key0 = Key::from(RingElement::new(11));
 
-        let ciph0 = ShiftCipher::encrypt(&Message::new("wewillmeetatmidnight").unwrap(), &key0);
+        let ciph0 = ShiftCipher::new()
+            .encrypt(&Message::new("wewillmeetatmidnight").unwrap(), &key0)
+            .unwrap();
 
         assert_eq!(ciph0, CIPH0.with(|ciph| ciph.clone())); // Ciphertext is correct
         assert_eq!(
-            ShiftCipher::decrypt(&ciph0, &key0),
+            ShiftCipher::new().decrypt(&ciph0, &key0).unwrap(),
             MSG0.with(|msg| msg.clone()) // Ciphertext decrypts correctly
         )
     }
 
     #[test]
+    // Debug builds catch the invalid element in the arithmetic. Release builds do not panic,
+    // and write the invalid letter as the replacement character.
+    #[cfg(debug_assertions)]
     #[should_panic(
-        expected = "Could not map to `char`: The definition of `RingElement::ALPH_ENCODING` must have an error or there is an invalid `RingElement`."
+        expected = "Arithmetic on an invalid `RingElement`: the operands must be in canonical form."
     )]
     fn unchecked_dec_panic() {
         // Sometimes you google to find out how to prevent things like backtraces
         // appearing in your output for tests that should panic
         let f = |_: &std::panic::PanicHookInfo| {};
         std::panic::set_hook(Box::new(f));
-        let ciph = Ciphertext(Ciphtxt(vec![RingElement(65)]));
+        let ciph = Ciphertext(Ciphtxt::from_iter(vec![RingElement::new(65)]));
 
-        let key = Key(RingElement(0));
-        println!("{}", ShiftCipher::decrypt(&ciph, &key));
+        let key = Key::from(RingElement::new(0));
+        println!("{}", ShiftCipher::new().decrypt(&ciph, &key).unwrap());
     }
 
     #[test]
     // Won't panic because appropriate constructor used for RingElement, but result
     // may surprise the library developer
     fn unchecked_dec_nopanic() {
-        let ciph = Ciphertext(Ciphtxt(vec![RingElement::from_i8(65)]));
+        let ciph = Ciphertext(Ciphtxt::from_iter(vec![RingElement::from_i32(65)]));
 
-        let key = Key(RingElement(0));
+        let key = Key::from(RingElement::new(0));
         assert_eq!(
-            ShiftCipher::decrypt(&ciph, &key),
+            ShiftCipher::new().decrypt(&ciph, &key).unwrap(),
             Message::from_str("n").expect("Test writer should ensure this example does not panic")
         );
     }
@@ -372,7 +1309,9 @@ mod tests {
         // If you encrypt, then decrypt with the same key used during encryption, you
         // get the same message back.
         assert_eq!(
-            ShiftCipher::decrypt(&ShiftCipher::encrypt(&msg1, &key1), &key1),
+            ShiftCipher::new()
+                .decrypt(&ShiftCipher::new().encrypt(&msg1, &key1).unwrap(), &key1)
+                .unwrap(),
             msg1
         );
 
@@ -380,9 +1319,11 @@ mod tests {
         // during encryption, you should not get the same one back. (Note this
         // test won't run if the keys collide, which they will with probability
         // 1/26, i.e., the keyspace for the Latin Shift Cipher system is *tiny*.
-        if key1 != key2 {
+        if key1.difference(&key2) != 0 {
             assert_ne!(
-                ShiftCipher::decrypt(&ShiftCipher::encrypt(&msg2, &key1), &key2),
+                ShiftCipher::new()
+                    .decrypt(&ShiftCipher::new().encrypt(&msg2, &key1).unwrap(), &key2)
+                    .unwrap(),
                 msg2
             )
         }
@@ -393,59 +1334,461 @@ mod tests {
     fn enc_dec_reprod_rand() {
         let mut rng = reprod_rng();
 
-        let key1 = Key(RingElement(rng.gen_range(0..RingElement::MODULUS)));
-        let key2 = Key(RingElement(rng.gen_range(0..RingElement::MODULUS)));
+        let key1 = Key::from(RingElement::new(rng.gen_range(0..<RingElement>::MODULUS)));
+        let key2 = Key::from(RingElement::new(rng.gen_range(0..<RingElement>::MODULUS)));
 
         let msg1 = Message::new("thisisyetanothertestmessage").unwrap();
 
         // This test is OK as long you check that it passes once
-        assert_ne!(key1, key2);
+        assert_ne!(key1.difference(&key2), 0);
 
         // Encrypted message always decrypts correctly
         assert_eq!(
-            ShiftCipher::decrypt(&ShiftCipher::encrypt(&msg1, &key1), &key1),
+            ShiftCipher::new()
+                .decrypt(&ShiftCipher::new().encrypt(&msg1, &key1).unwrap(), &key1)
+                .unwrap(),
             msg1
         );
-        // Encrypted message won't decrypt correctly without the correct key
-        // This test is OK because a manual check has been done to ensure the keys are
-        // different.
-        assert_ne!(
-            ShiftCipher::decrypt(&ShiftCipher::encrypt(&msg1, &key1), &key2),
-            msg1
-        )
+        // Encrypted message won't decrypt correctly without the correct key: decrypting with the
+        // wrong key leaves the message shifted by the difference of the keys
+        let decrypted = ShiftCipher::new()
+            .decrypt(&ShiftCipher::new().encrypt(&msg1, &key1).unwrap(), &key2)
+            .unwrap();
+        assert_ne!(decrypted, msg1);
+        assert_eq!(
+            decrypted.to_string().to_uppercase(),
+            ShiftCipher::new()
+                .encrypt(&msg1, &key1.relative_to(&key2))
+                .unwrap()
+                .to_string()
+        );
     }
 
     #[test]
-    fn new_key() {
-        assert_eq!(Key::from_str("0").unwrap(), Key(RingElement(0)));
-        assert_eq!(Key::from_str("5").unwrap(), Key(RingElement(5)))
+    fn iterate() {
+        let msg = Message::new("wewillmeetatmidnight").unwrap();
+        let key = Key::from_str("11").unwrap();
+        let ciphertxt = ShiftCipher::new().encrypt(&msg, &key).unwrap();
+
+        // Walking the texts agrees with the symbol-by-symbol definition of the cipher
+        for (x, y) in msg.iter().zip(&ciphertxt) {
+            assert_eq!(*x + key.shift(), *y);
+        }
     }
 
     #[test]
-    fn new_key_err() {
+    fn stream() {
+        let key = Key::from_str("11").unwrap();
+        let cipher = ShiftCipher::new();
+        let streamed: String = cipher
+            .encrypt_reader("we will meet\nat midnight\n".as_bytes(), &key)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(streamed, "HPHTWWXPPELEXTOYTRSE");
+
+        let decrypted: String = cipher
+            .decrypt_stream(streamed.to_lowercase().chars(), &key)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(decrypted, "wewillmeetatmidnight");
+
+        let results: Vec<_> = cipher.encrypt_stream("a!b".chars(), &key).collect();
+        assert!(matches!(
+            &results[1],
+            Err(StreamError::Encoding(EncodingError::InvalidMessage(e)))
+                if e.characters()[0].char_index == 1
+        ));
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            cipher.decrypt_reader(&b"AB\xff"[..], &key).last(),
+            Some(Err(StreamError::Io(_)))
+        ));
+
+        // Streams follow the alphabet and the case of the instance, as strings do
+        let alphabet = CustomAlphabet::from_alphabet::<Latin, 26>()
+            .keyword_mixed("kryptos")
+            .unwrap();
+        let mixed = ShiftCipher::with_alphabet(alphabet)
+            .unwrap()
+            .with_settings(CipherSettings::new().with_case(CasePolicy::Lowercase));
+        let streamed: String = mixed
+            .encrypt_stream("attack at dawn".chars(), &key)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            streamed,
+            mixed.encrypt_text("attack at dawn", &key).unwrap()
+        );
+        let decrypted: String = mixed
+            .decrypt_stream(streamed.chars(), &key)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(decrypted, "attackatdawn");
+    }
+
+    #[test]
+    fn traced() {
+        let key = Key::from_str("11").unwrap();
+        let (ciphertxt, trace) = MSG0
+            .with(|m| ShiftCipher::new().encrypt_traced(m, &key))
+            .unwrap();
+        assert_eq!(ciphertxt, CIPH0.with(|c| c.clone()));
+        assert_eq!(trace.len(), 20);
+        for step in &trace {
+            assert_eq!(step.input_element + step.key_element, step.output_element);
+        }
+        let letters: String = trace.iter().map(|s| s.output).collect();
+        assert_eq!(letters, CIPH0_STR.with(|s| s.clone()));
+    }
+
+    #[test]
+    fn instance() {
+        let key = Key::from_str("11").unwrap();
+        let latin = ShiftCipher::new();
         assert_eq!(
-            Key::from_str("65").unwrap_err(),
-            EncodingError::InvalidKey("65".to_string())
+            latin.encrypt_text("we will meet at midnight", &key),
+            Ok("HPHTWWXPPELEXTOYTRSE".to_string())
         );
         assert_eq!(
-            Key::from_str("").unwrap_err(),
-            EncodingError::InvalidKey("".to_string())
+            latin.decrypt_text("hphtw wxppe lexto ytrse", &key),
+            Ok("wewillmeetatmidnight".to_string())
+        );
+        assert!(matches!(
+            latin.encrypt_text("midnight!", &key),
+            Err(EncodingError::InvalidMessage(_))
+        ));
+
+        let settings = CipherSettings::new()
+            .with_policy(MessagePolicy::new().with_digits(DigitPolicy::SpellOut))
+            .with_group_size(4);
+        let cipher = latin.with_settings(settings);
+        assert_eq!(cipher.settings(), settings);
+        assert_eq!(cipher.encrypt_text("at 1", &key).unwrap(), "LEZY P");
+
+        // The case of an alphabet with uppercase letters is kept
+        let alphabet = CustomAlphabet::from_chars(&('A'..='Z').collect::<Vec<_>>()).unwrap();
+        let upper = ShiftCipher::with_alphabet(alphabet).unwrap();
+        assert_eq!(upper.encrypt_text("AB", &key).unwrap(), "LM");
+        assert!(upper.decrypt_text("lm", &key).is_err());
+
+        // The parse policy decides whether lowercase and invalid characters are accepted
+        let strict = ShiftCipher::new()
+            .with_settings(CipherSettings::new().with_parsing(ParsePolicy::Strict));
+        assert!(matches!(
+            strict.decrypt_text("hphtw wxppe", &key),
+            Err(EncodingError::InvalidCiphertext(_))
+        ));
+        assert_eq!(
+            strict.decrypt_text("HPHTW WXPPE", &key).unwrap(),
+            "wewillmeet"
         );
+        let permissive = ShiftCipher::new()
+            .with_settings(CipherSettings::new().with_parsing(ParsePolicy::Permissive));
         assert_eq!(
-            Key::from_str("-5").unwrap_err(),
-            EncodingError::InvalidKey("-5".to_string())
+            permissive.decrypt_text("HPHTW-wxppe, 10!", &key).unwrap(),
+            "wewillmeet"
         );
         assert_eq!(
-            Key::from_str("26").unwrap_err(),
-            EncodingError::InvalidKey("26".to_string())
+            upper
+                .with_settings(CipherSettings::new().with_parsing(ParsePolicy::Permissive))
+                .decrypt_text("LM lm", &key)
+                .unwrap(),
+            "AB"
         );
+
+        let small = CustomAlphabet::from_chars(&['a', 'b']).unwrap();
         assert_eq!(
-            Key::from_str("asdfas").unwrap_err(),
-            EncodingError::InvalidKey("asdfas".to_string())
+            ShiftCipher::with_alphabet(small),
+            Err(AlphabetError::WrongSize {
+                size: 2,
+                expected: 26
+            })
         );
+    }
+
+    #[test]
+    fn passphrase_key() {
+        let key = Key::derive_from_passphrase("attack at dawn");
+        assert_eq!(key, Key::derive_from_passphrase("attack at dawn"));
+
+        // Different phrases spread over the key space
+        let keys: std::collections::HashSet<u16> = (0..200)
+            .map(|i| {
+                Key::derive_from_passphrase(&format!("phrase {i}"))
+                    .shift()
+                    .into_inner()
+            })
+            .collect();
+        assert_eq!(keys.len(), 26);
+    }
+
+    #[test]
+    fn generate_key() {
+        // The key space is small, so OS randomness should hit several keys
+        let keys: std::collections::HashSet<u16> = (0..200)
+            .map(|_| Key::generate().shift().into_inner())
+            .collect();
+        assert!(keys.len() > 1);
+        assert!(keys.iter().all(|&k| k < 26));
+    }
+
+    #[test]
+    fn differences() {
+        for k1 in 0..26 {
+            let key1 = Key::from(RingElement::new(k1));
+            for k2 in 0..26 {
+                let key2 = Key::from(RingElement::new(k2));
+                assert_eq!(key2.difference(&key1), (k2 + 26 - k1) % 26);
+                assert_eq!(key2.relative_to(&key1).compose(&key1), key2);
+            }
+        }
+    }
+
+    #[test]
+    fn weak_keys() {
+        let weak: Vec<u16> = (0..26)
+            .filter(|&k| Key::from(RingElement::new(k)).is_weak().is_some())
+            .collect();
+        assert_eq!(weak, [0]);
+
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        assert!((0..500).all(|_| Key::new_strong(&mut rng).is_weak().is_none()));
+    }
+
+    #[test]
+    fn rekey() {
+        let msg = Message::new("wewillmeetatmidnight").unwrap();
+        for old in 0..26 {
+            let old_key = Key::from(RingElement::new(old));
+            let new_key = Key::from(RingElement::new((old * 7 + 3) % 26));
+            let ciphertxt = ShiftCipher::new().encrypt(&msg, &old_key).unwrap();
+            assert_eq!(
+                ShiftCipher::new().rekey(&ciphertxt, &old_key, &new_key),
+                ShiftCipher::new()
+                    .encrypt(&msg, &new_key)
+                    .map_err(RekeyError::Encryption)
+            );
+        }
+    }
+
+    #[test]
+    fn composed_keys() {
+        let msg = Message::new("wewillmeetatmidnight").unwrap();
+        for k1 in 0..26 {
+            let k1 = Key::from(RingElement::new(k1));
+            for k2 in 0..26 {
+                let k2 = Key::from(RingElement::new(k2));
+
+                // Encrypting twice is encrypting once with the composed key
+                let once: Message = ShiftCipher::new()
+                    .encrypt(&msg, &k1)
+                    .unwrap()
+                    .iter()
+                    .copied()
+                    .collect();
+                assert_eq!(
+                    ShiftCipher::new().encrypt(&once, &k2).unwrap(),
+                    ShiftCipher::new().encrypt(&msg, &k2.compose(&k1)).unwrap()
+                );
+            }
+
+            // Encrypting with the inverse key is decrypting
+            let ciphertxt = ShiftCipher::new().encrypt(&msg, &k1).unwrap();
+            let undone: Message = ShiftCipher::new()
+                .encrypt(&ciphertxt.iter().copied().collect(), &k1.inverse())
+                .unwrap()
+                .iter()
+                .copied()
+                .collect();
+            assert_eq!(undone, msg);
+            assert_eq!(k1.compose(&k1.inverse()), Key::from(RingElement::new(0)));
+        }
+    }
+
+    #[test]
+    fn new_key() {
+        assert_eq!(Key::from_str("0").unwrap(), Key::from(RingElement::new(0)));
+        assert_eq!(Key::from_str("5").unwrap(), Key::from(RingElement::new(5)));
+        assert_eq!(Key::from_str("a").unwrap(), Key::from(RingElement::new(0)));
+        assert_eq!(Key::from_str("z").unwrap(), Key::from(RingElement::new(25)));
         assert_eq!(
-            Key::from_str("4s").unwrap_err(),
-            EncodingError::InvalidKey("4s".to_string())
+            Key::from_str("25 (z)").unwrap(),
+            Key::from(RingElement::new(25))
         );
     }
+
+    #[test]
+    fn key_display_modes() {
+        for shift in 0..26 {
+            let key = Key::from(RingElement::new(shift));
+            for mode in [
+                KeyDisplayMode::Number,
+                KeyDisplayMode::Letter,
+                KeyDisplayMode::Both,
+            ] {
+                assert_eq!(Key::from_str(&key.export_as(mode)).unwrap(), key);
+            }
+        }
+        assert_eq!(
+            Key::from(RingElement::new(0)).export_as(KeyDisplayMode::Both),
+            "0 (a)"
+        );
+        assert_eq!(
+            Key::from(RingElement::new(3)).export_as(KeyDisplayMode::default()),
+            "3"
+        );
+    }
+
+    #[test]
+    fn new_key_err() {
+        let error = |key: &str| EncodingError::KeyFormat {
+            key: key.to_string(),
+            cipher: "shift",
+            expected: KEY_FORMATS,
+        };
+        assert_eq!(Key::from_str("65").unwrap_err(), error("65"));
+        assert_eq!(Key::from_str("").unwrap_err(), error(""));
+        assert_eq!(Key::from_str("-5").unwrap_err(), error("-5"));
+        assert_eq!(Key::from_str("26").unwrap_err(), error("26"));
+        assert_eq!(Key::from_str("asdfas").unwrap_err(), error("asdfas"));
+        assert_eq!(Key::from_str("4s").unwrap_err(), error("4s"));
+        assert_eq!(Key::from_str("D").unwrap_err(), error("D"));
+        assert_eq!(Key::from_str("ab").unwrap_err(), error("ab"));
+        assert_eq!(Key::from_str("3 (e)").unwrap_err(), error("3 (e)"));
+        assert_eq!(Key::from_str("3 e").unwrap_err(), error("3 e"));
+        assert_eq!(Key::from_str("(d)").unwrap_err(), error("(d)"));
+    }
+
+    #[test]
+    fn try_from() {
+        // Conversions work through generic bounds, and agree with `FromStr`
+        fn convert<T: TryFrom<&'static str>>(s: &'static str) -> Option<T> {
+            s.try_into().ok()
+        }
+
+        assert_eq!(convert::<Key>("11 (l)"), Some(Key::from_str("11").unwrap()));
+        assert_eq!(convert::<Key>("26"), None);
+        assert_eq!(
+            Key::try_from("ab".to_string()).unwrap_err(),
+            Key::from_str("ab").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn eq_ignore_format() {
+        let key = Key::from_str("11").unwrap();
+        let original = "We will meet at midnight!";
+        let msg = Message::new_lossy(original).0;
+        let decrypted = ShiftCipher::new()
+            .decrypt(&ShiftCipher::new().encrypt(&msg, &key).unwrap(), &key)
+            .unwrap();
+        assert!(decrypted.eq_ignore_format(original));
+    }
+
+    #[test]
+    fn read_and_write_files() {
+        let path = std::env::temp_dir().join(format!(
+            "classical_crypto_{}_ciphertext.txt",
+            std::process::id()
+        ));
+        let settings = CipherSettings::new().with_group_size(4);
+        let ciphertxt = CIPH0.with(|ciph| ciph.clone());
+
+        ciphertxt.write_to_file(&path, &settings).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "HPHT WWXP PELE XTOY TRSE\n"
+        );
+        let read = Ciphertext::read_from_file(&path, &settings).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read, ciphertxt);
+
+        assert!(matches!(
+            Message::read_from_file(&path, &settings),
+            Err(ReadError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn letter_counts() {
+        // Shifting permutes the counts of the letters
+        let ciphertxt = CIPH0.with(|ciph| ciph.clone());
+        let msg = ShiftCipher::new()
+            .decrypt(&ciphertxt, &Key::from_str("11").unwrap())
+            .unwrap();
+        let (msg_counts, counts) = (msg.letter_counts(), ciphertxt.letter_counts());
+        for (x, y) in msg
+            .to_string()
+            .chars()
+            .zip(ciphertxt.to_string().to_lowercase().chars())
+        {
+            assert_eq!(msg_counts.count(x), counts.count(y));
+        }
+    }
+
+    #[test]
+    fn distance() {
+        let msg1 = Message::new("wewillmeetatmidnight").unwrap();
+        let msg2 = Message::new("wewillmeetatdaybreak").unwrap();
+
+        // Reusing a key keeps the distance between the messages, which gives the reuse away
+        let key = Key::from_str("11").unwrap();
+        let ciphertxt1 = ShiftCipher::new().encrypt(&msg1, &key).unwrap();
+        let ciphertxt2 = ShiftCipher::new().encrypt(&msg2, &key).unwrap();
+        assert_eq!(ciphertxt1.distance(&ciphertxt2), msg1.distance(&msg2));
+
+        // Under different keys, every position differs
+        let other = ShiftCipher::new()
+            .encrypt(&msg1, &Key::from_str("12").unwrap())
+            .unwrap();
+        assert_eq!(ciphertxt1.distance(&other), Some(20));
+    }
+
+    #[test]
+    fn brute_force() {
+        let candidates = BruteForce::default().attack(&CIPH0.with(|ciph| ciph.clone()));
+
+        assert_eq!(candidates.len(), 26);
+        let best = candidates.best().unwrap();
+        assert_eq!(best.key(), &Key::from(RingElement::new(11)));
+        assert_eq!(best.plaintext(), &MSG0.with(|msg| msg.clone()));
+    }
+
+    #[test]
+    fn brute_force_other_language() {
+        // In this "language", every letter is a 'z'
+        let model = LanguageModel::from_text("zzz", "zzzz").unwrap();
+        let ciph = Ciphertext::from_str("CCC").unwrap();
+
+        let candidates = BruteForce::with_model(model).attack(&ciph);
+        let best = candidates.best().unwrap();
+        assert_eq!(best.key(), &Key::from(RingElement::new(3)));
+        assert_eq!(best.plaintext(), &Message::new("zzz").unwrap());
+    }
+
+    #[test]
+    fn known_plaintext() {
+        let msg = MSG0.with(|msg| msg.clone());
+        let ciph = CIPH0.with(|ciph| ciph.clone());
+
+        assert_eq!(
+            KnownPlaintext.attack(&msg, &ciph),
+            Some(Key::from(RingElement::new(11)))
+        );
+
+        // Plaintext and ciphertext don't match
+        let other_msg = Message::new("wewillmeetatmidnighx").unwrap();
+        assert_eq!(KnownPlaintext.attack(&other_msg, &ciph), None);
+        let short_msg = Message::new("wewill").unwrap();
+        assert_eq!(KnownPlaintext.attack(&short_msg, &ciph), None);
+    }
+
+    #[test]
+    fn chosen_plaintext() {
+        let mut rng = reprod_rng();
+        let key = Key::from(RingElement::new(rng.gen_range(0..<RingElement>::MODULUS)));
+
+        let found = ChosenPlaintext.attack(|msg| ShiftCipher::new().encrypt(msg, &key).unwrap());
+        assert_eq!(found, Some(key));
+    }
 }
@@ -0,0 +1,103 @@
+//! Streaming encryption and decryption, which read a text one character at a
+//! time, so that large files can be processed without holding the whole
+//! message in memory.
+//!
+//! The ciphers that encrypt letter by letter provide `encrypt_stream` and
+//! `decrypt_stream`, which take an iterator of characters, and
+//! `encrypt_reader` and `decrypt_reader`, which take an [`io::Read`], see,
+//! e.g., [`ShiftCipher::encrypt_stream`](crate::shift::ShiftCipher::encrypt_stream).
+//! Each yields the output one letter at a time, as the input is read.
+use crate::errors::StreamError;
+use std::io::{self, BufReader, Bytes, Read};
+
+/// An iterator over the characters of a reader, which decodes them from
+/// UTF-8 as they are read.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::stream::ReadChars;
+/// let chars: Vec<char> = ReadChars::new("añb".as_bytes())
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(chars, ['a', 'ñ', 'b']);
+/// ```
+#[derive(Debug)]
+pub struct ReadChars<R> {
+    bytes: Bytes<BufReader<R>>,
+}
+
+impl<R: Read> ReadChars<R> {
+    /// Read the characters of `reader`, which is buffered internally.
+    pub fn new(reader: R) -> Self {
+        ReadChars {
+            bytes: BufReader::new(reader).bytes(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for ReadChars<R> {
+    type Item = io::Result<char>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )
+        };
+
+        let first = match self.bytes.next()? {
+            Ok(byte) => byte,
+            Err(e) => return Some(Err(e)),
+        };
+
+        // The leading byte of a character gives the number of bytes that encode it
+        let width = match first.leading_ones() {
+            0 => 1,
+            2..=4 => first.leading_ones() as usize,
+            _ => return Some(Err(invalid())),
+        };
+
+        let mut buf = [first, 0, 0, 0];
+        for byte in &mut buf[1..width] {
+            *byte = match self.bytes.next() {
+                Some(Ok(byte)) => byte,
+                Some(Err(e)) => return Some(Err(e)),
+                None => return Some(Err(invalid())),
+            };
+        }
+
+        Some(
+            std::str::from_utf8(&buf[..width])
+                .ok()
+                .and_then(|s| s.chars().next())
+                .ok_or_else(invalid),
+        )
+    }
+}
+
+// Read the characters of `reader` as a stream for the ciphers.
+pub(crate) fn read_chars<R: Read>(reader: R) -> impl Iterator<Item = Result<char, StreamError>> {
+    ReadChars::new(reader).map(|c| Ok(c?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_chars() {
+        let chars: Vec<char> = ReadChars::new("Gödel, Escher, Bach 🎵".as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(chars.iter().collect::<String>(), "Gödel, Escher, Bach 🎵");
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        for bytes in [&b"a\xffb"[..], b"\xc3", b"\x80", b"\xe2\x28\xa1"] {
+            assert!(ReadChars::new(bytes).any(|c| c.is_err()), "{bytes:?}");
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! The tabula recta, i.e., the square table of all shifts of an alphabet.
+//!
+//! The tabula recta is the table used to encrypt and decrypt by hand with the
+//! polyalphabetic ciphers that shift each letter by a different amount, such
+//! as the Vigenère, Beaufort and Trithemius Ciphers.
+use crate::{
+    alphabet::{CustomAlphabet, Latin},
+    errors::AlphabetError,
+};
+use std::fmt;
+
+/// A tabula recta with row and column lookup.
+///
+/// Row `r` of the table is the alphabet shifted left by `r` places, so the
+/// cell in the row headed by the letter `k` and the column headed by the
+/// letter `x` holds the letter at index `index(k) + index(x)` (mod the size
+/// of the alphabet). Rows and columns are headed by the letters of the
+/// alphabet in order.
+///
+/// The table serves each of the following ciphers, see Stinson, Section 2.1.4,
+/// for the Vigenère Cipher:
+/// - _Vigenère_: the ciphertext letter is the [`cell`](Self::cell) in the row
+///   of the key letter and the column of the plaintext letter, and decryption
+///   looks up the [`column`](Self::column) of the ciphertext letter in the row
+///   of the key letter.
+/// - _Beaufort_: the ciphertext letter is the [`column`](Self::column) of the
+///   key letter in the row of the plaintext letter, and decryption is the
+///   same as encryption.
+/// - _Trithemius_: the Vigenère Cipher with the key `abc...`, i.e., the `i`th
+///   letter is encrypted with the [`row`](Self::row) with index `i`.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::tabula::TabulaRecta;
+/// let table = TabulaRecta::latin();
+///
+/// // Vigenère
+/// assert_eq!(table.cell('l', 'a'), Some('l'));
+/// assert_eq!(table.cell('e', 'x'), Some('b'));
+/// assert_eq!(table.column('e', 'b'), Some('x'));
+///
+/// // Beaufort
+/// assert_eq!(table.column('x', 'e'), Some('h'));
+/// assert_eq!(table.column('h', 'e'), Some('x'));
+///
+/// // Trithemius
+/// assert_eq!(table.row(2).unwrap()[0], 'c');
+///
+/// println!("{table}");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TabulaRecta {
+    alphabet: CustomAlphabet,
+}
+
+impl TabulaRecta {
+    /// Create the tabula recta of an alphabet. The alphabet may be mixed with
+    /// a keyword, see [`CustomAlphabet::keyword_mixed`].
+    pub fn new(alphabet: CustomAlphabet) -> Self {
+        TabulaRecta { alphabet }
+    }
+
+    /// Create the tabula recta of the Latin Alphabet.
+    pub fn latin() -> Self {
+        Self::new(CustomAlphabet::from_alphabet::<Latin, 26>())
+    }
+
+    /// Create the tabula recta of the Latin Alphabet mixed with a keyword.
+    ///
+    /// # Errors
+    /// This function returns an error if the keyword contains a character
+    /// that is not in the Latin Alphabet.
+    pub fn latin_keyword(keyword: &str) -> Result<Self, AlphabetError> {
+        Ok(Self::new(
+            CustomAlphabet::from_alphabet::<Latin, 26>().keyword_mixed(keyword)?,
+        ))
+    }
+
+    /// Get the alphabet heading the rows and columns of the table.
+    pub fn alphabet(&self) -> &CustomAlphabet {
+        &self.alphabet
+    }
+
+    /// Get the number of rows (and columns) of the table.
+    pub fn size(&self) -> usize {
+        self.alphabet.size()
+    }
+
+    /// Get the row with the given index, if it is in the table.
+    pub fn row(&self, index: usize) -> Option<Vec<char>> {
+        (index < self.size()).then(|| {
+            let chars = self.alphabet.chars();
+            chars[index..]
+                .iter()
+                .chain(&chars[..index])
+                .copied()
+                .collect()
+        })
+    }
+
+    /// Get the cell in the row and column headed by the given letters, if
+    /// both are in the alphabet.
+    pub fn cell(&self, row: char, col: char) -> Option<char> {
+        let row = self.alphabet.index_of(row)?;
+        let col = self.alphabet.index_of(col)?;
+        self.alphabet.char_at((row + col) % self.size())
+    }
+
+    /// Get the letter heading the column where the row headed by `row` holds
+    /// `symbol`, if both are in the alphabet. This inverts
+    /// [`cell`](Self::cell), i.e., `column(k, cell(k, x)) == x`.
+    pub fn column(&self, row: char, symbol: char) -> Option<char> {
+        let row = self.alphabet.index_of(row)?;
+        let symbol = self.alphabet.index_of(symbol)?;
+        self.alphabet
+            .char_at((symbol + self.size() - row) % self.size())
+    }
+}
+
+impl Default for TabulaRecta {
+    fn default() -> Self {
+        Self::latin()
+    }
+}
+
+/// Display the full table, with a header row and a header column.
+impl fmt::Display for TabulaRecta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, " ")?;
+        for c in self.alphabet.chars() {
+            write!(f, " {c}")?;
+        }
+        for (index, &c) in self.alphabet.chars().iter().enumerate() {
+            write!(f, "\n{c}")?;
+            for cell in self.row(index).expect("The index is in range") {
+                write!(f, " {cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let table = TabulaRecta::latin();
+        assert_eq!(table.size(), 26);
+        assert_eq!(table.cell('a', 'q'), Some('q'));
+        assert_eq!(table.cell('z', 'b'), Some('a'));
+        assert_eq!(table.cell('A', 'b'), None);
+        assert_eq!(table.column('z', 'a'), Some('b'));
+        assert_eq!(table.column('b', '!'), None);
+        assert_eq!(table.row(26), None);
+
+        for (k, x) in [('k', 'p'), ('a', 'z'), ('z', 'z')] {
+            assert_eq!(table.column(k, table.cell(k, x).unwrap()), Some(x));
+        }
+    }
+
+    #[test]
+    fn vigenere() {
+        // Stinson, Example 2.4
+        let table = TabulaRecta::latin();
+        let key = "cipher".chars().cycle();
+        let ciphertext: String = "thiscryptosystemisnotsecure"
+            .chars()
+            .zip(key)
+            .map(|(x, k)| table.cell(k, x).unwrap())
+            .collect();
+        assert_eq!(ciphertext, "vpxzgiaxivwpubttmjpwizitwzt");
+    }
+
+    #[test]
+    fn keyword() {
+        let table = TabulaRecta::latin_keyword("kryptos").unwrap();
+        assert_eq!(
+            table.row(0).unwrap()[..7],
+            ['k', 'r', 'y', 'p', 't', 'o', 's']
+        );
+        assert_eq!(table.cell('k', 'k'), Some('k'));
+        assert_eq!(table.cell('r', 'r'), Some('y'));
+        assert_eq!(table.column('r', 'y'), Some('r'));
+        assert!(TabulaRecta::latin_keyword("k2").is_err());
+    }
+
+    #[test]
+    fn display() {
+        let alphabet = CustomAlphabet::from_chars(&['a', 'b', 'c']).unwrap();
+        let table = TabulaRecta::new(alphabet);
+        assert_eq!(table.to_string(), "  a b c\na a b c\nb b c a\nc c a b");
+        assert_eq!(TabulaRecta::latin().to_string().lines().count(), 27);
+    }
+}
@@ -0,0 +1,385 @@
+//! A toy implementation of the Diffie-Hellman key exchange over a small
+//! multiplicative group modulo a prime, following Stinson's treatment of key
+//! agreement.
+//!
+//! Alice and Bob agree in public on a prime `p` and a primitive element `g`
+//! modulo `p`. Alice picks a secret exponent `a` and sends `g^a mod p` to
+//! Bob, and Bob picks a secret exponent `b` and sends `g^b mod p` to Alice.
+//! Each can then compute the shared secret `g^(ab) mod p`, which they reduce
+//! to a key for a classical cipher, e.g., a [`shift::Key`]
+//! or a Vigenère keyword for the [`TabulaRecta`](crate::tabula::TabulaRecta).
+//!
+//! The exchange is not authenticated, so an opponent who controls the channel
+//! can run a separate exchange with each of Alice and Bob, see
+//! [`ManInTheMiddle`]. The groups here are also small enough that the
+//! discrete logarithm of a public value can be found by exhaustive search.
+//! This module is for learning only and is NOT secure.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{CipherTrait, shift::{Message, ShiftCipher}, toy_dh::{Group, Party}};
+//! use rand::thread_rng;
+//!
+//! let group = Group::toy();
+//! let alice = Party::new(group, &mut thread_rng());
+//! let bob = Party::new(group, &mut thread_rng());
+//!
+//! // Each party combines their own secret with the public value of the other
+//! let alice_secret = alice.shared_secret(bob.public_value()).unwrap();
+//! let bob_secret = bob.shared_secret(alice.public_value()).unwrap();
+//! assert_eq!(alice_secret, bob_secret);
+//!
+//! let msg = Message::new("meet me at noon").unwrap();
+//! let ciphertxt = ShiftCipher::new().encrypt(&msg, &alice_secret.shift_key()).unwrap();
+//! assert_eq!(ShiftCipher::new().decrypt(&ciphertxt, &bob_secret.shift_key()).unwrap(), msg);
+//! ```
+use crate::{
+    alphabet::Latin,
+    errors::DhError,
+    math::{is_prime, mod_pow},
+    shift, RingElement,
+};
+use rand::{CryptoRng, Rng, RngCore};
+
+/// The greatest prime modulus of a group, so that the primality of the
+/// modulus can be checked by trial division.
+pub const MAX_MODULUS: i64 = i32::MAX as i64;
+
+/// A multiplicative group modulo a prime `p`, together with a primitive
+/// element `g` that generates it.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Group {
+    p: i64,
+    g: i64,
+}
+
+impl Group {
+    /// Create the group of units modulo the prime `p`, generated by `g`.
+    ///
+    /// # Errors
+    /// This function returns an error if `p` is not an odd prime of at most
+    /// [`MAX_MODULUS`], or if `g` is not a primitive element modulo `p`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::toy_dh::Group;
+    /// assert!(Group::new(467, 2).is_ok());
+    ///
+    /// // 4 is a square, so it only generates half of the group
+    /// assert!(Group::new(467, 4).is_err());
+    /// ```
+    pub fn new(p: i64, g: i64) -> Result<Self, DhError> {
+        if !(3..=MAX_MODULUS).contains(&p) || !is_prime(p) {
+            return Err(DhError::InvalidModulus(p));
+        }
+
+        // g is primitive if and only if g^((p - 1) / r) != 1 for each prime factor r of p - 1
+        let mut factors = Vec::new();
+        let mut rest = p - 1;
+        let mut r = 2;
+        while r * r <= rest {
+            if rest % r == 0 {
+                factors.push(r);
+                while rest % r == 0 {
+                    rest /= r;
+                }
+            }
+            r += 1;
+        }
+        if rest > 1 {
+            factors.push(rest);
+        }
+
+        if !(2..p).contains(&g)
+            || factors
+                .iter()
+                .any(|r| mod_pow(g, ((p - 1) / r) as u64, p) == 1)
+        {
+            return Err(DhError::InvalidGenerator { generator: g, p });
+        }
+
+        Ok(Group { p, g })
+    }
+
+    /// The group modulo 467 generated by 2, which Stinson uses for the
+    /// examples of the ElGamal Cryptosystem.
+    pub fn toy() -> Self {
+        Group { p: 467, g: 2 }
+    }
+
+    /// Get the prime modulus.
+    pub fn p(&self) -> i64 {
+        self.p
+    }
+
+    /// Get the generator.
+    pub fn g(&self) -> i64 {
+        self.g
+    }
+
+    // Check that `value` is a unit modulo p.
+    fn check(&self, value: i64) -> Result<(), DhError> {
+        if (1..self.p).contains(&value) {
+            Ok(())
+        } else {
+            Err(DhError::OutOfRange { value, p: self.p })
+        }
+    }
+}
+
+/// A party to a Diffie-Hellman key exchange, who holds a secret exponent.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Party {
+    group: Group,
+    secret: i64,
+}
+
+impl Party {
+    /// Join an exchange in `group` with a secret exponent chosen uniformly at
+    /// random between 1 and `p - 2`.
+    pub fn new<R: RngCore + CryptoRng>(group: Group, rng: &mut R) -> Self {
+        Party {
+            group,
+            secret: rng.gen_range(1..group.p - 1),
+        }
+    }
+
+    /// Join an exchange in `group` with a given secret exponent.
+    ///
+    /// # Errors
+    /// This function returns an error if `secret` is not between 1 and
+    /// `p - 1`.
+    pub fn from_secret(group: Group, secret: i64) -> Result<Self, DhError> {
+        group.check(secret)?;
+        Ok(Party { group, secret })
+    }
+
+    /// Get the group of the exchange.
+    pub fn group(&self) -> Group {
+        self.group
+    }
+
+    /// Get the value to send to the other party, i.e., `g^secret mod p`.
+    pub fn public_value(&self) -> i64 {
+        mod_pow(self.group.g, self.secret as u64, self.group.p)
+    }
+
+    /// Combine the secret exponent with the public value of the other party.
+    ///
+    /// # Errors
+    /// This method returns an error if `public` is not between 1 and `p - 1`.
+    pub fn shared_secret(&self, public: i64) -> Result<SharedSecret, DhError> {
+        self.group.check(public)?;
+        Ok(SharedSecret {
+            value: mod_pow(public, self.secret as u64, self.group.p),
+            p: self.group.p,
+        })
+    }
+}
+
+/// The secret shared by the two parties of an exchange, which can be reduced
+/// to a key for a classical cipher.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SharedSecret {
+    value: i64,
+    p: i64,
+}
+
+impl SharedSecret {
+    /// Get the shared element of the group.
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    /// Reduce the secret modulo 26 to a key for the Shift Cipher.
+    pub fn shift_key(&self) -> shift::Key {
+        shift::Key::from(RingElement::from_i64(self.value))
+    }
+
+    /// Write the secret in base 26 as a keyword for the Vigenère Cipher,
+    /// least significant digit first, with `a` for 0. Every secret of the
+    /// group gives a keyword of the same length, i.e., the number of base 26
+    /// digits of `p - 1`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::toy_dh::{Group, Party};
+    /// let alice = Party::from_secret(Group::toy(), 127).unwrap();
+    /// let secret = alice.shared_secret(2).unwrap();
+    ///
+    /// // 2^127 = 2 + 5 * 26 mod 467
+    /// assert_eq!(secret.value(), 132);
+    /// assert_eq!(secret.keyword(), "cf");
+    /// ```
+    pub fn keyword(&self) -> String {
+        let mut letters = String::new();
+        let (mut value, mut bound) = (self.value, self.p - 1);
+        while bound > 0 {
+            letters.push(RingElement::from_i64(value % 26).to_char_lossy::<Latin>());
+            value /= 26;
+            bound /= 26;
+        }
+        letters
+    }
+}
+
+/// An opponent who sits between Alice and Bob and runs a separate exchange
+/// with each of them. Alice and Bob each believe that they share a secret
+/// with the other, but they share secrets with the opponent instead, who can
+/// decrypt, read, and re-encrypt every message between them.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{CipherTrait, shift::{Message, ShiftCipher}, toy_dh::{Group, ManInTheMiddle, Party}};
+/// use rand::thread_rng;
+///
+/// let group = Group::toy();
+/// let alice = Party::new(group, &mut thread_rng());
+/// let bob = Party::new(group, &mut thread_rng());
+/// let oscar = ManInTheMiddle::new(group, &mut thread_rng());
+///
+/// // Oscar replaces the public value of each party with his own
+/// let alice_secret = alice.shared_secret(oscar.public_value_for_alice()).unwrap();
+/// let bob_secret = bob.shared_secret(oscar.public_value_for_bob()).unwrap();
+/// let (with_alice, with_bob) = oscar
+///     .intercept(alice.public_value(), bob.public_value())
+///     .unwrap();
+/// assert_eq!(with_alice, alice_secret);
+/// assert_eq!(with_bob, bob_secret);
+///
+/// // Oscar reads the message of Alice, and passes it on to Bob
+/// let msg = Message::new("attack at dawn").unwrap();
+/// let ciphertxt = ShiftCipher::new().encrypt(&msg, &alice_secret.shift_key()).unwrap();
+/// let read = ShiftCipher::new().decrypt(&ciphertxt, &with_alice.shift_key()).unwrap();
+/// let forwarded = ShiftCipher::new().encrypt(&read, &with_bob.shift_key()).unwrap();
+/// assert_eq!(ShiftCipher::new().decrypt(&forwarded, &bob_secret.shift_key()).unwrap(), msg);
+/// ```
+#[derive(Debug, Eq, PartialEq)]
+pub struct ManInTheMiddle {
+    to_alice: Party,
+    to_bob: Party,
+}
+
+impl ManInTheMiddle {
+    /// Pick a secret exponent for each of the two exchanges.
+    pub fn new<R: RngCore + CryptoRng>(group: Group, rng: &mut R) -> Self {
+        ManInTheMiddle {
+            to_alice: Party::new(group, rng),
+            to_bob: Party::new(group, rng),
+        }
+    }
+
+    /// Get the value to send to Alice in place of the public value of Bob.
+    pub fn public_value_for_alice(&self) -> i64 {
+        self.to_alice.public_value()
+    }
+
+    /// Get the value to send to Bob in place of the public value of Alice.
+    pub fn public_value_for_bob(&self) -> i64 {
+        self.to_bob.public_value()
+    }
+
+    /// Compute the secrets shared with Alice and with Bob, in that order,
+    /// from their intercepted public values.
+    ///
+    /// # Errors
+    /// This method returns an error if either public value is not between 1
+    /// and `p - 1`.
+    pub fn intercept(
+        &self,
+        alice_public: i64,
+        bob_public: i64,
+    ) -> Result<(SharedSecret, SharedSecret), DhError> {
+        Ok((
+            self.to_alice.shared_secret(alice_public)?,
+            self.to_bob.shared_secret(bob_public)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn groups() {
+        assert_eq!(Group::new(467, 2), Ok(Group::toy()));
+        assert!(Group::new(MAX_MODULUS, 7).is_ok());
+        assert_eq!(Group::new(11, 2).map(|g| (g.p(), g.g())), Ok((11, 2)));
+
+        // The powers of 3 modulo 11 are 3, 9, 5, 4, 1
+        assert_eq!(
+            Group::new(11, 3),
+            Err(DhError::InvalidGenerator {
+                generator: 3,
+                p: 11
+            })
+        );
+        assert_eq!(
+            Group::new(11, 1),
+            Err(DhError::InvalidGenerator {
+                generator: 1,
+                p: 11
+            })
+        );
+        assert_eq!(Group::new(15, 2), Err(DhError::InvalidModulus(15)));
+        assert_eq!(Group::new(2, 1), Err(DhError::InvalidModulus(2)));
+    }
+
+    #[test]
+    fn exchange() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let group = Group::new(MAX_MODULUS, 7).unwrap();
+        for _ in 0..20 {
+            let alice = Party::new(group, &mut rng);
+            let bob = Party::new(group, &mut rng);
+            let secret = alice.shared_secret(bob.public_value()).unwrap();
+            assert_eq!(secret, bob.shared_secret(alice.public_value()).unwrap());
+            assert_eq!(secret.keyword().len(), 7);
+            assert_eq!(
+                secret.shift_key(),
+                shift::Key::from(RingElement::from_i64(secret.value() % 26))
+            );
+        }
+    }
+
+    #[test]
+    fn man_in_the_middle() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let group = Group::toy();
+        let alice = Party::new(group, &mut rng);
+        let bob = Party::new(group, &mut rng);
+        let oscar = ManInTheMiddle::new(group, &mut rng);
+
+        let alice_secret = alice.shared_secret(oscar.public_value_for_alice()).unwrap();
+        let bob_secret = bob.shared_secret(oscar.public_value_for_bob()).unwrap();
+        assert_eq!(
+            oscar.intercept(alice.public_value(), bob.public_value()),
+            Ok((alice_secret, bob_secret))
+        );
+        assert_eq!(
+            oscar.intercept(0, bob.public_value()),
+            Err(DhError::OutOfRange { value: 0, p: 467 })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let group = Group::toy();
+        assert_eq!(
+            Party::from_secret(group, 467),
+            Err(DhError::OutOfRange { value: 467, p: 467 })
+        );
+        let party = Party::from_secret(group, 5).unwrap();
+        assert_eq!(party.public_value(), 32);
+        assert_eq!(
+            party.shared_secret(-3),
+            Err(DhError::OutOfRange { value: -3, p: 467 })
+        );
+    }
+}
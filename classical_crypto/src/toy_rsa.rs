@@ -0,0 +1,304 @@
+//! A toy implementation of the RSA Cryptosystem, with moduli small enough to
+//! factor by hand, following Stinson, Chapter 6.
+//!
+//! The private key is a pair of distinct primes `p` and `q` together with an
+//! exponent `a`, and the public key is the modulus `n = p * q` together with
+//! an exponent `b` such that `a * b ≡ 1 mod (p - 1)(q - 1)`. A plaintext `x`
+//! between 0 and `n - 1` is encrypted as `x^b mod n`, and a ciphertext `y` is
+//! decrypted as `y^a mod n`.
+//!
+//! The security of RSA rests on the difficulty of factoring `n`. Here the
+//! primes are at most [`MAX_PRIME`], so [`break_key`] recovers the private
+//! key from the public key at once by trial division. This module is for
+//! learning only and is NOT secure.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::toy_rsa::{break_key, PrivateKey};
+//! // The example of Stinson, with p = 101, q = 113 and b = 3533
+//! let private = PrivateKey::from_primes(101, 113, 3533).unwrap();
+//! let public = private.public_key();
+//! assert_eq!(public.n(), 11413);
+//! assert_eq!(private.a(), 6597);
+//!
+//! let y = public.encrypt(9726).unwrap();
+//! assert_eq!(y, 5761);
+//! assert_eq!(private.decrypt(y).unwrap(), 9726);
+//!
+//! // Factoring the modulus recovers the private key
+//! assert_eq!(break_key(&public), Some(private));
+//! ```
+use crate::{
+    errors::RsaError,
+    math::{gcd, is_prime, mod_inverse, mod_pow},
+};
+use rand::{CryptoRng, Rng, RngCore};
+
+/// The greatest prime factor of a modulus, so that every modulus is less
+/// than 2<sup>30</sup>.
+pub const MAX_PRIME: i64 = 1 << 15;
+
+/// A public key for the toy RSA Cryptosystem: the modulus `n` and the
+/// encryption exponent `b`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PublicKey {
+    n: i64,
+    b: i64,
+}
+
+impl PublicKey {
+    /// Get the modulus.
+    pub fn n(&self) -> i64 {
+        self.n
+    }
+
+    /// Get the encryption exponent.
+    pub fn b(&self) -> i64 {
+        self.b
+    }
+
+    /// Encrypt the plaintext `x` as `x^b mod n`.
+    ///
+    /// # Errors
+    /// This method returns an error if `x` is not between 0 and `n - 1`.
+    pub fn encrypt(&self, x: i64) -> Result<i64, RsaError> {
+        self.check(x)?;
+        Ok(mod_pow(x, self.b as u64, self.n))
+    }
+
+    // Check that `value` is an element of Z_n.
+    fn check(&self, value: i64) -> Result<(), RsaError> {
+        if (0..self.n).contains(&value) {
+            Ok(())
+        } else {
+            Err(RsaError::OutOfRange { value, n: self.n })
+        }
+    }
+}
+
+/// A private key for the toy RSA Cryptosystem: the primes `p` and `q`, and
+/// the decryption exponent `a`. The private key also holds the encryption
+/// exponent, so that it determines the public key.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PrivateKey {
+    p: i64,
+    q: i64,
+    a: i64,
+    public: PublicKey,
+}
+
+impl PrivateKey {
+    /// Generate a key: `p` and `q` are distinct random primes between
+    /// [`MAX_PRIME`]` / 2` and [`MAX_PRIME`], and `b` is a random unit modulo
+    /// `(p - 1)(q - 1)`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use classical_crypto::toy_rsa::PrivateKey;
+    /// use rand::thread_rng;
+    ///
+    /// let key = PrivateKey::new(&mut thread_rng());
+    /// let y = key.public_key().encrypt(42).unwrap();
+    /// assert_eq!(key.decrypt(y).unwrap(), 42);
+    /// ```
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let p = random_prime(rng);
+        let q = loop {
+            let q = random_prime(rng);
+            if q != p {
+                break q;
+            }
+        };
+
+        let phi = (p - 1) * (q - 1);
+        let b = loop {
+            let b = rng.gen_range(2..phi);
+            if gcd(b, phi) == 1 {
+                break b;
+            }
+        };
+
+        PrivateKey::from_primes(p, q, b).expect("The primes and exponent are valid")
+    }
+
+    /// Create a key from the primes `p` and `q` and the encryption exponent
+    /// `b`. The decryption exponent is the inverse of `b` modulo
+    /// `(p - 1)(q - 1)`.
+    ///
+    /// # Errors
+    /// This function returns an error if `p` or `q` is not a prime of at most
+    /// [`MAX_PRIME`], if `p` and `q` are equal, or if `b` is not a unit modulo
+    /// `(p - 1)(q - 1)`.
+    pub fn from_primes(p: i64, q: i64, b: i64) -> Result<Self, RsaError> {
+        for prime in [p, q] {
+            if prime > MAX_PRIME || !is_prime(prime) {
+                return Err(RsaError::InvalidPrime(prime));
+            }
+        }
+        if p == q {
+            return Err(RsaError::RepeatedPrime(p));
+        }
+
+        let phi = (p - 1) * (q - 1);
+        let a = match mod_inverse(b, phi) {
+            Some(a) if (1..phi).contains(&b) => a,
+            _ => return Err(RsaError::InvalidExponent { exponent: b, phi }),
+        };
+
+        Ok(PrivateKey {
+            p,
+            q,
+            a,
+            public: PublicKey { n: p * q, b },
+        })
+    }
+
+    /// Get the public key.
+    pub fn public_key(&self) -> PublicKey {
+        self.public
+    }
+
+    /// Get the first prime.
+    pub fn p(&self) -> i64 {
+        self.p
+    }
+
+    /// Get the second prime.
+    pub fn q(&self) -> i64 {
+        self.q
+    }
+
+    /// Get the decryption exponent.
+    pub fn a(&self) -> i64 {
+        self.a
+    }
+
+    /// Decrypt the ciphertext `y` as `y^a mod n`.
+    ///
+    /// # Errors
+    /// This method returns an error if `y` is not between 0 and `n - 1`.
+    pub fn decrypt(&self, y: i64) -> Result<i64, RsaError> {
+        self.public.check(y)?;
+        Ok(mod_pow(y, self.a as u64, self.public.n))
+    }
+}
+
+/// Factor `n` by trial division, returning its least prime factor and the
+/// remaining cofactor, or `None` if `n` is less than 2 or is prime.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::toy_rsa::factor;
+/// assert_eq!(factor(11413), Some((101, 113)));
+/// assert_eq!(factor(113), None);
+/// ```
+pub fn factor(n: i64) -> Option<(i64, i64)> {
+    (2..)
+        .take_while(|d| d * d <= n)
+        .find(|d| n % d == 0)
+        .map(|d| (d, n / d))
+}
+
+/// Break a public key by factoring its modulus, and return the private key,
+/// or `None` if the modulus is not the product of two distinct primes.
+pub fn break_key(key: &PublicKey) -> Option<PrivateKey> {
+    let (p, q) = factor(key.n)?;
+    PrivateKey::from_primes(p, q, key.b).ok()
+}
+
+// Pick a prime between MAX_PRIME / 2 and MAX_PRIME uniformly at random.
+fn random_prime<R: RngCore + CryptoRng>(rng: &mut R) -> i64 {
+    loop {
+        let n = rng.gen_range(MAX_PRIME / 2..=MAX_PRIME);
+        if is_prime(n) {
+            return n;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn factors() {
+        assert_eq!(factor(49), Some((7, 7)));
+        assert_eq!(factor(1_000_001), Some((101, 9901)));
+        assert_eq!(factor(1), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        for _ in 0..20 {
+            let key = PrivateKey::new(&mut rng);
+            let public = key.public_key();
+            assert_ne!(key.p(), key.q());
+            assert!(public.n() < 1 << 30);
+            assert_eq!(key.a() * public.b() % ((key.p() - 1) * (key.q() - 1)), 1);
+
+            for _ in 0..20 {
+                let x = rng.gen_range(0..public.n());
+                assert_eq!(key.decrypt(public.encrypt(x).unwrap()).unwrap(), x);
+            }
+        }
+    }
+
+    #[test]
+    fn broken() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        for _ in 0..20 {
+            let key = PrivateKey::new(&mut rng);
+            let broken = break_key(&key.public_key()).unwrap();
+            assert_eq!(broken.a(), key.a());
+            assert_eq!(broken.p() * broken.q(), key.p() * key.q());
+        }
+
+        // A modulus with a repeated factor is not an RSA modulus
+        assert_eq!(break_key(&PublicKey { n: 49, b: 5 }), None);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            PrivateKey::from_primes(101, 111, 7),
+            Err(RsaError::InvalidPrime(111))
+        );
+        assert_eq!(
+            PrivateKey::from_primes(32_771, 101, 7),
+            Err(RsaError::InvalidPrime(32_771))
+        );
+        assert_eq!(
+            PrivateKey::from_primes(101, 101, 7),
+            Err(RsaError::RepeatedPrime(101))
+        );
+        assert_eq!(
+            PrivateKey::from_primes(101, 113, 10),
+            Err(RsaError::InvalidExponent {
+                exponent: 10,
+                phi: 11200
+            })
+        );
+
+        let key = PrivateKey::from_primes(101, 113, 3533).unwrap();
+        assert_eq!(
+            key.public_key().encrypt(11413),
+            Err(RsaError::OutOfRange {
+                value: 11413,
+                n: 11413
+            })
+        );
+        assert_eq!(
+            key.decrypt(-1),
+            Err(RsaError::OutOfRange {
+                value: -1,
+                n: 11413
+            })
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! A toy implementation of the Shamir Threshold Scheme over a small prime
+//! field, following Stinson's treatment of secret sharing.
+//!
+//! To split a secret `K` into `w` shares with threshold `t`, the dealer picks
+//! a random polynomial `a(x)` of degree at most `t - 1` modulo a prime `p`
+//! with constant term `K`, and gives the share `(x, a(x))` to each of the
+//! participants `x = 1, ..., w`. Any `t` shares determine the polynomial, and
+//! so the secret, by Lagrange interpolation. Any `t - 1` shares reveal nothing
+//! about the secret: for every candidate secret there is exactly one
+//! polynomial that agrees with them.
+//!
+//! The scheme is secure in this information-theoretic sense for any prime,
+//! but the field here is small enough to check this by hand. This module is
+//! for learning only.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{shift::Key, toy_shamir::{Scheme, LATIN_PRIME}};
+//! # use std::str::FromStr;
+//! use rand::thread_rng;
+//!
+//! let scheme = Scheme::new(LATIN_PRIME, 3, 5).unwrap();
+//! let key = Key::from_str("11").unwrap();
+//! let shares = scheme.split_key(&key, &mut thread_rng());
+//!
+//! // Any three of the five shares give back the key
+//! assert_eq!(scheme.reconstruct_key(&shares[2..]).unwrap(), key);
+//! assert!(scheme.reconstruct_key(&shares[..2]).is_err());
+//! ```
+use crate::{
+    errors::SharingError,
+    math::{is_prime, mod_inverse},
+    shift, RingElement,
+};
+use rand::{CryptoRng, Rng, RngCore};
+
+/// The greatest prime modulus of a scheme, so that the arithmetic of the
+/// field fits in an `i64`.
+pub const MAX_PRIME: i64 = i32::MAX as i64;
+
+/// The least prime greater than 26, i.e., the smallest field that holds a
+/// key of the Shift Cipher.
+pub const LATIN_PRIME: i64 = 29;
+
+/// A share of a secret: the point `(x, y)` on the polynomial of the dealer.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Share {
+    x: i64,
+    y: i64,
+}
+
+impl Share {
+    /// Create a share from its coordinates, e.g., to reconstruct from shares
+    /// that were stored separately.
+    pub fn new(x: i64, y: i64) -> Self {
+        Share { x, y }
+    }
+
+    /// Get the `x`-coordinate, i.e., the participant who holds the share.
+    pub fn x(&self) -> i64 {
+        self.x
+    }
+
+    /// Get the `y`-coordinate, i.e., the value of the polynomial at `x`.
+    pub fn y(&self) -> i64 {
+        self.y
+    }
+}
+
+/// A threshold scheme that splits a secret modulo a prime `p` into `shares`
+/// shares, any `threshold` of which reconstruct the secret.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Scheme {
+    p: i64,
+    threshold: usize,
+    shares: usize,
+}
+
+impl Scheme {
+    /// Create a scheme modulo `p` with the given threshold and number of
+    /// shares.
+    ///
+    /// # Errors
+    /// This function returns an error if `p` is not a prime of at most
+    /// [`MAX_PRIME`], if the threshold is 0 or greater than the number of
+    /// shares, or if there are `p` or more shares, since each share needs a
+    /// distinct nonzero `x`-coordinate.
+    pub fn new(p: i64, threshold: usize, shares: usize) -> Result<Self, SharingError> {
+        if p > MAX_PRIME || !is_prime(p) {
+            return Err(SharingError::InvalidModulus(p));
+        }
+        if threshold == 0 || threshold > shares || shares as i64 >= p {
+            return Err(SharingError::InvalidThreshold {
+                threshold,
+                shares,
+                p,
+            });
+        }
+
+        Ok(Scheme {
+            p,
+            threshold,
+            shares,
+        })
+    }
+
+    /// Get the prime modulus.
+    pub fn p(&self) -> i64 {
+        self.p
+    }
+
+    /// Get the number of shares needed to reconstruct the secret.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Get the number of shares.
+    pub fn shares(&self) -> usize {
+        self.shares
+    }
+
+    /// Split `secret` into shares, with the `x`-coordinates 1 to the number
+    /// of shares.
+    ///
+    /// # Errors
+    /// This method returns an error if `secret` is not between 0 and `p - 1`.
+    pub fn split<R: RngCore + CryptoRng>(
+        &self,
+        secret: i64,
+        rng: &mut R,
+    ) -> Result<Vec<Share>, SharingError> {
+        self.check(secret)?;
+
+        let mut coefficients = vec![secret];
+        coefficients.extend((1..self.threshold).map(|_| rng.gen_range(0..self.p)));
+
+        Ok((1..=self.shares as i64)
+            .map(|x| Share::new(x, evaluate(&coefficients, x, self.p)))
+            .collect())
+    }
+
+    /// Reconstruct the secret from at least `threshold` shares by Lagrange
+    /// interpolation. Only the first `threshold` shares are used.
+    ///
+    /// # Errors
+    /// This method returns an error if there are too few shares, if a
+    /// coordinate of a share is not between 0 and `p - 1`, or if two shares
+    /// have the same `x`-coordinate.
+    pub fn reconstruct(&self, shares: &[Share]) -> Result<i64, SharingError> {
+        if shares.len() < self.threshold {
+            return Err(SharingError::TooFewShares {
+                given: shares.len(),
+                threshold: self.threshold,
+            });
+        }
+
+        let shares = &shares[..self.threshold];
+        for (i, share) in shares.iter().enumerate() {
+            self.check(share.x)?;
+            self.check(share.y)?;
+            if share.x == 0 {
+                return Err(SharingError::OutOfRange {
+                    value: 0,
+                    p: self.p,
+                });
+            }
+            if shares[..i].iter().any(|other| other.x == share.x) {
+                return Err(SharingError::RepeatedShare(share.x));
+            }
+        }
+
+        // K = sum of y_j * prod_{k != j} x_k / (x_k - x_j)
+        Ok(shares.iter().fold(0, |secret, share| {
+            let basis = shares
+                .iter()
+                .filter(|other| other.x != share.x)
+                .fold(1, |basis, other| {
+                    let inverse = mod_inverse(other.x - share.x, self.p)
+                        .expect("Distinct x-coordinates differ by a unit");
+                    basis * other.x % self.p * inverse % self.p
+                });
+            (secret + share.y * basis) % self.p
+        }))
+    }
+
+    /// Split a key of the Shift Cipher into shares.
+    ///
+    /// # Panics
+    /// This method panics if `p` is less than 26, since the field cannot hold
+    /// every key.
+    pub fn split_key<R: RngCore + CryptoRng>(&self, key: &shift::Key, rng: &mut R) -> Vec<Share> {
+        assert!(self.p >= 26, "The field must hold every key");
+
+        self.split(i64::from(key.shift().into_inner()), rng)
+            .expect("Every key is less than the modulus")
+    }
+
+    /// Reconstruct a key of the Shift Cipher, see
+    /// [`reconstruct`](Self::reconstruct).
+    ///
+    /// # Errors
+    /// This method also returns an error if the reconstructed secret is not
+    /// between 0 and 25, e.g., because a share was altered.
+    pub fn reconstruct_key(&self, shares: &[Share]) -> Result<shift::Key, SharingError> {
+        match self.reconstruct(shares)? {
+            secret @ 0..=25 => Ok(shift::Key::from(RingElement::from_i64(secret))),
+            secret => Err(SharingError::InvalidKey(secret)),
+        }
+    }
+
+    // Check that `value` is an element of the field.
+    fn check(&self, value: i64) -> Result<(), SharingError> {
+        if (0..self.p).contains(&value) {
+            Ok(())
+        } else {
+            Err(SharingError::OutOfRange { value, p: self.p })
+        }
+    }
+}
+
+// Evaluate the polynomial with the given coefficients, constant term first, at `x` modulo `p`
+// with Horner's method.
+fn evaluate(coefficients: &[i64], x: i64, p: i64) -> i64 {
+    coefficients.iter().rev().fold(0, |y, a| (y * x + a) % p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use std::collections::HashSet;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    #[test]
+    fn round_trip() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let scheme = Scheme::new(MAX_PRIME, 4, 7).unwrap();
+        for _ in 0..20 {
+            let secret = rng.gen_range(0..MAX_PRIME);
+            let mut shares = scheme.split(secret, &mut rng).unwrap();
+            assert_eq!(shares.len(), 7);
+            assert_eq!(scheme.reconstruct(&shares), Ok(secret));
+
+            // The order of the shares does not matter
+            shares.reverse();
+            assert_eq!(scheme.reconstruct(&shares[1..5]), Ok(secret));
+        }
+
+        // With threshold 1, every share is the secret
+        let scheme = Scheme::new(LATIN_PRIME, 1, 3).unwrap();
+        let shares = scheme.split(17, &mut rng).unwrap();
+        assert!(shares.iter().all(|share| share.y() == 17));
+    }
+
+    #[test]
+    fn keys() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let scheme = Scheme::new(LATIN_PRIME, 2, 3).unwrap();
+        for shift in 0..26 {
+            let key = shift::Key::from(RingElement::from_i64(shift));
+            let shares = scheme.split_key(&key, &mut rng);
+            assert_eq!(scheme.reconstruct_key(&shares[1..]), Ok(key));
+        }
+    }
+
+    // For threshold 3 modulo 29, each pair of values of two shares arises from exactly one
+    // polynomial for each secret, so the two shares are uniform whatever the secret is.
+    #[test]
+    fn too_few_shares_reveal_nothing() {
+        let p = LATIN_PRIME;
+        for secret in 0..p {
+            let pairs: HashSet<(i64, i64)> = (0..p)
+                .flat_map(|a1| (0..p).map(move |a2| [secret, a1, a2]))
+                .map(|coefficients| (evaluate(&coefficients, 1, p), evaluate(&coefficients, 2, p)))
+                .collect();
+            assert_eq!(pairs.len(), (p * p) as usize);
+        }
+
+        // Any candidate secret is consistent with two shares
+        let scheme = Scheme::new(p, 3, 5).unwrap();
+        let shares = scheme
+            .split(11, &mut ChaCha12Rng::from_seed(TEST_SEED))
+            .unwrap();
+        let candidates: HashSet<i64> = (0..p)
+            .map(|y| {
+                scheme
+                    .reconstruct(&[shares[0], shares[1], Share::new(3, y)])
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(candidates.len(), p as usize);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Scheme::new(26, 2, 3), Err(SharingError::InvalidModulus(26)));
+        assert_eq!(
+            Scheme::new(5, 2, 5),
+            Err(SharingError::InvalidThreshold {
+                threshold: 2,
+                shares: 5,
+                p: 5
+            })
+        );
+        assert!(Scheme::new(LATIN_PRIME, 0, 3).is_err());
+        assert!(Scheme::new(LATIN_PRIME, 4, 3).is_err());
+
+        let scheme = Scheme::new(LATIN_PRIME, 2, 3).unwrap();
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        assert_eq!(
+            scheme.split(29, &mut rng),
+            Err(SharingError::OutOfRange { value: 29, p: 29 })
+        );
+        assert_eq!(
+            scheme.reconstruct(&[Share::new(1, 3)]),
+            Err(SharingError::TooFewShares {
+                given: 1,
+                threshold: 2
+            })
+        );
+        assert_eq!(
+            scheme.reconstruct(&[Share::new(1, 3), Share::new(1, 4)]),
+            Err(SharingError::RepeatedShare(1))
+        );
+        assert_eq!(
+            scheme.reconstruct(&[Share::new(0, 3), Share::new(1, 4)]),
+            Err(SharingError::OutOfRange { value: 0, p: 29 })
+        );
+
+        // The line through (1, 0) and (2, 1) meets the y-axis at 28
+        assert_eq!(
+            scheme.reconstruct_key(&[Share::new(1, 0), Share::new(2, 1)]),
+            Err(SharingError::InvalidKey(28))
+        );
+    }
+}
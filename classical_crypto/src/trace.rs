@@ -0,0 +1,140 @@
+//! Traces of encryption, i.e., a record of each step that a cipher takes, so
+//! that a worked example can be shown the way it is done by hand.
+//!
+//! A [`TracedCipher`] encrypts a message letter by letter, and records for
+//! each letter the ring element that encodes it, the key element that is
+//! applied to it, and the resulting element of the ciphertext.
+use crate::{alphabet::Latin, CipherTrait, RingElement};
+use std::{fmt, slice};
+
+/// A step of a [`Trace`], i.e., the encryption of one letter.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct Step {
+    /// The letter of the message.
+    pub input: char,
+    /// The ring element that encodes the letter of the message.
+    pub input_element: RingElement,
+    /// The element of the key that is applied to the letter, e.g., the shift
+    /// of the Shift Cipher.
+    pub key_element: RingElement,
+    /// The ring element of the ciphertext.
+    pub output_element: RingElement,
+    /// The letter of the ciphertext, in ALL CAPS following Stinson's
+    /// convention.
+    pub output: char,
+}
+
+impl Step {
+    // Record the encryption of `input_element` to `output_element` under `key_element`.
+    pub(crate) fn new(
+        input_element: RingElement,
+        key_element: RingElement,
+        output_element: RingElement,
+    ) -> Self {
+        Step {
+            input: input_element.to_char_lossy::<Latin>(),
+            input_element,
+            key_element,
+            output_element,
+            output: output_element.to_char_lossy::<Latin>().to_ascii_uppercase(),
+        }
+    }
+}
+
+/// The steps of an encryption, in order, as returned by
+/// [`TracedCipher::encrypt_traced`].
+///
+/// The [`Display`](fmt::Display) implementation writes a table with one row
+/// per step.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{shift::{Key, Message, ShiftCipher}, trace::TracedCipher};
+/// # use std::str::FromStr;
+/// let msg = Message::new("we").unwrap();
+/// let key = Key::from_str("11").unwrap();
+/// let (ciphertxt, trace) = ShiftCipher::new().encrypt_traced(&msg, &key).unwrap();
+///
+/// assert_eq!(ciphertxt.to_string(), "HP");
+/// assert_eq!(trace.steps()[0].output_element.into_inner(), 7);
+/// assert_eq!(
+///     trace.to_string(),
+///     "in  x  k  y out\n w 22 11  7  H\n e  4 11 15  P"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Trace(Vec<Step>);
+
+impl Trace {
+    /// Get the steps of the trace.
+    pub fn steps(&self) -> &[Step] {
+        &self.0
+    }
+
+    /// Get the number of steps of the trace.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the trace has no steps and false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the steps of the trace.
+    pub fn iter(&self) -> slice::Iter<'_, Step> {
+        self.0.iter()
+    }
+}
+
+impl FromIterator<Step> for Trace {
+    fn from_iter<I: IntoIterator<Item = Step>>(iter: I) -> Self {
+        Trace(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a Trace {
+    type Item = &'a Step;
+    type IntoIter = slice::Iter<'a, Step>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Write a header row, then one row per step: the letter of the message, its
+/// ring element `x`, the key element `k`, the ring element `y` of the
+/// ciphertext, and the letter of the ciphertext.
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "in  x  k  y out")?;
+        for step in &self.0 {
+            write!(
+                f,
+                "\n{:>2} {:>2} {:>2} {:>2}  {}",
+                step.input,
+                step.input_element.into_inner(),
+                step.key_element.into_inner(),
+                step.output_element.into_inner(),
+                step.output
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A cipher that can record a [`Trace`] of each encryption, i.e., a cipher
+/// that encrypts letter by letter.
+pub trait TracedCipher: CipherTrait {
+    /// Encrypt a message, as [`CipherTrait::encrypt`] does, and also return
+    /// a trace with one step per letter.
+    ///
+    /// # Errors
+    /// This method returns an error if encryption fails.
+    fn encrypt_traced(
+        &self,
+        msg: &Self::Message,
+        key: &Self::Key,
+    ) -> Result<(Self::Ciphertext, Trace), Self::EncryptionError>;
+}
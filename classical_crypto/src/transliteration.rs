@@ -0,0 +1,174 @@
+//! Transliteration of text onto the Latin Alphabet before encryption, e.g.,
+//! folding accented letters such as `é` into `e`.
+//!
+//! The ciphers of this crate use the Latin Alphabet, so text in other
+//! languages must be transliterated first. A [`Transliterator`] replaces
+//! characters according to a table of rules, which may be extended with
+//! custom rules, e.g., for a language's conventions (`ä` as `ae` in German)
+//! or a different script.
+use std::collections::BTreeMap;
+
+// The accented (and other special) letters of the Latin-1 Supplement and Latin Extended-A blocks,
+// grouped by the letters they fold into.
+const ACCENTS: [(&str, &str); 45] = [
+    ("àáâãäåāăą", "a"),
+    ("ÀÁÂÃÄÅĀĂĄ", "A"),
+    ("æ", "ae"),
+    ("Æ", "AE"),
+    ("çćĉċč", "c"),
+    ("ÇĆĈĊČ", "C"),
+    ("ďđð", "d"),
+    ("ĎĐÐ", "D"),
+    ("èéêëēĕėęě", "e"),
+    ("ÈÉÊËĒĔĖĘĚ", "E"),
+    ("ĝğġģ", "g"),
+    ("ĜĞĠĢ", "G"),
+    ("ĥħ", "h"),
+    ("ĤĦ", "H"),
+    ("ìíîïĩīĭįı", "i"),
+    ("ÌÍÎÏĨĪĬĮİ", "I"),
+    ("ĵ", "j"),
+    ("Ĵ", "J"),
+    ("ķ", "k"),
+    ("Ķ", "K"),
+    ("ĺļľŀł", "l"),
+    ("ĹĻĽĿŁ", "L"),
+    ("ñńņňŉ", "n"),
+    ("ÑŃŅŇ", "N"),
+    ("òóôõöøōŏő", "o"),
+    ("ÒÓÔÕÖØŌŎŐ", "O"),
+    ("œ", "oe"),
+    ("Œ", "OE"),
+    ("ŕŗř", "r"),
+    ("ŔŖŘ", "R"),
+    ("śŝşš", "s"),
+    ("ŚŜŞŠ", "S"),
+    ("ß", "ss"),
+    ("ţťŧ", "t"),
+    ("ŢŤŦ", "T"),
+    ("þ", "th"),
+    ("Þ", "TH"),
+    ("ùúûüũūŭůűų", "u"),
+    ("ÙÚÛÜŨŪŬŮŰŲ", "U"),
+    ("ŵ", "w"),
+    ("Ŵ", "W"),
+    ("ýÿŷ", "y"),
+    ("ÝŸŶ", "Y"),
+    ("źżž", "z"),
+    ("ŹŻŽ", "Z"),
+];
+
+/// Replaces characters of a text according to a table of rules.
+///
+/// Characters without a rule are kept as they are. The table of
+/// [`Transliterator::accents`] folds the accented letters of most European
+/// languages that use the Latin script, keeping their case.
+///
+/// # Examples
+/// ```
+/// # use classical_crypto::{shift::Message, transliteration::Transliterator};
+/// let text = "Crème brûlée";
+/// let folded = Transliterator::accents().apply(text);
+/// assert_eq!(folded, "Creme brulee");
+///
+/// // Rules can be added or replaced, e.g., following German conventions.
+/// let german = Transliterator::accents().with_rule('ü', "ue");
+/// assert_eq!(german.apply("für"), "fuer");
+///
+/// let msg = Message::new(&german.apply("grüße").to_lowercase()).unwrap();
+/// assert_eq!(msg.to_string(), "gruesse");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Transliterator {
+    rules: BTreeMap<char, String>,
+}
+
+impl Transliterator {
+    /// Create a transliterator without rules, which keeps text as it is.
+    pub fn new() -> Self {
+        Transliterator::default()
+    }
+
+    /// Create a transliterator that folds accented letters into the letters
+    /// of the Latin Alphabet, e.g., `é` into `e`, `Ñ` into `N` and `ß` into
+    /// `ss`.
+    pub fn accents() -> Self {
+        let rules = ACCENTS
+            .iter()
+            .flat_map(|(from, to)| from.chars().map(move |c| (c, to.to_string())))
+            .collect();
+
+        Transliterator { rules }
+    }
+
+    /// Add a rule replacing `from` by `to`, replacing any earlier rule for
+    /// `from`.
+    pub fn with_rule(mut self, from: char, to: &str) -> Self {
+        let _ = self.rules.insert(from, to.to_string());
+        self
+    }
+
+    /// Add several rules, see [`with_rule`](Transliterator::with_rule).
+    pub fn with_rules<'a, I: IntoIterator<Item = (char, &'a str)>>(self, rules: I) -> Self {
+        rules.into_iter().fold(self, |transliterator, (from, to)| {
+            transliterator.with_rule(from, to)
+        })
+    }
+
+    /// Get the replacement of a character, if there is a rule for it.
+    pub fn rule(&self, from: char) -> Option<&str> {
+        self.rules.get(&from).map(String::as_str)
+    }
+
+    /// Transliterate a text.
+    pub fn apply(&self, text: &str) -> String {
+        let mut transliterated = String::with_capacity(text.len());
+        for c in text.chars() {
+            match self.rules.get(&c) {
+                Some(to) => transliterated.push_str(to),
+                None => transliterated.push(c),
+            }
+        }
+        transliterated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accents() {
+        let accents = Transliterator::accents();
+
+        assert_eq!(accents.apply("señor"), "senor");
+        assert_eq!(accents.apply("Ærøskøbing"), "AEroskobing");
+        assert_eq!(accents.apply("Łódź"), "Lodz");
+        assert_eq!(accents.apply("ŽIŽEK"), "ZIZEK");
+        assert_eq!(accents.apply("Straße"), "Strasse");
+        assert_eq!(accents.apply("plain text, 123!"), "plain text, 123!");
+        // Other scripts are kept as they are
+        assert_eq!(accents.apply("αβγ"), "αβγ");
+
+        // Every rule folds into ASCII letters
+        for (from, to) in &accents.rules {
+            assert!(!from.is_ascii());
+            assert!(to.chars().all(|c| c.is_ascii_alphabetic()));
+        }
+    }
+
+    #[test]
+    fn custom_rules() {
+        let greek = Transliterator::new().with_rules([('α', "a"), ('β', "b"), ('γ', "g")]);
+        assert_eq!(greek.apply("αβγδ"), "abgδ");
+        assert_eq!(greek.rule('β'), Some("b"));
+        assert_eq!(greek.rule('δ'), None);
+
+        // Later rules replace earlier ones
+        let german = Transliterator::accents().with_rule('ä', "ae");
+        assert_eq!(german.apply("Bär"), "Baer");
+        assert_eq!(german.apply("Bàr"), "Bar");
+
+        assert_eq!(Transliterator::new().apply("café"), "café");
+    }
+}
@@ -0,0 +1,158 @@
+//! Checking that a cipher is correct, i.e., that decryption inverts
+//! encryption, on random keys and messages.
+//!
+//! Stinson defines a cryptosystem to satisfy `decrypt(encrypt(x, k), k) == x`
+//! for every key `k` and every plaintext `x` (Stinson 4th Edition, Definition
+//! 2.1).
+//! [`verify_cipher`] checks this property on a sample of keys and messages, so
+//! it can be used in the tests of a new cipher, or as a self-test.
+//!
+//! # Examples
+//! ```
+//! # use classical_crypto::{alberti::AlbertiCipher, playfair::PlayfairCipher, shift::ShiftCipher, verify::verify_cipher};
+//! let mut rng = rand::thread_rng();
+//!
+//! assert!(verify_cipher(&ShiftCipher::new(), 100, &mut rng).is_ok());
+//! assert!(verify_cipher(&PlayfairCipher::new(), 100, &mut rng).is_ok());
+//! assert!(verify_cipher(&AlbertiCipher::new(), 100, &mut rng).is_ok());
+//! ```
+use crate::{errors::RoundTripError, CipherTrait, KeyTrait};
+use rand::{CryptoRng, Rng, RngCore};
+use std::fmt::Display;
+
+/// The greatest number of letters of a sample message.
+pub const MAX_SAMPLE_LENGTH: usize = 64;
+
+/// A cipher whose messages can be sampled at random, to check that decryption
+/// inverts encryption, see [`verify_cipher`].
+pub trait SampleMessage: CipherTrait {
+    /// Pick a message of `len` letters at random. Ciphers that prepare
+    /// messages before encryption, such as the Playfair Cipher, only pick
+    /// messages that are already prepared, since decryption cannot undo the
+    /// preparation.
+    fn sample_message<R: RngCore + CryptoRng>(rng: &mut R, len: usize) -> Self::Message;
+}
+
+/// Check that `cipher` decrypts what it encrypts for `samples` random keys and
+/// messages of 1 to [`MAX_SAMPLE_LENGTH`] letters. Each sample uses a new key.
+///
+/// # Errors
+/// This function returns an error for the first sample that fails to encrypt
+/// or decrypt, or that does not decrypt to the original message.
+pub fn verify_cipher<C, R>(cipher: &C, samples: usize, rng: &mut R) -> Result<(), RoundTripError>
+where
+    C: SampleMessage,
+    C::Message: Display + PartialEq,
+    C::Ciphertext: Display,
+    R: RngCore + CryptoRng,
+{
+    for sample in 0..samples {
+        let key = C::Key::new(rng);
+        let len = rng.gen_range(1..=MAX_SAMPLE_LENGTH);
+        let msg = C::sample_message(rng, len);
+
+        let ciphertxt = cipher
+            .encrypt(&msg, &key)
+            .map_err(|e| RoundTripError::Encryption {
+                sample,
+                message: msg.to_string(),
+                error: e.to_string(),
+            })?;
+        let decrypted =
+            cipher
+                .decrypt(&ciphertxt, &key)
+                .map_err(|e| RoundTripError::Decryption {
+                    sample,
+                    ciphertext: ciphertxt.to_string(),
+                    error: e.to_string(),
+                })?;
+
+        if decrypted != msg {
+            return Err(RoundTripError::Mismatch {
+                sample,
+                message: msg.to_string(),
+                decrypted: decrypted.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alberti::AlbertiCipher,
+        playfair::PlayfairCipher,
+        shift::{Ciphertext, Key, Message, ShiftCipher},
+        CipherInfo,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use std::convert::Infallible;
+
+    // Create a test seed for reproducible tests.
+    const TEST_SEED: [u8; 32] = *b"MY DISTRIBUTION IS NOT UNIFORM!!";
+
+    // A broken cipher, which forgets to decrypt the last letter.
+    #[derive(Debug)]
+    struct Forgetful;
+
+    impl CipherTrait for Forgetful {
+        type Message = Message;
+        type Ciphertext = Ciphertext;
+        type Key = Key;
+        type EncryptionError = Infallible;
+        type DecryptionError = Infallible;
+
+        fn encrypt(&self, msg: &Message, key: &Key) -> Result<Ciphertext, Infallible> {
+            ShiftCipher::new().encrypt(msg, key)
+        }
+
+        fn decrypt(&self, ciphertxt: &Ciphertext, key: &Key) -> Result<Message, Infallible> {
+            let last = ciphertxt.slice(ciphertxt.len() - 1..);
+            let decrypted =
+                ShiftCipher::new().decrypt(&ciphertxt.slice(..ciphertxt.len() - 1), key)?;
+            Ok(decrypted.into_iter().chain(last).collect())
+        }
+
+        fn info() -> CipherInfo {
+            ShiftCipher::info()
+        }
+    }
+
+    impl SampleMessage for Forgetful {
+        fn sample_message<R: RngCore + CryptoRng>(rng: &mut R, len: usize) -> Message {
+            ShiftCipher::sample_message(rng, len)
+        }
+    }
+
+    #[test]
+    fn ciphers() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        assert_eq!(verify_cipher(&ShiftCipher::new(), 200, &mut rng), Ok(()));
+        assert_eq!(verify_cipher(&PlayfairCipher::new(), 200, &mut rng), Ok(()));
+        assert_eq!(verify_cipher(&AlbertiCipher::new(), 200, &mut rng), Ok(()));
+    }
+
+    #[test]
+    fn playfair_samples() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        let msg = PlayfairCipher::sample_message(&mut rng, 7);
+        assert_eq!(msg.len(), 8);
+        for digraph in msg.chunks(2) {
+            assert_ne!(digraph[0], digraph[1]);
+        }
+    }
+
+    #[test]
+    fn mismatch() {
+        let mut rng = ChaCha12Rng::from_seed(TEST_SEED);
+        // The last letter only decrypts correctly under the key 0
+        assert!(matches!(
+            verify_cipher(&Forgetful, 100, &mut rng),
+            Err(RoundTripError::Mismatch { .. })
+        ));
+    }
+}
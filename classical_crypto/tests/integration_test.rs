@@ -1,7 +1,12 @@
 //! These integration tests exercise the public API of the crate, but they may
 //! not be entirely sensible as integration tests.
 use classical_crypto::{
-    shift::{Ciphertext, Key, Message, ShiftCipher},
+    alphabet::{Alphanumeric, ClassicalLatin, PrintableAscii},
+    policy::{DigitPolicy, Folding, MessagePolicy},
+    shift::{
+        AlphabetCiphertext, AlphabetKey, AlphabetMessage, AlphabetShiftCipher, Ciphertext, Key,
+        Message, ShiftCipher,
+    },
     CipherTrait, KeyTrait,
 };
 use rand::thread_rng;
@@ -19,17 +24,17 @@ fn generate_and_use_key() {
     assert_eq!(msg, Message::from_str("thisisanawkwardapichoice").unwrap());
 
     // Encrypt the test message.
-    let ciphertxt = ShiftCipher::encrypt(&msg, &key0);
+    let ciphertxt = ShiftCipher::new().encrypt(&msg, &key0).unwrap();
 
     // If we decrypt our ciphertext with the correct key, we
     // get our original message back.
-    let decrypted = ShiftCipher::decrypt(&ciphertxt, &key0);
+    let decrypted = ShiftCipher::new().decrypt(&ciphertxt, &key0).unwrap();
     assert_eq!(decrypted, msg);
 
     // If we decrypt using an incorrect key, we do not get
     //  our original message back
     if key0 != key1 {
-        assert_ne!(ShiftCipher::decrypt(&ciphertxt, &key1), msg);
+        assert_ne!(ShiftCipher::new().decrypt(&ciphertxt, &key1).unwrap(), msg);
     }
 
     // We can create ciphertexts from strings, too
@@ -74,8 +79,12 @@ fn short_msg_example() {
     let fixed_key_0 = fixed_key_0.unwrap();
     let fixed_key_1 = fixed_key_1.unwrap();
 
-    let small_ciphertext = ShiftCipher::encrypt(&small_msg_0, &fixed_key_0);
-    let small_decryption = ShiftCipher::decrypt(&small_ciphertext, &fixed_key_0);
+    let small_ciphertext = ShiftCipher::new()
+        .encrypt(&small_msg_0, &fixed_key_0)
+        .unwrap();
+    let small_decryption = ShiftCipher::new()
+        .decrypt(&small_ciphertext, &fixed_key_0)
+        .unwrap();
 
     // Encryption followed by decryption with the correct gets us back the original
     // message
@@ -85,7 +94,9 @@ fn short_msg_example() {
     // Encryption followed by decryption with an incorrect key gets us back a still
     // intelligible message somtimes.
     assert_eq!(
-        ShiftCipher::decrypt(&small_ciphertext, &fixed_key_1),
+        ShiftCipher::new()
+            .decrypt(&small_ciphertext, &fixed_key_1)
+            .unwrap(),
         small_msg_1
     );
 }
@@ -107,3 +118,86 @@ fn new_ciphtxt_err() {
         "Invalid Ciphertext. Failed to encode the following characters as ring elements: ;;"
     )
 }
+
+#[test]
+fn classical_latin_shift() {
+    type Caesar = AlphabetShiftCipher<ClassicalLatin, 23>;
+
+    // The Romans had no j, u or w, so fold them into i, v and vv first
+    let policy = MessagePolicy::new().with_folding(Folding::ClassicalLatin);
+    let msg =
+        AlphabetMessage::<ClassicalLatin, 23>::with_policy("julius was here", &policy).unwrap();
+    assert_eq!(msg.to_string(), "ivlivsvvashere");
+    assert!(AlphabetMessage::<ClassicalLatin, 23>::new("julius").is_err());
+
+    let key = AlphabetKey::from_str("3").unwrap();
+    let ciphertxt = Caesar::new().encrypt(&msg, &key).unwrap();
+    assert_eq!(ciphertxt.to_string(), "MZOMZXZZDXLHVH");
+    assert_eq!(
+        ciphertxt,
+        AlphabetCiphertext::from_str("mzomzxzzdxlhvh").unwrap()
+    );
+    assert_eq!(Caesar::new().decrypt(&ciphertxt, &key).unwrap(), msg);
+
+    // Keys are shifts from 0 to 22, and a fresh key decrypts what it encrypts
+    assert!(AlphabetKey::<23>::from_str("23").is_err());
+    assert_eq!(AlphabetKey::<23>::try_import(&key.export()).unwrap(), key);
+    let key = AlphabetKey::new(&mut thread_rng());
+    let ciphertxt = Caesar::new().encrypt(&msg, &key).unwrap();
+    assert_eq!(Caesar::new().decrypt(&ciphertxt, &key).unwrap(), msg);
+    assert_eq!(Caesar::info().alphabet_size, 23);
+}
+
+#[test]
+fn alphanumeric_shift() {
+    type Cipher = AlphabetShiftCipher<Alphanumeric, 36>;
+
+    // Digits are kept by default, and encrypted like letters
+    let msg = AlphabetMessage::<Alphanumeric, 36>::new("agent 007 at 9pm").unwrap();
+    assert_eq!(msg.to_string(), "agent007at9pm");
+    let key = AlphabetKey::from_str("10").unwrap();
+    let ciphertxt = Cipher::new().encrypt(&msg, &key).unwrap();
+    assert_eq!(ciphertxt.to_string(), "KQOX3AAHK3JZW");
+    assert_eq!(Cipher::new().decrypt(&ciphertxt, &key).unwrap(), msg);
+
+    // Digits round-trip, whatever the key
+    let msg = AlphabetMessage::<Alphanumeric, 36>::new("0123456789").unwrap();
+    let key = AlphabetKey::new(&mut thread_rng());
+    let ciphertxt = Cipher::new().encrypt(&msg, &key).unwrap();
+    assert_eq!(Cipher::new().decrypt(&ciphertxt, &key).unwrap(), msg);
+
+    // Other digit policies apply before the alphabet is
+    let policy = MessagePolicy::new().with_digits(DigitPolicy::SpellOut);
+    let msg = AlphabetMessage::<Alphanumeric, 36>::with_policy("agent 7", &policy).unwrap();
+    assert_eq!(msg.to_string(), "agentseven");
+    let policy = MessagePolicy::new().with_digits(DigitPolicy::Reject);
+    assert!(AlphabetMessage::<Alphanumeric, 36>::with_policy("agent 7", &policy).is_err());
+}
+
+#[test]
+fn printable_ascii_shift() {
+    type Cipher = AlphabetShiftCipher<PrintableAscii, 95>;
+
+    // Whole sentences are encrypted as they are, spaces and punctuation included
+    let sentence = "Meet me at 9, by the old oak tree!";
+    let msg = AlphabetMessage::<PrintableAscii, 95>::new(sentence).unwrap();
+    assert_eq!(msg.to_string(), sentence);
+    assert_eq!(msg.len(), sentence.len());
+
+    let key = AlphabetKey::from_str("1").unwrap();
+    let ciphertxt = Cipher::new().encrypt(&msg, &key).unwrap();
+    assert_eq!(ciphertxt.to_string(), "Nffu!nf!bu!:-!cz!uif!pme!pbl!usff\"");
+    assert_eq!(Cipher::new().decrypt(&ciphertxt, &key).unwrap(), msg);
+
+    // The ciphertext is not uppercased, so it parses back as written
+    let parsed = AlphabetCiphertext::<PrintableAscii, 95>::from_str(&ciphertxt.to_string());
+    assert_eq!(parsed.unwrap(), ciphertxt);
+
+    let key = AlphabetKey::new(&mut thread_rng());
+    let ciphertxt = Cipher::new().encrypt(&msg, &key).unwrap();
+    assert_eq!(
+        Cipher::new().decrypt(&ciphertxt, &key).unwrap().to_string(),
+        sentence
+    );
+    assert!(AlphabetMessage::<PrintableAscii, 95>::new("tab\there").is_err());
+}
@@ -5,11 +5,24 @@ use crate::{
 };
 use anyhow::{anyhow, Result};
 use classical_crypto::{
-    shift::{Ciphertext, Key, ShiftCipher},
+    alphabet::{CustomAlphabet, Latin},
+    disk::CipherDisk,
+    errors::KeyringError,
+    keyring::Keyring,
+    playfair::PlayfairCipher,
+    registry,
+    shift::{Ciphertext, Key, KeyDisplayMode, ShiftCipher},
+    tabula::TabulaRecta,
     CipherTrait, KeyTrait,
 };
 use rand::thread_rng;
-use std::io::{BufRead, Write};
+use std::{
+    env,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
 
 /// Creates keys and prints the key to standard output.
 pub fn make_key(mut reader: impl BufRead, mut writer: impl Write) -> Result<()> {
@@ -21,8 +34,16 @@ pub fn make_key(mut reader: impl BufRead, mut writer: impl Write) -> Result<()>
         let key = Key::new(&mut rng);
 
         writeln!(writer, "\nWe generated your key successfully!.")?;
-        writeln!(writer, "\nWe shouldn't export your key (or say, save it in logs), but we can!")?;
-        writeln!(writer, "Here it is: {}\n", ShiftCipher::insecure_key_export(&key))?;
+        writeln!(
+            writer,
+            "\nWe shouldn't export your key (or say, save it in logs), but we can!"
+        )?;
+        writeln!(
+            writer,
+            "Here it is: {}\n",
+            key.export_as(KeyDisplayMode::Both)
+        )?;
+        print_key_count(writer.by_ref())?;
 
         'inner: loop {
             writeln!(writer, "\nAre you happy with your key?")?;
@@ -31,7 +52,10 @@ pub fn make_key(mut reader: impl BufRead, mut writer: impl Write) -> Result<()>
             match process_input(&mut reader) {
                 Ok(ConsentMenu::NoKE) => continue 'outer,
                 Ok(ConsentMenu::YesKE) => {
-                    writeln!(writer, "\nGreat! We don't have a file system implemented (much less a secure one), so please \nremember your key in perpetuity!")?;
+                    let mut keyring = load_keyring()?;
+                    if save_key(&key, &mut keyring, &mut reader, writer.by_ref())? {
+                        save_keyring(&keyring, writer.by_ref())?;
+                    }
 
                     break 'outer Ok(());
                 }
@@ -44,6 +68,208 @@ pub fn make_key(mut reader: impl BufRead, mut writer: impl Write) -> Result<()>
     }
 }
 
+/// The file in which the demo keeps its keyring, in the current directory.
+const KEYRING_PATH: &str = "demo_keyring.toml";
+
+/// How long the keys that the demo saves may be used to encrypt, i.e., 7 days.
+const KEY_LIFETIME: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Gets the path of the file in which the demo keeps its keyring.
+fn keyring_path() -> Result<PathBuf> {
+    Ok(env::current_dir()?.join(KEYRING_PATH))
+}
+
+/// Loads the keyring of the demo, which is empty until the first key is saved.
+fn load_keyring() -> Result<Keyring> {
+    match Keyring::load(keyring_path()?) {
+        Err(KeyringError::Io(e)) if e.kind() == io::ErrorKind::NotFound => Ok(Keyring::new()),
+        keyring => Ok(keyring?),
+    }
+}
+
+/// Saves the keyring of the demo and tells the user where.
+fn save_keyring(keyring: &Keyring, mut writer: impl Write) -> Result<()> {
+    let path = keyring_path()?;
+    keyring.save(&path)?;
+    writeln!(writer, "\nWe saved your keyring in {}.", path.display())?;
+
+    Ok(())
+}
+
+/// Offers to add the key to the keyring under a label, and returns whether it was added. A label
+/// that reads as a key, e.g., "7" or "d", is rejected: `read_key` would take it for that key.
+fn save_key(
+    key: &Key,
+    keyring: &mut Keyring,
+    mut reader: impl BufRead,
+    mut writer: impl Write,
+) -> Result<bool> {
+    let shift =
+        registry::get("shift").ok_or_else(|| anyhow!("The Shift Cipher is not registered"))?;
+
+    loop {
+        writeln!(writer, "\nGreat! Enter a label to save your key in the keyring ({}), or leave it empty \nto remember your key in perpetuity instead. The keyring is not protected in any way, \nso keep it somewhere safe!", keyring_path()?.display())?;
+
+        let mut label = String::new();
+        reader.read_line(&mut label)?;
+        let label = label.trim();
+        if label.is_empty() {
+            return Ok(false);
+        }
+        if Key::from_str(label).is_ok() {
+            writeln!(writer, "Error: \"{}\" reads as a key itself, so it cannot label one. Please choose another label.", label)?;
+            continue;
+        }
+
+        match keyring.add(label, shift.import_key(&key.export())?) {
+            Ok(()) => {
+                keyring.set_expiry(label, Some(SystemTime::now() + KEY_LIFETIME))?;
+                writeln!(writer, "\nWe saved your key as \"{}\". Enter this label whenever you are asked for a key. \nThe key expires in 7 days: after that, you can still decrypt with it, but when you \nencrypt with it, we will rotate it, i.e., replace it with a fresh key.", label)?;
+                return Ok(true);
+            }
+            Err(e) => writeln!(writer, "Error: {}", e)?,
+        }
+    }
+}
+
+/// Reads a key, given either as a number or as the label of a key in the keyring. An expired key
+/// from the keyring is not used to encrypt: its replacement is used instead, see `rotate_key`.
+fn read_key(
+    mut reader: impl BufRead,
+    mut writer: impl Write,
+    keyring: &mut Keyring,
+    encrypting: bool,
+) -> Result<Key> {
+    let shift =
+        registry::get("shift").ok_or_else(|| anyhow!("The Shift Cipher is not registered"))?;
+
+    loop {
+        let mut input = String::new();
+        reader.read_line(&mut input)?;
+        let input = input.trim();
+
+        let key = match (Key::from_str(input), keyring.get(input)) {
+            // `save_key` rejects such labels, but a keyring written by hand may have them
+            (Ok(key), Some(_)) => {
+                writeln!(writer, "\nNote: \"{}\" is both a key and the label of a key in your keyring. We read it \nas the key {}, not as the label.", input, key.export_as(KeyDisplayMode::Both))?;
+                Ok(key)
+            }
+            (Ok(key), None) => Ok(key),
+            (Err(_), Some(_)) if encrypting && keyring.is_expired(input) => {
+                rotate_key(keyring, input, writer.by_ref())
+            }
+            (Err(_), Some(key)) => shift
+                .export_key(key)
+                .map_err(anyhow::Error::from)
+                .and_then(|key| Ok(Key::from_str(&key)?)),
+            (Err(e), None) => Err(e.into()),
+        };
+
+        match key {
+            Ok(key) => break Ok(key),
+            Err(e) => writeln!(writer, "Error: {}", e)?,
+        }
+    }
+}
+
+/// Finds the newest replacement of an expired key of the keyring and returns it if it has not
+/// expired. Otherwise, rotates it, i.e., adds a fresh key to the keyring to replace it, and returns
+/// the new key.
+fn rotate_key(keyring: &mut Keyring, label: &str, mut writer: impl Write) -> Result<Key> {
+    let shift =
+        registry::get("shift").ok_or_else(|| anyhow!("The Shift Cipher is not registered"))?;
+
+    // A label may be reused after its key is removed, so the successors may loop: stop at the first
+    // label that we have already visited.
+    let mut visited = vec![label];
+    let mut newest = label;
+    while let Some(successor) = keyring.successor(newest) {
+        if visited.contains(&successor) {
+            break;
+        }
+        visited.push(successor);
+        newest = successor;
+    }
+    let newest = newest.to_string();
+    if !keyring.is_expired(&newest) {
+        let key = keyring
+            .get(&newest)
+            .ok_or_else(|| anyhow!("The key {:?} is missing", newest))?;
+        writeln!(writer, "\nThe key \"{}\" has expired, so your message is encrypted with its replacement, \"{}\".", label, newest)?;
+        return Ok(Key::from_str(&shift.export_key(key)?)?);
+    }
+    let label = newest.as_str();
+
+    // Number the new key after the first key of the lineage, e.g., "alice v2", "alice v3"
+    let lineage = keyring.lineage(label);
+    let first = lineage.last().copied().unwrap_or(label).to_string();
+    let new_label = (lineage.len() + 1..)
+        .map(|version| format!("{} v{}", first, version))
+        .find(|new_label| keyring.get(new_label).is_none())
+        .expect("Some version is free");
+
+    let key = keyring.rotate(label, &new_label, Some(KEY_LIFETIME), &mut thread_rng())?;
+    let key = Key::from_str(&shift.export_key(key)?)?;
+
+    writeln!(writer, "\nThe key \"{}\" has expired, so we rotated it: your message is encrypted with a fresh \nkey, \"{}\", which is {} and expires in 7 days. Keep \"{}\" to decrypt your old \nmessages, and share \"{}\" with whoever should read your new ones.", label, new_label, key.export_as(KeyDisplayMode::Both), label, new_label)?;
+    writeln!(writer, "\nRotating keys limits how much ciphertext an attacker sees under any one key, and how \nlong a stolen key stays useful. Of course, 26 keys are too few for this to help much!")?;
+
+    Ok(key)
+}
+
+/// Prints the number of keys of the Shift Cipher, compared with the Playfair Cipher.
+fn print_key_count(mut writer: impl Write) -> Result<()> {
+    for info in [ShiftCipher::info(), PlayfairCipher::info()] {
+        if let (Some(count), Some(bits)) = (info.key_count(), info.security_bits()) {
+            writeln!(
+                writer,
+                "{}: {} keys, i.e., {:.1} bits",
+                info.name, count, bits
+            )?;
+        }
+    }
+    writeln!(writer, "\nAn attacker who tries every key needs at most 26 guesses to find yours. A larger \nkeyspace only rules out this attack: the Playfair Cipher falls to frequency analysis too!")?;
+
+    Ok(())
+}
+
+/// Prints the tabula recta of the Latin Alphabet.
+pub fn print_tabula(mut writer: impl Write) -> Result<()> {
+    writeln!(writer, "\nHere is the tabula recta.")?;
+    writeln!(
+        writer,
+        "Encrypting with the Shift Cipher under the key k replaces each letter in the top row by the letter below it in row k (counting from 0)."
+    )?;
+    writeln!(writer, "\n{}\n", TabulaRecta::latin())?;
+
+    Ok(())
+}
+
+/// Prints a cipher disk, before and after rotating it.
+pub fn print_disk(mut writer: impl Write) -> Result<()> {
+    let inner = CustomAlphabet::from_alphabet::<Latin, 26>().keyword_mixed("alberti")?;
+    let mut disk = CipherDisk::latin(inner)?;
+
+    writeln!(
+        writer,
+        "\nHere is a cipher disk, with its inner ring mixed by the keyword \"alberti\"."
+    )?;
+    writeln!(
+        writer,
+        "Each letter of the outer ring is encrypted as the letter under it on the inner ring."
+    )?;
+    writeln!(writer, "\n{}\n", disk)?;
+
+    disk.rotate(1);
+    writeln!(
+        writer,
+        "The Alberti Cipher rotates the inner ring as it goes, so after one step the disk reads:"
+    )?;
+    writeln!(writer, "\n{}\n", disk)?;
+
+    Ok(())
+}
+
 /// Takes in a key and a message and encrypts, then prints
 /// the result.
 pub fn encrypt(mut reader: impl BufRead, mut writer: impl Write) -> Result<()> {
@@ -63,27 +289,23 @@ pub fn encrypt(mut reader: impl BufRead, mut writer: impl Write) -> Result<()> {
 
     writeln!(writer, "\nNow, do you have a key that was generated uniformly at random that you remember and \nwould like to use? If yes, please enter your key. Otherwise, please pick a fresh key \nuniformly at random from the ring of integers modulo 26 yourself. \n\nYou won't be as good at this as a computer, but if you understand the cryptosystem \nyou are using (something we cryptographers routinely assume about other people, while \npretending that we aren't assuming this), you will probably not pick a key of 0, \nwhich is equivalent to sending your messages \"in the clear\", i.e., unencrypted. Good \nluck! \n")?;
 
-    let key = loop {
-        writeln!(
-            writer,
-            "\nPlease enter a key now. Keys are numbers between 0 and 25 inclusive."
-        )?;
-
-        let key = process_input(&mut reader);
+    writeln!(
+        writer,
+        "\nPlease enter a key now. Keys are numbers between 0 and 25 inclusive, the letters \nthat a is shifted to, or labels of keys in your keyring."
+    )?;
 
-        match key {
-            Ok(key) => break key,
-            Err(e) => {
-                writeln!(writer, "Error: {}", e)?;
-                continue;
-            }
-        }
-    };
+    let mut keyring = load_keyring()?;
+    let count = keyring.len();
+    let key = read_key(&mut reader, writer.by_ref(), &mut keyring, true)?;
+    // Rotating a key adds its replacement to the keyring
+    if keyring.len() != count {
+        save_keyring(&keyring, writer.by_ref())?;
+    }
 
     writeln!(
         writer,
         "\nYour ciphertext is {}",
-        ShiftCipher::encrypt(&msg, &key)
+        ShiftCipher::new().encrypt(&msg, &key)?
     )?;
 
     writeln!(writer, "\nLook for patterns in your ciphertext. Could you definitively figure out the key and \noriginal plaintext message if you didn't already know it?")?;
@@ -138,20 +360,10 @@ pub fn chosen_key(
     loop {
         writeln!(
             writer,
-            "\nPlease enter a key now. Keys are numbers between 0 and 25 inclusive."
+            "\nPlease enter a key now. Keys are numbers between 0 and 25 inclusive, the letters \nthat a is shifted to, or labels of keys in your keyring."
         )?;
 
-        let key = loop {
-            let key = process_input(&mut reader);
-
-            match key {
-                Ok(key) => break key,
-                Err(e) => {
-                    writeln!(writer, "Error: {}", e)?;
-                    continue;
-                }
-            }
-        };
+        let key = read_key(&mut reader, writer.by_ref(), &mut load_keyring()?, false)?;
 
         match try_decrypt(ciphertxt, key, &mut reader, writer.by_ref()) {
             Ok(_) => break Ok(()),
@@ -185,10 +397,11 @@ pub fn try_decrypt(
     mut reader: impl BufRead,
     mut writer: impl Write,
 ) -> Result<()> {
-    writeln!(writer, 
+    writeln!(
+        writer,
         "\nYour computed plaintext is {}\n",
-        ShiftCipher::decrypt(ciphertxt, &key)
-    );
+        ShiftCipher::new().decrypt(ciphertxt, &key)?
+    )?;
 
     let command = loop {
         writeln!(writer, "\nAre you happy with this decryption?")?;
@@ -210,3 +423,122 @@ pub fn try_decrypt(
         ConsentMenu::YesKE => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use classical_crypto::dynamic::DynKey;
+
+    // Get the key of the Shift Cipher as a key of the keyring
+    fn dyn_key(key: &str) -> DynKey {
+        registry::get("shift")
+            .unwrap()
+            .import_key(&Key::from_str(key).unwrap().export())
+            .unwrap()
+    }
+
+    // Get the key of the Shift Cipher under a label of the keyring
+    fn labeled_key(keyring: &Keyring, label: &str) -> Option<Key> {
+        let shift = registry::get("shift").unwrap();
+        keyring
+            .get(label)
+            .map(|key| Key::from_str(&shift.export_key(key).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn save_key() -> Result<()> {
+        let key = Key::from_str("3")?;
+        let mut keyring = Keyring::new();
+        let mut output = Vec::new();
+
+        // Labels that read as keys are rejected, before the key is saved under a valid label
+        let saved = super::save_key(&key, &mut keyring, "7\nd\nalice\n".as_bytes(), &mut output)?;
+        let output = String::from_utf8(output)?;
+        assert!(saved);
+        assert!(output.contains("Error: \"7\" reads as a key itself"));
+        assert!(output.contains("Error: \"d\" reads as a key itself"));
+        assert!(output.contains("We saved your key as \"alice\""));
+        assert_eq!(keyring.len(), 1);
+        assert_eq!(labeled_key(&keyring, "alice"), Some(Key::from_str("3")?));
+        assert!(!keyring.is_expired("alice"));
+
+        // A label is not reused, and an empty label saves nothing
+        let mut output = Vec::new();
+        let saved = super::save_key(&key, &mut keyring, "alice\n\n".as_bytes(), &mut output)?;
+        assert!(!saved);
+        assert!(String::from_utf8(output)?.contains("Error: "));
+        assert_eq!(keyring.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_key() -> Result<()> {
+        let mut keyring = Keyring::new();
+        keyring.add("alice", dyn_key("3"))?;
+
+        // Keys are read as numbers, letters or labels, and invalid input is asked for again
+        for (input, key) in [("7\n", "7"), ("h\n", "7"), ("bob\nalice\n", "3")] {
+            let mut output = Vec::new();
+            let read = super::read_key(input.as_bytes(), &mut output, &mut keyring, false)?;
+            assert_eq!(read, Key::from_str(key)?);
+            assert_eq!(output.is_empty(), input != "bob\nalice\n");
+        }
+
+        // A label that reads as a key is read as the key, and the user is told so
+        keyring.add("7", dyn_key("3"))?;
+        let mut output = Vec::new();
+        let read = super::read_key("7\n".as_bytes(), &mut output, &mut keyring, false)?;
+        assert_eq!(read, Key::from_str("7")?);
+        assert!(String::from_utf8(output)?.contains("\"7\" is both a key and the label"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotate_key() -> Result<()> {
+        let mut keyring = Keyring::new();
+        keyring.add("alice", dyn_key("3"))?;
+        keyring.set_expiry("alice", Some(SystemTime::UNIX_EPOCH))?;
+
+        // An expired key still decrypts
+        let read = super::read_key("alice\n".as_bytes(), io::sink(), &mut keyring, false)?;
+        assert_eq!(read, Key::from_str("3")?);
+        assert_eq!(keyring.len(), 1);
+
+        // Encrypting with it rotates it
+        let mut output = Vec::new();
+        let new = super::read_key("alice\n".as_bytes(), &mut output, &mut keyring, true)?;
+        assert!(String::from_utf8(output)?.contains("so we rotated it"));
+        assert_eq!(keyring.len(), 2);
+        assert_eq!(keyring.successor("alice"), Some("alice v2"));
+        assert_eq!(labeled_key(&keyring, "alice v2").as_ref(), Some(&new));
+
+        // The replacement is used until it expires too
+        let mut output = Vec::new();
+        let read = super::rotate_key(&mut keyring, "alice", &mut output)?;
+        assert_eq!(read, new);
+        assert!(String::from_utf8(output)?.contains("its replacement, \"alice v2\""));
+        assert_eq!(keyring.len(), 2);
+
+        keyring.set_expiry("alice v2", Some(SystemTime::UNIX_EPOCH))?;
+        super::rotate_key(&mut keyring, "alice", io::sink())?;
+        assert_eq!(
+            keyring.lineage("alice v3"),
+            ["alice v3", "alice v2", "alice"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn key_count() -> Result<()> {
+        let mut output = Vec::new();
+        print_key_count(&mut output)?;
+        let output = String::from_utf8(output)?;
+        assert!(output.starts_with("shift: 26 keys, i.e., 4.7 bits\n"));
+        assert!(output.contains("playfair: "));
+
+        Ok(())
+    }
+}
@@ -58,7 +58,7 @@ where
 #[cfg(test)]
 mod tests {
     use classical_crypto::shift::{Ciphertext, Key, Message};
-    use io::{Error, ErrorKind};
+    use io::Error;
 
     use super::*;
     use crate::menu::{ConsentMenu, DecryptMenu, MainMenu, Menu};
@@ -127,7 +127,7 @@ mod tests {
         fn flush(&mut self) -> io::Result<()> {
             let output = match from_utf8(&self.buffer) {
                 Ok(r) => Ok(r),
-                Err(_) => Err(Error::new(ErrorKind::Other, "oh no!")),
+                Err(_) => Err(Error::other("oh no!")),
             };
             self.mock_output.push_str(output.unwrap());
             self.buffer = Vec::new();
@@ -232,7 +232,7 @@ mod tests {
         let error = error.as_ref().unwrap_err();
         assert_eq!(
             error.to_string(),
-            "Parse error: Input \"65\" does not represent a valid key"
+            "Parse error: Input \"65\" does not represent a valid shift key; expected a number between 0 and 25, a lowercase letter, or both, as in \"11 (l)\""
         );
 
         assert!(matches!(error, ProcessInputError::CryptoParseError(_)));
@@ -319,7 +319,7 @@ mod tests {
         // Test reads
         assert_eq!(command, MainMenu::GenKE);
         // Test writes
-        assert_eq!(mock_writer.mock_output, "\nPlease enter one of the following options:\n1: Generate a key.\n2: Encrypt a message.\n3: Decrypt a ciphertext.\n4: Quit\n");
+        assert_eq!(mock_writer.mock_output, "\nPlease enter one of the following options:\n1: Generate a key.\n2: Encrypt a message.\n3: Decrypt a ciphertext.\n4: Print the tabula recta.\n5: See a cipher disk.\n6: Quit\n");
         Ok(())
     }
     //
@@ -338,9 +338,23 @@ mod tests {
     }
     //
     #[test]
-    fn main_quit() {
+    fn main_tabula() {
         let mut mock_reader = MockIoReader::new("4");
         let command: MainMenu = process_input(&mut mock_reader).unwrap();
+        assert_eq!(command, MainMenu::TabulaKE)
+    }
+    //
+    #[test]
+    fn main_disk() {
+        let mut mock_reader = MockIoReader::new("5");
+        let command: MainMenu = process_input(&mut mock_reader).unwrap();
+        assert_eq!(command, MainMenu::DiskKE)
+    }
+    //
+    #[test]
+    fn main_quit() {
+        let mut mock_reader = MockIoReader::new("6");
+        let command: MainMenu = process_input(&mut mock_reader).unwrap();
         assert_eq!(command, MainMenu::QuitKE)
     }
     //
@@ -6,7 +6,7 @@ pub mod crypto_functionality;
 mod io_helper;
 pub mod menu;
 
-use crate::crypto_functionality::{decrypt, encrypt, make_key};
+use crate::crypto_functionality::{decrypt, encrypt, make_key, print_disk, print_tabula};
 use crate::io_helper::process_input;
 use crate::menu::{DecryptMenu, MainMenu, Menu};
 
@@ -16,6 +16,8 @@ use crate::menu::{DecryptMenu, MainMenu, Menu};
 /// - Generate a key;
 /// - Encrypt a message;
 /// - Decrypt a message;
+/// - Print the tabula recta;
+/// - See a cipher disk;
 /// - Quit the CLI application.
 pub fn menu(mut reader: impl BufRead, mut writer: impl Write) -> Result<()> {
     loop {
@@ -39,6 +41,10 @@ pub fn menu(mut reader: impl BufRead, mut writer: impl Write) -> Result<()> {
                 // Proceed with decryption as specified by user
                 decrypt(command, &mut reader, writer.by_ref())?;
             }
+            // Print the tabula recta
+            Ok(MainMenu::TabulaKE) => print_tabula(writer.by_ref())?,
+            // See a cipher disk
+            Ok(MainMenu::DiskKE) => print_disk(writer.by_ref())?,
             // Quit the CLI application
             Ok(MainMenu::QuitKE) => break Ok(()),
             Err(_) => continue,
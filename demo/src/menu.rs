@@ -29,13 +29,24 @@ pub enum MainMenu {
     EncryptKE,
     /// User wants to decrypt a message.
     DecryptKE,
+    /// User wants to print the tabula recta.
+    TabulaKE,
+    /// User wants to see a cipher disk.
+    DiskKE,
     /// User wants to quit the CLI application.
     QuitKE,
 }
 
-impl Menu<4> for MainMenu {
-    fn menu_array() -> MenuArray<4> {
-        MenuArray([Self::GEN, Self::ENCRYPT, Self::DECRYPT, Self::QUIT])
+impl Menu<6> for MainMenu {
+    fn menu_array() -> MenuArray<6> {
+        MenuArray([
+            Self::GEN,
+            Self::ENCRYPT,
+            Self::DECRYPT,
+            Self::TABULA,
+            Self::DISK,
+            Self::QUIT,
+        ])
     }
 }
 
@@ -44,7 +55,9 @@ impl MainMenu {
     const GEN_KE: &'static str = "1"; // Key Event for "Generate a key"
     const ENCRYPT_KE: &'static str = "2"; // Key Event for "encrypt a message"
     const DECRYPT_KE: &'static str = "3"; // Key Event for "decrypt"
-    const QUIT_KE: &'static str = "4"; // Key Event for "quit"
+    const TABULA_KE: &'static str = "4"; // Key Event for "print the tabula recta"
+    const DISK_KE: &'static str = "5"; // Key Event for "see a cipher disk"
+    const QUIT_KE: &'static str = "6"; // Key Event for "quit"
 
     // Main Menu commands
     //
@@ -65,6 +78,18 @@ impl MainMenu {
         menu_msg: "Decrypt a ciphertext.",
     };
 
+    // Command to print the tabula recta
+    const TABULA: Command<'static> = Command {
+        key: Self::TABULA_KE,
+        menu_msg: "Print the tabula recta.",
+    };
+
+    // Command to see a cipher disk
+    const DISK: Command<'static> = Command {
+        key: Self::DISK_KE,
+        menu_msg: "See a cipher disk.",
+    };
+
     // Command to quit
     const QUIT: Command<'static> = Command {
         key: Self::QUIT_KE,
@@ -80,6 +105,8 @@ impl FromStr for MainMenu {
             MainMenu::GEN_KE => Ok(MainMenu::GenKE),
             MainMenu::ENCRYPT_KE => Ok(MainMenu::EncryptKE),
             MainMenu::DECRYPT_KE => Ok(MainMenu::DecryptKE),
+            MainMenu::TABULA_KE => Ok(MainMenu::TabulaKE),
+            MainMenu::DISK_KE => Ok(MainMenu::DiskKE),
             MainMenu::QUIT_KE => Ok(MainMenu::QuitKE),
             _ => Err(ProcessInputError::CommandParseError(s.to_string())),
         }